@@ -1,6 +1,8 @@
 use std::collections::HashMap;
+use std::fs;
 use std::sync::mpsc::{self, Receiver, TrySendError};
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
@@ -9,7 +11,8 @@ use alsa_sys as alsa_ffi;
 use anyhow::{anyhow, bail, Context, Result};
 use regex::Regex;
 
-use crate::models::{ControlDescriptor, ControlKind, RouteRef, RoutingIndex};
+use crate::models::{CardIdentity, ControlDescriptor, ControlKind, RouteRef, RoutingIndex};
+use crate::remote_backend::RemoteBackend;
 
 #[derive(Debug, Clone)]
 pub struct CardInfo {
@@ -17,17 +20,54 @@ pub struct CardInfo {
     pub name: String,
 }
 
+/// Backend activity since startup, for the metrics endpoint.
+#[derive(Debug, Default)]
+pub struct BackendCounters {
+    writes: AtomicU64,
+    errors: AtomicU64,
+    events: AtomicU64,
+}
+
+impl BackendCounters {
+    pub fn writes(&self) -> u64 {
+        self.writes.load(Ordering::Relaxed)
+    }
+
+    pub fn errors(&self) -> u64 {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    /// Control events read by the event listener, before UI throttling.
+    pub fn events(&self) -> u64 {
+        self.events.load(Ordering::Relaxed)
+    }
+
+    fn record_error<T>(&self, result: Result<T>) -> Result<T> {
+        if result.is_err() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BackendKind {
     Alsa,
+    /// Another instance's HTTP API, for a card attached to a different machine.
+    Remote,
 }
 
 pub struct AlsaBackend {
     pub card_index: u32,
     pub card_label: String,
+    pub card_identity: CardIdentity,
     ctl_handle: Option<Ctl>,
     hctl_handle: Option<HCtl>,
     kind_cache_by_numid: Mutex<HashMap<u32, ControlKind>>,
+    /// Lower maxima enforced on writes, below the control's own range.
+    value_ceilings: Mutex<HashMap<u32, i64>>,
+    pub counters: Arc<BackendCounters>,
+    remote: Option<RemoteBackend>,
 }
 
 impl AlsaBackend {
@@ -48,7 +88,9 @@ impl AlsaBackend {
         Ok(cards)
     }
 
-    pub fn pick_card(card_override: Option<u32>) -> Result<Self> {
+    /// `card_override` is an index from `--card`; otherwise the first card whose
+    /// name contains `preferred` (case-insensitive), then the FTU name heuristic.
+    pub fn pick_card(card_override: Option<u32>, preferred: Option<&str>) -> Result<Self> {
         let cards = Self::detect_cards()?;
         if cards.is_empty() {
             bail!("No ALSA cards detected");
@@ -61,11 +103,15 @@ impl AlsaBackend {
                 .cloned()
                 .ok_or_else(|| anyhow!("Requested card index {idx} not found"))?
         } else {
+            let preferred = preferred.map(str::to_lowercase).filter(|p| !p.is_empty());
             cards
                 .iter()
-                .find(|c| {
-                    let l = c.name.to_lowercase();
-                    l.contains("ultra") || l.contains("f8r") || l.contains("fast track")
+                .find(|c| preferred.as_ref().is_some_and(|p| c.name.to_lowercase().contains(p)))
+                .or_else(|| {
+                    cards.iter().find(|c| {
+                        let l = c.name.to_lowercase();
+                        l.contains("ultra") || l.contains("f8r") || l.contains("fast track")
+                    })
                 })
                 .cloned()
                 .or_else(|| cards.first().cloned())
@@ -77,14 +123,58 @@ impl AlsaBackend {
         Ok(Self {
             card_index: card.index,
             card_label: card.name,
+            card_identity: Self::read_card_identity(card.index),
             ctl_handle: Some(ctl),
             hctl_handle: Some(hctl),
             kind_cache_by_numid: Mutex::new(HashMap::new()),
+            value_ceilings: Mutex::new(HashMap::new()),
+            counters: Arc::default(),
+            remote: None,
+        })
+    }
+
+    /// Drives the card of a `--headless` instance through its HTTP API. The
+    /// remote side enforces its own ceilings and locks; ceilings set here
+    /// clamp the writes before they are sent.
+    pub fn connect_remote(remote: RemoteBackend) -> Result<Self> {
+        let card = remote
+            .card()
+            .with_context(|| format!("Failed to pair with {}", remote.address()))?;
+        Ok(Self {
+            card_index: card.index,
+            card_label: format!("{} @ {}", card.label, remote.address()),
+            card_identity: card.identity,
+            ctl_handle: None,
+            hctl_handle: None,
+            kind_cache_by_numid: Mutex::new(HashMap::new()),
+            value_ceilings: Mutex::new(HashMap::new()),
+            counters: Arc::default(),
+            remote: Some(remote),
         })
     }
 
+    /// Reads the USB vendor:product id and serial number for a card, when the
+    /// kernel exposes them (USB audio devices only).
+    fn read_card_identity(card_index: u32) -> CardIdentity {
+        let read_trimmed = |path: String| {
+            fs::read_to_string(path)
+                .ok()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+        CardIdentity {
+            usb_id: read_trimmed(format!("/proc/asound/card{card_index}/usbid")),
+            serial: read_trimmed(format!("/sys/class/sound/card{card_index}/device/../serial")),
+            control_count: 0,
+        }
+    }
+
     pub fn active_backend(&self) -> BackendKind {
-        BackendKind::Alsa
+        if self.remote.is_some() {
+            BackendKind::Remote
+        } else {
+            BackendKind::Alsa
+        }
     }
 
     pub fn start_event_listener<F>(&self, mut notify_ui: F) -> Option<Receiver<()>>
@@ -92,7 +182,26 @@ impl AlsaBackend {
         F: FnMut() + Send + 'static,
     {
         let card_index = self.card_index;
+        let counters = self.counters.clone();
         let (tx, rx) = mpsc::sync_channel(1);
+        if let Some(remote) = self.remote.clone() {
+            thread::spawn(move || loop {
+                // Reconnect after the remote restarts or the network drops.
+                let Ok(mut changes) = remote.open_change_stream() else {
+                    thread::sleep(Duration::from_secs(2));
+                    continue;
+                };
+                while let Ok(count) = changes.next_changes() {
+                    counters.events.fetch_add(count as u64, Ordering::Relaxed);
+                    match tx.try_send(()) {
+                        Ok(()) => notify_ui(),
+                        Err(TrySendError::Full(_)) => {}
+                        Err(TrySendError::Disconnected(_)) => return,
+                    }
+                }
+            });
+            return Some(rx);
+        }
         thread::spawn(move || {
             let Ok(hctl) = Self::open_hctl_handle(card_index) else {
                 return;
@@ -107,6 +216,7 @@ impl AlsaBackend {
                         if handled == 0 {
                             continue;
                         }
+                        counters.events.fetch_add(handled as u64, Ordering::Relaxed);
                         if last_notified.elapsed() < MIN_NOTIFY_INTERVAL {
                             continue;
                         }
@@ -128,6 +238,15 @@ impl AlsaBackend {
     }
 
     pub fn list_controls(&self) -> Result<Vec<ControlDescriptor>> {
+        if let Some(remote) = &self.remote {
+            let controls = self.counters.record_error(remote.list_controls())?;
+            self.refresh_kind_cache_by_numid(&controls);
+            return Ok(controls);
+        }
+        self.counters.record_error(self.list_controls_native())
+    }
+
+    fn list_controls_native(&self) -> Result<Vec<ControlDescriptor>> {
         let ctl = self
             .ctl_handle
             .as_ref()
@@ -284,19 +403,86 @@ impl AlsaBackend {
         unsafe { *(info as *const _ as *const *mut alsa_ffi::snd_ctl_elem_info_t) }
     }
 
+    /// Caps every later write to `numid` at `ceiling`; `None` lifts the cap.
+    pub fn set_value_ceiling(&self, numid: u32, ceiling: Option<i64>) {
+        if let Ok(mut ceilings) = self.value_ceilings.lock() {
+            match ceiling {
+                Some(ceiling) => ceilings.insert(numid, ceiling),
+                None => ceilings.remove(&numid),
+            };
+        }
+    }
+
     pub fn apply_values(&self, numid: u32, values: &[String]) -> Result<()> {
-        self.apply_values_native(numid, values)
+        self.counters.writes.fetch_add(1, Ordering::Relaxed);
+        if let Some(remote) = &self.remote {
+            let values = self.clamp_to_ceiling(numid, values);
+            return self.counters.record_error(remote.set_values(numid, &values));
+        }
+        self.counters.record_error(self.apply_values_native(numid, values))
+    }
+
+    /// `values` with integers held under the control's ceiling, for remote
+    /// writes that skip the native clamp.
+    fn clamp_to_ceiling(&self, numid: u32, values: &[String]) -> Vec<String> {
+        let ceiling = self
+            .value_ceilings
+            .lock()
+            .ok()
+            .and_then(|ceilings| ceilings.get(&numid).copied());
+        let kind = self
+            .kind_cache_by_numid
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&numid).cloned());
+        let (Some(ceiling), Some(ControlKind::Integer { min, max, .. })) = (ceiling, kind) else {
+            return values.to_vec();
+        };
+        let top = max.min(ceiling).max(min);
+        values
+            .iter()
+            .map(|v| match v.trim().parse::<i64>() {
+                Ok(raw) => raw.clamp(min, top).to_string(),
+                Err(_) => v.clone(),
+            })
+            .collect()
     }
 
     pub fn reload_control(&self, original: &ControlDescriptor) -> Result<ControlDescriptor> {
-        let values = self.read_values_by_numid_from_hctl(original.numid, &original.kind)?;
+        if let Some(remote) = &self.remote {
+            let reloaded = self.counters.record_error(remote.get_control(original.numid))?;
+            let mut out = original.clone();
+            out.values = reloaded.values;
+            return Ok(out);
+        }
+        let values = self
+            .counters
+            .record_error(self.read_values_by_numid_from_hctl(original.numid, &original.kind))?;
         let mut out = original.clone();
         out.values = values;
         Ok(out)
     }
 
     pub fn refresh_control_values(&self, controls: &mut [ControlDescriptor]) -> Result<usize> {
-        self.refresh_control_values_native(controls)
+        if let Some(remote) = &self.remote {
+            return self.counters.record_error(Self::refresh_control_values_remote(remote, controls));
+        }
+        self.counters.record_error(self.refresh_control_values_native(controls))
+    }
+
+    fn refresh_control_values_remote(remote: &RemoteBackend, controls: &mut [ControlDescriptor]) -> Result<usize> {
+        let latest: HashMap<u32, Vec<String>> =
+            remote.list_controls()?.into_iter().map(|c| (c.numid, c.values)).collect();
+        let mut updated = 0usize;
+        for control in controls.iter_mut() {
+            if let Some(values) = latest.get(&control.numid) {
+                if control.values != *values {
+                    control.values = values.clone();
+                    updated += 1;
+                }
+            }
+        }
+        Ok(updated)
     }
 
     fn refresh_control_values_native(&self, controls: &mut [ControlDescriptor]) -> Result<usize> {
@@ -405,6 +591,23 @@ impl AlsaBackend {
             .lock()
             .ok()
             .and_then(|cache| cache.get(&numid).cloned());
+        let ceiling = self
+            .value_ceilings
+            .lock()
+            .ok()
+            .and_then(|ceilings| ceilings.get(&numid).copied());
+        let control_kind = match (control_kind, ceiling) {
+            (Some(ControlKind::Integer { min, max, step, channels, db_range }), Some(ceiling)) => {
+                Some(ControlKind::Integer {
+                    min,
+                    max: max.min(ceiling).max(min),
+                    step,
+                    channels,
+                    db_range,
+                })
+            }
+            (kind, _) => kind,
+        };
 
         for elem in hctl.elem_iter() {
             let id = elem.get_id()?;
@@ -619,9 +822,30 @@ impl AlsaBackend {
             "Digital Routing".to_string()
         } else if name.to_lowercase().contains("fx") || name.to_lowercase().contains("effect") {
             "Effects".to_string()
+        } else if Self::is_input_control(name) {
+            "Inputs".to_string()
         } else {
             "Other".to_string()
         }
     }
 
+    fn is_input_control(name: &str) -> bool {
+        let lower = name.to_lowercase();
+        ["capture", "mic", "pad", "gain", "phantom", "line in", "input"]
+            .iter()
+            .any(|word| lower.contains(word))
+    }
+
+    /// Physical input a capture control belongs to, from the channel number in
+    /// its name ("Mic 2 Capture Volume") or else its ALSA index.
+    pub fn physical_input_label(control: &ControlDescriptor) -> String {
+        let number_re = Regex::new(r"(?i)(?:in(?:put)?|mic|line|ch(?:annel)?|capture)\s*(\d+)")
+            .expect("valid regex");
+        match number_re.captures(&control.name) {
+            Some(cap) => format!("Input {}", &cap[1]),
+            None if control.index > 0 => format!("Input {}", control.index + 1),
+            None => "General".to_string(),
+        }
+    }
+
 }
@@ -1,25 +1,238 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs,
-    path::Path,
+    path::{Path, PathBuf},
     sync::mpsc::Receiver,
     time::{Duration, Instant},
 };
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use eframe::egui::{self, Color32, RichText, Stroke, vec2};
 use rfd::FileDialog;
 
 use crate::{
-    alsa_backend::AlsaBackend,
-    config::AppUserConfig,
-    models::{ControlDescriptor, ControlKind, RouteRef, RoutingIndex},
-    presets,
+    alsa_backend::{AlsaBackend, BackendKind},
+    app_triggers::{self, ActiveClients, AppTrigger},
+    audio_server::{self, DeviceUsage},
+    autostart,
+    ceilings,
+    clipboard::ControlSnippet,
+    config::{AppUserConfig, CellWidget, DragAxis, FineModifier, KnobDragConfig, MasterBase, MatrixDensity, PresetCardCheck, RefreshConfig},
+    diagram::{Diagram, DiagramCell, DiagramGrid, DiagramRow},
+    event_hooks::{self, MixerEvent},
+    factory_presets::FactoryPreset,
+    fuzzy,
+    fx::{self, FxKind, FxModel, FxPreset},
+    gamepad::{self, AxisMode, GamepadEvent, GamepadTarget},
+    generator::{self, Generator, Signal, SignalSettings},
+    measure::{self, LatencyResult, Loopback, SweepResult},
+    global_hotkeys::{self, HotkeyAction, HotkeyMessage},
+    hot_folder::HotFolderWatcher,
+    http_api::{self, ApiCall, ApiRequest, ApiResponse, HttpApi, QuickAction},
+    i18n::{self, Language, tr, trf},
+    ipc::{self, IpcServer},
+    metering::{self, LevelMeter, MeterMode, MeterTap, MeteringConfig},
+    metrics::Metrics,
+    midi::{self, ProgramChange},
+    mqtt::{self, MqttClient, MqttStatus},
+    osc::OscBroadcaster,
+    pan::{self, PanLaw},
+    models::{
+        ChangeTracker, ControlDescriptor, ControlKey, ControlKind, PresetApplyPolicy, PresetFile, PresetMetadata,
+        RouteRef, RoutingIndex,
+    },
+    presets::{self, PresetLibraryEntry},
+    scratch::{self, Player},
+    scripting::{self, HookRun, ScriptCommand, ScriptEngine},
+    state_import,
+    templates::{self, DeviceProfile, PresetTemplate, TemplateParams},
+    theme::{ColorVision, Palette, ThemePreset},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Tab {
+    Favorites,
     MixRouting,
+    OutputMix,
+    CueMix,
+    Inputs,
+    Fx,
+    Browser,
+    Settings,
+}
+
+/// Search box and filters of the All controls tab; `None` shows everything.
+#[derive(Debug, Clone, Default)]
+struct ControlBrowser {
+    query: String,
+    /// A [`ControlKind::NAMES`] entry.
+    kind: Option<&'static str>,
+    group: Option<String>,
+    iface: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct PresetSaveDraft {
+    name: String,
+    description: String,
+    author: String,
+    tags: String,
+    mute_first: bool,
+    ramp_ms: u32,
+    exclude: String,
+}
+
+/// A preset fade in progress.
+struct PresetRamp {
+    started: Instant,
+    duration: Duration,
+    /// Numid with the start and target raw values of each channel.
+    ramps: Vec<(u32, Vec<i64>, Vec<i64>)>,
+    /// Every value of the preset, written once the fade is over.
+    targets: Vec<(u32, Vec<String>)>,
+}
+
+/// App triggers and their fallback as edited in the App triggers window.
+#[derive(Debug, Clone, Default)]
+struct AppTriggersDraft {
+    triggers: Vec<AppTrigger>,
+    fallback: Option<PathBuf>,
+}
+
+/// Control values as they were right after the current preset was loaded or saved.
+#[derive(Debug, Clone)]
+struct PresetBaseline {
+    label: String,
+    values: HashMap<u32, Vec<String>>,
+}
+
+/// Edits requested by a matrix cell or row header during a frame, applied
+/// once the grid is done borrowing `self`.
+#[derive(Debug, Clone)]
+enum CellAction {
+    Set(usize, Vec<String>),
+    ToggleMute(usize),
+    ToggleSolo(usize),
+    CopyLine(MatrixLine),
+    PasteLine(MatrixLine),
+    /// Offsets every live route of a row or column by this many dB.
+    TrimLine(MatrixLine, f64),
+    ToggleHidden(RenameTarget),
+    /// New master level (dB) of an output; `true` once the edit is finished.
+    Master(usize, f64, bool),
+    ToggleDim(usize),
+    /// Paint-mode drag over a cell; `true` for a right-drag (clear).
+    Paint(GridPos, usize, bool),
+    /// Moves the keyboard cursor onto a cell the user touched.
+    Focus(GridPos),
+    Hover(GridPos, usize),
+    ToggleSelect(usize),
+    Select(usize),
+    /// A selected cell moved to a raw value; the rest of the selection follows in dB.
+    Gang(usize, i64),
+    /// Snap to 0 dB (or the maximum without a dB range), undoable.
+    Reset(usize),
+    EditValue(usize),
+    CopyValue(usize),
+    PasteValue(usize),
+    ToggleLock(usize),
+    ToggleFavorite(usize),
+    EditNote(usize),
+    ToggleControlHidden(usize),
+    /// Copies the cell, or the whole selection it belongs to, as a JSON snippet.
+    CopyJson(usize),
+    /// Beeps on one output to find its jack.
+    Identify(usize),
+}
+
+/// Current value and range of an integer route control.
+#[derive(Debug, Clone, Copy)]
+struct RouteLevel {
+    raw: i64,
+    min: i64,
+    max: i64,
+    channels: usize,
+    db_range: Option<(i64, i64)>,
+}
+
+impl RouteLevel {
+    fn db(&self) -> Option<f64> {
+        ControlKind::integer(self.min, self.max, self.db_range).db_at_raw(self.raw)
+    }
+
+    fn raw_at_db(&self, db: f64) -> Option<i64> {
+        ControlKind::integer(self.min, self.max, self.db_range).raw_at_db(db)
+    }
+
+    fn amplitude(&self) -> f64 {
+        pan::amplitude_from_raw(self.raw, self.min, self.max, self.db_range)
+    }
+
+    /// Raw value after multiplying the amplitude by `factor`.
+    fn scaled(&self, factor: f64) -> i64 {
+        pan::raw_from_amplitude(self.amplitude() * factor, self.min, self.max, self.db_range)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FxTailRestore {
+    deadline: Instant,
+    saved: Vec<(usize, Vec<String>)>,
+}
+
+#[derive(Debug, Clone)]
+struct CellValueEdit {
+    control_index: usize,
+    text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MatrixBus {
+    Analog,
+    Digital,
+}
+
+/// A whole input row or output column of one routing matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MatrixLine {
+    bus: MatrixBus,
+    target: RenameTarget,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum MatrixGrid {
+    Monitoring,
+    DigitalRoutes,
+    CueMix,
+}
+
+/// A cell of a rendered matrix, by display row and column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct GridPos {
+    grid: MatrixGrid,
+    row: usize,
+    col: usize,
+}
+
+/// A paint drag in progress: the row it started on and the values it writes.
+#[derive(Debug, Clone)]
+struct PaintStroke {
+    row: (MatrixGrid, usize),
+    values: Vec<String>,
+}
+
+/// Route values of a copied line, keyed by the index along the other axis.
+#[derive(Debug, Clone)]
+struct LineClipboard {
+    source: MatrixLine,
+    values: HashMap<usize, Vec<String>>,
+}
+
+/// Analog input being soloed and the route values it muted, restored on release.
+#[derive(Debug, Clone)]
+struct SoloState {
+    input: usize,
+    saved: Vec<(usize, Vec<String>)>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,11 +242,26 @@ enum RenameTarget {
     Out(usize),
 }
 
+/// Screen reader name for widgets whose visible text is a symbol, a single
+/// letter or nothing, set through AccessKit.
+trait AccessibleName {
+    fn accessible_name(self, name: impl Into<String>) -> Self;
+}
+
+impl AccessibleName for egui::Response {
+    fn accessible_name(self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        self.ctx.accesskit_node_builder(self.id, |node| node.set_label(name));
+        self
+    }
+}
+
 pub struct MixerApp {
     backend: AlsaBackend,
     controls: Vec<ControlDescriptor>,
     routing_index: RoutingIndex,
     selected_tab: Tab,
+    browser: ControlBrowser,
     status_line: String,
     user_config: AppUserConfig,
     rename_target: Option<RenameTarget>,
@@ -43,34 +271,175 @@ pub struct MixerApp {
     alsa_event_rx: Option<Receiver<()>>,
     event_listener_initialized: bool,
     theme_initialized: bool,
+    palette: Palette,
+    /// UI scale while its Settings slider is dragged, applied on release.
+    ui_scale_drag: Option<f32>,
+    snapshots: Vec<Option<PresetFile>>,
+    preset_save_draft: Option<PresetSaveDraft>,
+    preset_library_open: bool,
+    preset_library: Vec<PresetLibraryEntry>,
+    preset_library_filter: String,
+    hot_folder: Option<HotFolderWatcher>,
+    osc: Option<OscBroadcaster>,
+    /// The HTTP API waits for the first frame, which has the egui context to wake.
+    http_api_pending: bool,
+    http_api: Option<HttpApi>,
+    ipc: Option<IpcServer>,
+    mqtt: Option<MqttClient>,
+    /// Control changes not yet pushed to the WebSocket and socket subscribers.
+    api_changes: ChangeTracker,
+    metrics: Metrics,
+    script: Option<ScriptEngine>,
+    /// System-wide shortcuts, registered on the first frame when enabled.
+    global_hotkeys_pending: bool,
+    global_hotkeys_rx: Option<Receiver<HotkeyMessage>>,
+    /// Talkback held through its global shortcut.
+    global_talk_held: bool,
+    midi_rx: Option<Receiver<ProgramChange>>,
+    input_tap: Option<MeterTap>,
+    /// One per capture channel, i.e. per AIn.
+    input_meters: Vec<LevelMeter>,
+    output_tap: Option<MeterTap>,
+    /// One per playback channel of the PipeWire sink, i.e. per Out.
+    output_meters: Vec<LevelMeter>,
+    meters_updated: Instant,
+    generator: Option<Generator>,
+    generator_open: bool,
+    /// Out the test signal is meant for.
+    generator_output: usize,
+    /// When the running pink noise stops by itself.
+    generator_deadline: Option<Instant>,
+    loopback_open: bool,
+    loopback_output: usize,
+    loopback_input: usize,
+    loopback_period: i64,
+    latency_rx: Option<Receiver<Result<LatencyResult>>>,
+    latency_result: Option<Result<LatencyResult, String>>,
+    sweep_rx: Option<Receiver<Result<SweepResult>>>,
+    sweep_result: Option<Result<SweepResult, String>>,
+    scratch_open: bool,
+    /// AIns to record, 0-based.
+    scratch_inputs: BTreeSet<usize>,
+    scratch_rx: Option<Receiver<Result<PathBuf>>>,
+    scratch_started: Instant,
+    scratch_file: Option<PathBuf>,
+    scratch_player: Option<Player>,
+    gamepad_rx: Option<Receiver<GamepadEvent>>,
+    /// Last position of every gamepad axis, by input name.
+    gamepad_axes: HashMap<String, f32>,
+    /// Sub-step remainder of rate-mode moves, by numid.
+    gamepad_carry: HashMap<u32, f64>,
+    gamepad_last_tick: Instant,
+    gamepad_talk_held: bool,
+    /// Sound server and streams holding the card's PCM devices.
+    device_usage_rx: Option<Receiver<DeviceUsage>>,
+    device_usage: DeviceUsage,
+    /// Values read back after our recent writes, to notice another program
+    /// setting them again.
+    own_writes: HashMap<u32, (Vec<String>, Instant)>,
+    /// Whether the last catalog load succeeded, to report the card going and coming back.
+    device_present: bool,
+    preset_baseline: Option<PresetBaseline>,
+    preset_changes_open: bool,
+    client_monitor_rx: Option<Receiver<ActiveClients>>,
+    active_trigger_preset: Option<PathBuf>,
+    preset_ramp: Option<PresetRamp>,
+    /// Copy of the triggers being edited, committed on Save.
+    app_triggers_draft: Option<AppTriggersDraft>,
+    preset_preview: Option<(PathBuf, PresetFile)>,
+    templates_open: bool,
+    templates: Vec<PresetTemplate>,
+    template_params: Option<TemplateParams>,
+    output_mix_pair: usize,
+    /// Last pan per (digital, input, output pair), kept while the level is at zero.
+    pan_memory: HashMap<(bool, usize, usize), f64>,
+    solo: Option<SoloState>,
+    line_clipboard: Option<LineClipboard>,
+    /// Route values to restore when the Talk button is released.
+    talkback_saved: Option<Vec<(usize, Vec<String>)>>,
+    talk_button_held: bool,
+    /// Window size to restore when leaving mini mode.
+    full_window_size: Option<egui::Vec2>,
+    /// Set by `--minimized`, on top of `start_minimized` in the config.
+    minimize_at_start: bool,
+    /// Route levels of crossfader scenes A and B, by control name.
+    scenes: [Option<BTreeMap<String, i64>>; 2],
+    crossfade: f32,
+    fx_presets: Vec<FxPreset>,
+    fx_preset_name: String,
+    /// FX states of the A/B comparison; the active slot is the live state
+    /// and is only captured when switching away from it.
+    fx_ab: [Option<FxPreset>; 2],
+    fx_ab_active: usize,
+    /// FX return values to write back once the old program's tail has gone.
+    fx_tail_restore: Option<FxTailRestore>,
+    fx_send_mapping_open: bool,
+    paint_mode: bool,
+    paint_stroke: Option<PaintStroke>,
+    /// Control index of every cell, per matrix, as laid out on the last frame.
+    matrix_cells: HashMap<MatrixGrid, Vec<Vec<Option<usize>>>>,
+    matrix_cursor: Option<GridPos>,
+    /// Cell under the pointer; its row and column are highlighted.
+    matrix_hover: Option<GridPos>,
+    matrix_hover_seen: bool,
+    /// Control indices of the selected matrix cells.
+    matrix_selection: BTreeSet<usize>,
+    selection_offset_db: f64,
+    selection_level_db: f64,
+    scroll_to_cursor: bool,
+    cell_clipboard: Option<Vec<String>>,
+    /// Text for the system clipboard, handed to egui on the next frame.
+    clipboard_out: Option<String>,
+    cell_value_edit: Option<CellValueEdit>,
+    note_edit: Option<CellValueEdit>,
+    /// Previous values of controls changed by undoable edits, newest last.
+    undo_stack: Vec<Vec<(usize, Vec<String>)>>,
 }
 
 impl MixerApp {
-    const KNOB_CELL_W: f32 = 82.0;
-    const KNOB_CELL_H: f32 = 74.0;
-    const ROW_LABEL_W: f32 = 150.0;
+    const COMPACT_CELL_W: f32 = 58.0;
+    const COMPACT_CELL_H: f32 = 28.0;
+    const UNDO_LIMIT: usize = 64;
+    const SNAPSHOT_KEYS: [egui::Key; 8] = [
+        egui::Key::Num1,
+        egui::Key::Num2,
+        egui::Key::Num3,
+        egui::Key::Num4,
+        egui::Key::Num5,
+        egui::Key::Num6,
+        egui::Key::Num7,
+        egui::Key::Num8,
+    ];
 
     pub fn bootstrap(
-        card_override: Option<u32>,
+        backend: AlsaBackend,
         startup_preset: Option<&str>,
+        watch_dir: Option<&str>,
+        osc_clients: &[String],
+        http_api: bool,
+        script_path: Option<&str>,
+        global_hotkeys: bool,
     ) -> Result<Self> {
-        let backend = AlsaBackend::pick_card(card_override)?;
-        let controls = backend.list_controls()?;
-        let mut status_line = format!("Ready ({:?} backend)", backend.active_backend());
+        let mut controls = backend.list_controls()?;
+        let mut status_line = trf("Ready ({} backend)", &[&format!("{:?}", backend.active_backend())]);
         let user_config = match AppUserConfig::load_or_default() {
             Ok(cfg) => cfg,
             Err(err) => {
-                status_line = format!("Config load warning: {err}");
+                status_line = trf("Config load warning: {}", &[&err]);
                 AppUserConfig::default()
             }
         };
+        i18n::set_language(user_config.language);
+        for c in &mut controls {
+            c.favorite = user_config.favorite_controls.contains(&c.key());
+        }
         let mut app = Self {
             routing_index: AlsaBackend::build_routing_index(&controls),
             backend,
             controls,
             selected_tab: Tab::MixRouting,
+            browser: ControlBrowser::default(),
             status_line,
-            user_config,
             rename_target: None,
             rename_buffer: String::new(),
             last_auto_refresh: Instant::now(),
@@ -78,29 +447,215 @@ impl MixerApp {
             alsa_event_rx: None,
             event_listener_initialized: false,
             theme_initialized: false,
+            palette: user_config.theme.palette(),
+            ui_scale_drag: None,
+            user_config,
+            snapshots: vec![None; Self::SNAPSHOT_KEYS.len()],
+            preset_save_draft: None,
+            preset_library_open: false,
+            preset_library: Vec::new(),
+            preset_library_filter: String::new(),
+            hot_folder: None,
+            osc: None,
+            http_api_pending: false,
+            http_api: None,
+            ipc: None,
+            mqtt: None,
+            api_changes: ChangeTracker::default(),
+            metrics: Metrics::default(),
+            script: None,
+            global_hotkeys_pending: false,
+            global_hotkeys_rx: None,
+            global_talk_held: false,
+            midi_rx: None,
+            input_tap: None,
+            input_meters: Vec::new(),
+            output_tap: None,
+            output_meters: Vec::new(),
+            meters_updated: Instant::now(),
+            generator: None,
+            generator_open: false,
+            generator_output: 0,
+            generator_deadline: None,
+            loopback_open: false,
+            loopback_output: 0,
+            loopback_input: 0,
+            loopback_period: 256,
+            latency_rx: None,
+            latency_result: None,
+            sweep_rx: None,
+            sweep_result: None,
+            scratch_open: false,
+            scratch_inputs: BTreeSet::from([0, 1]),
+            scratch_rx: None,
+            scratch_started: Instant::now(),
+            scratch_file: None,
+            scratch_player: None,
+            gamepad_rx: None,
+            gamepad_axes: HashMap::new(),
+            gamepad_carry: HashMap::new(),
+            gamepad_last_tick: Instant::now(),
+            gamepad_talk_held: false,
+            device_usage_rx: None,
+            device_usage: DeviceUsage::default(),
+            own_writes: HashMap::new(),
+            device_present: true,
+            preset_baseline: None,
+            preset_changes_open: false,
+            client_monitor_rx: None,
+            active_trigger_preset: None,
+            preset_ramp: None,
+            app_triggers_draft: None,
+            preset_preview: None,
+            templates_open: false,
+            templates: Vec::new(),
+            template_params: None,
+            output_mix_pair: 0,
+            pan_memory: HashMap::new(),
+            solo: None,
+            line_clipboard: None,
+            talkback_saved: None,
+            talk_button_held: false,
+            full_window_size: None,
+            minimize_at_start: false,
+            scenes: [None, None],
+            crossfade: 0.0,
+            fx_presets: Vec::new(),
+            fx_preset_name: String::new(),
+            fx_ab: [None, None],
+            fx_ab_active: 0,
+            fx_tail_restore: None,
+            fx_send_mapping_open: false,
+            paint_mode: false,
+            paint_stroke: None,
+            matrix_cells: HashMap::new(),
+            matrix_cursor: None,
+            matrix_hover: None,
+            matrix_hover_seen: false,
+            matrix_selection: BTreeSet::new(),
+            selection_offset_db: 2.0,
+            selection_level_db: 0.0,
+            scroll_to_cursor: false,
+            cell_clipboard: None,
+            clipboard_out: None,
+            cell_value_edit: None,
+            note_edit: None,
+            undo_stack: Vec::new(),
         };
+        if app.backend.active_backend() == BackendKind::Alsa {
+            app.device_usage_rx = Some(audio_server::start_usage_monitor(app.backend.card_index));
+        }
+        if !app.user_config.app_triggers.is_empty() {
+            app.client_monitor_rx = Some(app_triggers::start_client_monitor(app.backend.card_index));
+        }
+        let hot_folder_dir = watch_dir
+            .map(PathBuf::from)
+            .or_else(|| app.user_config.hot_folder_dir.clone());
+        app.hot_folder = hot_folder_dir.map(HotFolderWatcher::new);
+        let osc_clients: Vec<String> = app
+            .user_config
+            .osc_clients
+            .iter()
+            .chain(osc_clients)
+            .cloned()
+            .collect();
+        if !osc_clients.is_empty() {
+            match OscBroadcaster::new(&osc_clients) {
+                Ok(osc) => app.osc = Some(osc),
+                Err(err) => app.status_line = trf("OSC disabled: {}", &[&err]),
+            }
+        }
+        app.http_api_pending = http_api || app.user_config.http_api.enabled;
+        app.global_hotkeys_pending = global_hotkeys || app.user_config.global_hotkeys;
+        app.sync_value_ceilings();
+        match fx::load_fx_presets() {
+            Ok(fx_presets) => app.fx_presets = fx_presets,
+            Err(err) => app.status_line = trf("FX preset load warning: {}", &[&err]),
+        }
 
         if let Some(path) = startup_preset {
-            match app.load_preset_from(Path::new(path)) {
-                Ok(()) => {
-                    app.status_line = format!("Loaded startup preset: {path}");
-                }
-                Err(err) => {
-                    app.status_line = format!("Startup preset load failed: {err}");
+            if let Err(err) = app.load_preset_from(Path::new(path)) {
+                app.status_line = trf("Startup preset load failed: {}", &[&err]);
+            }
+        }
+
+        app.fire_event(MixerEvent::DeviceConnected, Vec::new());
+        let script_path = script_path
+            .map(PathBuf::from)
+            .or_else(|| app.user_config.script_path.clone())
+            .or_else(scripting::default_script_path);
+        if let Some(path) = script_path {
+            match ScriptEngine::load(&path) {
+                Ok(script) => {
+                    app.status_line = trf("Script loaded: {}", &[&script.path().display()]);
+                    app.script = Some(script);
+                    app.run_script(|script, controls| script.on_startup(controls));
+                    app.run_script(|script, controls| script.on_device_connected(controls));
                 }
+                Err(err) => app.status_line = trf("Script disabled: {}", &[&format!("{err:#}")]),
             }
         }
 
         Ok(app)
     }
 
+    /// Serves the HTTP API (and the IPC socket) without a window, so the card
+    /// can be driven from `--remote` on another machine. Never returns on success.
+    pub fn start_minimized(&mut self) {
+        self.minimize_at_start = true;
+    }
+
+    pub fn run_headless(mut self) -> Result<()> {
+        self.start_http_api(|| {});
+        anyhow::ensure!(self.http_api.is_some(), "{}", self.status_line);
+        if self.user_config.ipc_socket {
+            match ipc::start(|| {}) {
+                Ok(server) => self.ipc = Some(server),
+                Err(err) => eprintln!("IPC socket disabled: {err:#}"),
+            }
+        }
+        if self.user_config.mqtt.enabled {
+            self.start_mqtt(|| {});
+        }
+        let events = self.backend.start_event_listener(|| {});
+        let mut last_status = String::new();
+        loop {
+            let call = self
+                .http_api
+                .as_ref()
+                .and_then(|api| api.calls.recv_timeout(Duration::from_millis(50)).ok());
+            if let Some(call) = call {
+                let response = self.answer_api(call.request.clone());
+                call.respond(response);
+            }
+            self.poll_http_api();
+            self.poll_mqtt_status();
+            self.poll_preset_ramp();
+            let got_event = events.as_ref().is_some_and(|rx| rx.try_iter().count() > 0);
+            let refresh = self.user_config.refresh;
+            if got_event || self.last_auto_refresh.elapsed() >= refresh.event_fallback() {
+                self.refresh_live_values_only();
+                self.last_auto_refresh = Instant::now();
+            }
+            if self.last_full_refresh.elapsed() >= refresh.full_refresh() {
+                self.refresh_controls_with_status(false);
+            }
+            self.poll_hot_folder();
+            self.poll_script();
+            self.broadcast_osc();
+            self.publish_api_changes();
+            if self.status_line != last_status {
+                eprintln!("{}", self.status_line);
+                last_status = self.status_line.clone();
+            }
+        }
+    }
+
     fn refresh_controls(&mut self) {
         let _ = self.refresh_controls_with_status(true);
     }
 
     fn refresh_controls_with_status(&mut self, show_success_status: bool) -> bool {
-        let favorite_map: HashMap<u32, bool> =
-            self.controls.iter().map(|c| (c.numid, c.favorite)).collect();
         match self.backend.list_controls() {
             Ok(mut controls) => {
                 let had_catalog_change = controls.len() != self.controls.len()
@@ -108,19 +663,37 @@ impl MixerApp {
                         .iter()
                         .zip(self.controls.iter())
                         .any(|(new_c, old_c)| new_c.numid != old_c.numid || new_c.values != old_c.values);
+                let old_numids: HashSet<u32> = self.controls.iter().map(|c| c.numid).collect();
+                let reconnected = controls.iter().map(|c| c.numid).collect::<HashSet<_>>() != old_numids;
+                if reconnected {
+                    self.metrics.device_resets += 1;
+                }
                 for c in &mut controls {
-                    c.favorite = favorite_map.get(&c.numid).copied().unwrap_or(false);
+                    c.favorite = self.user_config.favorite_controls.contains(&c.key());
                 }
                 self.routing_index = AlsaBackend::build_routing_index(&controls);
                 self.controls = controls;
+                self.sync_value_ceilings();
                 if show_success_status {
-                    self.status_line = "Control catalog refreshed".to_string();
+                    self.status_line = tr("Control catalog refreshed").to_string();
+                }
+                if reconnected || !self.device_present {
+                    self.device_present = true;
+                    self.fire_event(MixerEvent::DeviceConnected, Vec::new());
+                    self.run_script(|script, controls| script.on_device_connected(controls));
                 }
                 self.last_full_refresh = Instant::now();
                 had_catalog_change
             }
             Err(err) => {
-                self.status_line = format!("Refresh failed: {err}");
+                self.status_line = trf("Refresh failed: {}", &[&err]);
+                if let Some(owner) = self.device_usage.streams.first() {
+                    self.status_line
+                        .push_str(&trf(" — the card is held by {} (pid {})", &[&owner.owner, &owner.pid]));
+                }
+                if std::mem::replace(&mut self.device_present, false) {
+                    self.fire_event(MixerEvent::DeviceLost, vec![("FTU_ERROR", format!("{err:#}"))]);
+                }
                 true
             }
         }
@@ -131,19 +704,21 @@ impl MixerApp {
             return;
         };
         if let Err(err) = self.backend.apply_values(control.numid, &values) {
-            self.status_line = format!("Write failed for {}: {err}", control.name);
+            self.status_line = trf("Write failed for {}: {}", &[&control.name, &err]);
             return;
         }
         match self.backend.reload_control(&control) {
             Ok(mut reloaded) => {
                 reloaded.favorite = control.favorite;
                 reloaded.grouped_label = control.grouped_label;
+                self.own_writes
+                    .insert(reloaded.numid, (reloaded.values.clone(), Instant::now()));
                 self.controls[control_index] = reloaded;
-                self.status_line = format!("Updated {}", control.name);
+                self.status_line = trf("Updated {}", &[&control.name]);
                 self.last_full_refresh = Instant::now();
             }
             Err(err) => {
-                self.status_line = format!("Reload failed for {}: {err}", control.name);
+                self.status_line = trf("Reload failed for {}: {}", &[&control.name, &err]);
             }
         }
     }
@@ -152,671 +727,7566 @@ impl MixerApp {
         match self.backend.refresh_control_values(&mut self.controls) {
             Ok(updated) => updated > 0,
             Err(err) => {
-                self.status_line = format!("Live refresh failed: {err}");
+                self.status_line = trf("Live refresh failed: {}", &[&err]);
                 true
             }
         }
     }
 
+    /// Remote clients name a preset of the library instead of giving a path.
+    fn load_library_preset(&mut self, name: &str) -> Result<()> {
+        let dir = self
+            .user_config
+            .preset_library_dir
+            .clone()
+            .context("No preset library folder is set")?;
+        let path = presets::resolve_library_preset(&dir, name)?;
+        self.load_preset_from(&path)
+    }
+
     fn load_preset_from(&mut self, path: &Path) -> Result<()> {
         let preset = presets::load_preset(path)?;
-        let by_numid: HashMap<u32, Vec<String>> = preset
-            .controls
-            .into_iter()
-            .map(|v| (v.numid, v.values))
-            .collect();
-
-        let mut applied = 0usize;
-        for control in self.controls.clone() {
-            if let Some(values) = by_numid.get(&control.numid) {
-                self.backend.apply_values(control.numid, values)?;
-                applied += 1;
+        let mismatch =
+            presets::card_mismatch(&preset, &self.backend.card_identity, self.controls.len());
+        let warning = match (self.user_config.preset_card_check, mismatch) {
+            (PresetCardCheck::Refuse, Some(reason)) => {
+                anyhow::bail!("Preset refused: {reason}");
             }
+            (PresetCardCheck::Warn, Some(reason)) => Some(reason),
+            _ => None,
+        };
+        let applied = self.apply_preset(&preset)?;
+        self.mark_preset_baseline(Self::preset_label(&preset, path), &preset);
+        self.status_line = trf("Preset loaded: {} ({} controls)", &[&path.display(), &applied]);
+        if let Some(reason) = warning {
+            self.status_line.push_str(&trf(" — warning: {}", &[&reason]));
         }
-        self.refresh_controls();
-        self.status_line = format!("Preset applied ({applied} controls)");
         Ok(())
     }
 
-    fn render_toolbar(&mut self, ui: &mut egui::Ui) {
-        ui.horizontal_wrapped(|ui| {
-            ui.label(RichText::new("FTU Mixer").strong().size(15.0));
-            ui.separator();
-            ui.label(format!(
-                "Card: hw:{} ({})",
-                self.backend.card_index, self.backend.card_label
-            ));
-            if ui.button("Refresh").clicked() {
-                self.refresh_controls();
-            }
-            if ui.button("Save preset").clicked() {
-                if let Some(path) = FileDialog::new()
-                    .set_file_name("fast-track-ultra-preset.json")
-                    .save_file()
-                {
-                    let preset = presets::to_preset(&self.backend.card_label, &self.controls);
-                    match presets::save_preset(&path, &preset) {
-                        Ok(()) => self.status_line = format!("Preset saved: {}", path.display()),
-                        Err(err) => self.status_line = format!("Save failed: {err}"),
-                    }
+    /// Writes a preset, or starts its fade when the policy asks for one; the
+    /// fade then runs a step per frame in [`Self::poll_preset_ramp`].
+    fn apply_preset(&mut self, preset: &PresetFile) -> Result<usize> {
+        const MAX_RAMP_MS: u32 = 5000;
+
+        let policy = &preset.apply_policy;
+        let excluded: HashSet<String> = policy
+            .exclude
+            .iter()
+            .map(|n| n.trim().to_lowercase())
+            .collect();
+        let by_numid: HashMap<u32, &Vec<String>> = preset
+            .controls
+            .iter()
+            .map(|v| (v.numid, &v.values))
+            .collect();
+        let targets: Vec<(ControlDescriptor, Vec<String>)> = self
+            .controls
+            .iter()
+            .filter(|c| !excluded.contains(&c.name.to_lowercase()) && !self.is_locked(c))
+            .filter_map(|c| by_numid.get(&c.numid).map(|v| (c.clone(), (*v).clone())))
+            .collect();
+
+        let mut muted: HashSet<u32> = HashSet::new();
+        if policy.mute_first {
+            let route_indices: HashSet<usize> = self
+                .routing_index
+                .analog_routes
+                .iter()
+                .chain(self.routing_index.digital_routes.iter())
+                .map(|r| r.control_index)
+                .collect();
+            for (idx, control) in self.controls.iter().enumerate() {
+                if !route_indices.contains(&idx) || !by_numid.contains_key(&control.numid) {
+                    continue;
+                }
+                if excluded.contains(&control.name.to_lowercase()) || self.is_locked(control) {
+                    continue;
+                }
+                if let ControlKind::Integer { min, channels, .. } = control.kind {
+                    self.backend
+                        .apply_values(control.numid, &vec![min.to_string(); channels])?;
+                    muted.insert(control.numid);
                 }
             }
-            if ui.button("Load preset").clicked() {
-                if let Some(path) = FileDialog::new().pick_file() {
-                    match self.load_preset_from(&path) {
-                        Ok(()) => {
-                            self.status_line = format!("Preset loaded: {}", path.display());
-                        }
-                        Err(err) => self.status_line = format!("Load failed: {err}"),
-                    }
+        }
+
+        // A new preset replaces a fade still running.
+        self.preset_ramp = None;
+        let ramp_ms = policy.ramp_ms.min(MAX_RAMP_MS);
+        if ramp_ms > 0 {
+            // Linear fade in raw units, which for the FTU's dB-linear controls is a dB fade.
+            let ramps: Vec<(u32, Vec<i64>, Vec<i64>)> = targets
+                .iter()
+                .filter_map(|(control, values)| {
+                    let ControlKind::Integer { min, .. } = control.kind else {
+                        return None;
+                    };
+                    let parse = |v: &String| v.parse::<i64>().unwrap_or(min);
+                    let from: Vec<i64> = if muted.contains(&control.numid) {
+                        vec![min; control.values.len()]
+                    } else {
+                        control.values.iter().map(parse).collect()
+                    };
+                    let to: Vec<i64> = values.iter().map(parse).collect();
+                    (from != to).then_some((control.numid, from, to))
+                })
+                .collect();
+            if !ramps.is_empty() {
+                // Fade steps are our writes too; do not report them as overrides.
+                for (numid, _, _) in &ramps {
+                    self.own_writes.remove(numid);
                 }
+                self.preset_ramp = Some(PresetRamp {
+                    started: Instant::now(),
+                    duration: Duration::from_millis(ramp_ms as u64),
+                    ramps,
+                    targets: targets.iter().map(|(c, v)| (c.numid, v.clone())).collect(),
+                });
+                return Ok(targets.len());
             }
-        });
+        }
+
+        for (control, values) in &targets {
+            self.backend.apply_values(control.numid, values)?;
+        }
+        let written: HashSet<u32> = targets.iter().map(|(c, _)| c.numid).collect();
+        self.finish_preset_writes(&written);
+        Ok(targets.len())
     }
 
-    fn render_quick_actions(&mut self, ui: &mut egui::Ui) {
-        ui.horizontal_wrapped(|ui| {
-            if ui.button("Mute Analog Monitoring").clicked() {
-                self.mute_hardware_routes();
-            }
-            if ui.button("Pass-through Analog Monitoring to Channel 1/2").clicked() {
-                self.pass_through_inputs();
-            }
-            if ui.button("Disable FX").clicked() {
-                self.disable_fx_controls();
-            }
-            if ui.button("Mute most digital routes").clicked() {
-                self.mute_most_digital_routes();
+    /// Writes the next step of a running preset fade, then the preset values
+    /// once it is over. Returns `true` while the fade is still running.
+    fn poll_preset_ramp(&mut self) -> bool {
+        let Some(ramp) = &self.preset_ramp else {
+            return false;
+        };
+        let progress = ramp.started.elapsed().as_secs_f64() / ramp.duration.as_secs_f64();
+        if progress < 1.0 {
+            // Linear fade in raw units, which for the FTU's dB-linear controls is a dB fade.
+            let steps: Vec<(u32, Vec<String>)> = ramp
+                .ramps
+                .iter()
+                .map(|(numid, from, to)| {
+                    let values = to
+                        .iter()
+                        .enumerate()
+                        .map(|(ch, &target)| {
+                            let start = from.get(ch).copied().unwrap_or(target);
+                            (start + ((target - start) as f64 * progress) as i64).to_string()
+                        })
+                        .collect();
+                    (*numid, values)
+                })
+                .collect();
+            for (numid, values) in steps {
+                if let Err(err) = self.backend.apply_values(numid, &values) {
+                    self.status_line = trf("Preset fade stopped: {}", &[&format!("{err:#}")]);
+                    self.preset_ramp = None;
+                    return false;
+                }
             }
-            if ui.button("Mute All Monitoring").clicked() {
-                self.panic_mute();
+            return true;
+        }
+        let Some(ramp) = self.preset_ramp.take() else {
+            return false;
+        };
+        for (numid, values) in &ramp.targets {
+            if let Err(err) = self.backend.apply_values(*numid, values) {
+                self.status_line = trf("Preset fade stopped: {}", &[&format!("{err:#}")]);
+                return false;
             }
-            if ui.button("Reset aliases").clicked() {
-                self.user_config.ain_aliases.clear();
-                self.user_config.din_aliases.clear();
-                self.user_config.out_aliases.clear();
-                self.rename_target = None;
-                self.rename_buffer.clear();
-                self.save_user_config();
+        }
+        let written: HashSet<u32> = ramp.targets.iter().map(|(numid, _)| *numid).collect();
+        self.finish_preset_writes(&written);
+        // The baseline was taken when the fade started.
+        if let Some(baseline) = self.preset_baseline.as_mut() {
+            for control in self.controls.iter().filter(|c| written.contains(&c.numid)) {
+                if let Some(values) = baseline.values.get_mut(&control.numid) {
+                    values.clone_from(&control.values);
+                }
             }
-        });
+        }
+        false
     }
 
-    fn render_mix_routing_tab(&mut self, ui: &mut egui::Ui) {
-        egui::Frame::new()
-            .fill(Color32::from_rgb(20, 24, 30))
-            .stroke(Stroke::new(1.0, Color32::from_rgb(46, 55, 68)))
-            .inner_margin(egui::Margin::symmetric(8, 6))
-            .show(ui, |ui| {
-                ui.label(RichText::new("Actions rapides").strong());
-                self.render_quick_actions(ui);
-            });
+    /// Reads back what a preset wrote and remembers it as the mixer's own writes.
+    fn finish_preset_writes(&mut self, written: &HashSet<u32>) {
+        self.refresh_controls();
+        for control in self.controls.iter().filter(|c| written.contains(&c.numid)) {
+            self.own_writes
+                .insert(control.numid, (control.values.clone(), Instant::now()));
+        }
+    }
 
-        ui.add_space(6.0);
-        ui.columns(2, |cols| {
-            egui::Frame::new()
-                .fill(Color32::from_rgb(18, 22, 27))
-                .stroke(Stroke::new(1.0, Color32::from_rgb(44, 52, 64)))
-                .inner_margin(egui::Margin::symmetric(8, 6))
-                .show(&mut cols[0], |ui| {
-                    ui.label(RichText::new("Monitoring analogique").strong().size(14.0));
-                    ui.small("AIn -> Out");
-                    ui.separator();
-                    self.render_monitoring_matrix(ui);
-                });
+    fn preset_label(preset: &PresetFile, path: &Path) -> String {
+        preset.metadata.name.clone().unwrap_or_else(|| {
+            path.file_name()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string())
+        })
+    }
 
-            egui::Frame::new()
-                .fill(Color32::from_rgb(18, 22, 27))
-                .stroke(Stroke::new(1.0, Color32::from_rgb(44, 52, 64)))
-                .inner_margin(egui::Margin::symmetric(8, 6))
-                .show(&mut cols[1], |ui| {
-                    ui.label(RichText::new("Routage digital").strong().size(14.0));
-                    ui.small("DIn -> Out");
-                    ui.separator();
-                    self.render_route_matrix(ui, false);
-                });
-        });
+    /// Records the live values of the preset's controls, read back after applying,
+    /// so hardware clamping does not show up as a modification.
+    fn mark_preset_baseline(&mut self, label: String, preset: &PresetFile) {
+        let numids: HashSet<u32> = preset.controls.iter().map(|c| c.numid).collect();
+        let values = self
+            .controls
+            .iter()
+            .filter(|c| numids.contains(&c.numid))
+            .map(|c| (c.numid, c.values.clone()))
+            .collect();
+        self.metrics.preset_applied(&label);
+        self.fire_event(MixerEvent::PresetApplied, vec![("FTU_PRESET", label.clone())]);
+        self.preset_baseline = Some(PresetBaseline { label, values });
+    }
 
-        ui.add_space(6.0);
-        egui::Frame::new()
-            .fill(Color32::from_rgb(18, 22, 27))
-            .stroke(Stroke::new(1.0, Color32::from_rgb(44, 52, 64)))
-            .inner_margin(egui::Margin::symmetric(8, 6))
-            .show(ui, |ui| {
-                self.render_effects_section(ui);
-            });
+    /// Controls that diverged from the loaded preset, with their preset values.
+    fn preset_changes(&self) -> Vec<(usize, Vec<String>)> {
+        let Some(baseline) = &self.preset_baseline else {
+            return Vec::new();
+        };
+        self.controls
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, c)| {
+                let saved = baseline.values.get(&c.numid)?;
+                (saved != &c.values).then(|| (idx, saved.clone()))
+            })
+            .collect()
     }
 
-    fn render_monitoring_matrix(&mut self, ui: &mut egui::Ui) {
-        let refs = &self.routing_index.analog_routes;
-        if refs.is_empty() {
-            ui.label("No analog monitoring routes found.");
+    fn render_preset_changes_window(&mut self, ctx: &egui::Context) {
+        if !self.preset_changes_open {
             return;
         }
-
-        let max_input = refs.iter().map(|r| r.input).max().unwrap_or(0);
-        let max_output = refs.iter().map(|r| r.output).max().unwrap_or(0);
-        let mut by_pair: HashMap<(usize, usize), usize> = HashMap::new();
-        for r in refs {
-            by_pair.insert((r.input, r.output), r.control_index);
-        }
-        let ain_send_map = self.find_fx_send_map(false);
-
-        let mut actions: Vec<(usize, Vec<String>)> = Vec::new();
-        egui::Grid::new("monitoring_matrix_grid")
-            .striped(true)
-            .show(ui, |ui| {
-                ui.label("Input \\ Output");
-                for output in 0..=max_output {
-                    ui.allocate_ui_with_layout(
-                        vec2(Self::KNOB_CELL_W, 18.0),
-                        egui::Layout::top_down(egui::Align::Center),
-                        |ui| {
-                            self.render_alias_label(ui, RenameTarget::Out(output), true, Self::KNOB_CELL_W);
-                        },
-                    );
+        let Some(label) = self.preset_baseline.as_ref().map(|b| b.label.clone()) else {
+            self.preset_changes_open = false;
+            return;
+        };
+        let changes = self.preset_changes();
+        let mut open = true;
+        let mut revert: Vec<(usize, Vec<String>)> = Vec::new();
+        egui::Window::new(trf("Changes since \"{}\"", &[&label]))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                if changes.is_empty() {
+                    ui.label(tr("No control differs from the loaded preset."));
+                    return;
                 }
-                ui.end_row();
-
-                for input in 0..=max_input {
-                    ui.allocate_ui_with_layout(
-                        vec2(Self::ROW_LABEL_W, Self::KNOB_CELL_H),
-                        egui::Layout::top_down(egui::Align::Min),
-                        |ui| {
-                            self.render_input_row_header(
-                                ui,
-                                RenameTarget::Ain(input),
-                                ain_send_map.get(&input).copied(),
-                                &mut actions,
-                            );
-                        },
-                    );
-                    for output in 0..=max_output {
-                        if let Some(control_idx) = by_pair.get(&(input, output)).copied() {
-                            if let Some(control) = self.controls.get(control_idx) {
-                                if let Some(values) = Self::render_route_cell(ui, control) {
-                                    actions.push((control_idx, values));
+                if ui.button(tr("Revert all")).clicked() {
+                    revert = changes.clone();
+                }
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    egui::Grid::new("preset_changes_grid")
+                        .num_columns(4)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr("Control")).strong());
+                            ui.label(RichText::new("Preset").strong());
+                            ui.label(RichText::new(tr("Current")).strong());
+                            ui.label("");
+                            ui.end_row();
+                            for (idx, saved) in &changes {
+                                let control = &self.controls[*idx];
+                                ui.label(&control.name);
+                                ui.monospace(saved.join(", "));
+                                ui.monospace(control.values.join(", "));
+                                if ui.small_button(tr("Revert")).clicked() {
+                                    revert.push((*idx, saved.clone()));
                                 }
+                                ui.end_row();
                             }
-                        } else {
-                            ui.label("-");
-                        }
-                    }
-                    ui.end_row();
-                }
+                        });
+                });
             });
-
-        for (idx, values) in actions {
+        self.preset_changes_open = open;
+        for (idx, values) in revert {
             self.apply_values_to_control(idx, values);
         }
     }
 
-    fn render_effects_section(&mut self, ui: &mut egui::Ui) {
-        let fx_indices: Vec<usize> = self
-            .controls
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, c)| {
-                if self.is_fx_control(c) && !self.is_channel_fx_send(c) {
-                    Some(idx)
-                } else {
-                    None
-                }
-            })
-            .collect();
+    fn snapshot_label(slot: usize) -> char {
+        (b'A' + slot as u8) as char
+    }
 
-        if fx_indices.is_empty() {
-            ui.label(RichText::new("Effets (FX)").strong());
-            ui.label("Contrôles FX dédiés de la Fast Track Ultra.");
-            ui.label("Aucun contrôle FX détecté sur cette carte.");
+    fn store_snapshot(&mut self, slot: usize) {
+        let Some(entry) = self.snapshots.get_mut(slot) else {
             return;
-        }
+        };
+        *entry = Some(presets::to_preset(
+            &self.backend.card_label,
+            &self.backend.card_identity,
+            &self.controls,
+        ));
+        self.status_line = trf("Snapshot {} stored", &[&Self::snapshot_label(slot)]);
+    }
 
-        let mut actions: Vec<(usize, Vec<String>)> = Vec::new();
-        let mut used = HashSet::new();
-        ui.columns(2, |cols| {
-            egui::Frame::new()
-                .fill(Color32::from_rgb(20, 24, 30))
-                .stroke(Stroke::new(1.0, Color32::from_rgb(44, 52, 64)))
-                .inner_margin(egui::Margin::symmetric(6, 6))
-                .show(&mut cols[0], |ui| {
-                    ui.label(RichText::new("Effets (FX)").strong());
-                    ui.small("Contrôles FX dédiés de la Fast Track Ultra.");
-                    if ui.button("Disable FX").clicked() {
-                        self.disable_fx_controls();
-                    }
+    fn recall_snapshot(&mut self, slot: usize) {
+        let Some(Some(snapshot)) = self.snapshots.get(slot).cloned() else {
+            self.status_line = trf("Snapshot {} is empty", &[&Self::snapshot_label(slot)]);
+            return;
+        };
+        match self.apply_preset(&snapshot) {
+            Ok(applied) => {
+                self.status_line = trf(
+                    "Snapshot {} recalled ({} controls)",
+                    &[&Self::snapshot_label(slot), &applied],
+                );
+            }
+            Err(err) => {
+                self.status_line =
+                    trf("Snapshot {} recall failed: {}", &[&Self::snapshot_label(slot), &err]);
+            }
+        }
+    }
+
+    fn handle_snapshot_shortcuts(&mut self, ctx: &egui::Context) {
+        // Match on the physical key so Ctrl+Shift+1 still reads as "1" on layouts
+        // where Shift turns the digit row into symbols.
+        let hit = ctx.input_mut(|i| {
+            let mut hit = None;
+            i.events.retain(|event| {
+                let egui::Event::Key {
+                    key,
+                    physical_key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } = event
+                else {
+                    return true;
+                };
+                if hit.is_some() || !modifiers.command || modifiers.alt {
+                    return true;
+                }
+                let pressed = physical_key.unwrap_or(*key);
+                match Self::SNAPSHOT_KEYS.iter().position(|k| *k == pressed) {
+                    Some(slot) => {
+                        hit = Some((slot, modifiers.shift));
+                        false
+                    }
+                    None => true,
+                }
+            });
+            hit
+        });
+        match hit {
+            Some((slot, true)) => self.store_snapshot(slot),
+            Some((slot, false)) => self.recall_snapshot(slot),
+            None => {}
+        }
+    }
+
+    fn render_snapshot_slots(&mut self, ui: &mut egui::Ui) {
+        ui.label(tr("Snapshots:"));
+        for slot in 0..self.snapshots.len() {
+            let filled = self.snapshots[slot].is_some();
+            let text = RichText::new(Self::snapshot_label(slot).to_string()).monospace();
+            let text = if filled {
+                text.strong().color(Color32::from_rgb(90, 220, 220))
+            } else {
+                text.weak()
+            };
+            let resp = ui
+                .add(egui::Button::new(text).min_size(vec2(20.0, 18.0)))
+                .accessible_name(trf("Snapshot {}", &[&Self::snapshot_label(slot)]))
+                .on_hover_text(trf(
+                    "Click: recall (Ctrl+{})\nShift+click: store current state (Ctrl+Shift+{})",
+                    &[&(slot + 1), &(slot + 1)],
+                ));
+            if resp.clicked() {
+                if ui.input(|i| i.modifiers.shift) {
+                    self.store_snapshot(slot);
+                } else {
+                    self.recall_snapshot(slot);
+                }
+            }
+        }
+    }
+
+    fn render_toolbar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label(RichText::new("FTU Mixer").strong().size(15.0));
+            if ui
+                .button(tr("Mini"))
+                .on_hover_text(tr("Shrink to a small strip with output levels, panic mute and talkback"))
+                .clicked()
+            {
+                self.set_mini_mode(ui.ctx(), true);
+            }
+            self.render_pin_button(ui);
+            ui.separator();
+            ui.label(trf(
+                "Card: hw:{} ({})",
+                &[&self.backend.card_index, &self.backend.card_label],
+            ));
+            if let Some(summary) = self.device_usage.summary() {
+                ui.label(RichText::new(summary).color(Color32::from_rgb(120, 190, 235)))
+                    .on_hover_text(self.device_usage.details());
+            }
+            if ui.button(tr("Refresh")).clicked() {
+                self.refresh_controls();
+            }
+            if ui
+                .add_enabled(!self.undo_stack.is_empty(), egui::Button::new(tr("Undo")))
+                .on_hover_text("Ctrl+Z")
+                .clicked()
+            {
+                self.undo();
+            }
+            if ui.button(tr("Save preset")).clicked() {
+                self.preset_save_draft = Some(PresetSaveDraft {
+                    author: self.user_config.preset_author.clone().unwrap_or_default(),
+                    ..Default::default()
+                });
+            }
+            if ui.button(tr("Load preset")).clicked() {
+                if let Some(path) = FileDialog::new().pick_file() {
+                    if let Err(err) = self.load_preset_from(&path) {
+                        self.status_line = trf("Load failed: {}", &[&err]);
+                    }
+                }
+            }
+            ui.menu_button(tr("Factory presets"), |ui| {
+                for factory in FactoryPreset::ALL {
+                    if ui
+                        .button(factory.name())
+                        .on_hover_text(factory.description())
+                        .clicked()
+                    {
+                        self.apply_factory_preset(factory);
+                        ui.close();
+                    }
+                }
+            });
+            if ui.button(tr("Templates")).clicked() {
+                self.templates_open = !self.templates_open;
+                if self.templates_open {
+                    self.reload_templates();
+                }
+            }
+            if ui.button(tr("Preview preset")).clicked() {
+                if let Some(path) = FileDialog::new().pick_file() {
+                    self.open_preset_preview(path);
+                }
+            }
+            if ui
+                .button(tr("Import ALSA state"))
+                .on_hover_text(tr("Import an `alsactl store` file or saved `amixer contents` output"))
+                .clicked()
+            {
+                if let Some(path) = FileDialog::new()
+                    .add_filter(tr("ALSA state"), &["state", "txt"])
+                    .add_filter(tr("All files"), &["*"])
+                    .pick_file()
+                {
+                    self.import_alsa_state(&path);
+                }
+            }
+            if ui
+                .button(tr("Export diagram"))
+                .on_hover_text(tr("Save the analog and digital routing, with aliases and levels, as SVG or PNG"))
+                .clicked()
+            {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("SVG", &["svg"])
+                    .add_filter("PNG", &["png"])
+                    .set_file_name("routing.svg")
+                    .save_file()
+                {
+                    match self.export_diagram(&path) {
+                        Ok(()) => self.status_line = trf("Diagram exported to {}", &[&path.display()]),
+                        Err(err) => self.status_line = trf("Diagram export failed: {}", &[&format!("{err:#}")]),
+                    }
+                }
+            }
+            if ui.button(tr("App triggers")).clicked() {
+                self.app_triggers_draft = match self.app_triggers_draft {
+                    Some(_) => None,
+                    None => Some(AppTriggersDraft {
+                        triggers: self.user_config.app_triggers.clone(),
+                        fallback: self.user_config.app_trigger_fallback.clone(),
+                    }),
+                };
+            }
+            if ui.button(tr("Tone generator")).clicked() {
+                self.generator_open = !self.generator_open;
+            }
+            if ui.button(tr("Loopback test")).clicked() {
+                self.loopback_open = !self.loopback_open;
+            }
+            if ui.button(tr("Scratch recording")).clicked() {
+                self.scratch_open = !self.scratch_open;
+            }
+            if ui.button(tr("Preset library")).clicked() {
+                self.preset_library_open = !self.preset_library_open;
+                if self.preset_library_open {
+                    self.rescan_preset_library();
+                }
+            }
+            if let Some(label) = self.preset_baseline.as_ref().map(|b| b.label.clone()) {
+                ui.separator();
+                let changed = self.preset_changes().len();
+                let text = if changed > 0 {
+                    RichText::new(trf("● {} (modified: {})", &[&label, &changed]))
+                        .color(Color32::from_rgb(240, 190, 90))
+                } else {
+                    RichText::new(label)
+                };
+                if ui
+                    .add(egui::Button::new(text).frame(false))
+                    .on_hover_text(tr("Show controls changed since the preset was loaded"))
+                    .clicked()
+                {
+                    self.preset_changes_open = !self.preset_changes_open;
+                }
+            }
+            ui.separator();
+            self.render_snapshot_slots(ui);
+        });
+    }
+
+    /// About 80 px per output master plus the fixed buttons, two rows high.
+    fn mini_window_size(&self) -> egui::Vec2 {
+        vec2(80.0 * self.output_count() as f32 + 360.0, 86.0) * self.ui_scale()
+    }
+
+    fn set_mini_mode(&mut self, ctx: &egui::Context, on: bool) {
+        if on == self.user_config.mini_mode {
+            return;
+        }
+        let size = if on {
+            self.full_window_size = ctx.input(|i| i.viewport().inner_rect).map(|r| r.size());
+            self.mini_window_size()
+        } else {
+            self.full_window_size.take().unwrap_or(vec2(1280.0, 800.0))
+        };
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(size));
+        self.user_config.mini_mode = on;
+        self.save_user_config();
+    }
+
+    fn render_pin_button(&mut self, ui: &mut egui::Ui) {
+        let mut pinned = self.user_config.always_on_top;
+        if ui
+            .toggle_value(&mut pinned, "📌")
+            .accessible_name(tr("Keep the mixer above other windows"))
+            .on_hover_text(tr("Keep the mixer above other windows"))
+            .changed()
+        {
+            let level = if pinned {
+                egui::WindowLevel::AlwaysOnTop
+            } else {
+                egui::WindowLevel::Normal
+            };
+            ui.ctx().send_viewport_cmd(egui::ViewportCommand::WindowLevel(level));
+            self.user_config.always_on_top = pinned;
+            self.save_user_config();
+        }
+    }
+
+    fn render_mini_mode(&mut self, ctx: &egui::Context) {
+        let mut actions = Vec::new();
+        egui::CentralPanel::default()
+            .frame(
+                egui::Frame::new()
+                    .fill(self.palette.background)
+                    .inner_margin(egui::Margin::symmetric(8, 6)),
+            )
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("⛶")
+                        .accessible_name(tr("Back to the full mixer"))
+                        .on_hover_text(tr("Back to the full mixer"))
+                        .clicked()
+                    {
+                        self.set_mini_mode(ui.ctx(), false);
+                    }
+                    self.render_pin_button(ui);
+                    for output in 0..self.output_count() {
+                        ui.vertical(|ui| {
+                            ui.label(RichText::new(self.alias_text(RenameTarget::Out(output))).small());
+                            self.render_master_cell(ui, output, 72.0, &mut actions);
+                        });
+                    }
                     ui.separator();
-                    ui.horizontal_wrapped(|ui| {
-                        if let Some(idx) = self.find_first_fx_with(&fx_indices, &used, |n| {
-                            n.contains("effect program")
-                        }) {
-                            used.insert(idx);
-                            if let Some(values) = self.render_effect_tile(ui, idx) {
-                                actions.push((idx, values));
-                            }
+                    let panic = RichText::new(tr("PANIC")).strong().color(Color32::from_rgb(240, 90, 90));
+                    if ui
+                        .button(panic)
+                        .on_hover_text(tr("Mute All Monitoring"))
+                        .clicked()
+                    {
+                        self.panic_mute();
+                    }
+                    let active = self.talkback_saved.is_some();
+                    let text = RichText::new(tr("TALK (T)")).strong();
+                    let text = if active {
+                        text.color(Color32::from_rgb(240, 90, 90))
+                    } else {
+                        text
+                    };
+                    let resp = ui
+                        .add_enabled(
+                            self.user_config.talkback.input.is_some(),
+                            egui::Button::new(text).selected(active),
+                        )
+                        .on_hover_text(tr("Hold to talk (or hold T)"))
+                        .on_disabled_hover_text(tr("Pick a talkback input in the full mixer first"));
+                    if resp.is_pointer_button_down_on() {
+                        self.talk_button_held = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    match self.preset_baseline.as_ref().map(|b| b.label.clone()) {
+                        Some(label) if !self.preset_changes().is_empty() => {
+                            ui.label(RichText::new(format!("● {label}")).color(Color32::from_rgb(240, 190, 90)))
+                                .on_hover_text(tr("Modified since the preset was loaded"));
                         }
-                        if let Some(idx) = self.find_first_fx_with(&fx_indices, &used, |n| {
-                            n.contains("effect")
-                                && !n.contains("program")
-                                && !n.contains("duration")
-                                && !n.contains("feedback")
-                                && !n.contains("return")
-                        }) {
-                            used.insert(idx);
-                            if let Some(values) = self.render_effect_tile(ui, idx) {
-                                actions.push((idx, values));
-                            }
+                        Some(label) => {
+                            ui.label(label);
                         }
-                    });
+                        None => {
+                            ui.weak(tr("No preset loaded"));
+                        }
+                    }
+                    ui.separator();
+                    ui.label(RichText::new(&self.status_line).small().weak());
                 });
+            });
+        self.run_cell_actions(actions);
+    }
 
-            egui::Frame::new()
-                .fill(Color32::from_rgb(20, 24, 30))
-                .stroke(Stroke::new(1.0, Color32::from_rgb(44, 52, 64)))
-                .inner_margin(egui::Margin::symmetric(6, 6))
-                .show(&mut cols[1], |ui| {
-                    ui.label(RichText::new("Returns / Duration / Feedback").strong());
-                    let return_indices: Vec<usize> = fx_indices
-                        .iter()
-                        .copied()
-                        .filter(|idx| {
-                            let name = self.controls[*idx].name.to_lowercase();
-                            name.contains("return") && !used.contains(idx)
-                        })
-                        .collect();
-                    let duration_idx =
-                        self.find_first_fx_with(&fx_indices, &used, |n| n.contains("duration"));
-                    let feedback_idx =
-                        self.find_first_fx_with(&fx_indices, &used, |n| n.contains("feedback"));
-
-                    egui::Grid::new("fx_returns_duration_feedback_grid")
-                        .num_columns(3)
-                        .spacing(vec2(4.0, 4.0))
-                        .show(ui, |ui| {
-                            let mut ret_iter = return_indices.iter().copied();
-                            if let Some(idx) = ret_iter.next() {
-                                used.insert(idx);
-                                if let Some(values) = self.render_effect_tile(ui, idx) {
-                                    actions.push((idx, values));
-                                }
-                            } else {
-                                ui.label("");
-                            }
-                            if let Some(idx) = ret_iter.next() {
-                                used.insert(idx);
-                                if let Some(values) = self.render_effect_tile(ui, idx) {
-                                    actions.push((idx, values));
-                                }
-                            } else {
-                                ui.label("");
-                            }
-                            if let Some(idx) = duration_idx {
-                                used.insert(idx);
-                                if let Some(values) = self.render_effect_tile(ui, idx) {
-                                    actions.push((idx, values));
-                                }
-                            } else {
-                                ui.label("");
-                            }
-                            ui.end_row();
+    fn export_diagram(&self, path: &Path) -> Result<()> {
+        let fonts = egui::FontDefinitions::default();
+        let font = fonts
+            .font_data
+            .get("Ubuntu-Light")
+            .context("Default UI font is missing")?;
+        self.routing_diagram(&self.controls).save(path, &font.font)
+    }
 
-                            if let Some(idx) = ret_iter.next() {
-                                used.insert(idx);
-                                if let Some(values) = self.render_effect_tile(ui, idx) {
-                                    actions.push((idx, values));
-                                }
-                            } else {
-                                ui.label("");
-                            }
-                            if let Some(idx) = ret_iter.next() {
-                                used.insert(idx);
-                                if let Some(values) = self.render_effect_tile(ui, idx) {
-                                    actions.push((idx, values));
-                                }
-                            } else {
-                                ui.label("");
+    /// The monitoring and digital matrices as shown in the routing tab,
+    /// including their transposition, with the levels of `controls`.
+    fn routing_diagram(&self, controls: &[ControlDescriptor]) -> Diagram {
+        let grid = |bus: MatrixBus| {
+            let routes = self.bus_routes(bus);
+            let (make_input, name, input_name): (fn(usize) -> RenameTarget, _, _) = match bus {
+                MatrixBus::Analog => (RenameTarget::Ain, "Monitoring", "AIn"),
+                MatrixBus::Digital => (RenameTarget::Din, "Playback", "DIn"),
+            };
+            let max_input = routes.iter().map(|r| r.input).max().unwrap_or(0);
+            let max_output = routes.iter().map(|r| r.output).max().unwrap_or(0);
+            let by_pair: HashMap<(usize, usize), usize> = routes
+                .iter()
+                .map(|r| ((r.input, r.output), r.control_index))
+                .collect();
+            let inputs: Vec<RenameTarget> = (0..=max_input).map(make_input).collect();
+            let outputs: Vec<RenameTarget> = (0..=max_output).map(RenameTarget::Out).collect();
+            let transposed = self.is_transposed(bus);
+            let (rows, columns) = if transposed {
+                (outputs, inputs)
+            } else {
+                (inputs, outputs)
+            };
+            let index = |target: RenameTarget| match target {
+                RenameTarget::Ain(i) | RenameTarget::Din(i) | RenameTarget::Out(i) => i,
+            };
+            DiagramGrid {
+                title: if transposed {
+                    format!("{name} (Out × {input_name})")
+                } else {
+                    format!("{name} ({input_name} × Out)")
+                },
+                columns: columns.iter().map(|t| self.alias_text(*t)).collect(),
+                rows: rows
+                    .iter()
+                    .map(|&row| DiagramRow {
+                        label: self.alias_text(row),
+                        cells: columns
+                            .iter()
+                            .map(|&col| {
+                                let pair = if transposed {
+                                    (index(col), index(row))
+                                } else {
+                                    (index(row), index(col))
+                                };
+                                let level = Self::route_level_of(controls.get(*by_pair.get(&pair)?)?)?;
+                                Some(DiagramCell {
+                                    text: Self::level_text(&level),
+                                    level: level.amplitude() as f32,
+                                })
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            }
+        };
+        let grids = [MatrixBus::Analog, MatrixBus::Digital]
+            .into_iter()
+            .filter(|bus| !self.bus_routes(*bus).is_empty())
+            .map(grid)
+            .collect();
+        Diagram {
+            title: format!("{} — hw:{}", self.backend.card_label, self.backend.card_index),
+            grids,
+        }
+    }
+
+    /// Short level text: dB when the control has a dB range, else percent.
+    fn level_text(level: &RouteLevel) -> String {
+        if level.raw <= level.min {
+            return tr("off").to_string();
+        }
+        match level.db() {
+            Some(db) => format!("{db:.1} dB"),
+            None => format!(
+                "{}%",
+                Self::control_percent(level.raw, level.min, level.max, level.db_range)
+            ),
+        }
+    }
+
+    fn apply_factory_preset(&mut self, factory: FactoryPreset) {
+        let preset = factory.build(
+            &self.backend.card_label,
+            &self.backend.card_identity,
+            &self.controls,
+            &self.routing_index,
+        );
+        match self.apply_preset(&preset) {
+            Ok(applied) => {
+                self.mark_preset_baseline(factory.name().to_string(), &preset);
+                self.status_line =
+                    trf("Factory preset \"{}\" applied ({} controls)", &[&factory.name(), &applied]);
+            }
+            Err(err) => self.status_line = trf("Factory preset failed: {}", &[&err]),
+        }
+    }
+
+    fn reload_templates(&mut self) {
+        self.templates = templates::builtin_templates();
+        match templates::load_user_templates() {
+            Ok(user) => self.templates.extend(user),
+            Err(err) => self.status_line = trf("Template load warning: {}", &[&err]),
+        }
+    }
+
+    fn render_templates_window(&mut self, ctx: &egui::Context) {
+        if !self.templates_open {
+            return;
+        }
+        let profile = DeviceProfile::from_routing(&self.routing_index);
+        let max_inputs = profile.analog_inputs.max(profile.digital_inputs);
+        let params = self.template_params.get_or_insert(TemplateParams {
+            inputs: max_inputs,
+            outputs: profile.outputs,
+        });
+        let mut open = true;
+        let mut apply: Option<usize> = None;
+        egui::Window::new(tr("Preset templates"))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(trf("Device profile: {}", &[&profile.summary()]));
+                ui.horizontal(|ui| {
+                    ui.label(tr("Apply to"));
+                    ui.add(egui::DragValue::new(&mut params.inputs).range(1..=max_inputs.max(1)));
+                    ui.label(tr("inputs ×"));
+                    ui.add(egui::DragValue::new(&mut params.outputs).range(1..=profile.outputs.max(1)));
+                    ui.label(tr("outputs"));
+                });
+                if let Ok(dir) = templates::user_templates_dir() {
+                    ui.small(trf("User templates: {}", &[&dir.display()]));
+                }
+                ui.separator();
+                for (i, template) in self.templates.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        let supported = profile.supports(template);
+                        if ui.add_enabled(supported, egui::Button::new(tr("Apply"))).clicked() {
+                            apply = Some(i);
+                        }
+                        ui.vertical(|ui| {
+                            ui.label(RichText::new(&template.name).strong());
+                            if !template.description.is_empty() {
+                                ui.small(&template.description);
                             }
-                            if let Some(idx) = feedback_idx {
-                                used.insert(idx);
-                                if let Some(values) = self.render_effect_tile(ui, idx) {
-                                    actions.push((idx, values));
-                                }
-                            } else {
-                                ui.label("");
+                            if !supported {
+                                ui.small(trf(
+                                    "Needs at least {} inputs and {} outputs",
+                                    &[&template.min_inputs, &template.min_outputs],
+                                ));
                             }
-                            ui.end_row();
                         });
-                });
-        });
+                    });
+                }
+            });
+        self.templates_open = open;
+
+        let params = *params;
+        if let Some(template) = apply.and_then(|i| self.templates.get(i).cloned()) {
+            let preset = template.instantiate(
+                params,
+                &self.backend.card_label,
+                &self.backend.card_identity,
+                &self.controls,
+                &self.routing_index,
+            );
+            let label = preset.metadata.name.clone().unwrap_or_else(|| template.name.clone());
+            match self.apply_preset(&preset) {
+                Ok(applied) => {
+                    self.mark_preset_baseline(label.clone(), &preset);
+                    self.status_line = trf("Template \"{}\" applied ({} controls)", &[&label, &applied]);
+                }
+                Err(err) => self.status_line = trf("Template failed: {}", &[&err]),
+            }
+        }
+    }
+
+    fn import_alsa_state(&mut self, path: &Path) {
+        let imported = state_import::import_state_file(
+            path,
+            &self.backend.card_label,
+            &self.backend.card_identity,
+            &self.controls,
+        );
+        match imported.and_then(|preset| self.apply_preset(&preset)) {
+            Ok(applied) => {
+                self.status_line = trf(
+                    "Imported {} ({} controls) — use Save preset to keep it",
+                    &[&path.display(), &applied],
+                );
+            }
+            Err(err) => self.status_line = trf("Import failed: {}", &[&err]),
+        }
+    }
+
+    fn save_preset_with_draft(&mut self, draft: &PresetSaveDraft) -> bool {
+        let file_name = Self::preset_file_name(&draft.name);
+        let Some(path) = FileDialog::new().set_file_name(file_name).save_file() else {
+            return false;
+        };
+        let mut preset = presets::to_preset(
+            &self.backend.card_label,
+            &self.backend.card_identity,
+            &self.controls,
+        );
+        let non_empty = |s: &str| {
+            let s = s.trim();
+            (!s.is_empty()).then(|| s.to_string())
+        };
+        preset.metadata = PresetMetadata {
+            name: non_empty(&draft.name),
+            description: non_empty(&draft.description),
+            author: non_empty(&draft.author),
+            tags: presets::parse_tags(&draft.tags),
+            created: preset.metadata.created,
+        };
+        preset.apply_policy = PresetApplyPolicy {
+            mute_first: draft.mute_first,
+            ramp_ms: draft.ramp_ms,
+            exclude: draft
+                .exclude
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(str::to_string)
+                .collect(),
+        };
+        match presets::save_preset(&path, &preset) {
+            Ok(()) => {
+                if self.user_config.preset_author != preset.metadata.author {
+                    self.user_config.preset_author = preset.metadata.author.clone();
+                    self.save_user_config();
+                }
+                self.mark_preset_baseline(Self::preset_label(&preset, &path), &preset);
+                self.status_line = trf("Preset saved: {}", &[&path.display()]);
+                if self.preset_library_open {
+                    self.rescan_preset_library();
+                }
+            }
+            Err(err) => self.status_line = trf("Save failed: {}", &[&err]),
+        }
+        true
+    }
+
+    fn preset_file_name(name: &str) -> String {
+        let slug: String = name
+            .trim()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+            .collect();
+        let slug = slug
+            .split('-')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        if slug.is_empty() {
+            "fast-track-ultra-preset.json".to_string()
+        } else {
+            format!("{slug}.json")
+        }
+    }
+
+    fn rescan_preset_library(&mut self) {
+        let Some(dir) = self.user_config.preset_library_dir.clone() else {
+            self.preset_library.clear();
+            return;
+        };
+        match presets::scan_library(&dir) {
+            Ok(entries) => self.preset_library = entries,
+            Err(err) => {
+                self.preset_library.clear();
+                self.status_line = trf("Preset library scan failed: {}", &[&err]);
+            }
+        }
+    }
+
+    fn poll_hot_folder(&mut self) {
+        let Some(watcher) = self.hot_folder.as_mut() else {
+            return;
+        };
+        for path in watcher.poll() {
+            if let Err(err) = self.load_preset_from(&path) {
+                self.status_line =
+                    trf("Hot-folder preset rejected ({}): {}", &[&path.display(), &err]);
+            }
+        }
+    }
+
+    fn start_http_api(&mut self, notify: impl Fn() + Send + Sync + 'static) {
+        if self.user_config.http_api.token.is_empty() {
+            match http_api::generate_token() {
+                Ok(token) => self.user_config.http_api.token = token,
+                Err(err) => {
+                    self.status_line = trf("HTTP API disabled: {}", &[&err]);
+                    return;
+                }
+            }
+            self.save_user_config();
+        }
+        let bind = self.user_config.http_api.bind.clone();
+        match http_api::start(&bind, self.user_config.http_api.token.clone(), notify) {
+            Ok(api) => {
+                self.http_api = Some(api);
+                self.status_line = trf("HTTP API on http://{} (token in ~/.ftu-mixer/config.json)", &[&bind]);
+            }
+            Err(err) => self.status_line = trf("HTTP API disabled: {}", &[&err]),
+        }
+    }
+
+    fn start_mqtt(&mut self, notify: impl Fn() + Send + Sync + 'static) {
+        let config = self.user_config.mqtt.clone();
+        self.status_line = trf("MQTT: connecting to {}", &[&config.broker]);
+        self.mqtt = Some(mqtt::start(config, notify));
+    }
+
+    fn poll_mqtt_status(&mut self) {
+        let Some(mqtt) = &self.mqtt else {
+            return;
+        };
+        let statuses: Vec<MqttStatus> = mqtt.status.try_iter().collect();
+        for status in statuses {
+            match status {
+                MqttStatus::Connected => {
+                    let prefix = &self.user_config.mqtt.topic_prefix;
+                    self.status_line = trf(
+                        "MQTT connected to {} (topics under {}/)",
+                        &[&self.user_config.mqtt.broker, &prefix],
+                    );
+                    mqtt.publish_controls(&self.controls);
+                }
+                MqttStatus::Disconnected(err) => self.status_line = trf("MQTT: {}, retrying", &[&err]),
+            }
+        }
+    }
+
+    /// Answers calls from the HTTP API, the IPC socket and MQTT.
+    fn poll_http_api(&mut self) {
+        let mut calls: Vec<ApiCall> = Vec::new();
+        if let Some(api) = &self.http_api {
+            calls.extend(api.calls.try_iter());
+        }
+        if let Some(ipc) = &self.ipc {
+            calls.extend(ipc.calls.try_iter());
+        }
+        if let Some(mqtt) = &self.mqtt {
+            calls.extend(mqtt.calls.try_iter());
+        }
+        for call in calls {
+            let response = self.answer_api(call.request.clone());
+            call.respond(response);
+        }
+    }
+
+    /// Pushes control changes to the WebSocket and socket subscribers and to
+    /// the MQTT broker, batched per frame.
+    fn publish_api_changes(&mut self) {
+        if self.http_api.is_none() && self.ipc.is_none() && self.mqtt.is_none() {
+            return;
+        }
+        let changed = self.api_changes.changed(&self.controls);
+        if let Some(mqtt) = self.mqtt.as_ref().filter(|mqtt| mqtt.is_connected()) {
+            mqtt.publish_controls(changed.iter().copied());
+        }
+        let http_api = self.http_api.as_ref().filter(|api| api.has_subscribers());
+        let ipc = self.ipc.as_ref().filter(|ipc| ipc.has_subscribers());
+        if changed.is_empty() || (http_api.is_none() && ipc.is_none()) {
+            return;
+        }
+        let controls = serde_json::Value::Array(
+            changed
+                .into_iter()
+                .map(|c| serde_json::json!({ "numid": c.numid, "name": c.name, "values": c.values }))
+                .collect(),
+        );
+        if let Some(api) = http_api {
+            api.publish(&serde_json::json!({ "type": "changes", "controls": controls }));
+        }
+        if let Some(ipc) = ipc {
+            ipc.publish(&controls);
+        }
+    }
+
+    fn answer_api(&mut self, request: ApiRequest) -> ApiResponse {
+        let find = |controls: &[ControlDescriptor], numid: u32| controls.iter().position(|c| c.numid == numid);
+        match request {
+            ApiRequest::ListControls => ApiResponse::ok(serde_json::json!(self.controls)),
+            ApiRequest::GetControl(numid) => match find(&self.controls, numid) {
+                Some(idx) => ApiResponse::ok(serde_json::json!(self.controls[idx])),
+                None => ApiResponse::error(404, format!("No control with numid {numid}")),
+            },
+            ApiRequest::SetControl(numid, values) => {
+                let Some(idx) = find(&self.controls, numid) else {
+                    return ApiResponse::error(404, format!("No control with numid {numid}"));
+                };
+                self.push_undo(&[idx]);
+                self.apply_values_to_control(idx, values);
+                ApiResponse::ok(serde_json::json!(self.controls[idx]))
+            }
+            ApiRequest::ApplyPreset(name) => match self.load_library_preset(&name) {
+                Ok(()) => ApiResponse::ok(serde_json::json!({ "status": self.status_line })),
+                Err(err) => {
+                    self.status_line = trf("Load failed: {}", &[&err]);
+                    ApiResponse::error(500, format!("{err:#}"))
+                }
+            },
+            ApiRequest::QuickAction(action) => {
+                match action {
+                    QuickAction::MuteAnalog => self.mute_hardware_routes(),
+                    QuickAction::PassThrough => self.pass_through_inputs(),
+                    QuickAction::DisableFx => self.disable_fx_controls(),
+                    QuickAction::MuteDigital => self.mute_most_digital_routes(),
+                    QuickAction::MuteAll => self.panic_mute(),
+                }
+                ApiResponse::ok(serde_json::json!({ "status": self.status_line }))
+            }
+            ApiRequest::Metrics => {
+                ApiResponse::text(self.metrics.render(&self.backend.counters, self.controls.len()))
+            }
+            ApiRequest::CardInfo => ApiResponse::ok(serde_json::json!({
+                "index": self.backend.card_index,
+                "label": self.backend.card_label,
+                "identity": self.backend.card_identity,
+            })),
+        }
+    }
+
+    /// Runs the shell hooks configured for `event`, with the card in `FTU_CARD`.
+    fn fire_event(&mut self, event: MixerEvent, mut env: Vec<(&str, String)>) {
+        env.push(("FTU_CARD", self.backend.card_index.to_string()));
+        if let Some(usb_id) = &self.backend.card_identity.usb_id {
+            env.push(("FTU_USB_ID", usb_id.clone()));
+        }
+        if let Err(err) = event_hooks::run_hooks(&self.user_config.event_hooks, event, &env) {
+            self.status_line = trf("Event hook: {}", &[&format!("{err:#}")]);
+        }
+    }
+
+    /// Runs a script hook, then the writes and presets it asked for.
+    fn run_script(
+        &mut self,
+        hook: impl FnOnce(&mut ScriptEngine, &[ControlDescriptor]) -> HookRun,
+    ) {
+        let Some(script) = self.script.as_mut() else {
+            return;
+        };
+        // Writes queued before an error still run, like the statements before it.
+        let run = hook(script, &self.controls);
+        let mut wrote = false;
+        for command in run.commands {
+            match command {
+                ScriptCommand::Set { numid, values } => {
+                    let Some(idx) = self.controls.iter().position(|c| c.numid == numid) else {
+                        continue;
+                    };
+                    if self.is_locked_idx(idx) {
+                        continue;
+                    }
+                    self.apply_values_to_control(idx, values);
+                    wrote = true;
+                }
+                ScriptCommand::ApplyPreset(path) => {
+                    if let Err(err) = self.load_preset_from(&path) {
+                        self.status_line = trf("Script preset failed ({}): {}", &[&path.display(), &err]);
+                    }
+                    wrote = true;
+                }
+                ScriptCommand::Log(message) => self.status_line = trf("Script: {}", &[&message]),
+            }
+        }
+        if let (true, Some(script)) = (wrote, self.script.as_mut()) {
+            script.absorb_own_writes(&self.controls);
+        }
+        if let Some(err) = run.error {
+            self.status_line = trf("Script: {}", &[&format!("{err:#}")]);
+        }
+    }
+
+    fn poll_script(&mut self) {
+        self.run_script(|script, controls| script.on_controls_changed(controls));
+        self.run_script(|script, controls| script.run_timers(controls));
+    }
+
+    /// Loads the preset mapped to each Program Change, or the library preset
+    /// at that position.
+    fn poll_midi_programs(&mut self) {
+        let Some(rx) = &self.midi_rx else {
+            return;
+        };
+        let changes: Vec<ProgramChange> = rx.try_iter().collect();
+        let config = self.user_config.midi_presets.clone();
+        for change in changes {
+            if config.channel.is_some_and(|ch| ch != change.channel) {
+                continue;
+            }
+            let number = change.number(config.use_bank);
+            if !config.presets.contains_key(&number) && self.preset_library.is_empty() {
+                self.rescan_preset_library();
+            }
+            let path = config
+                .presets
+                .get(&number)
+                .cloned()
+                .or_else(|| self.preset_library.get(number as usize).map(|e| e.path.clone()));
+            let Some(path) = path else {
+                self.status_line = trf("MIDI program {}: no preset mapped", &[&number]);
+                continue;
+            };
+            match self.load_preset_from(&path) {
+                Ok(()) => self.status_line = trf("MIDI program {} — {}", &[&number, &self.status_line]),
+                Err(err) => self.status_line = trf("MIDI program {} failed ({}): {}", &[&number, &path.display(), &err]),
+            }
+        }
+    }
+
+    fn poll_gamepad(&mut self, ctx: &egui::Context) {
+        const RATE_TICK: Duration = Duration::from_millis(30);
+        let Some(rx) = &self.gamepad_rx else {
+            return;
+        };
+        let events: Vec<GamepadEvent> = rx.try_iter().collect();
+        let config = self.user_config.gamepad.clone();
+        for event in events {
+            match event {
+                GamepadEvent::Connected(name) => self.status_line = trf("Gamepad connected: {}", &[&name]),
+                GamepadEvent::Disconnected(name) => {
+                    self.status_line = trf("Gamepad disconnected: {}", &[&name]);
+                    self.gamepad_axes.clear();
+                    self.gamepad_talk_held = false;
+                }
+                GamepadEvent::Axis { input, value } => {
+                    let mut mapped = false;
+                    for mapping in config.mappings.iter().filter(|m| m.input == input) {
+                        mapped = true;
+                        if let (AxisMode::Absolute, GamepadTarget::Control(name)) = (mapping.mode, &mapping.target) {
+                            let value = if mapping.invert { -value } else { value };
+                            self.set_gamepad_level(name, |_, min, max| {
+                                // Sticks span -1..1, triggers 0..1.
+                                let pos = if input.contains("trigger") { value } else { (value + 1.0) / 2.0 };
+                                min as f64 + pos.clamp(0.0, 1.0) as f64 * (max - min) as f64
+                            });
+                        }
+                    }
+                    if !mapped && value.abs() > 0.5 && self.gamepad_axes.get(&input).is_none_or(|v| v.abs() <= 0.5) {
+                        self.status_line = trf("Gamepad: {} (unmapped)", &[&input]);
+                    }
+                    self.gamepad_axes.insert(input, value);
+                }
+                GamepadEvent::Button { input, pressed } => {
+                    let targets: Vec<GamepadTarget> = config
+                        .mappings
+                        .iter()
+                        .filter(|m| m.input == input)
+                        .map(|m| m.target.clone())
+                        .collect();
+                    if targets.is_empty() && pressed {
+                        self.status_line = trf("Gamepad: {} (unmapped)", &[&input]);
+                    }
+                    for target in targets {
+                        match target {
+                            GamepadTarget::Talkback => self.gamepad_talk_held = pressed,
+                            GamepadTarget::PanicMute if pressed => self.panic_mute(),
+                            GamepadTarget::Snapshot(n) if pressed && n >= 1 => self.recall_snapshot(n - 1),
+                            GamepadTarget::Control(name) if pressed => self.toggle_gamepad_switch(&name),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+
+        let elapsed = self.gamepad_last_tick.elapsed();
+        if elapsed < RATE_TICK {
+            return;
+        }
+        self.gamepad_last_tick = Instant::now();
+        let dt = elapsed.min(RATE_TICK * 4).as_secs_f64();
+        let mut moving = false;
+        for mapping in config.mappings.iter().filter(|m| m.mode == AxisMode::Rate) {
+            let GamepadTarget::Control(name) = &mapping.target else {
+                continue;
+            };
+            let value = self.gamepad_axes.get(&mapping.input).copied().unwrap_or(0.0);
+            if value.abs() <= config.deadzone {
+                continue;
+            }
+            moving = true;
+            // Rescale so the move starts from zero at the edge of the deadzone.
+            let deflection = (value.abs() - config.deadzone) / (1.0 - config.deadzone) * value.signum();
+            let deflection = if mapping.invert { -deflection } else { deflection };
+            let Some(control) = self.controls.iter().find(|c| &c.name == name) else {
+                continue;
+            };
+            let ControlKind::Integer { min, max, step, .. } = control.kind else {
+                continue;
+            };
+            let numid = control.numid;
+            let step = step.max(1);
+            // Move in whole steps and keep the remainder for the next tick.
+            let carry = self.gamepad_carry.get(&numid).copied().unwrap_or(0.0);
+            let steps = carry + mapping.speed as f64 * deflection as f64 * dt * (max - min) as f64 / step as f64;
+            self.gamepad_carry.insert(numid, steps.fract());
+            let moved = steps.trunc() as i64 * step;
+            if moved != 0 {
+                self.set_gamepad_level(name, |current, _, _| (current + moved) as f64);
+            }
+        }
+        if moving {
+            ctx.request_repaint_after(RATE_TICK);
+        }
+    }
+
+    /// Sets every channel of an integer control to `level(current, min, max)`,
+    /// rounded to the control's step. Nothing is written when the raw value
+    /// would not change.
+    fn set_gamepad_level(&mut self, name: &str, mut level: impl FnMut(i64, i64, i64) -> f64) {
+        let Some(idx) = self.controls.iter().position(|c| c.name == name) else {
+            return;
+        };
+        if self.is_locked_idx(idx) {
+            return;
+        }
+        let control = &self.controls[idx];
+        let ControlKind::Integer { min, max, step, .. } = control.kind else {
+            return;
+        };
+        let step = step.max(1);
+        let values: Vec<String> = control
+            .values
+            .iter()
+            .map(|v| {
+                let current = v.parse::<i64>().unwrap_or(min);
+                let target = level(current, min, max).round() as i64;
+                (min + (target - min + step / 2) / step * step).clamp(min, max).to_string()
+            })
+            .collect();
+        if values != control.values {
+            self.apply_values_to_control(idx, values);
+        }
+    }
+
+    fn toggle_gamepad_switch(&mut self, name: &str) {
+        let Some(idx) = self.controls.iter().position(|c| c.name == name) else {
+            self.status_line = trf("Gamepad: no control named \"{}\"", &[&name]);
+            return;
+        };
+        if self.is_locked_idx(idx) || !matches!(self.controls[idx].kind, ControlKind::Boolean { .. }) {
+            return;
+        }
+        let on = self.controls[idx]
+            .values
+            .first()
+            .is_some_and(|v| v.eq_ignore_ascii_case("on") || v == "1");
+        let values = vec![if on { "off" } else { "on" }.to_string(); self.controls[idx].values.len()];
+        self.apply_values_to_control(idx, values);
+    }
+
+    /// Opens the capture tap behind the AIn meters. The capture PCM is
+    /// exclusive on a raw card, so this fails while a sound server holds it
+    /// unless `metering.capture_device` points at a loopback or dsnoop.
+    fn start_input_meters(&mut self, ctx: &egui::Context) {
+        if self.backend.active_backend() != BackendKind::Alsa {
+            self.status_line = tr("Input meters need the card on this machine").to_string();
+            return;
+        }
+        let device = self.capture_device();
+        let channels = self.capture_channels();
+        match metering::start_capture_tap(&device, channels, self.meter_repaint(ctx)) {
+            Ok(tap) => {
+                self.status_line = trf("Input meters on {}", &[&device]);
+                self.input_meters = vec![LevelMeter::default(); channels as usize];
+                self.input_tap = Some(tap);
+            }
+            Err(err) => {
+                self.status_line = trf("Input meters unavailable: {}", &[&format!("{err:#}")]);
+                if let Some(server) = self.device_usage.server() {
+                    self.status_line.push_str(&trf(
+                        " — {} holds the card; set metering.capture_device to a loopback or dsnoop device",
+                        &[&server],
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Attaches the Out meters to the monitor of the card's PipeWire sink.
+    /// The sink carries the playback streams, so analog monitoring mixed in
+    /// by the card's DSP does not show up here.
+    fn start_output_meters(&mut self, ctx: &egui::Context) {
+        if self.backend.active_backend() != BackendKind::Alsa {
+            self.status_line = tr("Output meters need the card on this machine").to_string();
+            return;
+        }
+        let Some((sink, channels)) = metering::find_pipewire_sink(self.backend.card_index) else {
+            self.status_line = tr("Output meters unavailable: no PipeWire sink for this card").to_string();
+            return;
+        };
+        match metering::start_monitor_tap(&sink, channels, self.meter_repaint(ctx)) {
+            Ok(tap) => {
+                self.status_line = trf("Output meters on {}", &[&tap.device]);
+                self.output_meters = vec![LevelMeter::default(); channels as usize];
+                self.output_tap = Some(tap);
+            }
+            Err(err) => self.status_line = trf("Output meters unavailable: {}", &[&format!("{err:#}")]),
+        }
+    }
+
+    /// Wakes the UI for new levels; LEDs only need a few frames a second.
+    fn meter_repaint(&self, ctx: &egui::Context) -> impl Fn() + Send + 'static {
+        let egui_ctx = ctx.clone();
+        let delay = if self.user_config.metering.leds {
+            Duration::from_millis(250)
+        } else {
+            Duration::ZERO
+        };
+        move || egui_ctx.request_repaint_after(delay)
+    }
+
+    fn paint_led(ui: &egui::Ui, rect: egui::Rect, meter: &LevelMeter, palette: &Palette) {
+        let color = if meter.has_signal() {
+            palette.good
+        } else {
+            Color32::from_rgb(50, 58, 68)
+        };
+        ui.painter()
+            .circle_filled(egui::pos2(rect.left() + 4.0, rect.center().y), 3.0, color);
+    }
+
+    fn poll_meters(&mut self) {
+        let dt = self.meters_updated.elapsed().as_secs_f32();
+        self.meters_updated = Instant::now();
+        for (message, tap, meters) in [
+            ("Input meters stopped ({}): {}", &mut self.input_tap, &mut self.input_meters),
+            ("Output meters stopped ({}): {}", &mut self.output_tap, &mut self.output_meters),
+        ] {
+            let Some(current) = tap.as_ref() else {
+                continue;
+            };
+            if let Some(err) = current.error() {
+                self.status_line = trf(message, &[&current.device, &err]);
+                *tap = None;
+                meters.clear();
+                continue;
+            }
+            for (meter, stats) in meters.iter_mut().zip(current.take_stats()) {
+                meter.update(stats, dt, &self.user_config.metering);
+            }
+        }
+    }
+
+    /// Thin level bar along the bottom of a header label.
+    fn paint_meter(ui: &egui::Ui, rect: egui::Rect, meter: &LevelMeter, config: &MeteringConfig, palette: &Palette) {
+        let bar = egui::Rect::from_min_max(egui::pos2(rect.left(), rect.bottom() - 3.0), rect.right_bottom());
+        let painter = ui.painter();
+        painter.rect_filled(bar, 1.0, ui.visuals().faint_bg_color);
+        let reading = meter.reading(config);
+        let at = |fraction: f32| bar.left() + bar.width() * fraction;
+        painter.vline(at(reading.reference), bar.y_range(), Stroke::new(1.0, Color32::from_rgb(90, 100, 115)));
+        if reading.fraction > 0.0 {
+            let color = if reading.danger {
+                palette.danger
+            } else if reading.warn {
+                palette.warn
+            } else {
+                palette.good
+            };
+            let lit = egui::Rect::from_min_size(bar.min, vec2(bar.width() * reading.fraction, bar.height()));
+            painter.rect_filled(lit, 1.0, color);
+            if reading.danger && palette.state_cues {
+                // The bar is too thin to hatch: frame the whole label instead.
+                painter.rect_stroke(rect, 2.0, Stroke::new(1.5, palette.danger), egui::StrokeKind::Inside);
+            }
+        }
+        if let Some(hold) = reading.hold {
+            painter.vline(at(hold), bar.y_range(), Stroke::new(1.5, Color32::from_rgb(220, 225, 235)));
+        }
+    }
+
+    /// Vertical bar for the meter bridge, bottom to top.
+    fn paint_meter_column(
+        ui: &mut egui::Ui,
+        meter: Option<&LevelMeter>,
+        config: &MeteringConfig,
+        palette: &Palette,
+        size: egui::Vec2,
+    ) {
+        let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+        let painter = ui.painter();
+        painter.rect_filled(rect, 2.0, ui.visuals().faint_bg_color);
+        let Some(meter) = meter else {
+            return;
+        };
+        let reading = meter.reading(config);
+        let at = |fraction: f32| rect.bottom() - rect.height() * fraction;
+        painter.hline(rect.x_range(), at(reading.reference), Stroke::new(1.0, Color32::from_rgb(90, 100, 115)));
+        if reading.fraction > 0.0 {
+            let color = if reading.danger {
+                palette.danger
+            } else if reading.warn {
+                palette.warn
+            } else {
+                palette.good
+            };
+            let lit = egui::Rect::from_min_max(egui::pos2(rect.left(), at(reading.fraction)), rect.right_bottom());
+            painter.rect_filled(lit, 2.0, color);
+            if reading.danger && palette.state_cues {
+                Self::paint_hatch(painter, lit, Stroke::new(1.5, Color32::from_black_alpha(160)));
+                painter.rect_stroke(rect.expand(1.0), 2.0, Stroke::new(1.5, palette.text), egui::StrokeKind::Outside);
+            }
+        }
+        if let Some(hold) = reading.hold {
+            painter.hline(rect.x_range(), at(hold), Stroke::new(1.5, Color32::from_rgb(220, 225, 235)));
+        }
+    }
+
+    /// Diagonal stripes over `rect`, the pattern behind colored states.
+    fn paint_hatch(painter: &egui::Painter, rect: egui::Rect, stroke: Stroke) {
+        let painter = painter.with_clip_rect(rect.intersect(painter.clip_rect()));
+        let step = 6.0;
+        let mut x = rect.left() - rect.height();
+        while x < rect.right() {
+            painter.line_segment(
+                [egui::pos2(x, rect.bottom()), egui::pos2(x + rect.height(), rect.top())],
+                stroke,
+            );
+            x += step;
+        }
+    }
+
+    /// Slim window with only the meters and the output mutes, kept above the
+    /// DAW while the mixer itself is minimized.
+    fn render_meter_bridge(&mut self, ctx: &egui::Context) {
+        if !self.user_config.metering.bridge {
+            return;
+        }
+        let inputs = self.capture_channels() as usize;
+        let outputs = self.output_count();
+        let width = 30.0 * (inputs + outputs) as f32 + 40.0;
+        let mut close = false;
+        let mut mute = None;
+        let mut start_inputs = false;
+        let mut start_outputs = false;
+        ctx.show_viewport_immediate(
+            egui::ViewportId::from_hash_of("meter_bridge"),
+            egui::ViewportBuilder::default()
+                .with_title(tr("FTU meters"))
+                .with_always_on_top()
+                .with_inner_size([width, 220.0]),
+            |ctx, _| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.horizontal_top(|ui| {
+                        let column = |ui: &mut egui::Ui, app: &Self, target: RenameTarget, meter: Option<&LevelMeter>| {
+                            ui.vertical(|ui| {
+                                ui.set_width(24.0);
+                                Self::paint_meter_column(ui, meter, &app.user_config.metering, &app.palette, vec2(10.0, 140.0));
+                                ui.label(RichText::new(app.alias_text(target)).small())
+                                    .on_hover_text(app.alias_text(target));
+                                if let RenameTarget::Out(output) = target {
+                                    let muted = app.user_config.muted_outputs.contains_key(&output);
+                                    let text = RichText::new("M").small();
+                                    let text = if muted {
+                                        text.strong().color(app.palette.danger)
+                                    } else {
+                                        text
+                                    };
+                                    ui.add(egui::Button::new(text).selected(muted).small())
+                                        .accessible_name(trf(
+                                            if muted { "Unmute {}" } else { "Mute {}" },
+                                            &[&app.alias_text(target)],
+                                        ))
+                                        .on_hover_text(tr("Mute every route to this output"))
+                                        .clicked()
+                                } else {
+                                    false
+                                }
+                            })
+                            .inner
+                        };
+                        if self.input_tap.is_some() {
+                            for input in 0..inputs {
+                                column(ui, self, RenameTarget::Ain(input), self.input_meters.get(input));
+                            }
+                        } else if ui.small_button(tr("Input meters")).clicked() {
+                            start_inputs = true;
+                        }
+                        ui.separator();
+                        for output in 0..outputs {
+                            let meter = self.output_meters.get(output);
+                            if column(ui, self, RenameTarget::Out(output), meter) {
+                                mute = Some(output);
+                            }
+                        }
+                        if self.output_tap.is_none() && ui.small_button(tr("Output meters")).clicked() {
+                            start_outputs = true;
+                        }
+                    });
+                });
+                close = ctx.input(|i| i.viewport().close_requested());
+            },
+        );
+        if let Some(output) = mute {
+            self.toggle_output_mute(output);
+        }
+        if start_inputs {
+            self.user_config.metering.inputs = true;
+            self.start_input_meters(ctx);
+            self.save_user_config();
+        }
+        if start_outputs {
+            self.user_config.metering.outputs = true;
+            self.start_output_meters(ctx);
+            self.save_user_config();
+        }
+        if close {
+            self.user_config.metering.bridge = false;
+            self.save_user_config();
+        }
+    }
+
+    /// Playback channel that reaches `output`: the DIn with an open route to
+    /// it that feeds the fewest other outputs, else the DIn of the same
+    /// number (with `false`).
+    fn generator_channel(&self, output: usize) -> (usize, bool) {
+        let open: Vec<&RouteRef> = self
+            .routing_index
+            .digital_routes
+            .iter()
+            .filter(|r| {
+                self.integer_route_level(r.control_index)
+                    .is_some_and(|level| level.amplitude() > 0.0)
+            })
+            .collect();
+        open.iter()
+            .filter(|r| r.output == output)
+            .min_by_key(|r| open.iter().filter(|other| other.input == r.input).count())
+            .map(|r| (r.input, true))
+            .unwrap_or((output, false))
+    }
+
+    fn capture_device(&self) -> String {
+        self.user_config
+            .metering
+            .capture_device
+            .clone()
+            .unwrap_or_else(|| format!("plughw:{},0", self.backend.card_index))
+    }
+
+    fn capture_channels(&self) -> u32 {
+        self.routing_index
+            .analog_routes
+            .iter()
+            .map(|r| r.input + 1)
+            .max()
+            .unwrap_or(8) as u32
+    }
+
+    fn generator_device(&self) -> String {
+        self.user_config
+            .generator
+            .playback_device
+            .clone()
+            .unwrap_or_else(|| format!("plughw:{},0", self.backend.card_index))
+    }
+
+    fn playback_channels(&self) -> u32 {
+        self.routing_index
+            .digital_routes
+            .iter()
+            .map(|r| r.input + 1)
+            .max()
+            .unwrap_or(8) as u32
+    }
+
+    /// Beeps as many times as the output's number on that output only.
+    fn identify_output(&mut self, output: usize) {
+        if self.backend.active_backend() != BackendKind::Alsa {
+            self.status_line = tr("Identify needs the card on this machine").to_string();
+            return;
+        }
+        let (channel, routed) = self.generator_channel(output);
+        let name = self.alias_text(RenameTarget::Out(output));
+        if !routed {
+            self.status_line = trf(
+                "No DIn is routed to {}; open one in the digital matrix to identify it",
+                &[&name],
+            );
+            return;
+        }
+        let device = self.generator_device();
+        match generator::identify(&device, self.playback_channels(), channel, output + 1) {
+            Ok(()) => {
+                let din = self.alias_text(RenameTarget::Din(channel));
+                self.status_line = trf("{}: {} beep(s) via {}", &[&name, &(output + 1), &din]);
+            }
+            Err(err) => self.status_line = trf("Identify failed: {}", &[&format!("{err:#}")]),
+        }
+    }
+
+    fn signal_settings(&self) -> SignalSettings {
+        SignalSettings {
+            signal: self.user_config.generator.signal,
+            frequency_hz: self.user_config.generator.frequency_hz,
+            level_dbfs: self.user_config.generator.level_dbfs,
+        }
+    }
+
+    /// The selected Out, or both Outs of its pair.
+    fn generator_outputs(&self) -> Vec<usize> {
+        if self.user_config.generator.pair {
+            let left = self.generator_output & !1;
+            (left..(left + 2).min(self.output_count().max(left + 1))).collect()
+        } else {
+            vec![self.generator_output]
+        }
+    }
+
+    fn start_generator(&mut self, ctx: &egui::Context) {
+        self.generator = None;
+        self.generator_deadline = None;
+        if self.backend.active_backend() != BackendKind::Alsa {
+            self.status_line = tr("The tone generator needs the card on this machine").to_string();
+            return;
+        }
+        let device = self.generator_device();
+        let outputs = self.generator_outputs();
+        let routes: Vec<(usize, usize, bool)> = outputs
+            .iter()
+            .map(|&output| {
+                let (channel, routed) = self.generator_channel(output);
+                (output, channel, routed)
+            })
+            .collect();
+        let mut targets: Vec<usize> = routes.iter().map(|&(_, channel, _)| channel).collect();
+        targets.dedup();
+        let egui_ctx = ctx.clone();
+        match generator::start(&device, self.playback_channels(), targets, self.signal_settings(), move || {
+            egui_ctx.request_repaint()
+        }) {
+            Ok(generator) => {
+                let described: Vec<String> = routes
+                    .iter()
+                    .map(|&(output, channel, routed)| {
+                        let din = self.alias_text(RenameTarget::Din(channel));
+                        let output = self.alias_text(RenameTarget::Out(output));
+                        if routed {
+                            format!("{din} → {output}")
+                        } else {
+                            format!("{din} (route to {output} is off — open it in the digital matrix)")
+                        }
+                    })
+                    .collect();
+                let signal = match self.user_config.generator.signal {
+                    Signal::Sine => "Tone",
+                    Signal::PinkNoise => {
+                        let timeout = Duration::from_secs(self.user_config.generator.noise_timeout_secs.max(1));
+                        self.generator_deadline = Some(Instant::now() + timeout);
+                        "Pink noise"
+                    }
+                };
+                self.status_line = trf("{} on {} ({})", &[&signal, &described.join(", "), &generator.device]);
+                self.generator = Some(generator);
+            }
+            Err(err) => {
+                self.status_line = trf("Tone generator unavailable: {}", &[&format!("{err:#}")]);
+                if let Some(server) = self.device_usage.server() {
+                    self.status_line.push_str(&trf(
+                        " — {} holds the card; set generator.playback_device to e.g. \"pipewire\"",
+                        &[&server],
+                    ));
+                }
+            }
+        }
+    }
+
+    fn render_generator_window(&mut self, ctx: &egui::Context) {
+        if let Some(err) = self.generator.as_ref().and_then(Generator::error) {
+            self.status_line = trf("Tone generator stopped: {}", &[&err]);
+            self.generator = None;
+        }
+        if let Some(deadline) = self.generator_deadline {
+            let now = Instant::now();
+            if self.generator.is_none() {
+                self.generator_deadline = None;
+            } else if now >= deadline {
+                self.generator = None;
+                self.generator_deadline = None;
+                self.status_line = tr("Pink noise stopped after its timeout").to_string();
+            } else {
+                ctx.request_repaint_after((deadline - now).min(Duration::from_secs(1)));
+            }
+        }
+        if !self.generator_open {
+            return;
+        }
+        let mut open = true;
+        let mut start = false;
+        let mut changed = false;
+        let outputs = self.output_count().max(1);
+        egui::Window::new(tr("Tone generator"))
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                let playing = self.generator.is_some();
+                let before = (self.generator_output, self.user_config.generator.pair);
+                ui.horizontal(|ui| {
+                    ui.label(tr("Output"));
+                    let pair = self.user_config.generator.pair;
+                    let label = |app: &Self, output: usize| {
+                        if pair && output + 1 < outputs {
+                            format!(
+                                "{} + {}",
+                                app.alias_text(RenameTarget::Out(output)),
+                                app.alias_text(RenameTarget::Out(output + 1))
+                            )
+                        } else {
+                            app.alias_text(RenameTarget::Out(output))
+                        }
+                    };
+                    let step = if pair { 2 } else { 1 };
+                    if pair {
+                        self.generator_output &= !1;
+                    }
+                    egui::ComboBox::from_id_salt("generator_output")
+                        .selected_text(label(self, self.generator_output))
+                        .show_ui(ui, |ui| {
+                            for output in (0..outputs).step_by(step) {
+                                let text = label(self, output);
+                                ui.selectable_value(&mut self.generator_output, output, text);
+                            }
+                        });
+                    ui.checkbox(&mut self.user_config.generator.pair, tr("Pair"));
+                });
+                // Moving to other outputs restarts on their playback channels.
+                if (self.generator_output, self.user_config.generator.pair) != before {
+                    start |= playing;
+                    changed = true;
+                }
+                let config = &mut self.user_config.generator;
+                ui.horizontal(|ui| {
+                    let before = config.signal;
+                    ui.selectable_value(&mut config.signal, Signal::Sine, tr("Sine"));
+                    ui.selectable_value(&mut config.signal, Signal::PinkNoise, tr("Pink noise"))
+                        .on_hover_text(tr("Band-limited 500 Hz-2 kHz, level is RMS: -20 dBFS is the usual reference for 85 dB SPL (C-weighted, slow) per speaker"));
+                    if config.signal != before {
+                        changed = true;
+                        // Restarting arms (or clears) the noise timeout.
+                        start |= playing;
+                    }
+                });
+                if config.signal == Signal::Sine {
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Frequency"));
+                        changed |= ui
+                            .add(
+                                egui::Slider::new(&mut config.frequency_hz, 20.0..=20000.0)
+                                    .logarithmic(true)
+                                    .suffix(" Hz")
+                                    .max_decimals(0),
+                            )
+                            .changed();
+                    });
+                    ui.horizontal(|ui| {
+                        for hz in [100.0, 440.0, 1000.0, 10000.0] {
+                            if ui.button(format!("{hz} Hz")).clicked() {
+                                config.frequency_hz = hz;
+                                changed = true;
+                            }
+                        }
+                    });
+                }
+                ui.horizontal(|ui| {
+                    ui.label(tr("Level"));
+                    let suffix = match config.signal {
+                        Signal::Sine => " dBFS",
+                        Signal::PinkNoise => " dBFS RMS",
+                    };
+                    changed |= ui
+                        .add(egui::Slider::new(&mut config.level_dbfs, -60.0..=0.0).suffix(suffix))
+                        .changed();
+                });
+                if config.signal == Signal::PinkNoise {
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Auto stop"));
+                        changed |= ui
+                            .add(egui::Slider::new(&mut config.noise_timeout_secs, 5..=600).suffix(" s"))
+                            .changed();
+                    });
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if playing {
+                        if ui.button(tr("■ Stop")).clicked() {
+                            self.generator = None;
+                            self.status_line = tr("Tone stopped").to_string();
+                        }
+                    } else if ui.button(tr("▶ Play")).clicked() {
+                        start = true;
+                    }
+                    if let Some(deadline) = self.generator_deadline {
+                        let left = deadline.saturating_duration_since(Instant::now()).as_secs() + 1;
+                        ui.small(trf("stops in {} s", &[&left]));
+                    }
+                    for output in self.generator_outputs() {
+                        let (channel, routed) = self.generator_channel(output);
+                        let din = self.alias_text(RenameTarget::Din(channel));
+                        if routed {
+                            ui.small(trf("via {}", &[&din]));
+                        } else {
+                            ui.colored_label(Color32::from_rgb(235, 200, 70), trf("{} is not routed here", &[&din]));
+                        }
+                    }
+                });
+            });
+        if changed {
+            if let Some(generator) = &self.generator {
+                generator.set(self.signal_settings());
+            }
+        }
+        if start {
+            self.start_generator(ctx);
+        }
+        if changed || start {
+            self.save_user_config();
+        }
+        if !open {
+            self.generator_open = false;
+            self.generator = None;
+        }
+    }
+
+    /// Loopback tests need the card's capture and playback PCMs to
+    /// themselves, so the input meters and the generator pause until they
+    /// are done.
+    fn loopback_setup(&mut self) -> Option<Loopback> {
+        if self.backend.active_backend() != BackendKind::Alsa {
+            self.status_line = tr("The loopback test needs the card on this machine").to_string();
+            return None;
+        }
+        let (output_channel, routed) = self.generator_channel(self.loopback_output);
+        if !routed {
+            self.status_line = trf(
+                "No DIn is routed to {}; open one in the digital matrix first",
+                &[&self.alias_text(RenameTarget::Out(self.loopback_output))],
+            );
+            return None;
+        }
+        self.generator = None;
+        self.input_tap = None;
+        Some(Loopback {
+            playback_device: self.generator_device(),
+            playback_channels: self.playback_channels(),
+            output_channel,
+            capture_device: self.capture_device(),
+            capture_channels: self.capture_channels(),
+            input_channel: self.loopback_input,
+            period: self.loopback_period,
+        })
+    }
+
+    fn start_latency_test(&mut self, ctx: &egui::Context) {
+        let Some(loopback) = self.loopback_setup() else {
+            return;
+        };
+        let egui_ctx = ctx.clone();
+        self.latency_rx = Some(measure::measure_latency(loopback, move || egui_ctx.request_repaint()));
+        self.latency_result = None;
+        self.status_line = tr("Measuring round-trip latency…").to_string();
+    }
+
+    fn start_sweep(&mut self, ctx: &egui::Context) {
+        let Some(loopback) = self.loopback_setup() else {
+            return;
+        };
+        let egui_ctx = ctx.clone();
+        self.sweep_rx = Some(measure::measure_sweep(loopback, move || egui_ctx.request_repaint()));
+        self.sweep_result = None;
+        self.status_line = tr("Sweeping…").to_string();
+    }
+
+    /// Like the loopback test, recording pauses the input meters since the
+    /// capture PCM is exclusive.
+    fn start_scratch_recording(&mut self, ctx: &egui::Context) {
+        if self.backend.active_backend() != BackendKind::Alsa {
+            self.status_line = tr("Recording needs the card on this machine").to_string();
+            return;
+        }
+        let dir = match scratch::scratch_dir(self.user_config.scratch_dir.as_deref()) {
+            Ok(dir) => dir,
+            Err(err) => {
+                self.status_line = trf("Recording failed: {}", &[&format!("{err:#}")]);
+                return;
+            }
+        };
+        self.input_tap = None;
+        self.scratch_player = None;
+        let egui_ctx = ctx.clone();
+        self.scratch_rx = Some(scratch::record(
+            &self.capture_device(),
+            self.capture_channels(),
+            self.scratch_inputs.iter().copied().collect(),
+            dir,
+            move || egui_ctx.request_repaint(),
+        ));
+        self.scratch_started = Instant::now();
+        self.status_line = trf("Recording {} s…", &[&scratch::SECONDS]);
+    }
+
+    fn play_scratch(&mut self, ctx: &egui::Context, path: &Path) {
+        self.scratch_player = None;
+        let egui_ctx = ctx.clone();
+        match scratch::play(&self.generator_device(), self.playback_channels(), path, move || {
+            egui_ctx.request_repaint()
+        }) {
+            Ok(player) => {
+                self.status_line = trf(
+                    "Playing {} on {} + {}",
+                    &[
+                        &path.display(),
+                        &self.alias_text(RenameTarget::Din(0)),
+                        &self.alias_text(RenameTarget::Din(1)),
+                    ],
+                );
+                self.scratch_player = Some(player);
+            }
+            Err(err) => self.status_line = trf("Playback failed: {}", &[&format!("{err:#}")]),
+        }
+    }
+
+    fn render_scratch_window(&mut self, ctx: &egui::Context) {
+        if let Some(result) = self.scratch_rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.scratch_rx = None;
+            match result {
+                Ok(path) => {
+                    self.status_line = trf("Recorded {}", &[&path.display()]);
+                    self.scratch_file = Some(path);
+                }
+                Err(err) => self.status_line = trf("Recording failed: {}", &[&format!("{err:#}")]),
+            }
+            if self.user_config.metering.inputs {
+                self.start_input_meters(ctx);
+            }
+        }
+        if self.scratch_player.as_ref().is_some_and(Player::is_done) {
+            self.scratch_player = None;
+        }
+        if !self.scratch_open {
+            return;
+        }
+        let mut open = true;
+        let mut record = false;
+        let mut play = None;
+        let inputs = self.capture_channels() as usize;
+        egui::Window::new(tr("Scratch recording"))
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.small(tr("Inputs to record"));
+                ui.horizontal_wrapped(|ui| {
+                    for input in 0..inputs {
+                        let mut selected = self.scratch_inputs.contains(&input);
+                        if ui
+                            .checkbox(&mut selected, self.alias_text(RenameTarget::Ain(input)))
+                            .changed()
+                        {
+                            if selected {
+                                self.scratch_inputs.insert(input);
+                            } else {
+                                self.scratch_inputs.remove(&input);
+                            }
+                        }
+                    }
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let recording = self.scratch_rx.is_some();
+                    if ui
+                        .add_enabled(
+                            !recording && !self.scratch_inputs.is_empty(),
+                            egui::Button::new(trf("⏺ Record {} s", &[&scratch::SECONDS])),
+                        )
+                        .clicked()
+                    {
+                        record = true;
+                    }
+                    if recording {
+                        let left = (scratch::SECONDS as f32 - self.scratch_started.elapsed().as_secs_f32()).max(0.0);
+                        ui.spinner();
+                        ui.label(format!("{left:.0} s"));
+                        ctx.request_repaint_after(Duration::from_millis(250));
+                    }
+                });
+                if let Some(path) = &self.scratch_file {
+                    ui.horizontal(|ui| {
+                        if self.scratch_player.is_some() {
+                            if ui.button(tr("■ Stop")).clicked() {
+                                self.scratch_player = None;
+                            }
+                        } else if ui
+                            .button(tr("▶ Play"))
+                            .on_hover_text(tr("On the first two playback channels (DIn1/2)"))
+                            .clicked()
+                        {
+                            play = Some(path.clone());
+                        }
+                        ui.small(path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default());
+                    });
+                }
+            });
+        if record {
+            self.start_scratch_recording(ctx);
+        }
+        if let Some(path) = play {
+            self.play_scratch(ctx, &path);
+        }
+        if !open {
+            self.scratch_open = false;
+            self.scratch_player = None;
+        }
+    }
+
+    /// Gain against frequency on log axes, scaled around the measured curve.
+    fn paint_sweep(ui: &mut egui::Ui, sweep: &SweepResult) {
+        let (rect, _) = ui.allocate_exact_size(vec2(ui.available_width().max(320.0), 180.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, Color32::from_rgb(22, 26, 32));
+        let top = sweep.points.iter().map(|&(_, db)| db).fold(f32::MIN, f32::max);
+        let top = (top / 6.0).ceil() * 6.0 + 6.0;
+        let bottom = top - 42.0;
+        let (low, high) = (20f32.log10(), 20000f32.log10());
+        let x = |hz: f32| rect.left() + (hz.log10() - low) / (high - low) * rect.width();
+        let y = |db: f32| rect.top() + (top - db.clamp(bottom, top)) / (top - bottom) * rect.height();
+        let grid = Stroke::new(1.0, Color32::from_rgb(44, 50, 60));
+        let label = Color32::from_rgb(130, 140, 155);
+        for (hz, text) in [(100.0, "100"), (1000.0, "1k"), (10000.0, "10k")] {
+            painter.vline(x(hz), rect.y_range(), grid);
+            painter.text(
+                egui::pos2(x(hz) + 2.0, rect.bottom() - 2.0),
+                egui::Align2::LEFT_BOTTOM,
+                text,
+                egui::FontId::proportional(10.0),
+                label,
+            );
+        }
+        for step in 1..7 {
+            let db = top - step as f32 * 6.0;
+            painter.hline(rect.x_range(), y(db), grid);
+            painter.text(
+                egui::pos2(rect.left() + 2.0, y(db)),
+                egui::Align2::LEFT_BOTTOM,
+                format!("{db:.0} dB"),
+                egui::FontId::proportional(10.0),
+                label,
+            );
+        }
+        let line: Vec<egui::Pos2> = sweep.points.iter().map(|&(hz, db)| egui::pos2(x(hz), y(db))).collect();
+        painter.add(egui::Shape::line(line, Stroke::new(1.5, Color32::from_rgb(80, 200, 110))));
+    }
+
+    fn render_loopback_window(&mut self, ctx: &egui::Context) {
+        if let Some(result) = self.latency_rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.latency_rx = None;
+            self.status_line = match &result {
+                Ok(latency) => trf(
+                    "Round-trip latency: {} samples ({} ms)",
+                    &[&latency.frames, &format!("{:.2}", latency.ms(latency.frames))],
+                ),
+                Err(err) => trf("Latency test failed: {}", &[&format!("{err:#}")]),
+            };
+            self.latency_result = Some(result.map_err(|err| format!("{err:#}")));
+            if self.user_config.metering.inputs {
+                self.start_input_meters(ctx);
+            }
+        }
+        if let Some(result) = self.sweep_rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.sweep_rx = None;
+            self.status_line = match &result {
+                Ok(sweep) => trf("Sweep done ({} points)", &[&sweep.points.len()]),
+                Err(err) => trf("Sweep failed: {}", &[&format!("{err:#}")]),
+            };
+            self.sweep_result = Some(result.map_err(|err| format!("{err:#}")));
+            if self.user_config.metering.inputs {
+                self.start_input_meters(ctx);
+            }
+        }
+        if !self.loopback_open {
+            return;
+        }
+        let mut open = true;
+        let mut measure = false;
+        let mut sweep = false;
+        let outputs = self.output_count().max(1);
+        let inputs = self.capture_channels() as usize;
+        egui::Window::new(tr("Loopback test"))
+            .open(&mut open)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                ui.small(tr("Connect an output to an input with a cable. Turn speakers and headphones down: the test plays a click."));
+                egui::Grid::new("loopback_grid").num_columns(2).show(ui, |ui| {
+                    ui.label(tr("Output"));
+                    egui::ComboBox::from_id_salt("loopback_output")
+                        .selected_text(self.alias_text(RenameTarget::Out(self.loopback_output)))
+                        .show_ui(ui, |ui| {
+                            for output in 0..outputs {
+                                let label = self.alias_text(RenameTarget::Out(output));
+                                ui.selectable_value(&mut self.loopback_output, output, label);
+                            }
+                        });
+                    ui.end_row();
+                    ui.label(tr("Input"));
+                    egui::ComboBox::from_id_salt("loopback_input")
+                        .selected_text(self.alias_text(RenameTarget::Ain(self.loopback_input)))
+                        .show_ui(ui, |ui| {
+                            for input in 0..inputs {
+                                let label = self.alias_text(RenameTarget::Ain(input));
+                                ui.selectable_value(&mut self.loopback_input, input, label);
+                            }
+                        });
+                    ui.end_row();
+                    ui.label(tr("Period"));
+                    egui::ComboBox::from_id_salt("loopback_period")
+                        .selected_text(trf("{} frames", &[&self.loopback_period]))
+                        .show_ui(ui, |ui| {
+                            for period in [32, 64, 128, 256, 512, 1024] {
+                                ui.selectable_value(&mut self.loopback_period, period, trf("{} frames", &[&period]));
+                            }
+                        });
+                    ui.end_row();
+                });
+                ui.separator();
+                let running = self.latency_rx.is_some() || self.sweep_rx.is_some();
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!running, egui::Button::new(tr("Measure latency"))).clicked() {
+                        measure = true;
+                    }
+                    if ui
+                        .add_enabled(!running, egui::Button::new(tr("Sweep 20 Hz-20 kHz")))
+                        .on_hover_text(tr("4 s logarithmic sine sweep at -12 dBFS, recorded at the same time"))
+                        .clicked()
+                    {
+                        sweep = true;
+                    }
+                    if running {
+                        ui.spinner();
+                    }
+                });
+                match &self.latency_result {
+                    Some(Ok(latency)) => {
+                        ui.label(
+                            RichText::new(trf(
+                                "{} samples = {} ms @ {} Hz",
+                                &[&latency.frames, &format!("{:.2}", latency.ms(latency.frames)), &latency.rate],
+                            ))
+                            .strong(),
+                        );
+                        ui.small(tr("Converters, USB and DSP, without any buffering."));
+                        ui.label(trf(
+                            "With a {}-frame period (2 per buffer): {} samples = {} ms round trip",
+                            &[
+                                &latency.period,
+                                &latency.with_buffers(),
+                                &format!("{:.2}", latency.ms(latency.with_buffers())),
+                            ],
+                        ));
+                        if !latency.linked {
+                            ui.small(tr("Streams could not be linked; lined up with their start timestamps (±1 sample)."));
+                        }
+                    }
+                    Some(Err(err)) => {
+                        ui.colored_label(Color32::from_rgb(230, 90, 80), err);
+                    }
+                    None => {}
+                }
+                match &self.sweep_result {
+                    Some(Ok(result)) => {
+                        ui.separator();
+                        ui.small(trf(
+                            "Gain from the output to the input (dB), 1/3-octave smoothing. Latency {} samples @ {} Hz.",
+                            &[&result.latency_frames, &result.rate],
+                        ));
+                        Self::paint_sweep(ui, result);
+                    }
+                    Some(Err(err)) => {
+                        ui.colored_label(Color32::from_rgb(230, 90, 80), err);
+                    }
+                    None => {}
+                }
+            });
+        if measure {
+            self.start_latency_test(ctx);
+        }
+        if sweep {
+            self.start_sweep(ctx);
+        }
+        if !open {
+            self.loopback_open = false;
+        }
+    }
+
+    fn poll_device_usage(&mut self) {
+        if let Some(usage) = self.device_usage_rx.as_ref().and_then(|rx| rx.try_iter().last()) {
+            self.device_usage = usage;
+        }
+    }
+
+    /// Warns when a control we just wrote was set to something else by
+    /// another program, e.g. a sound server restoring its own mixer state.
+    fn check_overridden_writes(&mut self) {
+        const WATCH: Duration = Duration::from_secs(2);
+        self.own_writes.retain(|_, (_, at)| at.elapsed() < WATCH);
+        let overridden = self.own_writes.iter().find_map(|(numid, (values, _))| {
+            self.controls
+                .iter()
+                .find(|c| c.numid == *numid && c.values != *values)
+        });
+        let Some(control) = overridden else {
+            return;
+        };
+        let by = match (self.device_usage.server(), self.device_usage.streams.first()) {
+            (Some(server), _) => format!("{server} may be managing this control"),
+            (None, Some(stream)) => format!("{} may be managing this control", stream.owner),
+            (None, None) => "another program is writing to the mixer".to_string(),
+        };
+        self.status_line = trf("{} was changed back right after our write — {}", &[&control.name, &by]);
+        let numid = control.numid;
+        self.own_writes.remove(&numid);
+    }
+
+    fn poll_global_hotkeys(&mut self) {
+        let Some(rx) = &self.global_hotkeys_rx else {
+            return;
+        };
+        let messages: Vec<HotkeyMessage> = rx.try_iter().collect();
+        for message in messages {
+            match message {
+                HotkeyMessage::Registered(via) => {
+                    self.status_line = trf("Global shortcuts active ({}): Ctrl+Alt+M, Ctrl+Alt+T, Ctrl+Alt+1…8", &[&via]);
+                }
+                HotkeyMessage::Failed(err) => {
+                    self.status_line = trf("Global shortcuts unavailable: {}", &[&err]);
+                    self.global_hotkeys_rx = None;
+                    return;
+                }
+                HotkeyMessage::Key(event) => match event.action {
+                    HotkeyAction::Talkback => self.global_talk_held = event.pressed,
+                    HotkeyAction::PanicMute if event.pressed => self.panic_mute(),
+                    HotkeyAction::RecallSnapshot(slot) if event.pressed => self.recall_snapshot(slot),
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    fn broadcast_osc(&mut self) {
+        let Some(osc) = self.osc.as_mut() else {
+            return;
+        };
+        if let Err(err) = osc.broadcast_changes(&self.controls) {
+            self.status_line = trf("OSC: {}", &[&err]);
+        }
+    }
+
+    fn poll_app_triggers(&mut self) {
+        let Some(rx) = &self.client_monitor_rx else {
+            return;
+        };
+        let Some(clients) = rx.try_iter().last() else {
+            return;
+        };
+        let trigger = app_triggers::pick_trigger(&self.user_config.app_triggers, &clients).cloned();
+        let desired = trigger
+            .as_ref()
+            .map(|t| t.preset.clone())
+            .or_else(|| self.user_config.app_trigger_fallback.clone().filter(|p| p.is_file()));
+        if desired == self.active_trigger_preset {
+            return;
+        }
+        self.active_trigger_preset = desired.clone();
+        let Some(path) = desired else {
+            return;
+        };
+        match self.load_preset_from(&path) {
+            Ok(()) => {
+                let reason = trigger
+                    .map(|t| trf("\"{}\" is running", &[&t.pattern]))
+                    .unwrap_or_else(|| tr("no trigger active").to_string());
+                self.status_line = format!("{} — {reason}", self.status_line);
+            }
+            Err(err) => {
+                self.status_line = trf("App trigger preset failed ({}): {}", &[&path.display(), &err]);
+            }
+        }
+    }
+
+    fn render_app_triggers_window(&mut self, ctx: &egui::Context) {
+        let Some(draft) = self.app_triggers_draft.as_mut() else {
+            return;
+        };
+        let mut open = true;
+        let mut save = false;
+        let mut cancel = false;
+        let mut remove: Option<usize> = None;
+        egui::Window::new(tr("App triggers"))
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                ui.small(
+                    "Apply a preset while a matching process or PipeWire node is present \
+                     (case-insensitive substring, first match wins).",
+                );
+                ui.separator();
+                egui::Grid::new("app_triggers_grid")
+                    .num_columns(5)
+                    .show(ui, |ui| {
+                        ui.label(RichText::new(tr("Process / node")).strong());
+                        ui.label(RichText::new("Preset").strong());
+                        ui.end_row();
+                        for (i, trigger) in draft.triggers.iter_mut().enumerate() {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut trigger.pattern)
+                                    .hint_text("ardour")
+                                    .desired_width(120.0),
+                            );
+                            ui.monospace(trigger.preset.display().to_string());
+                            if ui.button(tr("Browse…")).clicked() {
+                                if let Some(path) = FileDialog::new().pick_file() {
+                                    trigger.preset = path;
+                                }
+                            }
+                            if ui.button("✕").accessible_name(tr("Remove")).clicked() {
+                                remove = Some(i);
+                            }
+                            if !trigger.is_armed() {
+                                ui.weak(tr("inactive"))
+                                    .on_hover_text(tr("Needs a pattern and an existing preset file"));
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if ui.button(tr("Add trigger")).clicked() {
+                    draft.triggers.push(AppTrigger::default());
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label(tr("When nothing matches:"));
+                    let fallback = draft
+                        .fallback
+                        .as_ref()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_else(|| tr("keep current state").to_string());
+                    ui.monospace(fallback);
+                    if ui.button(tr("Browse…")).clicked() {
+                        if let Some(path) = FileDialog::new().pick_file() {
+                            draft.fallback = Some(path);
+                        }
+                    }
+                    if draft.fallback.is_some() && ui.button(tr("Clear")).clicked() {
+                        draft.fallback = None;
+                    }
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    save = ui.button(tr("Save")).clicked();
+                    cancel = ui.button(tr("Cancel")).clicked();
+                });
+            });
+        if let Some(i) = remove {
+            draft.triggers.remove(i);
+        }
+        if save {
+            let draft = self.app_triggers_draft.take().unwrap_or_default();
+            self.user_config.app_triggers = draft.triggers;
+            self.user_config.app_trigger_fallback = draft.fallback;
+            self.save_user_config();
+            self.active_trigger_preset = None;
+            if self.client_monitor_rx.is_none() && !self.user_config.app_triggers.is_empty() {
+                self.client_monitor_rx =
+                    Some(app_triggers::start_client_monitor(self.backend.card_index));
+            }
+        } else if cancel || !open {
+            self.app_triggers_draft = None;
+        }
+    }
+
+    fn render_preset_save_window(&mut self, ctx: &egui::Context) {
+        let Some(mut draft) = self.preset_save_draft.take() else {
+            return;
+        };
+        let mut open = true;
+        let mut save = false;
+        let mut cancel = false;
+        egui::Window::new(tr("Save preset"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("preset_save_metadata_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label(tr("Name"));
+                        ui.text_edit_singleline(&mut draft.name);
+                        ui.end_row();
+                        ui.label(tr("Author"));
+                        ui.text_edit_singleline(&mut draft.author);
+                        ui.end_row();
+                        ui.label(tr("Tags"));
+                        ui.add(
+                            egui::TextEdit::singleline(&mut draft.tags)
+                                .hint_text(tr("tracking, headphones, ...")),
+                        );
+                        ui.end_row();
+                        ui.label(tr("Description"));
+                        ui.text_edit_multiline(&mut draft.description);
+                        ui.end_row();
+                    });
+                ui.separator();
+                ui.label(RichText::new(tr("When applying")).strong());
+                egui::Grid::new("preset_save_policy_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("");
+                        ui.checkbox(&mut draft.mute_first, tr("Mute routes first, then set"));
+                        ui.end_row();
+                        ui.label(tr("Ramp"));
+                        ui.add(
+                            egui::DragValue::new(&mut draft.ramp_ms)
+                                .range(0..=5000)
+                                .suffix(" ms"),
+                        );
+                        ui.end_row();
+                        ui.label(tr("Never touch"));
+                        ui.add(
+                            egui::TextEdit::multiline(&mut draft.exclude)
+                                .hint_text(tr("One control name per line"))
+                                .desired_rows(2),
+                        );
+                        ui.end_row();
+                    });
+                ui.horizontal(|ui| {
+                    save = ui.button(tr("Save…")).clicked();
+                    cancel = ui.button(tr("Cancel")).clicked();
+                });
+            });
+        if save && self.save_preset_with_draft(&draft) {
+            return;
+        }
+        if open && !cancel {
+            self.preset_save_draft = Some(draft);
+        }
+    }
+
+    fn render_preset_library_window(&mut self, ctx: &egui::Context) {
+        if !self.preset_library_open {
+            return;
+        }
+        let mut open = true;
+        let mut pick_folder = false;
+        let mut rescan = false;
+        let mut save_config = false;
+        let mut pick_hot_folder = false;
+        let mut stop_hot_folder = false;
+        let mut to_load: Option<PathBuf> = None;
+        let mut to_preview: Option<PathBuf> = None;
+        egui::Window::new(tr("Preset library"))
+            .open(&mut open)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let folder = self
+                        .user_config
+                        .preset_library_dir
+                        .as_ref()
+                        .map(|d| d.display().to_string())
+                        .unwrap_or_else(|| tr("(no folder selected)").to_string());
+                    ui.label(RichText::new(folder).monospace());
+                    pick_folder = ui.button(tr("Choose folder…")).clicked();
+                    rescan = ui.button(tr("Rescan")).clicked();
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr("Hot folder:"));
+                    let watched = self
+                        .hot_folder
+                        .as_ref()
+                        .map(|w| w.dir().display().to_string())
+                        .unwrap_or_else(|| tr("off").to_string());
+                    ui.label(RichText::new(watched).monospace());
+                    pick_hot_folder = ui.button(tr("Watch folder…")).clicked();
+                    if self.hot_folder.is_some() && ui.button(tr("Stop")).clicked() {
+                        stop_hot_folder = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr("Preset from another card:"));
+                    let before = self.user_config.preset_card_check;
+                    egui::ComboBox::from_id_salt("preset_card_check")
+                        .selected_text(before.label())
+                        .show_ui(ui, |ui| {
+                            for mode in PresetCardCheck::ALL {
+                                ui.selectable_value(&mut self.user_config.preset_card_check, mode, mode.label());
+                            }
+                        });
+                    if self.user_config.preset_card_check != before {
+                        save_config = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr("Filter:"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.preset_library_filter)
+                            .hint_text(tr("name, tag, author…")),
+                    );
+                });
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    let visible: Vec<(usize, &PresetLibraryEntry)> = self
+                        .preset_library
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, e)| e.matches(&self.preset_library_filter))
+                        .collect();
+                    if visible.is_empty() {
+                        ui.label(tr("No presets found."));
+                    }
+                    let show_programs = self.user_config.midi_presets.enabled;
+                    for (position, entry) in visible {
+                        ui.horizontal(|ui| {
+                            if ui.button(tr("Load")).clicked() {
+                                to_load = Some(entry.path.clone());
+                            }
+                            if ui.button(tr("Preview")).clicked() {
+                                to_preview = Some(entry.path.clone());
+                            }
+                            ui.vertical(|ui| {
+                                ui.horizontal(|ui| {
+                                    ui.label(RichText::new(entry.display_name()).strong());
+                                    if show_programs && position < 128 {
+                                        ui.small(format!("PC {position}"))
+                                            .on_hover_text(tr("MIDI Program Change that loads it, unless mapped in the config"));
+                                    }
+                                });
+                                let meta = &entry.metadata;
+                                let mut details = Vec::new();
+                                if let Some(author) = &meta.author {
+                                    details.push(trf("by {}", &[author]));
+                                }
+                                if let Some(created) = &meta.created {
+                                    details.push(created.clone());
+                                }
+                                details.push(trf("{} ({} controls)", &[&entry.card_name, &entry.control_count]));
+                                ui.small(details.join(" · "));
+                                if !meta.tags.is_empty() {
+                                    ui.small(trf("Tags: {}", &[&meta.tags.join(", ")]));
+                                }
+                                if let Some(description) = &meta.description {
+                                    ui.label(description);
+                                }
+                            });
+                        });
+                        ui.separator();
+                    }
+                });
+            });
+        self.preset_library_open = open;
+        if pick_hot_folder {
+            if let Some(dir) = FileDialog::new().pick_folder() {
+                self.hot_folder = Some(HotFolderWatcher::new(dir.clone()));
+                self.user_config.hot_folder_dir = Some(dir);
+                save_config = true;
+            }
+        }
+        if stop_hot_folder {
+            self.hot_folder = None;
+            self.user_config.hot_folder_dir = None;
+            save_config = true;
+        }
+        if save_config {
+            self.save_user_config();
+        }
+
+        if pick_folder {
+            let mut dialog = FileDialog::new();
+            if let Some(dir) = &self.user_config.preset_library_dir {
+                dialog = dialog.set_directory(dir);
+            }
+            if let Some(dir) = dialog.pick_folder() {
+                self.user_config.preset_library_dir = Some(dir);
+                self.save_user_config();
+                rescan = true;
+            }
+        }
+        if rescan {
+            self.rescan_preset_library();
+        }
+        if let Some(path) = to_load {
+            if let Err(err) = self.load_preset_from(&path) {
+                self.status_line = trf("Load failed: {}", &[&err]);
+            }
+        }
+        if let Some(path) = to_preview {
+            self.open_preset_preview(path);
+        }
+    }
+
+    fn open_preset_preview(&mut self, path: PathBuf) {
+        match presets::load_preset(&path) {
+            Ok(preset) => self.preset_preview = Some((path, preset)),
+            Err(err) => self.status_line = trf("Preview failed: {}", &[&err]),
+        }
+    }
+
+    /// Shows a preset with the regular matrix and FX widgets, disabled, by
+    /// rendering against a copy of the control list carrying the preset values.
+    fn render_preset_preview_window(&mut self, ctx: &egui::Context) {
+        let Some((path, preset)) = self.preset_preview.take() else {
+            return;
+        };
+        let mut preview_controls = self.controls.clone();
+        let by_numid: HashMap<u32, &Vec<String>> =
+            preset.controls.iter().map(|c| (c.numid, &c.values)).collect();
+        for control in &mut preview_controls {
+            if let Some(values) = by_numid.get(&control.numid) {
+                control.values = (*values).clone();
+            }
+        }
+        let mismatch =
+            presets::card_mismatch(&preset, &self.backend.card_identity, self.controls.len());
+
+        let diagram = self.routing_diagram(&preview_controls);
+        let fx = FxModel::from_controls(&preview_controls);
+
+        let mut open = true;
+        let mut load = false;
+        egui::Window::new(trf("Preview: {}", &[&Self::preset_label(&preset, &path)]))
+            .id(egui::Id::new("preset_preview_window"))
+            .open(&mut open)
+            .default_size(vec2(900.0, 620.0))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    load = ui.button(tr("Load this preset")).clicked();
+                    ui.small(trf(
+                        "{} · {} controls · {}",
+                        &[&preset.card_name, &preset.controls.len(), &path.display()],
+                    ));
+                });
+                if let Some(description) = &preset.metadata.description {
+                    ui.label(description);
+                }
+                if let Some(reason) = &mismatch {
+                    ui.colored_label(Color32::from_rgb(240, 190, 90), format!("⚠ {reason}"));
+                }
+                ui.separator();
+                // Drawn from the preview copy with plain labels: nothing here
+                // can reach the card or the live matrix state.
+                egui::ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
+                    for (n, grid) in diagram.grids.iter().enumerate() {
+                        self.render_preview_grid(ui, n, grid);
+                        ui.separator();
+                    }
+                    self.render_preview_fx(ui, &preview_controls, &fx);
+                });
+            });
+
+        if load {
+            if let Err(err) = self.load_preset_from(&path) {
+                self.status_line = trf("Load failed: {}", &[&err]);
+            }
+        } else if open {
+            self.preset_preview = Some((path, preset));
+        }
+    }
+
+    fn render_preview_grid(&self, ui: &mut egui::Ui, n: usize, grid: &DiagramGrid) {
+        ui.label(RichText::new(&grid.title).strong());
+        let (cell_w, _) = self.cell_size();
+        egui::Grid::new(("preset_preview_grid", n)).striped(true).show(ui, |ui| {
+            ui.label("");
+            for column in &grid.columns {
+                ui.label(RichText::new(column).small());
+            }
+            ui.end_row();
+            for row in &grid.rows {
+                ui.label(&row.label);
+                for cell in &row.cells {
+                    let Some(cell) = cell else {
+                        ui.label("-");
+                        continue;
+                    };
+                    let fill = self.palette.accent.gamma_multiply(0.15 + 0.6 * cell.level.clamp(0.0, 1.0));
+                    egui::Frame::new()
+                        .fill(if cell.level > 0.0 { fill } else { Color32::TRANSPARENT })
+                        .corner_radius(4.0)
+                        .inner_margin(egui::Margin::symmetric(4, 2))
+                        .show(ui, |ui| {
+                            ui.set_min_width(cell_w - 8.0);
+                            ui.label(RichText::new(&cell.text).small());
+                        });
+                }
+                ui.end_row();
+            }
+        });
+    }
+
+    fn render_preview_fx(&self, ui: &mut egui::Ui, controls: &[ControlDescriptor], fx: &FxModel) {
+        ui.label(RichText::new(tr("Effects (FX)")).strong());
+        if fx.is_empty() {
+            ui.label(tr("No FX control found on this card."));
+            return;
+        }
+        let indices = [fx.program, fx.volume, fx.duration, fx.feedback]
+            .into_iter()
+            .flatten()
+            .chain(fx.other.iter().copied());
+        egui::Grid::new("preset_preview_fx").striped(true).show(ui, |ui| {
+            for idx in indices {
+                let Some(control) = controls.get(idx) else {
+                    continue;
+                };
+                ui.label(&control.name);
+                let value = match Self::route_level_of(control) {
+                    Some(level) => Self::level_text(&level),
+                    None => control.values.join(", "),
+                };
+                ui.label(value);
+                ui.end_row();
+            }
+        });
+    }
+
+    fn render_quick_actions(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            if ui.button(tr("Mute Analog Monitoring")).clicked() {
+                self.mute_hardware_routes();
+            }
+            if ui.button(tr("Pass-through Analog Monitoring to Channel 1/2")).clicked() {
+                self.pass_through_inputs();
+            }
+            if ui.button(tr("Disable FX")).clicked() {
+                self.disable_fx_controls();
+            }
+            if ui.button(tr("Mute most digital routes")).clicked() {
+                self.mute_most_digital_routes();
+            }
+            if ui.button(tr("Mute All Monitoring")).clicked() {
+                self.panic_mute();
+            }
+            if ui
+                .toggle_value(&mut self.user_config.metering.inputs, tr("Input meters"))
+                .on_hover_text(tr("Peak meters on the AIn headers, read from the capture PCM"))
+                .changed()
+            {
+                if self.user_config.metering.inputs {
+                    self.start_input_meters(ui.ctx());
+                } else {
+                    self.input_tap = None;
+                    self.input_meters.clear();
+                }
+                self.save_user_config();
+            }
+            if ui
+                .toggle_value(&mut self.user_config.metering.outputs, tr("Output meters"))
+                .on_hover_text(tr("Peak meters on the Out headers: what PipeWire sends to each playback channel"))
+                .changed()
+            {
+                if self.user_config.metering.outputs {
+                    self.start_output_meters(ui.ctx());
+                } else {
+                    self.output_tap = None;
+                    self.output_meters.clear();
+                }
+                self.save_user_config();
+            }
+            if ui
+                .toggle_value(&mut self.user_config.metering.bridge, tr("Meter bridge"))
+                .on_hover_text(tr("Floating window with the meters and output mutes, kept on top of other windows"))
+                .changed()
+            {
+                self.save_user_config();
+            }
+            if (self.input_tap.is_some() || self.output_tap.is_some())
+                && ui
+                    .toggle_value(&mut self.user_config.metering.leds, "LEDs")
+                    .on_hover_text(tr("Signal LEDs in the headers instead of bars, refreshed 4 times a second"))
+                    .changed()
+            {
+                // The taps wake the UI at the rate chosen when they start.
+                if self.input_tap.take().is_some() {
+                    self.start_input_meters(ui.ctx());
+                }
+                if self.output_tap.take().is_some() {
+                    self.start_output_meters(ui.ctx());
+                }
+                self.save_user_config();
+            }
+            if (self.input_tap.is_some() || self.output_tap.is_some()) && !self.user_config.metering.leds {
+                let mode = &mut self.user_config.metering.mode;
+                let before = *mode;
+                egui::ComboBox::from_id_salt("meter_mode")
+                    .selected_text(mode.label())
+                    .width(70.0)
+                    .show_ui(ui, |ui| {
+                        for option in MeterMode::ALL {
+                            ui.selectable_value(mode, option, option.label());
+                        }
+                    })
+                    .response
+                    .on_hover_text(tr("Peak (dBFS), RMS over 300 ms (dBFS) or short-term loudness per channel (LUFS, 3 s)"));
+                if *mode != before {
+                    self.save_user_config();
+                }
+            }
+            if self.input_tap.is_some() || self.output_tap.is_some() {
+                let mut save = false;
+                ui.menu_button("⚙", |ui| {
+                    let config = &mut self.user_config.metering;
+                    let mut slider = |ui: &mut egui::Ui, label: &'static str, slider: egui::Slider| {
+                        ui.horizontal(|ui| {
+                            ui.label(tr(label));
+                            let resp = ui.add(slider);
+                            save |= resp.drag_stopped() || (resp.changed() && !resp.dragged());
+                        });
+                    };
+                    slider(
+                        ui,
+                        "Attack",
+                        egui::Slider::new(&mut config.attack_ms, 0.0..=300.0).suffix(" ms").max_decimals(0),
+                    );
+                    slider(
+                        ui,
+                        "Release",
+                        egui::Slider::new(&mut config.release_db_per_sec, 1.0..=60.0)
+                            .suffix(" dB/s")
+                            .max_decimals(0),
+                    );
+                    slider(
+                        ui,
+                        "Peak hold",
+                        egui::Slider::new(&mut config.peak_hold_ms, 0..=5000).suffix(" ms"),
+                    );
+                    slider(
+                        ui,
+                        "Reference",
+                        egui::Slider::new(&mut config.reference_dbfs, -30.0..=-6.0)
+                            .suffix(" dBFS")
+                            .max_decimals(0),
+                    );
+                    ui.small(tr("Attack and release shape the peak bars; the reference is marked on every bar and sets where they turn yellow."));
+                })
+                .response
+                .on_hover_text(tr("Meter ballistics and reference level"));
+                if save {
+                    self.save_user_config();
+                }
+            }
+            if ui
+                .toggle_value(&mut self.user_config.compact_matrix, tr("Compact matrix"))
+                .on_hover_text(tr("Show matrix cells as dB/percent numbers instead of knobs"))
+                .changed()
+            {
+                self.save_user_config();
+            }
+            ui.label(tr("Cells:"));
+            let mut layout_changed = false;
+            for density in MatrixDensity::ALL {
+                layout_changed |= ui
+                    .selectable_value(&mut self.user_config.matrix_density, density, density.label())
+                    .changed();
+            }
+            let zoom = ui.add(
+                egui::Slider::new(&mut self.user_config.matrix_zoom, 0.5..=2.0)
+                    .text("Zoom")
+                    .fixed_decimals(2),
+            );
+            layout_changed |= zoom.drag_stopped() || (zoom.changed() && !zoom.dragged());
+            if layout_changed {
+                self.save_user_config();
+            }
+            let hidden = self.hidden_count();
+            if ui
+                .add_enabled(
+                    hidden > 0,
+                    egui::Button::new(trf("Show all ({} hidden)", &[&hidden]))
+                        .selected(self.user_config.show_hidden),
+                )
+                .on_hover_text(tr("Right-click a row or column header, or a cell, to hide it"))
+                .clicked()
+            {
+                self.user_config.show_hidden = !self.user_config.show_hidden;
+                self.save_user_config();
+            }
+            ui.toggle_value(&mut self.paint_mode, tr("Paint mode")).on_hover_text(
+                tr("Drag across a row to copy the first cell's level onto every cell touched; right-drag clears them"),
+            );
+            if ui.button(tr("Reset aliases")).clicked() {
+                self.user_config.ain_aliases.clear();
+                self.user_config.din_aliases.clear();
+                self.user_config.out_aliases.clear();
+                self.rename_target = None;
+                self.rename_buffer.clear();
+                self.save_user_config();
+            }
+        });
+    }
+
+    fn render_mix_routing_tab(&mut self, ui: &mut egui::Ui) {
+        egui::Frame::new()
+            .fill(self.palette.raised)
+            .stroke(Stroke::new(1.0, self.palette.stroke))
+            .inner_margin(egui::Margin::symmetric(8, 6))
+            .show(ui, |ui| {
+                ui.label(RichText::new(tr("Quick actions")).strong());
+                self.render_quick_actions(ui);
+                self.render_stereo_links(ui);
+                self.render_talkback(ui);
+                self.render_scene_crossfader(ui);
+            });
+
+        ui.add_space(6.0);
+        ui.columns(2, |cols| {
+            egui::Frame::new()
+                .fill(self.palette.section)
+                .stroke(Stroke::new(1.0, self.palette.stroke))
+                .inner_margin(egui::Margin::symmetric(8, 6))
+                .show(&mut cols[0], |ui| {
+                    ui.label(RichText::new(tr("Analog monitoring")).strong().size(14.0));
+                    ui.horizontal(|ui| {
+                        ui.small("AIn -> Out");
+                        self.render_transpose_toggle(ui, MatrixBus::Analog);
+                        if self.render_cell_widget_picker(ui, MatrixGrid::Monitoring) {
+                            self.save_user_config();
+                        }
+                    });
+                    ui.separator();
+                    self.render_route_matrix(ui, MatrixBus::Analog);
+                });
+
+            egui::Frame::new()
+                .fill(self.palette.section)
+                .stroke(Stroke::new(1.0, self.palette.stroke))
+                .inner_margin(egui::Margin::symmetric(8, 6))
+                .show(&mut cols[1], |ui| {
+                    ui.label(RichText::new(tr("Digital routing")).strong().size(14.0));
+                    ui.horizontal(|ui| {
+                        ui.small("DIn -> Out");
+                        self.render_transpose_toggle(ui, MatrixBus::Digital);
+                        if self.render_cell_widget_picker(ui, MatrixGrid::DigitalRoutes) {
+                            self.save_user_config();
+                        }
+                    });
+                    ui.separator();
+                    self.render_route_matrix(ui, MatrixBus::Digital);
+                });
+        });
+
+        let returns = fx::return_map(&self.controls);
+        if returns.is_empty() {
+            return;
+        }
+        ui.add_space(6.0);
+        let mut actions: Vec<CellAction> = Vec::new();
+        egui::Frame::new()
+            .fill(self.palette.section)
+            .stroke(Stroke::new(1.0, self.palette.stroke))
+            .inner_margin(egui::Margin::symmetric(8, 6))
+            .show(ui, |ui| {
+                ui.label(RichText::new(tr("FX → Outputs")).strong().size(14.0));
+                ui.small(tr("Effect Return level on each output, e.g. to keep reverb out of a cue mix"));
+                let count = returns.len();
+                ui.horizontal_wrapped(|ui| {
+                    for (key, idx) in returns {
+                        let outputs = self.fx_return_outputs(key, count);
+                        let hidden = outputs.iter().all(|o| self.is_hidden(RenameTarget::Out(*o)));
+                        if (hidden && !self.user_config.show_hidden) || !self.is_control_shown(idx) {
+                            continue;
+                        }
+                        let label = self.fx_return_label(&outputs);
+                        self.render_fx_strip(ui, label, idx, &mut actions);
+                    }
+                });
+            });
+        self.run_cell_actions(actions);
+    }
+
+    fn link_pairs(&self, target: RenameTarget) -> &BTreeSet<usize> {
+        match target {
+            RenameTarget::Ain(_) => &self.user_config.linked_ain_pairs,
+            RenameTarget::Din(_) => &self.user_config.linked_din_pairs,
+            RenameTarget::Out(_) => &self.user_config.linked_out_pairs,
+        }
+    }
+
+    fn linked_partner(&self, target: RenameTarget) -> Option<RenameTarget> {
+        let (i, make): (usize, fn(usize) -> RenameTarget) = match target {
+            RenameTarget::Ain(i) => (i, RenameTarget::Ain),
+            RenameTarget::Din(i) => (i, RenameTarget::Din),
+            RenameTarget::Out(i) => (i, RenameTarget::Out),
+        };
+        self.link_pairs(target).contains(&(i / 2)).then(|| make(i ^ 1))
+    }
+
+    /// Second channel of a linked pair, hidden when linked pairs render as one cell.
+    fn is_merged_partner(&self, target: RenameTarget) -> bool {
+        let i = match target {
+            RenameTarget::Ain(i) | RenameTarget::Din(i) | RenameTarget::Out(i) => i,
+        };
+        self.user_config.merge_linked_cells && i % 2 == 1 && self.linked_partner(target).is_some()
+    }
+
+    fn hidden_set(&self, target: RenameTarget) -> (&BTreeSet<usize>, usize) {
+        match target {
+            RenameTarget::Ain(i) => (&self.user_config.hidden_ain, i),
+            RenameTarget::Din(i) => (&self.user_config.hidden_din, i),
+            RenameTarget::Out(i) => (&self.user_config.hidden_out, i),
+        }
+    }
+
+    fn is_hidden(&self, target: RenameTarget) -> bool {
+        let (set, i) = self.hidden_set(target);
+        set.contains(&i)
+    }
+
+    fn toggle_hidden(&mut self, target: RenameTarget) {
+        let (set, i) = match target {
+            RenameTarget::Ain(i) => (&mut self.user_config.hidden_ain, i),
+            RenameTarget::Din(i) => (&mut self.user_config.hidden_din, i),
+            RenameTarget::Out(i) => (&mut self.user_config.hidden_out, i),
+        };
+        let hidden = !set.remove(&i);
+        if hidden {
+            set.insert(i);
+        }
+        self.status_line = trf(if hidden { "{} hidden" } else { "{} shown" }, &[&self.alias_text(target)]);
+        self.save_user_config();
+    }
+
+    fn hidden_count(&self) -> usize {
+        self.user_config.hidden_ain.len()
+            + self.user_config.hidden_din.len()
+            + self.user_config.hidden_out.len()
+            + self.user_config.hidden_controls.len()
+    }
+
+    fn is_control_hidden(&self, idx: usize) -> bool {
+        self.controls
+            .get(idx)
+            .is_some_and(|c| self.user_config.hidden_controls.contains(&c.key()))
+    }
+
+    /// False for a hidden control, unless "Show all" is on.
+    fn is_control_shown(&self, idx: usize) -> bool {
+        self.user_config.show_hidden || !self.is_control_hidden(idx)
+    }
+
+    fn toggle_control_hidden(&mut self, idx: usize) {
+        let Some((name, key)) = self.controls.get(idx).map(|c| (c.name.clone(), c.key())) else {
+            return;
+        };
+        let hidden = !self.user_config.hidden_controls.remove(&key);
+        if hidden {
+            self.user_config.hidden_controls.insert(key);
+        }
+        self.save_user_config();
+        self.status_line = trf(if hidden { "{} hidden" } else { "{} shown" }, &[&name]);
+    }
+
+    fn visible_axis(&self, max: usize, make: fn(usize) -> RenameTarget) -> Vec<usize> {
+        (0..=max)
+            .filter(|i| !self.is_merged_partner(make(*i)))
+            .filter(|i| self.user_config.show_hidden || !self.is_hidden(make(*i)))
+            .collect()
+    }
+
+    /// Routes that follow `control_index` through input and/or output links.
+    fn linked_route_partners(&self, control_index: usize) -> Vec<usize> {
+        if self.controls.get(control_index).is_some_and(fx::is_fx_return) {
+            return self.linked_fx_return_partner(control_index).into_iter().collect();
+        }
+        let (routes, make_input): (&[RouteRef], fn(usize) -> RenameTarget) = if self
+            .routing_index
+            .analog_routes
+            .iter()
+            .any(|r| r.control_index == control_index)
+        {
+            (&self.routing_index.analog_routes, RenameTarget::Ain)
+        } else {
+            (&self.routing_index.digital_routes, RenameTarget::Din)
+        };
+        let Some(route) = routes.iter().find(|r| r.control_index == control_index) else {
+            return Vec::new();
+        };
+        let input_partner = self.linked_partner(make_input(route.input)).map(|_| route.input ^ 1);
+        let output_partner = self
+            .linked_partner(RenameTarget::Out(route.output))
+            .map(|_| route.output ^ 1);
+        let mut pairs = Vec::new();
+        if let Some(pi) = input_partner {
+            pairs.push((pi, route.output));
+        }
+        if let Some(po) = output_partner {
+            pairs.push((route.input, po));
+        }
+        if let (Some(pi), Some(po)) = (input_partner, output_partner) {
+            pairs.push((pi, po));
+        }
+        pairs
+            .into_iter()
+            .filter_map(|(input, output)| {
+                routes
+                    .iter()
+                    .find(|r| r.input == input && r.output == output)
+                    .map(|r| r.control_index)
+            })
+            .collect()
+    }
+
+    /// The other return of a linked pair of per-output FX returns.
+    fn linked_fx_return_partner(&self, control_index: usize) -> Option<usize> {
+        let returns = fx::return_map(&self.controls);
+        let key = returns
+            .iter()
+            .find(|(_, idx)| **idx == control_index)
+            .map(|(key, _)| *key)?;
+        if !self.user_config.linked_fx_return_pairs.contains(&(key / 2)) {
+            return None;
+        }
+        returns.get(&(key ^ 1)).copied()
+    }
+
+    /// Writes a route and moves its stereo-linked partners by the same amount.
+    fn apply_route_values(&mut self, control_index: usize, values: Vec<String>) {
+        let first_int = |values: &[String]| values.first().and_then(|v| v.parse::<i64>().ok());
+        let old = self
+            .controls
+            .get(control_index)
+            .and_then(|c| first_int(&c.values));
+        let new = first_int(&values);
+        self.apply_values_to_control(control_index, values);
+        let (Some(old), Some(new)) = (old, new) else {
+            return;
+        };
+        let delta = new - old;
+        if delta == 0 {
+            return;
+        }
+        for partner in self.linked_route_partners(control_index) {
+            let Some(current) = self.controls.get(partner).and_then(|c| first_int(&c.values)) else {
+                continue;
+            };
+            self.apply_integer_route(partner, current + delta);
+        }
+    }
+
+    fn render_stereo_links(&mut self, ui: &mut egui::Ui) {
+        let max_ain = self.routing_index.analog_routes.iter().map(|r| r.input + 1).max().unwrap_or(0);
+        let max_din = self.routing_index.digital_routes.iter().map(|r| r.input + 1).max().unwrap_or(0);
+        let max_out = self.output_count();
+        // Returns that already feed an output pair each have nothing to link.
+        let fx_returns = fx::return_map(&self.controls).len();
+        let fx_returns = if fx_returns * 2 <= max_out { 0 } else { fx_returns };
+        let mut changed = false;
+        ui.horizontal_wrapped(|ui| {
+            ui.label(tr("Stereo links:"));
+            for (prefix, count, set) in [
+                ("AIn", max_ain, &mut self.user_config.linked_ain_pairs),
+                ("DIn", max_din, &mut self.user_config.linked_din_pairs),
+                ("Out", max_out, &mut self.user_config.linked_out_pairs),
+                ("FX Ret", fx_returns, &mut self.user_config.linked_fx_return_pairs),
+            ] {
+                for pair in 0..count / 2 {
+                    let mut linked = set.contains(&pair);
+                    let label = format!("{prefix}{}+{}", pair * 2 + 1, pair * 2 + 2);
+                    if ui.toggle_value(&mut linked, label).changed() {
+                        if linked {
+                            set.insert(pair);
+                        } else {
+                            set.remove(&pair);
+                        }
+                        changed = true;
+                    }
+                }
+                ui.separator();
+            }
+            changed |= ui
+                .checkbox(&mut self.user_config.merge_linked_cells, tr("Merge linked pairs"))
+                .changed();
+        });
+        if changed {
+            self.save_user_config();
+        }
+    }
+
+    fn render_talkback(&mut self, ui: &mut egui::Ui) {
+        let max_ain = self.routing_index.analog_routes.iter().map(|r| r.input + 1).max().unwrap_or(0);
+        let max_out = self.output_count();
+        let mut changed = false;
+        ui.horizontal_wrapped(|ui| {
+            ui.label(tr("Talkback:"));
+            let selected = self
+                .user_config
+                .talkback
+                .input
+                .map(|i| self.alias_text(RenameTarget::Ain(i)))
+                .unwrap_or_else(|| "—".to_string());
+            let mut input = self.user_config.talkback.input;
+            egui::ComboBox::from_id_salt("talkback_input")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut input, None, "—");
+                    for i in 0..max_ain {
+                        ui.selectable_value(&mut input, Some(i), self.alias_text(RenameTarget::Ain(i)));
+                    }
+                });
+            if input != self.user_config.talkback.input {
+                self.user_config.talkback.input = input;
+                changed = true;
+            }
+            ui.label("→");
+            for output in 0..max_out {
+                let mut cue = self.user_config.talkback.cue_outputs.contains(&output);
+                if ui
+                    .toggle_value(&mut cue, self.alias_text(RenameTarget::Out(output)))
+                    .changed()
+                {
+                    if cue {
+                        self.user_config.talkback.cue_outputs.insert(output);
+                    } else {
+                        self.user_config.talkback.cue_outputs.remove(&output);
+                    }
+                    changed = true;
+                }
+            }
+            let level = ui.add(
+                egui::DragValue::new(&mut self.user_config.talkback.level_db)
+                    .range(-40.0..=6.0)
+                    .speed(0.2)
+                    .fixed_decimals(1)
+                    .suffix(" dB"),
+            );
+            changed |= level.drag_stopped() || (level.changed() && !level.dragged());
+            changed |= ui
+                .checkbox(&mut self.user_config.talkback.dim_others, tr("Dim the rest"))
+                .changed();
+            let active = self.talkback_saved.is_some();
+            let text = RichText::new(tr("TALK (T)")).strong();
+            let text = if active {
+                text.color(Color32::from_rgb(240, 90, 90))
+            } else {
+                text
+            };
+            let resp = ui
+                .add(egui::Button::new(text).selected(active))
+                .on_hover_text(tr("Hold to talk (or hold T)"));
+            if resp.is_pointer_button_down_on() {
+                self.talk_button_held = true;
+            }
+        });
+        if changed {
+            self.save_user_config();
+        }
+    }
+
+    fn render_scene_crossfader(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label(tr("Scenes:"));
+            for (slot, name) in ["A", "B"].into_iter().enumerate() {
+                let stored = self.scenes[slot].is_some();
+                let text = RichText::new(trf("Store {}", &[&name]));
+                let text = if stored {
+                    text.color(Color32::from_rgb(90, 220, 220))
+                } else {
+                    text
+                };
+                if ui
+                    .button(text)
+                    .on_hover_text(trf("Save the current route levels as scene {}", &[&name]))
+                    .clicked()
+                {
+                    self.store_scene(slot);
+                }
+                if slot == 0 {
+                    let ready = self.scenes.iter().all(Option::is_some);
+                    let fader = ui
+                        .add_enabled(
+                            ready,
+                            egui::Slider::new(&mut self.crossfade, 0.0..=1.0)
+                                .show_value(false)
+                                .text(""),
+                        )
+                        .on_hover_text(tr("Crossfade every route level from scene A to scene B"));
+                    if fader.drag_started() {
+                        let indices: Vec<usize> = self
+                            .scene_targets(self.crossfade)
+                            .into_iter()
+                            .map(|(idx, _)| idx)
+                            .collect();
+                        self.push_undo(&indices);
+                    }
+                    if fader.changed() {
+                        self.apply_crossfade();
+                    }
+                }
+            }
+        });
+    }
+
+    fn store_scene(&mut self, slot: usize) {
+        let levels: BTreeMap<String, i64> = self
+            .routing_index
+            .analog_routes
+            .iter()
+            .chain(&self.routing_index.digital_routes)
+            .filter_map(|r| {
+                let level = self.integer_route_level(r.control_index)?;
+                Some((self.controls[r.control_index].name.clone(), level.raw))
+            })
+            .collect();
+        let count = levels.len();
+        self.scenes[slot] = Some(levels);
+        self.crossfade = slot as f32;
+        self.status_line = trf("Scene {} stored ({} routes)", &[&["A", "B"][slot], &count]);
+    }
+
+    /// Route levels at crossfader position `t`, interpolated by amplitude so
+    /// the fade sounds even; routes missing from either scene are left alone.
+    fn scene_targets(&self, t: f32) -> Vec<(usize, i64)> {
+        let [Some(a), Some(b)] = &self.scenes else {
+            return Vec::new();
+        };
+        let t = t.clamp(0.0, 1.0) as f64;
+        self.controls
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, control)| {
+                let (raw_a, raw_b) = (*a.get(&control.name)?, *b.get(&control.name)?);
+                let level = self.integer_route_level(idx)?;
+                if t == 0.0 || t == 1.0 {
+                    return Some((idx, if t == 0.0 { raw_a } else { raw_b }));
+                }
+                let amp = |raw| pan::amplitude_from_raw(raw, level.min, level.max, level.db_range);
+                let mixed = amp(raw_a) * (1.0 - t) + amp(raw_b) * t;
+                Some((idx, pan::raw_from_amplitude(mixed, level.min, level.max, level.db_range)))
+            })
+            .collect()
+    }
+
+    fn apply_crossfade(&mut self) {
+        let targets = self.scene_targets(self.crossfade);
+        self.apply_group(targets, false);
+        self.status_line = trf("Crossfade A→B: {}%", &[&format!("{:.0}", self.crossfade * 100.0)]);
+    }
+
+    /// Engages talkback while `held`, releases it (restoring every touched route) otherwise.
+    fn update_talkback(&mut self, held: bool) {
+        match (held, self.talkback_saved.is_some()) {
+            (true, false) => {
+                self.engage_talkback();
+                self.run_script(|script, controls| script.on_talkback(controls, true));
+            }
+            (false, true) => {
+                for (idx, values) in self.talkback_saved.take().unwrap_or_default() {
+                    self.apply_values_to_control(idx, values);
+                }
+                self.status_line = tr("Talkback off").to_string();
+                self.run_script(|script, controls| script.on_talkback(controls, false));
+            }
+            _ => {}
+        }
+    }
+
+    fn engage_talkback(&mut self) {
+        let talkback = self.user_config.talkback.clone();
+        let Some(input) = talkback.input else {
+            self.status_line = tr("Talkback: choose an input first").to_string();
+            // Mark as engaged so the message is not repeated every frame.
+            self.talkback_saved = Some(Vec::new());
+            return;
+        };
+        let dim = 10f64.powf(self.user_config.dim_db.min(0.0) / 20.0);
+        let talk_amp = 10f64.powf(talkback.level_db / 20.0);
+        let mut saved = Vec::new();
+        let mut targets = Vec::new();
+        for (analog, routes) in [
+            (true, &self.routing_index.analog_routes),
+            (false, &self.routing_index.digital_routes),
+        ] {
+            for route in routes.iter().filter(|r| talkback.cue_outputs.contains(&r.output)) {
+                let idx = route.control_index;
+                let Some(level) = self.integer_route_level(idx) else {
+                    continue;
+                };
+                let raw = if analog && route.input == input {
+                    level.raw_at_db(talkback.level_db)
+                        .unwrap_or_else(|| {
+                            pan::raw_from_amplitude(talk_amp, level.min, level.max, level.db_range)
+                        })
+                } else if talkback.dim_others && level.amplitude() > 0.0 {
+                    level.scaled(dim)
+                } else {
+                    continue;
+                };
+                saved.push((idx, self.controls[idx].values.clone()));
+                targets.push((idx, raw));
+            }
+        }
+        self.apply_group(targets, false);
+        self.talkback_saved = Some(saved);
+        self.status_line = trf("Talkback: {} → cues", &[&self.alias_text(RenameTarget::Ain(input))]);
+    }
+
+    /// AIn and DIn sends to the headphone outputs in one grid, so a cue mix
+    /// can be built without switching between the two matrices.
+    fn render_cue_mix_tab(&mut self, ui: &mut egui::Ui) {
+        let output_count = self.output_count();
+        let mut changed = false;
+        ui.horizontal_wrapped(|ui| {
+            ui.label(RichText::new(tr("Headphone outputs")).strong());
+            for output in 0..output_count {
+                let mut shown = self.user_config.cue_mix_outputs.contains(&output);
+                if ui
+                    .toggle_value(&mut shown, self.alias_text(RenameTarget::Out(output)))
+                    .changed()
+                {
+                    if shown {
+                        self.user_config.cue_mix_outputs.insert(output);
+                    } else {
+                        self.user_config.cue_mix_outputs.remove(&output);
+                    }
+                    changed = true;
+                }
+            }
+            ui.separator();
+            changed |= self.render_cell_widget_picker(ui, MatrixGrid::CueMix);
+        });
+        if changed {
+            self.save_user_config();
+        }
+        ui.separator();
+
+        let outputs: Vec<usize> = self
+            .user_config
+            .cue_mix_outputs
+            .iter()
+            .copied()
+            .filter(|o| *o < output_count && !self.is_merged_partner(RenameTarget::Out(*o)))
+            .collect();
+        if outputs.is_empty() {
+            ui.label(tr("Select at least one output."));
+            return;
+        }
+        let mut rows: Vec<(MatrixBus, RenameTarget, HashMap<usize, usize>)> = Vec::new();
+        for (bus, make) in [
+            (MatrixBus::Analog, RenameTarget::Ain as fn(usize) -> RenameTarget),
+            (MatrixBus::Digital, RenameTarget::Din),
+        ] {
+            let routes = self.bus_routes(bus);
+            let Some(max_input) = routes.iter().map(|r| r.input).max() else {
+                continue;
+            };
+            for input in self.visible_axis(max_input, make) {
+                let by_output = routes
+                    .iter()
+                    .filter(|r| r.input == input)
+                    .map(|r| (r.output, r.control_index))
+                    .collect();
+                rows.push((bus, make(input), by_output));
+            }
+        }
+
+        let (cell_w, cell_h) = self.cell_size();
+        let mut actions: Vec<CellAction> = Vec::new();
+        let mut cells = Vec::new();
+        egui::Grid::new("cue_mix_grid").striped(true).show(ui, |ui| {
+            ui.label(tr("Input \\ Cue"));
+            for &output in &outputs {
+                ui.allocate_ui_with_layout(
+                    vec2(cell_w, 18.0),
+                    egui::Layout::top_down(egui::Align::Center),
+                    |ui| {
+                        self.render_alias_label(ui, RenameTarget::Out(output), true, cell_w);
+                    },
+                );
+            }
+            ui.end_row();
+            self.render_master_row(ui, &outputs, cell_w, &mut actions);
+            self.render_dim_row(ui, &outputs, cell_w, &mut actions);
+
+            for (row, (bus, target, by_output)) in rows.iter().enumerate() {
+                ui.allocate_ui_with_layout(
+                    vec2(self.row_label_w(), cell_h),
+                    egui::Layout::top_down(egui::Align::Min),
+                    |ui| {
+                        let width = self.row_label_w();
+                        self.render_line_header(ui, *bus, *target, true, width, &mut actions);
+                    },
+                );
+                let mut row_cells = Vec::new();
+                for (col, output) in outputs.iter().enumerate() {
+                    let cell = by_output.get(output).copied().filter(|idx| self.is_control_shown(*idx));
+                    row_cells.push(cell);
+                    if let Some(control_idx) = cell {
+                        let pos = GridPos {
+                            grid: MatrixGrid::CueMix,
+                            row,
+                            col,
+                        };
+                        actions.extend(self.render_route_cell(ui, control_idx, pos));
+                    } else {
+                        ui.label("-");
+                    }
+                }
+                cells.push(row_cells);
+                ui.end_row();
+            }
+        });
+
+        self.matrix_cells.insert(MatrixGrid::CueMix, cells);
+        self.run_cell_actions(actions);
+    }
+
+    /// Capture gains and input switches, one frame per physical input.
+    fn render_inputs_tab(&mut self, ui: &mut egui::Ui) {
+        let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (idx, control) in self.controls.iter().enumerate() {
+            if control.grouped_label == "Inputs" {
+                groups
+                    .entry(AlsaBackend::physical_input_label(control))
+                    .or_default()
+                    .push(idx);
+            }
+        }
+        if groups.is_empty() {
+            ui.label(tr("No capture gain or input switch controls found on this card."));
+            return;
+        }
+
+        let mut updates: Vec<(usize, Vec<String>)> = Vec::new();
+        let mut toggles: Vec<CellAction> = Vec::new();
+        ui.horizontal_wrapped(|ui| {
+            for (label, indices) in &groups {
+                egui::Frame::new()
+                    .fill(self.palette.section)
+                    .stroke(Stroke::new(1.0, self.palette.stroke))
+                    .inner_margin(egui::Margin::symmetric(8, 6))
+                    .show(ui, |ui| {
+                        ui.vertical(|ui| {
+                            ui.label(RichText::new(label).strong().size(14.0));
+                            ui.separator();
+                            for &idx in indices {
+                                let control = &self.controls[idx];
+                                ui.horizontal(|ui| {
+                                    ui.label(RichText::new(&control.name).small());
+                                    if self.render_favorite_button(ui, idx) {
+                                        toggles.push(CellAction::ToggleFavorite(idx));
+                                    }
+                                    if self.render_note_button(ui, idx) {
+                                        toggles.push(CellAction::EditNote(idx));
+                                    }
+                                });
+                                if let Some(values) =
+                                    Self::render_control_editor(ui, self.user_config.knob_drag, control)
+                                {
+                                    updates.push((idx, values));
+                                }
+                                ui.add_space(4.0);
+                            }
+                        });
+                    });
+            }
+        });
+        for (idx, values) in updates {
+            self.apply_values_to_control(idx, values);
+        }
+        self.run_cell_actions(toggles);
+    }
+
+    /// Starred controls of any kind, each with its editor.
+    fn render_favorites_tab(&mut self, ui: &mut egui::Ui) {
+        let favorites: Vec<usize> = self
+            .controls
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.favorite)
+            .map(|(idx, _)| idx)
+            .collect();
+        if favorites.is_empty() {
+            ui.label(tr("No favorites yet: star a control with ☆, or use the menu of a matrix cell."));
+            return;
+        }
+
+        let mut updates: Vec<(usize, Vec<String>)> = Vec::new();
+        let mut toggles: Vec<CellAction> = Vec::new();
+        ui.horizontal_wrapped(|ui| {
+            for idx in favorites {
+                let control = &self.controls[idx];
+                self.section_frame().show(ui, |ui| {
+                    ui.vertical(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(&control.name).strong());
+                            if self.render_favorite_button(ui, idx) {
+                                toggles.push(CellAction::ToggleFavorite(idx));
+                            }
+                            if self.render_note_button(ui, idx) {
+                                toggles.push(CellAction::EditNote(idx));
+                            }
+                        });
+                        if let Some(note) = self.control_note(idx) {
+                            ui.small(note);
+                        }
+                        ui.separator();
+                        if let Some(values) = Self::render_control_editor(ui, self.user_config.knob_drag, control) {
+                            updates.push((idx, values));
+                        }
+                    });
+                });
+            }
+        });
+        for (idx, values) in updates {
+            self.apply_values_to_control(idx, values);
+        }
+        self.run_cell_actions(toggles);
+    }
+
+    /// Every control of the card with its editor, searchable and filtered.
+    fn render_browser_tab(&mut self, ui: &mut egui::Ui) {
+        let groups: BTreeSet<String> = self.controls.iter().map(|c| c.grouped_label.clone()).collect();
+        let ifaces: BTreeSet<String> = self.controls.iter().map(|c| c.iface.clone()).collect();
+        let hidden = self.user_config.hidden_controls.len();
+        let mut show_hidden_changed = false;
+        let browser = &mut self.browser;
+        ui.horizontal_wrapped(|ui| {
+            ui.label(tr("Search:"));
+            ui.add(
+                egui::TextEdit::singleline(&mut browser.query)
+                    .hint_text(tr("e.g. ain1 out3"))
+                    .desired_width(220.0),
+            );
+            let kinds = ControlKind::NAMES.iter().map(|k| (*k, (*k).to_string())).collect();
+            Self::render_filter_combo(ui, "browser_kind", tr("Kind"), &mut browser.kind, kinds);
+            let groups = groups.into_iter().map(|g| (g.clone(), Self::group_title(&g).to_string())).collect();
+            Self::render_filter_combo(ui, "browser_group", tr("Group"), &mut browser.group, groups);
+            let ifaces = ifaces.into_iter().map(|i| (i.clone(), i)).collect();
+            Self::render_filter_combo(ui, "browser_iface", tr("Interface"), &mut browser.iface, ifaces);
+            show_hidden_changed = ui
+                .add_enabled(
+                    hidden > 0 || self.user_config.show_hidden,
+                    egui::Button::new(trf("Show hidden ({})", &[&hidden])).selected(self.user_config.show_hidden),
+                )
+                .on_hover_text(tr("Right-click a control name to hide it"))
+                .clicked();
+        });
+        if show_hidden_changed {
+            self.user_config.show_hidden ^= true;
+            self.save_user_config();
+        }
+
+        let mut matches: Vec<(u32, usize)> = self
+            .controls
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| self.is_control_shown(*idx))
+            .filter(|(_, c)| {
+                self.browser.kind.is_none_or(|k| c.kind.name() == k)
+                    && self.browser.group.as_ref().is_none_or(|g| c.grouped_label == *g)
+                    && self.browser.iface.as_ref().is_none_or(|i| c.iface == *i)
+            })
+            .filter_map(|(idx, c)| Some((fuzzy::score(&self.browser.query, &c.name)?, idx)))
+            .collect();
+        // Best match first; ties keep the card's order.
+        matches.sort_by_key(|(score, idx)| (std::cmp::Reverse(*score), *idx));
+        ui.small(trf("{} of {} controls", &[&matches.len(), &self.controls.len()]));
+        ui.separator();
+
+        let mut by_group: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (_, idx) in matches {
+            by_group.entry(&self.controls[idx].grouped_label).or_default().push(idx);
+        }
+
+        let mut updates: Vec<(usize, Vec<String>)> = Vec::new();
+        let mut toggles: Vec<CellAction> = Vec::new();
+        let mut folded: Vec<String> = Vec::new();
+        for (group, indices) in by_group {
+            let collapsed = self.user_config.collapsed_groups.contains(group);
+            let title = format!("{} ({})", Self::group_title(group), indices.len());
+            let header = egui::CollapsingHeader::new(RichText::new(title).strong())
+                .id_salt(("browser_group", group))
+                .open(Some(!collapsed))
+                .show(ui, |ui| {
+                    egui::Grid::new(("control_browser_grid", group))
+                        .striped(true)
+                        .num_columns(4)
+                        .show(ui, |ui| {
+                            for header in ["numid", tr("Name"), tr("Interface"), tr("Value")] {
+                                ui.label(RichText::new(header).strong());
+                            }
+                            ui.end_row();
+                            for idx in indices {
+                                let control = &self.controls[idx];
+                                ui.label(RichText::new(control.numid.to_string()).monospace());
+                                ui.horizontal(|ui| {
+                                    if self.render_favorite_button(ui, idx) {
+                                        toggles.push(CellAction::ToggleFavorite(idx));
+                                    }
+                                    if self.render_note_button(ui, idx) {
+                                        toggles.push(CellAction::EditNote(idx));
+                                    }
+                                    let hidden = self.is_control_hidden(idx);
+                                    let name = if hidden {
+                                        RichText::new(&control.name).italics().weak()
+                                    } else {
+                                        RichText::new(&control.name)
+                                    };
+                                    ui.label(name)
+                                        .on_hover_text(control.kind.name())
+                                        .context_menu(|ui| {
+                                            let text = if hidden { "Unhide this control" } else { "Hide this control" };
+                                            if ui.button(tr(text)).clicked() {
+                                                toggles.push(CellAction::ToggleControlHidden(idx));
+                                                ui.close();
+                                            }
+                                        });
+                                });
+                                ui.label(&control.iface);
+                                if let Some(values) =
+                                    Self::render_control_editor(ui, self.user_config.knob_drag, control)
+                                {
+                                    updates.push((idx, values));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            if header.header_response.clicked() {
+                folded.push(group.to_string());
+            }
+        }
+        for group in folded {
+            if !self.user_config.collapsed_groups.remove(&group) {
+                self.user_config.collapsed_groups.insert(group);
+            }
+            self.save_user_config();
+        }
+        for (idx, values) in updates {
+            self.apply_values_to_control(idx, values);
+        }
+        self.run_cell_actions(toggles);
+    }
+
+    /// Display name of a `grouped_label`.
+    fn group_title(group: &str) -> &str {
+        match group {
+            "Analog Routing" => tr("Analog Routing"),
+            "Digital Routing" => tr("Digital Routing"),
+            "Effects" => tr("Effects"),
+            "Inputs" => tr("Inputs"),
+            "Other" => tr("Other"),
+            other => other,
+        }
+    }
+
+    /// Combo box choosing one of `options` (value, label), or "All".
+    fn render_filter_combo<T: PartialEq>(
+        ui: &mut egui::Ui,
+        id: &str,
+        label: &str,
+        value: &mut Option<T>,
+        options: Vec<(T, String)>,
+    ) {
+        ui.label(label);
+        let selected = options
+            .iter()
+            .find(|(option, _)| value.as_ref() == Some(option))
+            .map_or(tr("All"), |(_, text)| text.as_str())
+            .to_string();
+        egui::ComboBox::from_id_salt(id).selected_text(selected).show_ui(ui, |ui| {
+            if ui.selectable_label(value.is_none(), tr("All")).clicked() {
+                *value = None;
+            }
+            for (option, text) in options {
+                if ui.selectable_label(value.as_ref() == Some(&option), text).clicked() {
+                    *value = Some(option);
+                }
+            }
+        });
+    }
+
+    fn section_frame(&self) -> egui::Frame {
+        egui::Frame::new()
+            .fill(self.palette.section)
+            .stroke(Stroke::new(1.0, self.palette.stroke))
+            .inner_margin(egui::Margin::symmetric(8, 6))
+    }
+
+    fn render_settings_tab(&mut self, ui: &mut egui::Ui) {
+        let committed = |resp: &egui::Response| resp.drag_stopped() || (resp.changed() && !resp.dragged());
+        let mut save = false;
+        let mut cap_changed = false;
+        let mut pick_library = false;
+        let mut pick_scratch = false;
+        let mut theme_changed = false;
+        let mut ceilings_changed = false;
+        ui.horizontal_wrapped(|ui| {
+            self.section_frame().show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.label(RichText::new(tr("Appearance")).strong().size(14.0));
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Language:"));
+                        egui::ComboBox::from_id_salt("settings_language")
+                            .selected_text(self.user_config.language.label())
+                            .show_ui(ui, |ui| {
+                                for language in Language::ALL {
+                                    if ui
+                                        .selectable_value(&mut self.user_config.language, language, language.label())
+                                        .changed()
+                                    {
+                                        i18n::set_language(language);
+                                        save = true;
+                                    }
+                                }
+                            });
+                    });
+                    let mut scale = self.ui_scale_drag.unwrap_or(self.user_config.ui_scale);
+                    let resp = ui
+                        .add(
+                            egui::Slider::new(&mut scale, 0.5..=3.0)
+                                .text(tr("UI scale"))
+                                .fixed_decimals(1),
+                        )
+                        .on_hover_text(tr("Also Ctrl+= / Ctrl+- / Ctrl+0; applied when the slider is released"));
+                    if committed(&resp) {
+                        self.user_config.ui_scale = scale;
+                        self.ui_scale_drag = None;
+                        save = true;
+                    } else if resp.changed() {
+                        self.ui_scale_drag = Some(scale);
+                    }
+                    let theme = &mut self.user_config.theme;
+                    ui.horizontal(|ui| {
+                        for preset in ThemePreset::ALL {
+                            theme_changed |= ui.selectable_value(&mut theme.preset, preset, preset.label()).changed();
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(tr("State colors:"));
+                        egui::ComboBox::from_id_salt("settings_color_vision")
+                            .selected_text(theme.vision.label())
+                            .show_ui(ui, |ui| {
+                                for vision in ColorVision::ALL {
+                                    theme_changed |= ui.selectable_value(&mut theme.vision, vision, vision.label()).changed();
+                                }
+                            });
+                    });
+                    theme_changed |= ui
+                        .checkbox(&mut theme.state_cues, tr("Patterns and symbols for states"))
+                        .on_hover_text(tr("Hatch muted cells and clipping meters, and mark linked channels with 🔗"))
+                        .changed();
+                    let defaults = theme.palette();
+                    for (label, color, default) in [
+                        ("Custom accent", &mut theme.accent, defaults.accent),
+                        ("Custom background", &mut theme.background, defaults.background),
+                    ] {
+                        ui.horizontal(|ui| {
+                            let mut custom = color.is_some();
+                            if ui.checkbox(&mut custom, tr(label)).changed() {
+                                *color = custom.then(|| [default.r(), default.g(), default.b()]);
+                                theme_changed = true;
+                            }
+                            if let Some(rgb) = color {
+                                theme_changed |= ui.color_edit_button_srgb(rgb).changed();
+                            }
+                        });
+                    }
+                });
+            });
+
+            self.section_frame().show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.label(RichText::new(tr("Matrix")).strong().size(14.0));
+                    ui.separator();
+                    save |= ui
+                        .checkbox(&mut self.user_config.compact_matrix, tr("Compact matrix (numbers instead of knobs)"))
+                        .changed();
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Cells:"));
+                        for density in MatrixDensity::ALL {
+                            save |= ui
+                                .selectable_value(&mut self.user_config.matrix_density, density, density.label())
+                                .changed();
+                        }
+                    });
+                    egui::Grid::new("settings_cell_widgets").show(ui, |ui| {
+                        for (label, grid) in [
+                            ("Analog monitoring", MatrixGrid::Monitoring),
+                            ("Digital routing", MatrixGrid::DigitalRoutes),
+                            ("Headphone mix", MatrixGrid::CueMix),
+                        ] {
+                            ui.label(tr(label));
+                            save |= self.render_cell_widget_picker(ui, grid);
+                            ui.end_row();
+                        }
+                    });
+                    let zoom = ui.add(
+                        egui::Slider::new(&mut self.user_config.matrix_zoom, 0.5..=2.0)
+                            .text("Zoom")
+                            .fixed_decimals(2),
+                    );
+                    save |= committed(&zoom);
+                    save |= ui
+                        .checkbox(&mut self.user_config.merge_linked_cells, tr("Merge linked pairs"))
+                        .changed();
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Pan law"));
+                        egui::ComboBox::from_id_salt("settings_pan_law")
+                            .selected_text(self.user_config.pan_law.label())
+                            .show_ui(ui, |ui| {
+                                for law in PanLaw::ALL {
+                                    save |= ui
+                                        .selectable_value(&mut self.user_config.pan_law, law, law.label())
+                                        .changed();
+                                }
+                            });
+                    });
+                });
+            });
+
+            self.section_frame().show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.label(RichText::new(tr("Knobs")).strong().size(14.0));
+                    ui.separator();
+                    let drag = &mut self.user_config.knob_drag;
+                    let resp = ui
+                        .add(
+                            egui::Slider::new(&mut drag.range_px, 40.0..=800.0)
+                                .text(tr("Drag range"))
+                                .suffix(" px")
+                                .fixed_decimals(0),
+                        )
+                        .on_hover_text(tr("Mouse travel from minimum to maximum; larger is slower"));
+                    save |= committed(&resp);
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Drag:"));
+                        for axis in DragAxis::ALL {
+                            save |= ui.selectable_value(&mut drag.axis, axis, axis.label()).changed();
+                        }
+                    });
+                    save |= ui
+                        .checkbox(&mut drag.inverted, tr("Invert direction"))
+                        .on_hover_text(tr("Turn up by dragging down / left"))
+                        .changed();
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Fine adjustment:"));
+                        egui::ComboBox::from_id_salt("settings_fine_modifier")
+                            .selected_text(drag.fine_modifier.label())
+                            .show_ui(ui, |ui| {
+                                for modifier in FineModifier::ALL {
+                                    save |= ui
+                                        .selectable_value(&mut drag.fine_modifier, modifier, modifier.label())
+                                        .changed();
+                                }
+                            });
+                    })
+                    .response
+                    .on_hover_text(tr("Hold while dragging to move ten times slower; Shift+drag also selects matrix cells"));
+                });
+            });
+
+            self.section_frame().show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.label(RichText::new(tr("Safety")).strong().size(14.0));
+                    ui.separator();
+                    let cap = ui
+                        .add(
+                            egui::Slider::new(&mut self.user_config.fx_feedback_cap_pct, 10.0..=100.0)
+                                .text(tr("Feedback max"))
+                                .suffix(" %")
+                                .fixed_decimals(0),
+                        )
+                        .on_hover_text(tr("Ceiling on Effect Feedback, also enforced on presets and scenes"));
+                    cap_changed = committed(&cap);
+                    let random = &mut self.user_config.fx_random;
+                    let resp = ui.add(
+                        egui::Slider::new(&mut random.feedback_max_pct, random.feedback_min_pct..=100.0)
+                            .text(tr("Random FX feedback max"))
+                            .suffix(" %")
+                            .fixed_decimals(0),
+                    );
+                    save |= committed(&resp);
+                    let resp = ui.add(
+                        egui::Slider::new(&mut random.level_cap_db, -40.0..=0.0)
+                            .text(tr("Random FX level cap"))
+                            .suffix(" dB")
+                            .fixed_decimals(0),
+                    );
+                    save |= committed(&resp);
+                    let resp = ui
+                        .add(
+                            egui::Slider::new(&mut self.user_config.dim_db, -60.0..=0.0)
+                                .text("Dim")
+                                .suffix(" dB")
+                                .fixed_decimals(0),
+                        )
+                        .on_hover_text(tr("Attenuation of the Dim buttons"));
+                    save |= committed(&resp);
+                    let resp = ui
+                        .add(
+                            egui::Slider::new(&mut self.user_config.generator.noise_timeout_secs, 5..=600)
+                                .text(tr("Pink noise timeout"))
+                                .suffix(" s"),
+                        )
+                        .on_hover_text(tr("The tone generator stops pink noise on its own after this long"));
+                    save |= committed(&resp);
+                });
+            });
+
+            self.section_frame().show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.label(RichText::new(tr("Output limits")).strong().size(14.0))
+                        .on_hover_text(tr("Any write above the limit is clamped: knobs, presets, scenes, the command line and remote control"));
+                    ui.separator();
+                    egui::Grid::new("settings_output_ceilings").show(ui, |ui| {
+                        for output in 0..self.output_count() {
+                            let name = self.alias_text(RenameTarget::Out(output));
+                            let ceilings = &mut self.user_config.output_ceilings_db;
+                            let mut limited = ceilings.contains_key(&output);
+                            if ui.checkbox(&mut limited, name).changed() {
+                                if limited {
+                                    ceilings.insert(output, 0.0);
+                                } else {
+                                    ceilings.remove(&output);
+                                }
+                                ceilings_changed = true;
+                            }
+                            if let Some(db) = ceilings.get_mut(&output) {
+                                let resp = ui.add(
+                                    egui::Slider::new(db, -60.0..=12.0)
+                                        .suffix(" dB")
+                                        .fixed_decimals(1),
+                                );
+                                ceilings_changed |= committed(&resp);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+
+            self.section_frame().show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.label(RichText::new(tr("Refresh rates")).strong().size(14.0));
+                    ui.separator();
+                    let refresh = &mut self.user_config.refresh;
+                    ui.horizontal(|ui| {
+                        for (label, preset) in [
+                            ("Responsive", RefreshConfig::default()),
+                            ("Battery saver", RefreshConfig::BATTERY_SAVER),
+                        ] {
+                            if ui.selectable_label(*refresh == preset, tr(label)).clicked() {
+                                *refresh = preset;
+                                save = true;
+                            }
+                        }
+                    });
+                    let resp = ui
+                        .add(
+                            egui::Slider::new(&mut refresh.event_fallback_ms, 100..=5000)
+                                .logarithmic(true)
+                                .text(tr("Event fallback"))
+                                .suffix(" ms"),
+                        )
+                        .on_hover_text(tr("Re-read the levels this often even without ALSA events, in case one was missed"));
+                    save |= committed(&resp);
+                    let resp = ui
+                        .add(
+                            egui::Slider::new(&mut refresh.auto_refresh_ms, 50..=5000)
+                                .logarithmic(true)
+                                .text(tr("Auto refresh"))
+                                .suffix(" ms"),
+                        )
+                        .on_hover_text(tr("Level polling when the card sends no ALSA events"));
+                    save |= committed(&resp);
+                    let resp = ui
+                        .add(
+                            egui::Slider::new(&mut refresh.full_refresh_secs, 1..=300)
+                                .logarithmic(true)
+                                .text(tr("Full refresh"))
+                                .suffix(" s"),
+                        )
+                        .on_hover_text(tr("Reload of the whole control list, to pick up controls that appear or vanish"));
+                    save |= committed(&resp);
+                });
+            });
+
+            self.section_frame().show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.label(RichText::new(tr("Card")).strong().size(14.0));
+                    ui.separator();
+                    ui.label(trf(
+                        "Current: {} (hw:{})",
+                        &[&self.backend.card_label, &self.backend.card_index],
+                    ));
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Default card:"));
+                        let selected = self
+                            .user_config
+                            .default_card
+                            .clone()
+                            .unwrap_or_else(|| tr("Auto (FTU)").to_string());
+                        egui::ComboBox::from_id_salt("settings_default_card")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                save |= ui
+                                    .selectable_value(&mut self.user_config.default_card, None, tr("Auto (FTU)"))
+                                    .changed();
+                                for card in AlsaBackend::detect_cards().unwrap_or_default() {
+                                    let label = format!("{} (hw:{})", card.name, card.index);
+                                    save |= ui
+                                        .selectable_value(&mut self.user_config.default_card, Some(card.name), label)
+                                        .changed();
+                                }
+                            });
+                    });
+                    ui.small(tr("Used at the next start when --card is not given."));
+                });
+            });
+
+            self.section_frame().show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.label(RichText::new("Presets").strong().size(14.0));
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Library:"));
+                        let folder = self
+                            .user_config
+                            .preset_library_dir
+                            .as_ref()
+                            .map(|d| d.display().to_string())
+                            .unwrap_or_else(|| tr("(no folder selected)").to_string());
+                        ui.label(RichText::new(folder).monospace());
+                        pick_library = ui.button(tr("Choose folder…")).clicked();
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Author:"));
+                        let mut author = self.user_config.preset_author.clone().unwrap_or_default();
+                        if ui.text_edit_singleline(&mut author).lost_focus() {
+                            let author = Some(author.trim().to_string()).filter(|a| !a.is_empty());
+                            if author != self.user_config.preset_author {
+                                self.user_config.preset_author = author;
+                                save = true;
+                            }
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Preset from another card:"));
+                        egui::ComboBox::from_id_salt("settings_preset_card_check")
+                            .selected_text(self.user_config.preset_card_check.label())
+                            .show_ui(ui, |ui| {
+                                for mode in PresetCardCheck::ALL {
+                                    save |= ui
+                                        .selectable_value(&mut self.user_config.preset_card_check, mode, mode.label())
+                                        .changed();
+                                }
+                            });
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Scratch recordings:"));
+                        let folder = scratch::scratch_dir(self.user_config.scratch_dir.as_deref())
+                            .map(|d| d.display().to_string())
+                            .unwrap_or_default();
+                        ui.label(RichText::new(folder).monospace());
+                        pick_scratch = ui.button(tr("Choose folder…")).clicked();
+                    });
+                });
+            });
+
+            self.section_frame().show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.label(RichText::new(tr("Integrations")).strong().size(14.0));
+                    ui.separator();
+                    save |= ui
+                        .checkbox(&mut self.user_config.ipc_socket, tr("Local command socket"))
+                        .changed();
+                    save |= ui
+                        .checkbox(&mut self.user_config.global_hotkeys, tr("Global hotkeys"))
+                        .changed();
+                    save |= ui.checkbox(&mut self.user_config.http_api.enabled, "HTTP API").changed();
+                    save |= ui.checkbox(&mut self.user_config.mqtt.enabled, "MQTT").changed();
+                    save |= ui
+                        .checkbox(&mut self.user_config.midi_presets.enabled, tr("MIDI program changes"))
+                        .changed();
+                    save |= ui.checkbox(&mut self.user_config.gamepad.enabled, tr("Gamepad")).changed();
+                    ui.small(tr("These take effect at the next start."));
+                });
+            });
+
+            self.section_frame().show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.label(RichText::new(tr("Startup")).strong().size(14.0));
+                    ui.separator();
+                    save |= ui
+                        .checkbox(&mut self.user_config.start_minimized, tr("Start minimized"))
+                        .on_hover_text(tr("The window opens minimized; restore it from the taskbar"))
+                        .changed();
+                    if autostart::is_installed() {
+                        ui.label(tr("Starts at login"));
+                        if ui.button(tr("Remove autostart entry")).clicked() {
+                            self.status_line = match autostart::remove() {
+                                Ok(()) => tr("Autostart entry removed").to_string(),
+                                Err(err) => trf("Autostart entry not removed: {}", &[&format!("{err:#}")]),
+                            };
+                        }
+                    } else if ui
+                        .button(tr("Create autostart entry"))
+                        .on_hover_text(tr("Start the mixer minimized at login, via a desktop file in ~/.config/autostart"))
+                        .clicked()
+                    {
+                        self.status_line = match autostart::install() {
+                            Ok(path) => trf("Autostart entry written to {}", &[&path.display()]),
+                            Err(err) => trf("Autostart entry not written: {}", &[&format!("{err:#}")]),
+                        };
+                    }
+                });
+            });
+        });
+
+        if pick_library {
+            let mut dialog = FileDialog::new();
+            if let Some(dir) = &self.user_config.preset_library_dir {
+                dialog = dialog.set_directory(dir);
+            }
+            if let Some(dir) = dialog.pick_folder() {
+                self.user_config.preset_library_dir = Some(dir);
+                self.rescan_preset_library();
+                save = true;
+            }
+        }
+        if pick_scratch {
+            if let Some(dir) = FileDialog::new().pick_folder() {
+                self.user_config.scratch_dir = Some(dir);
+                save = true;
+            }
+        }
+        if theme_changed {
+            self.apply_theme_colors(ui.ctx());
+            save = true;
+        }
+        if ceilings_changed {
+            self.sync_value_ceilings();
+            self.enforce_output_ceilings();
+            save = true;
+        }
+        if cap_changed {
+            self.save_user_config();
+            self.sync_value_ceilings();
+            self.enforce_fx_feedback_cap(&FxModel::from_controls(&self.controls));
+        }
+        if save {
+            self.save_user_config();
+        }
+    }
+
+    fn render_tab_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.selected_tab, Tab::Favorites, tr("★ Favorites"));
+            ui.selectable_value(&mut self.selected_tab, Tab::MixRouting, tr("Monitoring & Routing"));
+            ui.selectable_value(&mut self.selected_tab, Tab::OutputMix, tr("Mix per output"));
+            ui.selectable_value(&mut self.selected_tab, Tab::CueMix, tr("Headphone mix"));
+            ui.selectable_value(&mut self.selected_tab, Tab::Inputs, tr("Inputs"));
+            ui.selectable_value(&mut self.selected_tab, Tab::Fx, "FX");
+            ui.selectable_value(&mut self.selected_tab, Tab::Browser, tr("All controls"));
+            ui.selectable_value(&mut self.selected_tab, Tab::Settings, tr("Settings"));
+        });
+        ui.add_space(4.0);
+    }
+
+    fn output_count(&self) -> usize {
+        self.routing_index
+            .analog_routes
+            .iter()
+            .chain(self.routing_index.digital_routes.iter())
+            .map(|r| r.output + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn alias_text(&self, target: RenameTarget) -> String {
+        match target {
+            RenameTarget::Ain(i) => self.user_config.ain_aliases.get(&i).cloned(),
+            RenameTarget::Din(i) => self.user_config.din_aliases.get(&i).cloned(),
+            RenameTarget::Out(i) => self.user_config.out_aliases.get(&i).cloned(),
+        }
+        .unwrap_or_else(|| match target {
+            RenameTarget::Ain(i) => format!("AIn{}", i + 1),
+            RenameTarget::Din(i) => format!("DIn{}", i + 1),
+            RenameTarget::Out(i) => format!("Out{}", i + 1),
+        })
+    }
+
+    /// One output pair at a time, with a vertical fader per route feeding it.
+    fn render_output_mix_tab(&mut self, ui: &mut egui::Ui) {
+        let output_count = self.output_count();
+        if output_count == 0 {
+            ui.label(tr("No routes found."));
+            return;
+        }
+        let pair_count = output_count.div_ceil(2);
+        self.output_mix_pair = self.output_mix_pair.min(pair_count - 1);
+        let pair_label = |app: &Self, pair: usize| {
+            let left = pair * 2;
+            if left + 1 < output_count {
+                format!(
+                    "{} / {}",
+                    app.alias_text(RenameTarget::Out(left)),
+                    app.alias_text(RenameTarget::Out(left + 1))
+                )
+            } else {
+                app.alias_text(RenameTarget::Out(left))
+            }
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(tr("Output")).strong());
+            let selected = pair_label(self, self.output_mix_pair);
+            let mut pair = self.output_mix_pair;
+            egui::ComboBox::from_id_salt("output_mix_pair")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for p in 0..pair_count {
+                        ui.selectable_value(&mut pair, p, pair_label(self, p));
+                    }
+                });
+            self.output_mix_pair = pair;
+            ui.separator();
+            ui.label(tr("Pan law"));
+            let mut law = self.user_config.pan_law;
+            egui::ComboBox::from_id_salt("pan_law")
+                .selected_text(law.label())
+                .show_ui(ui, |ui| {
+                    for l in PanLaw::ALL {
+                        ui.selectable_value(&mut law, l, l.label());
+                    }
+                });
+            if law != self.user_config.pan_law {
+                self.user_config.pan_law = law;
+                self.save_user_config();
+            }
+            ui.separator();
+            let pair = self.output_mix_pair;
+            let linked = self.user_config.linked_out_pairs.contains(&pair);
+            let mono = self.user_config.mono_out_pairs.contains_key(&pair);
+            if ui
+                .add_enabled(linked || mono, egui::Button::new("Mono").selected(mono))
+                .on_hover_text(tr("Mono check: send each input equally to L and R, restore on release"))
+                .clicked()
+            {
+                self.toggle_mono_sum(pair);
+            }
+        });
+        ui.separator();
+        let pair_linked = self
+            .user_config
+            .linked_out_pairs
+            .contains(&self.output_mix_pair);
+        let mut pan_memory = std::mem::take(&mut self.pan_memory);
+        let mut pan_actions: Vec<(usize, Vec<String>)> = Vec::new();
+
+        let outputs: Vec<usize> = [self.output_mix_pair * 2, self.output_mix_pair * 2 + 1]
+            .into_iter()
+            .filter(|o| *o < output_count)
+            .collect();
+        let mut actions: Vec<(usize, Vec<String>)> = Vec::new();
+        for (title, routes, digital) in [
+            ("Analog inputs", self.routing_index.analog_routes.clone(), false),
+            ("Digital playback", self.routing_index.digital_routes.clone(), true),
+        ] {
+            let max_input = routes.iter().map(|r| r.input).max();
+            let Some(max_input) = max_input else {
+                continue;
+            };
+            ui.label(RichText::new(tr(title)).strong());
+            ui.horizontal(|ui| {
+                for input in 0..=max_input {
+                    let feeding: Vec<(usize, usize)> = outputs
+                        .iter()
+                        .filter_map(|o| {
+                            routes
+                                .iter()
+                                .find(|r| r.input == input && r.output == *o)
+                                .map(|r| (*o, r.control_index))
+                        })
+                        .collect();
+                    if feeding.is_empty() {
+                        continue;
+                    }
+                    let target = if digital {
+                        RenameTarget::Din(input)
+                    } else {
+                        RenameTarget::Ain(input)
+                    };
+                    egui::Frame::new()
+                        .fill(self.palette.raised)
+                        .stroke(Stroke::new(1.0, self.palette.stroke))
+                        .inner_margin(egui::Margin::symmetric(6, 6))
+                        .show(ui, |ui| {
+                            ui.vertical(|ui| {
+                                ui.set_width(84.0);
+                                ui.add(
+                                    egui::Label::new(RichText::new(self.alias_text(target)).strong())
+                                        .truncate(),
+                                );
+                                if pair_linked && feeding.len() == 2 && self.linked_partner(target).is_none() {
+                                    let key = (digital, input, self.output_mix_pair);
+                                    let left = &self.controls[feeding[0].1];
+                                    let right = &self.controls[feeding[1].1];
+                                    let memory = pan_memory.entry(key).or_insert(0.0);
+                                    if let Some((l, r)) = Self::render_pan_strip(
+                                        ui,
+                                        self.user_config.pan_law,
+                                        memory,
+                                        left,
+                                        right,
+                                    ) {
+                                        pan_actions.push((feeding[0].1, l));
+                                        pan_actions.push((feeding[1].1, r));
+                                    }
+                                    return;
+                                }
+                                ui.horizontal(|ui| {
+                                    for (output, control_idx) in &feeding {
+                                        let Some(control) = self.controls.get(*control_idx) else {
+                                            continue;
+                                        };
+                                        ui.vertical(|ui| {
+                                            if let Some(values) = Self::render_fader_cell(ui, control) {
+                                                actions.push((*control_idx, values));
+                                            }
+                                            ui.small(self.alias_text(RenameTarget::Out(*output)));
+                                        });
+                                    }
+                                });
+                            });
+                        });
+                }
+            });
+            ui.add_space(6.0);
+        }
+
+        self.pan_memory = pan_memory;
+        for (idx, values) in actions {
+            self.apply_route_values(idx, values);
+        }
+        // Pan writes both sides of the linked pair explicitly, bypassing link propagation.
+        for (idx, values) in pan_actions {
+            self.apply_values_to_control(idx, values);
+        }
+    }
+
+    /// Level fader and pan slider for a mono input feeding a linked output pair.
+    fn render_pan_strip(
+        ui: &mut egui::Ui,
+        law: PanLaw,
+        pan_memory: &mut f64,
+        left: &ControlDescriptor,
+        right: &ControlDescriptor,
+    ) -> Option<(Vec<String>, Vec<String>)> {
+        let (
+            ControlKind::Integer {
+                min,
+                max,
+                db_range,
+                channels: left_channels,
+                ..
+            },
+            ControlKind::Integer {
+                channels: right_channels,
+                ..
+            },
+        ) = (&left.kind, &right.kind)
+        else {
+            ui.label("...");
+            return None;
+        };
+        let raw = |c: &ControlDescriptor| {
+            c.values
+                .first()
+                .and_then(|x| x.parse::<i64>().ok())
+                .unwrap_or(*min)
+        };
+        let amp_l = pan::amplitude_from_raw(raw(left), *min, *max, *db_range);
+        let amp_r = pan::amplitude_from_raw(raw(right), *min, *max, *db_range);
+        let (mut level, pan) = law.level_and_pan(amp_l, amp_r);
+        let mut pan = pan.unwrap_or(*pan_memory);
+        *pan_memory = pan;
+
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui
+                .add(
+                    egui::Slider::new(&mut level, 0.0..=1.0)
+                        .vertical()
+                        .show_value(false),
+                )
+                .on_hover_text(tr("Level"))
+                .changed();
+            ui.small(format!("{}%", (level * 100.0).round()));
+        });
+        changed |= ui
+            .add(
+                egui::Slider::new(&mut pan, -1.0..=1.0)
+                    .show_value(false)
+                    .step_by(0.01),
+            )
+            .on_hover_text(tr("Pan (double-click: center)"))
+            .changed();
+        if ui.small_button(pan::pan_text(pan)).double_clicked() {
+            pan = 0.0;
+            changed = true;
+        }
+        if !changed {
+            return None;
+        }
+        *pan_memory = pan;
+        let (gain_l, gain_r) = law.gains(pan);
+        let to_raw = |gain: f64| pan::raw_from_amplitude(level * gain, *min, *max, *db_range);
+        Some((
+            vec![to_raw(gain_l).to_string(); *left_channels],
+            vec![to_raw(gain_r).to_string(); *right_channels],
+        ))
+    }
+
+    fn render_fader_cell(ui: &mut egui::Ui, control: &ControlDescriptor) -> Option<Vec<String>> {
+        let ControlKind::Integer {
+            min,
+            max,
+            db_range,
+            channels,
+            ..
+        } = control.kind
+        else {
+            ui.label("...");
+            return None;
+        };
+        let mut v = control
+            .values
+            .first()
+            .and_then(|x| x.parse::<i64>().ok())
+            .unwrap_or(min);
+        Self::render_fader(ui, &mut v, min, max, db_range).then(|| vec![v.to_string(); channels])
+    }
+
+    fn render_fader(
+        ui: &mut egui::Ui,
+        value: &mut i64,
+        min: i64,
+        max: i64,
+        db_range: Option<(i64, i64)>,
+    ) -> bool {
+        let old = *value;
+        let mut progress = Self::knob_progress_from_value(*value, min, max, db_range);
+        let response = ui.add(
+            egui::Slider::new(&mut progress, 0.0..=1.0)
+                .vertical()
+                .show_value(false),
+        );
+        if response.changed() {
+            *value = Self::value_from_knob_progress(progress, min, max, db_range);
+        }
+        let percent = Self::control_percent(*value, min, max, db_range);
+        ui.small(format!("{percent}%"));
+        old != *value
+    }
+
+    fn render_effects_section(&mut self, ui: &mut egui::Ui) {
+        let model = FxModel::from_controls(&self.controls);
+        if model.is_empty() {
+            ui.label(RichText::new(tr("Effects (FX)")).strong());
+            ui.label(tr("Dedicated FX controls of the Fast Track Ultra."));
+            ui.label(tr("No FX control found on this card."));
+            return;
+        }
+        let kind = self.fx_kind(&model);
+
+        let mut actions: Vec<(usize, Vec<String>)> = Vec::new();
+        let mut program_change = None;
+        let mut cap_changed = false;
+        let mut toggles: Vec<CellAction> = Vec::new();
+        ui.columns(2, |cols| {
+            egui::Frame::new()
+                .fill(self.palette.raised)
+                .stroke(Stroke::new(1.0, self.palette.stroke))
+                .inner_margin(egui::Margin::symmetric(6, 6))
+                .show(&mut cols[0], |ui| {
+                    ui.label(RichText::new(tr("Effects (FX)")).strong());
+                    ui.small(tr("Dedicated FX controls of the Fast Track Ultra."));
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("Disable FX")).clicked() {
+                            self.disable_fx_controls();
+                        }
+                        let bypassed = self.user_config.fx_bypass.is_some();
+                        let text = RichText::new("Bypass");
+                        let text = if bypassed {
+                            text.strong().color(Color32::from_rgb(240, 190, 90))
+                        } else {
+                            text
+                        };
+                        if ui
+                            .add(egui::Button::new(text).selected(bypassed))
+                            .on_hover_text(tr(if bypassed {
+                                "Restore the FX sends and returns exactly as they were"
+                            } else {
+                                "Zero every FX send and return, remembering their levels"
+                            }))
+                            .clicked()
+                        {
+                            self.toggle_fx_bypass();
+                        }
+                    });
+                    self.render_fx_presets(ui);
+                    self.render_fx_surprise(ui, model.program);
+                    self.render_fx_ab(ui);
+                    ui.horizontal(|ui| {
+                        let mut changed = ui
+                            .checkbox(&mut self.user_config.fx_clear_tail, tr("Cut the tail"))
+                            .on_hover_text(tr("Mute the FX returns while the program changes, then bring them back"))
+                            .changed();
+                        let delay = ui.add_enabled(
+                            self.user_config.fx_clear_tail,
+                            egui::DragValue::new(&mut self.user_config.fx_tail_ms)
+                                .range(50..=3000)
+                                .speed(10.0)
+                                .suffix(" ms"),
+                        );
+                        changed |= delay.drag_stopped() || (delay.changed() && !delay.dragged());
+                        if changed {
+                            self.save_user_config();
+                        }
+                    });
+                    ui.separator();
+                    ui.horizontal_wrapped(|ui| {
+                        if let Some(idx) = model.program {
+                            let tile = |ui: &mut egui::Ui| self.render_fx_program_tile(ui, idx);
+                            if let Some(values) = self.render_lockable_tile(ui, idx, &mut toggles, tile) {
+                                program_change = Some((idx, values));
+                            }
+                        }
+                        if let Some(idx) = model.volume {
+                            let tile = |ui: &mut egui::Ui| self.render_effect_tile(ui, idx);
+                            if let Some(values) = self.render_lockable_tile(ui, idx, &mut toggles, tile) {
+                                actions.push((idx, values));
+                            }
+                        }
+                    });
+                });
+
+            egui::Frame::new()
+                .fill(self.palette.raised)
+                .stroke(Stroke::new(1.0, self.palette.stroke))
+                .inner_margin(egui::Margin::symmetric(6, 6))
+                .show(&mut cols[1], |ui| {
+                    // Without a readable program, show both parameters under their own names.
+                    let (title, duration_label, show_feedback) = match kind {
+                        Some(kind) => (kind.label(), Some(kind.duration_label()), kind.uses_feedback()),
+                        None => (tr("Duration / Feedback"), None, true),
+                    };
+                    ui.label(RichText::new(title).strong());
+                    ui.horizontal_wrapped(|ui| {
+                        if let Some(idx) = model.duration {
+                            let label = duration_label.unwrap_or(tr("Duration"));
+                            let tile = |ui: &mut egui::Ui| self.render_fx_duration_tile(ui, idx, label);
+                            if let Some(values) = self.render_lockable_tile(ui, idx, &mut toggles, tile) {
+                                actions.push((idx, values));
+                            }
+                        }
+                        if let Some(idx) = model.feedback.filter(|_| show_feedback) {
+                            let tile = |ui: &mut egui::Ui| self.render_fx_feedback_tile(ui, idx);
+                            if let Some(values) = self.render_lockable_tile(ui, idx, &mut toggles, tile) {
+                                actions.push((idx, values));
+                            }
+                        }
+                    });
+                    if model.feedback.is_some() && !show_feedback {
+                        ui.small(tr("Feedback only acts on Delay / Echo."));
+                    }
+                    if model.feedback.is_some() {
+                        ui.horizontal(|ui| {
+                            ui.label(tr("Feedback max"));
+                            let cap = ui
+                                .add(
+                                    egui::DragValue::new(&mut self.user_config.fx_feedback_cap_pct)
+                                        .range(10.0..=100.0)
+                                        .speed(0.5)
+                                        .fixed_decimals(0)
+                                        .suffix(" %"),
+                                )
+                                .on_hover_text(tr("Ceiling on Effect Feedback, also enforced on presets and scenes"));
+                            if cap.drag_stopped() || (cap.changed() && !cap.dragged()) {
+                                self.save_user_config();
+                                self.sync_value_ceilings();
+                                cap_changed = true;
+                            }
+                        });
+                    }
+                });
+        });
+
+        if !model.other.is_empty() {
+            ui.separator();
+            let title = trf("Other FX controls ({})", &[&model.other.len()]);
+            let header = egui::CollapsingHeader::new(title)
+                .id_salt("fx_other")
+                .open(Some(!self.user_config.fx_other_collapsed))
+                .show(ui, |ui| {
+                    ui.horizontal_wrapped(|ui| {
+                        for &idx in &model.other {
+                            let tile = |ui: &mut egui::Ui| self.render_effect_tile(ui, idx);
+                            if let Some(values) = self.render_lockable_tile(ui, idx, &mut toggles, tile) {
+                                actions.push((idx, values));
+                            }
+                        }
+                    });
+                });
+            if header.header_response.clicked() {
+                self.user_config.fx_other_collapsed ^= true;
+                self.save_user_config();
+            }
+        }
+
+        if let Some((idx, values)) = program_change {
+            self.change_fx_program(idx, values);
+        }
+        if cap_changed {
+            self.enforce_fx_feedback_cap(&model);
+        }
+        self.run_cell_actions(toggles);
+        for (idx, values) in actions {
+            self.apply_values_to_control(idx, values);
+        }
+    }
+
+    /// Raw ceiling of the Effect Feedback control at `idx`, from the cap in percent.
+    fn fx_feedback_ceiling(&self, idx: usize) -> Option<i64> {
+        ceilings::fx_feedback_ceiling(self.controls.get(idx)?, self.user_config.fx_feedback_cap_pct)
+    }
+
+    /// Hands the output ceilings and the feedback cap to the backend so every
+    /// write path (presets, scenes, undo, the remote APIs) respects them.
+    fn sync_value_ceilings(&mut self) {
+        ceilings::install(&self.backend, &self.controls, &self.user_config);
+    }
+
+    /// Pulls the current feedback down after the cap was lowered below it.
+    fn enforce_fx_feedback_cap(&mut self, model: &FxModel) {
+        let Some(idx) = model.feedback else {
+            return;
+        };
+        let Some(ceiling) = self.fx_feedback_ceiling(idx) else {
+            return;
+        };
+        let control = &self.controls[idx];
+        let over = control
+            .values
+            .iter()
+            .any(|v| v.parse::<i64>().map(|raw| raw > ceiling).unwrap_or(false));
+        if over {
+            let values = vec![ceiling.to_string(); control.values.len().max(1)];
+            self.apply_values_to_control(idx, values);
+        }
+    }
+
+    /// Output ceiling of a matrix route, in dB.
+    fn route_ceiling_db(&self, idx: usize) -> Option<f64> {
+        let (_, route) = self.route_of(idx)?;
+        self.user_config.output_ceilings_db.get(&route.output).copied()
+    }
+
+    /// Lowest ceiling among `outputs`, in dB.
+    fn outputs_ceiling_db(&self, outputs: &[usize]) -> Option<f64> {
+        ceilings::outputs_ceiling_db(&self.user_config.output_ceilings_db, outputs)
+    }
+
+    /// Pulls routes and FX returns down to a ceiling that was just lowered below them.
+    fn enforce_output_ceilings(&mut self) {
+        let returns = fx::return_map(&self.controls);
+        let mut over = Vec::new();
+        for (idx, control) in self.controls.iter().enumerate() {
+            let ceiling_db = match returns.iter().find(|(_, i)| **i == idx) {
+                Some((&key, _)) => self.outputs_ceiling_db(&self.fx_return_outputs(key, returns.len())),
+                None => self.route_ceiling_db(idx),
+            };
+            let Some(ceiling) = ceiling_db.and_then(|db| control.kind.raw_at_db(db)) else {
+                continue;
+            };
+            if control
+                .values
+                .iter()
+                .any(|v| v.parse::<i64>().is_ok_and(|raw| raw > ceiling))
+            {
+                over.push((idx, vec![ceiling.to_string(); control.values.len().max(1)]));
+            }
+        }
+        for (idx, values) in over {
+            self.apply_values_to_control(idx, values);
+        }
+    }
+
+    fn change_fx_program(&mut self, idx: usize, values: Vec<String>) {
+        if !self.user_config.fx_clear_tail || self.user_config.fx_bypass.is_some() {
+            self.apply_values_to_control(idx, values);
+            return;
+        }
+        // A change during a pending restore keeps the values saved by the first one.
+        let saved = match self.fx_tail_restore.take() {
+            Some(pending) => pending.saved,
+            None => fx::return_map(&self.controls)
+                .into_values()
+                .filter_map(|i| self.controls.get(i).map(|c| (i, c.values.clone())))
+                .collect(),
+        };
+        for (i, _) in &saved {
+            if let ControlKind::Integer { min, channels, .. } = self.controls[*i].kind {
+                self.apply_values_to_control(*i, vec![min.to_string(); channels]);
+            }
+        }
+        self.apply_values_to_control(idx, values);
+        let delay = Duration::from_millis(self.user_config.fx_tail_ms);
+        self.fx_tail_restore = Some(FxTailRestore {
+            deadline: Instant::now() + delay,
+            saved,
+        });
+    }
+
+    /// Writes the FX returns back once the program-change delay has passed.
+    fn poll_fx_tail_restore(&mut self, ctx: &egui::Context) {
+        let Some(deadline) = self.fx_tail_restore.as_ref().map(|p| p.deadline) else {
+            return;
+        };
+        let now = Instant::now();
+        if now < deadline {
+            ctx.request_repaint_after(deadline - now);
+            return;
+        }
+        for (idx, values) in self.fx_tail_restore.take().map(|p| p.saved).unwrap_or_default() {
+            self.apply_values_to_control(idx, values);
+        }
+        self.status_line = tr("FX returns restored after program change").to_string();
+    }
+
+    /// Integer FX sends and returns: what Bypass zeroes and the macro scales.
+    fn fx_level_controls(&self) -> Vec<usize> {
+        let mut indices: BTreeSet<usize> = self
+            .controls
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| {
+                matches!(c.kind, ControlKind::Integer { .. })
+                    && (fx::is_channel_fx_send(c) || fx::is_fx_return(c))
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+        // Sends mapped by hand need not look like sends.
+        indices.extend(self.find_fx_send_map(false).into_values());
+        indices.extend(self.find_fx_send_map(true).into_values());
+        indices.into_iter().collect()
+    }
+
+    /// Same mechanism as the output masters: moving the macro writes every
+    /// send and return at its base level times the macro, keeping their balance.
+    fn set_fx_macro(&mut self, db: f64) {
+        let previous = self.user_config.fx_macro_db;
+        if (db - previous).abs() < f64::EPSILON {
+            return;
+        }
+        let bases = std::mem::take(&mut self.user_config.fx_macro_bases);
+        let bases = self.apply_master(self.fx_level_controls(), bases, previous, db);
+        if db != 0.0 {
+            self.user_config.fx_macro_bases = bases;
+        }
+        self.user_config.fx_macro_db = db;
+        self.status_line = trf("FX amount: {} dB", &[&format!("{db:+.1}")]);
+    }
+
+    fn render_fx_macro(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(tr("FX amount")).strong())
+                .on_hover_text(tr("Scales every FX send and return together, keeping their balance"));
+            let mut centi_db = (self.user_config.fx_macro_db * 100.0).round() as i64;
+            let drag = self.user_config.knob_drag;
+            let knob = ui
+                .add_enabled_ui(self.user_config.fx_bypass.is_none(), |ui| {
+                    Self::render_knob(ui, drag, &mut centi_db, -4000, 1200, None, Some((-4000, 1200)))
+                        .accessible_name(tr("FX amount"))
+                })
+                .inner;
+            if knob.changed() {
+                self.set_fx_macro(centi_db as f64 / 100.0);
+            }
+            if knob.double_clicked() {
+                self.set_fx_macro(0.0);
+            }
+            if knob.drag_stopped() || knob.double_clicked() || (knob.changed() && !knob.dragged()) {
+                self.save_user_config();
+            }
+        });
+    }
+
+    /// Like Dim, the saved values live in the config so a bypass survives a
+    /// restart. Locked sends and returns are neither zeroed nor restored.
+    fn toggle_fx_bypass(&mut self) {
+        if let Some(saved) = self.user_config.fx_bypass.take() {
+            for (key, values) in saved {
+                if let Some(idx) = self.control_index(&key).filter(|&idx| !self.is_locked_idx(idx)) {
+                    self.apply_values_to_control(idx, values);
+                }
+            }
+            self.save_user_config();
+            self.status_line = tr("FX bypass off: sends and returns restored").to_string();
+            return;
+        }
+        let mut indices = self.fx_level_controls();
+        indices.retain(|&idx| !self.is_locked_idx(idx));
+        let mut saved = self.saved_values(&indices);
+        // Returns muted for a program change are stashed at their real level;
+        // the ones bypass leaves alone get it back now.
+        for (idx, values) in self.fx_tail_restore.take().map(|p| p.saved).unwrap_or_default() {
+            let Some(key) = self.controls.get(idx).map(|c| c.key()) else {
+                continue;
+            };
+            match saved.get_mut(&key) {
+                Some(stashed) => *stashed = values,
+                None => self.apply_values_to_control(idx, values),
+            }
+        }
+        for &idx in &indices {
+            let ControlKind::Integer { min, channels, .. } = self.controls[idx].kind else {
+                continue;
+            };
+            self.apply_values_to_control(idx, vec![min.to_string(); channels]);
+        }
+        self.user_config.fx_bypass = Some(saved);
+        self.save_user_config();
+        self.status_line = trf("FX bypassed ({} sends/returns zeroed)", &[&indices.len()]);
+    }
+
+    fn render_fx_presets(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            let mut chosen = None;
+            egui::ComboBox::from_id_salt("fx_presets")
+                .selected_text(tr("Preset FX…"))
+                .show_ui(ui, |ui| {
+                    if self.fx_presets.is_empty() {
+                        ui.weak(tr("No FX preset"));
+                    }
+                    for (i, preset) in self.fx_presets.iter().enumerate() {
+                        if ui.selectable_label(false, &preset.name).clicked() {
+                            chosen = Some(i);
+                        }
+                    }
+                });
+            ui.add(
+                egui::TextEdit::singleline(&mut self.fx_preset_name)
+                    .hint_text(tr("Name"))
+                    .desired_width(90.0),
+            );
+            if ui
+                .add_enabled(!self.fx_preset_name.trim().is_empty(), egui::Button::new(tr("Save FX")))
+                .on_hover_text(tr("Save program, duration, feedback, sends and returns only"))
+                .clicked()
+            {
+                self.save_fx_preset();
+            }
+            if let Some(i) = chosen {
+                let preset = self.fx_presets[i].clone();
+                self.apply_fx_preset(&preset);
+            }
+        });
+    }
+
+    fn render_fx_surprise(&mut self, ui: &mut egui::Ui, program: Option<usize>) {
+        ui.horizontal(|ui| {
+            if ui
+                .button(tr("Surprise me"))
+                .on_hover_text(tr("Random program, duration and feedback within the ranges"))
+                .clicked()
+            {
+                self.surprise_fx();
+            }
+            let mut changed = false;
+            ui.menu_button(tr("Ranges…"), |ui| {
+                let random = &mut self.user_config.fx_random;
+                if let Some(control) = program.and_then(|idx| self.controls.get(idx)) {
+                    ui.label(RichText::new(tr("Programs")).strong());
+                    let profile = DeviceProfile::from_routing(&self.routing_index);
+                    let choices: Vec<(i64, String)> = match &control.kind {
+                        ControlKind::Integer { min, max, .. } => {
+                            (*min..=*max).map(|v| (v, profile.fx_program_name(v))).collect()
+                        }
+                        ControlKind::Enumerated { items, .. } => {
+                            items.iter().cloned().enumerate().map(|(i, item)| (i as i64, item)).collect()
+                        }
+                        _ => Vec::new(),
+                    };
+                    for (value, name) in &choices {
+                        let mut allowed = random.programs.is_empty() || random.programs.contains(value);
+                        if ui.checkbox(&mut allowed, name).changed() {
+                            if random.programs.is_empty() {
+                                random.programs = choices.iter().map(|(v, _)| *v).collect();
+                            }
+                            if allowed {
+                                random.programs.insert(*value);
+                            } else {
+                                random.programs.remove(value);
+                            }
+                            // Everything ticked is stored as "any".
+                            if random.programs.len() == choices.len() {
+                                random.programs.clear();
+                            }
+                            changed = true;
+                        }
+                    }
+                    ui.separator();
+                }
+                let mut range = |ui: &mut egui::Ui, label: &'static str, lo: &mut f64, hi: &mut f64| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr(label));
+                        for value in [lo, hi] {
+                            let r = ui.add(
+                                egui::DragValue::new(value)
+                                    .range(0.0..=100.0)
+                                    .speed(0.5)
+                                    .fixed_decimals(0)
+                                    .suffix(" %"),
+                            );
+                            changed |= r.drag_stopped() || (r.changed() && !r.dragged());
+                        }
+                    });
+                };
+                range(ui, "Duration", &mut random.duration_min_pct, &mut random.duration_max_pct);
+                range(ui, "Feedback", &mut random.feedback_min_pct, &mut random.feedback_max_pct);
+                ui.horizontal(|ui| {
+                    ui.label(tr("Max sends / returns"));
+                    let r = ui.add(
+                        egui::DragValue::new(&mut random.level_cap_db)
+                            .range(-60.0..=0.0)
+                            .speed(0.25)
+                            .fixed_decimals(1)
+                            .suffix(" dB"),
+                    );
+                    changed |= r.drag_stopped() || (r.changed() && !r.dragged());
+                });
+            });
+            if changed {
+                self.save_user_config();
+            }
+        });
+    }
+
+    /// Random program, duration and feedback within the configured ranges, as
+    /// one undo step. Sends and returns are never raised; any above the cap is
+    /// brought down to it so an odd combination cannot blast. Locked controls
+    /// are left alone.
+    fn surprise_fx(&mut self) {
+        let model = FxModel::from_controls(&self.controls);
+        let random = self.user_config.fx_random.clone();
+        let unlocked = |idx: &usize| !self.is_locked_idx(*idx);
+        let program = model
+            .program
+            .filter(unlocked)
+            .and_then(|idx| self.random_program(idx, &random.programs).map(|p| (idx, p)));
+        let mut targets: Vec<(usize, Vec<String>)> = Vec::new();
+        for (idx, lo, hi) in [
+            (model.duration, random.duration_min_pct, random.duration_max_pct),
+            (model.feedback, random.feedback_min_pct, random.feedback_max_pct),
+        ] {
+            let Some(idx) = idx.filter(unlocked) else {
+                continue;
+            };
+            let Some(ControlKind::Integer { min, max, channels, .. }) =
+                self.controls.get(idx).map(|c| &c.kind)
+            else {
+                continue;
+            };
+            let (min, max, channels) = (*min, *max, *channels);
+            let (lo, hi) = (lo.min(hi), lo.max(hi));
+            let pct = lo + fastrand::f64() * (hi - lo);
+            let raw = min + ((max - min) as f64 * pct / 100.0).round() as i64;
+            targets.push((idx, vec![raw.to_string(); channels]));
+        }
+        let randomized = targets.len();
+        let cap = 10f64.powf(random.level_cap_db / 20.0);
+        for idx in self.fx_level_controls().into_iter().filter(unlocked) {
+            let Some(level) = self.integer_route_level(idx) else {
+                continue;
+            };
+            let ceiling = pan::raw_from_amplitude(cap, level.min, level.max, level.db_range);
+            if level.raw > ceiling {
+                targets.push((idx, vec![ceiling.to_string(); level.channels]));
+            }
+        }
+
+        let mut touched: Vec<usize> = targets.iter().map(|(idx, _)| *idx).collect();
+        touched.extend(program.as_ref().map(|(idx, _)| *idx));
+        if touched.is_empty() {
+            self.status_line = tr("Nothing to randomize (no unlocked FX controls)").to_string();
+            return;
+        }
+        self.push_undo(&touched);
+        let capped = targets.len() - randomized;
+        for (idx, values) in targets {
+            self.apply_values_to_control(idx, values);
+        }
+        let name = match program {
+            Some((idx, (values, name))) => {
+                self.change_fx_program(idx, values);
+                name
+            }
+            None => "program unchanged".to_string(),
+        };
+        self.status_line = if capped > 0 {
+            trf("FX randomized: {} ({} sends/returns capped)", &[&name, &capped])
+        } else {
+            trf("FX randomized: {}", &[&name])
+        };
+    }
+
+    /// A random allowed program other than the current one, with its name.
+    fn random_program(&self, idx: usize, allowed: &BTreeSet<i64>) -> Option<(Vec<String>, String)> {
+        let control = self.controls.get(idx)?;
+        let profile = DeviceProfile::from_routing(&self.routing_index);
+        let choices: Vec<(String, String)> = match &control.kind {
+            ControlKind::Integer { min, max, .. } => (*min..=*max)
+                .filter(|v| allowed.is_empty() || allowed.contains(v))
+                .map(|v| (v.to_string(), profile.fx_program_name(v)))
+                .collect(),
+            ControlKind::Enumerated { items, .. } => items
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| allowed.is_empty() || allowed.contains(&(*i as i64)))
+                .map(|(_, item)| (item.clone(), item.clone()))
+                .collect(),
+            _ => return None,
+        };
+        let current = control.values.first();
+        let fresh: Vec<&(String, String)> = choices.iter().filter(|(v, _)| Some(v) != current).collect();
+        let pool = if fresh.is_empty() { choices.iter().collect() } else { fresh };
+        if pool.is_empty() {
+            return None;
+        }
+        let (value, name) = pool[fastrand::usize(..pool.len())].clone();
+        Some((vec![value; control.values.len().max(1)], name))
+    }
+
+    fn save_fx_preset(&mut self) {
+        let preset = FxPreset::capture(&self.fx_preset_name, &self.controls);
+        let file_name = Self::preset_file_name(&preset.name);
+        match fx::save_fx_preset(&preset, &file_name) {
+            Ok(path) => {
+                self.status_line = trf(
+                    "FX preset \"{}\" saved ({} controls) to {}",
+                    &[&preset.name, &preset.controls.len(), &path.display()],
+                );
+                self.fx_presets.retain(|p| p.name != preset.name);
+                self.fx_presets.push(preset);
+                self.fx_presets.sort_by(|a, b| a.name.cmp(&b.name));
+                self.fx_preset_name.clear();
+            }
+            Err(err) => self.status_line = trf("FX preset save failed: {}", &[&format!("{err:#}")]),
+        }
+    }
+
+    /// Controls an FX preset would change, skipping locked ones.
+    fn fx_preset_targets(&self, preset: &FxPreset) -> Vec<(usize, Vec<String>)> {
+        preset
+            .controls
+            .iter()
+            .filter_map(|(key, values)| {
+                let idx = self.control_index(key)?;
+                (!self.is_locked_idx(idx) && self.controls[idx].values != *values).then(|| (idx, values.clone()))
+            })
+            .collect()
+    }
+
+    fn apply_fx_preset(&mut self, preset: &FxPreset) {
+        let targets = self.fx_preset_targets(preset);
+        let indices: Vec<usize> = targets.iter().map(|(idx, _)| *idx).collect();
+        self.push_undo(&indices);
+        for (idx, values) in targets {
+            self.apply_values_to_control(idx, values);
+        }
+        let missing = preset
+            .controls
+            .keys()
+            .filter(|key| self.control_index(key).is_none())
+            .count();
+        self.status_line = if missing > 0 {
+            trf(
+                "FX preset \"{}\" applied ({} changed, {} controls not on this card)",
+                &[&preset.name, &indices.len(), &missing],
+            )
+        } else {
+            trf("FX preset \"{}\" applied ({} changed)", &[&preset.name, &indices.len()])
+        };
+    }
+
+    fn render_fx_ab(&mut self, ui: &mut egui::Ui) {
+        // A bypassed state would be captured as silent sends and returns.
+        let enabled = self.user_config.fx_bypass.is_none();
+        ui.add_enabled_ui(enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("Compare:"));
+                for (slot, name) in ["A", "B"].into_iter().enumerate() {
+                    let active = self.fx_ab_active == slot;
+                    let text = RichText::new(name).strong();
+                    let text = if active {
+                        text.color(Color32::from_rgb(90, 220, 220))
+                    } else {
+                        text
+                    };
+                    if ui
+                        .add(egui::Button::new(text).selected(active))
+                        .on_hover_text(trf("Switch to FX state {}", &[&name]))
+                        .clicked()
+                    {
+                        self.switch_fx_ab(slot);
+                    }
+                }
+                let (from, to) = if self.fx_ab_active == 0 { ("A", "B") } else { ("B", "A") };
+                if ui
+                    .small_button(trf("Copy {} to {}", &[&from, &to]))
+                    .on_hover_text(trf("Copy the current FX state into {}", &[&to]))
+                    .clicked()
+                {
+                    self.fx_ab[1 - self.fx_ab_active] = Some(FxPreset::capture(to, &self.controls));
+                    self.status_line = trf("FX state {} copied to {}", &[&from, &to]);
+                }
+            });
+        });
+    }
+
+    /// Plugin-style A/B: the live FX state goes into the active slot and the
+    /// other slot is written back. An empty slot starts as a copy of the
+    /// live state, so the first switch changes nothing audible.
+    fn switch_fx_ab(&mut self, slot: usize) {
+        if slot == self.fx_ab_active {
+            return;
+        }
+        let names = ["A", "B"];
+        self.fx_ab[self.fx_ab_active] = Some(FxPreset::capture(names[self.fx_ab_active], &self.controls));
+        self.fx_ab_active = slot;
+        let Some(state) = self.fx_ab[slot].clone() else {
+            self.fx_ab[slot] = Some(FxPreset::capture(names[slot], &self.controls));
+            self.status_line = trf("FX state {} (copy of the current settings)", &[&names[slot]]);
+            return;
+        };
+        let targets = self.fx_preset_targets(&state);
+        for (idx, values) in targets {
+            self.apply_values_to_control(idx, values);
+        }
+        self.status_line = trf("FX state {}", &[&names[slot]]);
+    }
+
+    /// Effect family of the current program, when the program control is known.
+    fn fx_kind(&self, model: &FxModel) -> Option<FxKind> {
+        let control = self.controls.get(model.program?)?;
+        let name = match &control.kind {
+            ControlKind::Integer { .. } => {
+                let index = control.values.first()?.parse::<i64>().ok()?;
+                DeviceProfile::from_routing(&self.routing_index).fx_program_name(index)
+            }
+            _ => control.values.first()?.clone(),
+        };
+        Some(FxKind::of_program(&name))
+    }
+
+    /// FX sends laid out like an aux bus: one strip per input, then one
+    /// return strip per output, then the effect itself.
+    fn render_fx_tab(&mut self, ui: &mut egui::Ui) {
+        let mut actions: Vec<CellAction> = Vec::new();
+        egui::Frame::new()
+            .fill(self.palette.section)
+            .stroke(Stroke::new(1.0, self.palette.stroke))
+            .inner_margin(egui::Margin::symmetric(8, 6))
+            .show(ui, |ui| self.render_fx_macro(ui));
+
+        ui.add_space(6.0);
+        egui::Frame::new()
+            .fill(self.palette.section)
+            .stroke(Stroke::new(1.0, self.palette.stroke))
+            .inner_margin(egui::Margin::symmetric(8, 6))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(tr("FX sends")).strong().size(14.0));
+                    if ui
+                        .small_button(tr("Mapping…"))
+                        .on_hover_text(tr("Choose the FX send control of each input by hand"))
+                        .clicked()
+                    {
+                        self.fx_send_mapping_open = !self.fx_send_mapping_open;
+                    }
+                });
+                let mut any = false;
+                for (title, digital, make) in [
+                    ("Analog inputs", false, RenameTarget::Ain as fn(usize) -> RenameTarget),
+                    ("Digital playback", true, RenameTarget::Din),
+                ] {
+                    let sends: BTreeMap<usize, usize> =
+                        self.find_fx_send_map(digital).into_iter().collect();
+                    if sends.is_empty() {
+                        continue;
+                    }
+                    any = true;
+                    ui.small(tr(title));
+                    ui.horizontal_wrapped(|ui| {
+                        for (input, idx) in sends {
+                            let label = self.alias_text(make(input));
+                            self.render_fx_strip(ui, label, idx, &mut actions);
+                        }
+                    });
+                }
+                if !any {
+                    ui.label(tr("No FX send found on this card."));
+                }
+            });
+
+        ui.add_space(6.0);
+        egui::Frame::new()
+            .fill(self.palette.section)
+            .stroke(Stroke::new(1.0, self.palette.stroke))
+            .inner_margin(egui::Margin::symmetric(8, 6))
+            .show(ui, |ui| {
+                ui.label(RichText::new(tr("FX returns")).strong().size(14.0));
+                let returns = fx::return_map(&self.controls);
+                if returns.is_empty() {
+                    ui.label(tr("No FX return found on this card."));
+                }
+                let count = returns.len();
+                ui.horizontal_wrapped(|ui| {
+                    for (key, idx) in returns {
+                        let label = self.fx_return_label(&self.fx_return_outputs(key, count));
+                        self.render_fx_strip(ui, label, idx, &mut actions);
+                    }
+                });
+            });
+
+        ui.add_space(6.0);
+        egui::Frame::new()
+            .fill(self.palette.section)
+            .stroke(Stroke::new(1.0, self.palette.stroke))
+            .inner_margin(egui::Margin::symmetric(8, 6))
+            .show(ui, |ui| {
+                let header = egui::CollapsingHeader::new(
+                    RichText::new(tr("Effect processor")).strong().size(14.0),
+                )
+                .id_salt("fx_section")
+                .open(Some(!self.user_config.fx_section_collapsed))
+                .show(ui, |ui| self.render_effects_section(ui));
+                if header.header_response.clicked() {
+                    self.user_config.fx_section_collapsed ^= true;
+                    self.save_user_config();
+                }
+            });
+
+        self.run_cell_actions(actions);
+    }
+
+    /// Outputs fed by return `key` of `count`: one output each, or a pair each
+    /// when the card has half as many returns as outputs.
+    fn fx_return_outputs(&self, key: usize, count: usize) -> Vec<usize> {
+        ceilings::fx_return_outputs(key, count, self.output_count())
+    }
+
+    fn fx_return_label(&self, outputs: &[usize]) -> String {
+        outputs
+            .iter()
+            .map(|o| self.alias_text(RenameTarget::Out(*o)))
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+
+    fn render_fx_strip(
+        &self,
+        ui: &mut egui::Ui,
+        label: String,
+        control_idx: usize,
+        actions: &mut Vec<CellAction>,
+    ) {
+        let Some(control) = self.controls.get(control_idx) else {
+            return;
+        };
+        let (cell_w, cell_h) = self.cell_size();
+        ui.allocate_ui_with_layout(
+            vec2(cell_w, cell_h + 18.0),
+            egui::Layout::top_down(egui::Align::Center),
+            |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(label).small().strong())
+                        .on_hover_text(&control.name)
+                        .context_menu(|ui| {
+                            if ui.button(tr("Copy as JSON")).clicked() {
+                                actions.push(CellAction::CopyJson(control_idx));
+                                ui.close();
+                            }
+                            let hidden = self.is_control_hidden(control_idx);
+                            if ui.button(tr(if hidden { "Unhide this control" } else { "Hide this control" })).clicked() {
+                                actions.push(CellAction::ToggleControlHidden(control_idx));
+                                ui.close();
+                            }
+                        });
+                    if self.render_lock_button(ui, control_idx) {
+                        actions.push(CellAction::ToggleLock(control_idx));
+                    }
+                    if self.render_favorite_button(ui, control_idx) {
+                        actions.push(CellAction::ToggleFavorite(control_idx));
+                    }
+                    if self.render_note_button(ui, control_idx) {
+                        actions.push(CellAction::EditNote(control_idx));
+                    }
+                });
+                match &control.kind {
+                    ControlKind::Integer {
+                        min, max, db_range, ..
+                    } => {
+                        let mut v = control
+                            .values
+                            .first()
+                            .and_then(|x| x.parse::<i64>().ok())
+                            .unwrap_or(*min);
+                        let resp = if self.user_config.compact_matrix {
+                            Self::render_compact_value(ui, &mut v, *min, *max, *db_range)
+                        } else {
+                            Self::render_knob(ui, self.user_config.knob_drag, &mut v, *min, *max, None, *db_range)
+                        }
+                        .accessible_name(self.control_spoken_name(control_idx));
+                        if resp.changed() {
+                            actions.push(CellAction::Set(control_idx, vec![v.to_string()]));
+                        }
+                        if resp.double_clicked() {
+                            actions.push(CellAction::Reset(control_idx));
+                        }
+                    }
+                    _ => {
+                        if let Some(values) = Self::render_control_editor(ui, self.user_config.knob_drag, control) {
+                            actions.push(CellAction::Set(control_idx, values));
+                        }
+                    }
+                }
+            },
+        );
+    }
+
+    /// Duration knob with an exact entry in ms when the scaling is known for
+    /// the card, else in percent of the range.
+    fn render_fx_duration_tile(
+        &self,
+        ui: &mut egui::Ui,
+        idx: usize,
+        label: &str,
+    ) -> Option<Vec<String>> {
+        let control = self.controls.get(idx)?;
+        let ControlKind::Integer { min, max, .. } = control.kind else {
+            return self.render_effect_tile_labeled(ui, idx, label);
+        };
+        if max <= min {
+            return self.render_effect_tile_labeled(ui, idx, label);
+        }
+        let in_ms = DeviceProfile::from_routing(&self.routing_index).duration_in_ms(min, max);
+        let current = control
+            .values
+            .first()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(min);
+        let mut raw = current;
+        ui.allocate_ui_with_layout(
+            vec2(124.0, 92.0),
+            egui::Layout::top_down(egui::Align::Center),
+            |ui| {
+                ui.label(RichText::new(label).strong())
+                    .on_hover_text(format!("{} (numid={})", control.name, control.numid));
+                Self::render_knob(ui, self.user_config.knob_drag, &mut raw, min, max, None, None)
+                    .accessible_name(label);
+                if in_ms {
+                    ui.add(
+                        egui::DragValue::new(&mut raw)
+                            .range(min..=max)
+                            .speed(5.0)
+                            .suffix(" ms"),
+                    );
+                } else {
+                    let span = (max - min) as f64;
+                    let mut percent = (raw - min) as f64 * 100.0 / span;
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut percent)
+                                .range(0.0..=100.0)
+                                .speed(0.5)
+                                .fixed_decimals(0)
+                                .suffix(" %"),
+                        )
+                        .changed()
+                    {
+                        raw = min + (percent * span / 100.0).round() as i64;
+                    }
+                }
+            },
+        );
+        (raw != current).then(|| vec![raw.to_string(); control.values.len().max(1)])
+    }
+
+    /// An effect tile with its padlock and star underneath; clicks on them are
+    /// queued in `toggles`.
+    fn render_lockable_tile(
+        &self,
+        ui: &mut egui::Ui,
+        idx: usize,
+        toggles: &mut Vec<CellAction>,
+        tile: impl FnOnce(&mut egui::Ui) -> Option<Vec<String>>,
+    ) -> Option<Vec<String>> {
+        ui.vertical(|ui| {
+            let values = tile(ui);
+            ui.horizontal(|ui| {
+                if self.render_lock_button(ui, idx) {
+                    toggles.push(CellAction::ToggleLock(idx));
+                }
+                if self.render_favorite_button(ui, idx) {
+                    toggles.push(CellAction::ToggleFavorite(idx));
+                }
+                if self.render_note_button(ui, idx) {
+                    toggles.push(CellAction::EditNote(idx));
+                }
+            });
+            values
+        })
+        .inner
+    }
+
+    fn render_effect_tile(&self, ui: &mut egui::Ui, idx: usize) -> Option<Vec<String>> {
+        let display_name = Self::fx_display_name(&self.controls.get(idx)?.name);
+        self.render_effect_tile_labeled(ui, idx, &display_name)
+    }
+
+    fn render_effect_tile_labeled(
+        &self,
+        ui: &mut egui::Ui,
+        idx: usize,
+        display_name: &str,
+    ) -> Option<Vec<String>> {
+        let control = self.controls.get(idx)?.clone();
+        Self::render_effect_tile_for(ui, self.user_config.knob_drag, &control, display_name)
+    }
+
+    /// Effect Feedback with its knob range ending at the safety cap.
+    fn render_fx_feedback_tile(&self, ui: &mut egui::Ui, idx: usize) -> Option<Vec<String>> {
+        let mut control = self.controls.get(idx)?.clone();
+        if let (Some(ceiling), ControlKind::Integer { min, max, .. }) =
+            (self.fx_feedback_ceiling(idx), &mut control.kind)
+        {
+            *max = ceiling.max(*min);
+        }
+        let display_name = Self::fx_display_name(&control.name);
+        Self::render_effect_tile_for(ui, self.user_config.knob_drag, &control, &display_name)
+    }
+
+    fn render_effect_tile_for(
+        ui: &mut egui::Ui,
+        drag: KnobDragConfig,
+        control: &ControlDescriptor,
+        display_name: &str,
+    ) -> Option<Vec<String>> {
+        let mut out = None;
+        ui.allocate_ui_with_layout(
+            vec2(124.0, 92.0),
+            egui::Layout::top_down(egui::Align::Center),
+            |ui| {
+                ui.add_sized(
+                    vec2(118.0, 28.0),
+                    egui::Label::new(RichText::new(display_name).strong())
+                        .wrap()
+                        .sense(egui::Sense::hover()),
+                );
+                out = Self::render_effect_control_inline(ui, drag, control);
+            },
+        );
+        out
+    }
+
+    /// Effect Program as a list of effect names when the kernel exposes it as
+    /// a bare integer; the enum index is still what gets written.
+    fn render_fx_program_tile(&self, ui: &mut egui::Ui, idx: usize) -> Option<Vec<String>> {
+        let control = self.controls.get(idx)?;
+        let ControlKind::Integer { min, max, .. } = control.kind else {
+            return self.render_effect_tile(ui, idx);
+        };
+        let profile = DeviceProfile::from_routing(&self.routing_index);
+        let current = control
+            .values
+            .first()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(min);
+        let mut selected = current;
+        ui.allocate_ui_with_layout(
+            vec2(124.0, 92.0),
+            egui::Layout::top_down(egui::Align::Center),
+            |ui| {
+                ui.add_sized(
+                    vec2(118.0, 28.0),
+                    egui::Label::new(RichText::new(tr("Effect Program")).strong())
+                        .sense(egui::Sense::hover()),
+                )
+                .on_hover_text(format!("{} (numid={})", control.name, control.numid));
+                egui::ComboBox::from_id_salt(("fx_program", idx))
+                    .selected_text(profile.fx_program_name(current))
+                    .width(110.0)
+                    .show_ui(ui, |ui| {
+                        for value in min..=max {
+                            ui.selectable_value(&mut selected, value, profile.fx_program_name(value));
+                        }
+                    });
+            },
+        );
+        (selected != current).then(|| vec![selected.to_string(); control.values.len().max(1)])
+    }
+
+    fn render_effect_control_inline(
+        ui: &mut egui::Ui,
+        drag: KnobDragConfig,
+        control: &ControlDescriptor,
+    ) -> Option<Vec<String>> {
+        match &control.kind {
+            ControlKind::Integer {
+                min,
+                max,
+                channels,
+                db_range,
+                ..
+            } => {
+                let mut new_values = control.values.clone();
+                let mut changed = false;
+                ui.horizontal_wrapped(|ui| {
+                    for ch in 0..*channels {
+                        let mut v = control
+                            .values
+                            .get(ch)
+                            .and_then(|x| x.parse::<i64>().ok())
+                            .unwrap_or(*min);
+                        let ch_label = if *channels > 1 {
+                            Some(format!("Ch{}", ch + 1))
+                        } else {
+                            None
+                        };
+                        let name = match &ch_label {
+                            Some(ch) => format!("{} {ch}", control.name),
+                            None => control.name.clone(),
+                        };
+                        changed |= Self::render_knob(
+                            ui,
+                            drag,
+                            &mut v,
+                            *min,
+                            *max,
+                            ch_label,
+                            *db_range,
+                        )
+                        .accessible_name(name)
+                        .changed();
+                        if ch < new_values.len() {
+                            new_values[ch] = v.to_string();
+                        } else {
+                            new_values.push(v.to_string());
+                        }
+                    }
+                });
+                if changed {
+                    return Some(new_values);
+                }
+            }
+            ControlKind::Boolean { channels } => {
+                let mut new_values = control.values.clone();
+                let mut changed = false;
+                ui.horizontal_wrapped(|ui| {
+                    for ch in 0..*channels {
+                        let mut on = control
+                            .values
+                            .get(ch)
+                            .map(|v| v.eq_ignore_ascii_case("on") || v == "1")
+                            .unwrap_or(false);
+                        changed |= ui.checkbox(&mut on, format!("Ch{}", ch + 1)).changed();
+                        if ch < new_values.len() {
+                            new_values[ch] = if on { "on" } else { "off" }.to_string();
+                        } else {
+                            new_values.push(if on { "on" } else { "off" }.to_string());
+                        }
+                    }
+                });
+                if changed {
+                    return Some(new_values);
+                }
+            }
+            ControlKind::Enumerated { items, channels } => {
+                let mut new_values = control.values.clone();
+                let mut changed = false;
+                ui.horizontal_wrapped(|ui| {
+                    for ch in 0..*channels {
+                        let mut current = control
+                            .values
+                            .get(ch)
+                            .cloned()
+                            .unwrap_or_else(|| items.first().cloned().unwrap_or_default());
+                        egui::ComboBox::from_label(format!("Ch{}", ch + 1))
+                            .selected_text(current.clone())
+                            .show_ui(ui, |ui| {
+                                for item in items {
+                                    if ui.selectable_label(current == *item, item).clicked() {
+                                        current = item.clone();
+                                        changed = true;
+                                    }
+                                }
+                            });
+                        if ch < new_values.len() {
+                            new_values[ch] = current;
+                        } else {
+                            new_values.push(current);
+                        }
+                    }
+                });
+                if changed {
+                    return Some(new_values);
+                }
+            }
+            ControlKind::Unknown { .. } => {
+                return Self::render_control_editor(ui, drag, control);
+            }
+        }
+        None
+    }
+
+    fn fx_display_name(name: &str) -> String {
+        name.replace(" Capture Volume", "")
+            .replace(" Playback Volume", "")
+            .replace(" Switch", "")
+            .replace(" Volume", "")
+    }
+
+    fn is_transposed(&self, bus: MatrixBus) -> bool {
+        match bus {
+            MatrixBus::Analog => self.user_config.transpose_monitoring,
+            MatrixBus::Digital => self.user_config.transpose_digital,
+        }
+    }
+
+    fn render_transpose_toggle(&mut self, ui: &mut egui::Ui, bus: MatrixBus) {
+        let flag = match bus {
+            MatrixBus::Analog => &mut self.user_config.transpose_monitoring,
+            MatrixBus::Digital => &mut self.user_config.transpose_digital,
+        };
+        if ui
+            .toggle_value(flag, RichText::new(tr("⇄ Outs as rows")).small())
+            .on_hover_text(tr("Transpose: outputs as rows, inputs as columns"))
+            .changed()
+        {
+            self.save_user_config();
+        }
+    }
+
+    fn cell_widget(&self, grid: MatrixGrid) -> CellWidget {
+        match grid {
+            MatrixGrid::Monitoring => self.user_config.monitoring_widget,
+            MatrixGrid::DigitalRoutes => self.user_config.digital_widget,
+            MatrixGrid::CueMix => self.user_config.cue_mix_widget,
+        }
+    }
+
+    fn cell_widget_mut(&mut self, grid: MatrixGrid) -> &mut CellWidget {
+        match grid {
+            MatrixGrid::Monitoring => &mut self.user_config.monitoring_widget,
+            MatrixGrid::DigitalRoutes => &mut self.user_config.digital_widget,
+            MatrixGrid::CueMix => &mut self.user_config.cue_mix_widget,
+        }
+    }
+
+    /// Knob / slider / fader choice shown above a matrix.
+    fn render_cell_widget_picker(&mut self, ui: &mut egui::Ui, grid: MatrixGrid) -> bool {
+        let widget = self.cell_widget_mut(grid);
+        let mut changed = false;
+        egui::ComboBox::from_id_salt(("cell_widget", grid))
+            .selected_text(RichText::new(widget.label()).small())
+            .show_ui(ui, |ui| {
+                for option in CellWidget::ALL {
+                    changed |= ui.selectable_value(widget, option, option.label()).changed();
+                }
+            });
+        changed
+    }
+
+    /// AIn or DIn × Out matrix, with inputs as rows unless the bus is transposed.
+    fn render_route_matrix(&mut self, ui: &mut egui::Ui, bus: MatrixBus) {
+        let (make_input, grid, input_name): (fn(usize) -> RenameTarget, _, _) = match bus {
+            MatrixBus::Analog => (RenameTarget::Ain, MatrixGrid::Monitoring, "AIn"),
+            MatrixBus::Digital => (RenameTarget::Din, MatrixGrid::DigitalRoutes, "DIn"),
+        };
+        let refs = self.bus_routes(bus);
+        if refs.is_empty() {
+            ui.label(tr(match bus {
+                MatrixBus::Analog => "No analog monitoring routes found.",
+                MatrixBus::Digital => "No routes found for this group.",
+            }));
+            return;
+        }
+
+        let max_input = refs.iter().map(|r| r.input).max().unwrap_or(0);
+        let max_output = refs.iter().map(|r| r.output).max().unwrap_or(0);
+        let by_pair: HashMap<(usize, usize), usize> = refs
+            .iter()
+            .map(|r| ((r.input, r.output), r.control_index))
+            .collect();
+        let inputs = self.visible_axis(max_input, make_input);
+        let outputs = self.visible_axis(max_output, RenameTarget::Out);
+        let transposed = self.is_transposed(bus);
+
+        let (cell_w, cell_h) = self.cell_size();
+        let mut actions: Vec<CellAction> = Vec::new();
+        let mut cells = Vec::new();
+        egui::Grid::new(match bus {
+            MatrixBus::Analog => "monitoring_matrix_grid",
+            MatrixBus::Digital => "digital_grid",
+        })
+        .striped(true)
+        .show(ui, |ui| {
+            if transposed {
+                ui.label(format!("Out \\ {input_name}"));
+                for input in inputs.iter().copied() {
+                    ui.allocate_ui_with_layout(
+                        vec2(cell_w, 18.0),
+                        egui::Layout::top_down(egui::Align::Center),
+                        |ui| self.render_input_header(ui, make_input(input), cell_w, &mut actions),
+                    );
+                }
+                ui.label(RichText::new("Master").small())
+                    .on_hover_text(tr("Overall level of each output: scales every route feeding it"));
+                self.render_dim_amount(ui);
+                ui.end_row();
+
+                for (row, output) in outputs.iter().copied().enumerate() {
+                    ui.allocate_ui_with_layout(
+                        vec2(self.row_label_w(), cell_h),
+                        egui::Layout::top_down(egui::Align::Min),
+                        |ui| {
+                            self.render_line_header(
+                                ui,
+                                bus,
+                                RenameTarget::Out(output),
+                                true,
+                                self.row_label_w(),
+                                &mut actions,
+                            );
+                        },
+                    );
+                    let mut row_cells = Vec::new();
+                    for (col, input) in inputs.iter().copied().enumerate() {
+                        let cell = by_pair.get(&(input, output)).copied().filter(|idx| self.is_control_shown(*idx));
+                        row_cells.push(cell);
+                        if let Some(control_idx) = cell {
+                            let pos = GridPos { grid, row, col };
+                            actions.extend(self.render_route_cell(ui, control_idx, pos));
+                        } else {
+                            ui.label("-");
+                        }
+                    }
+                    self.render_master_cell(ui, output, cell_w, &mut actions);
+                    self.render_dim_button(ui, output, cell_w, &mut actions);
+                    cells.push(row_cells);
+                    ui.end_row();
+                }
+            } else {
+                ui.label(format!("{input_name} \\ Out"));
+                for output in outputs.iter().copied() {
+                    ui.allocate_ui_with_layout(
+                        vec2(cell_w, 18.0),
+                        egui::Layout::top_down(egui::Align::Center),
+                        |ui| {
+                            self.render_line_header(
+                                ui,
+                                bus,
+                                RenameTarget::Out(output),
+                                true,
+                                cell_w,
+                                &mut actions,
+                            );
+                        },
+                    );
+                }
+                ui.end_row();
+                self.render_master_row(ui, &outputs, cell_w, &mut actions);
+                self.render_dim_row(ui, &outputs, cell_w, &mut actions);
+
+                for (row, input) in inputs.iter().copied().enumerate() {
+                    ui.allocate_ui_with_layout(
+                        vec2(self.row_label_w(), cell_h),
+                        egui::Layout::top_down(egui::Align::Min),
+                        |ui| {
+                            self.render_input_header(
+                                ui,
+                                make_input(input),
+                                self.row_label_w(),
+                                &mut actions,
+                            );
+                        },
+                    );
+                    let mut row_cells = Vec::new();
+                    for (col, output) in outputs.iter().copied().enumerate() {
+                        let cell = by_pair.get(&(input, output)).copied().filter(|idx| self.is_control_shown(*idx));
+                        row_cells.push(cell);
+                        if let Some(control_idx) = cell {
+                            let pos = GridPos { grid, row, col };
+                            actions.extend(self.render_route_cell(ui, control_idx, pos));
+                        } else {
+                            ui.label("-");
+                        }
+                    }
+                    cells.push(row_cells);
+                    ui.end_row();
+                }
+            }
+        });
+
+        self.matrix_cells.insert(grid, cells);
+        self.run_cell_actions(actions);
+    }
+
+    fn render_route_cell(
+        &self,
+        ui: &mut egui::Ui,
+        control_idx: usize,
+        pos: GridPos,
+    ) -> Vec<CellAction> {
+        let mut out = Vec::new();
+        let Some(control) = self.controls.get(control_idx) else {
+            return out;
+        };
+        let muted = self.user_config.muted_routes.contains_key(&control.key());
+        let name = self.control_spoken_name(control_idx);
+        let compact = self.user_config.compact_matrix;
+        let (cell_w, cell_h) = self.cell_size();
+        // Shift+drag draws a selection band instead of turning knobs.
+        let banding = ui.input(|i| i.modifiers.shift && i.pointer.primary_down());
+        let gesture = self.paint_mode || banding;
+        let background = ui.painter().add(egui::Shape::Noop);
+        let cell = ui.allocate_ui_with_layout(
+            vec2(cell_w, cell_h),
+            egui::Layout::top_down(egui::Align::Center),
+            |ui| match &control.kind {
+            ControlKind::Integer {
+                min, max, db_range, ..
+            } => {
+                let mut v = control
+                    .values
+                    .first()
+                    .and_then(|x| x.parse::<i64>().ok())
+                    .unwrap_or(*min);
+                if compact {
+                    let value = Self::render_compact_value(ui, &mut v, *min, *max, *db_range)
+                        .accessible_name(name.clone())
+                        .on_hover_ui(|ui| {
+                            ui.label(self.route_tooltip(control_idx));
+                        });
+                    if value.changed() && !gesture {
+                        out.push(self.edit_action(control_idx, v));
+                    }
+                    if value.drag_started() || value.gained_focus() {
+                        out.push(CellAction::Focus(pos));
+                    }
+                    if !self.paint_mode {
+                        value.context_menu(|ui| self.render_cell_menu(ui, control_idx, muted, &mut out));
+                    }
+                    return;
+                }
+                let knob = match self.cell_widget(pos.grid) {
+                    CellWidget::Knob => {
+                        Self::render_knob(ui, self.user_config.knob_drag, &mut v, *min, *max, None, *db_range)
+                    }
+                    widget => Self::render_cell_fader(ui, widget, &mut v, *min, *max, *db_range),
+                }
+                .accessible_name(name.clone())
+                .on_hover_ui(|ui| {
+                        ui.label(self.route_tooltip(control_idx));
+                    });
+                if knob.changed() && !gesture {
+                    out.push(self.edit_action(control_idx, v));
+                }
+                if knob.double_clicked() && !self.paint_mode {
+                    out.push(CellAction::Reset(control_idx));
+                }
+                if knob.clicked() || knob.drag_started() {
+                    out.push(CellAction::Focus(pos));
+                }
+                if !self.paint_mode {
+                    knob.context_menu(|ui| self.render_cell_menu(ui, control_idx, muted, &mut out));
+                }
+                let mute_text = RichText::new("M").small();
+                let mute_text = if muted {
+                    mute_text.strong().color(self.palette.muted)
+                } else {
+                    mute_text
+                };
+                ui.horizontal(|ui| {
+                    if ui
+                        .add(egui::Button::new(mute_text).selected(muted).small())
+                        .accessible_name(trf(if muted { "Unmute {}" } else { "Mute {}" }, &[&name]))
+                        .on_hover_text(tr(if muted {
+                            "Unmute (restore previous level)"
+                        } else {
+                            "Mute (remember current level)"
+                        }))
+                        .clicked()
+                    {
+                        out.push(CellAction::ToggleMute(control_idx));
+                    }
+                    if self.is_locked(control) && self.render_lock_button(ui, control_idx) {
+                        out.push(CellAction::ToggleLock(control_idx));
+                    }
+                    if control.favorite && self.render_favorite_button(ui, control_idx) {
+                        out.push(CellAction::ToggleFavorite(control_idx));
+                    }
+                    if self.control_note(control_idx).is_some() && self.render_note_button(ui, control_idx) {
+                        out.push(CellAction::EditNote(control_idx));
+                    }
+                });
+            }
+            ControlKind::Boolean { .. } => {
+                let mut is_on = control
+                    .values
+                    .first()
+                    .map(|v| v.eq_ignore_ascii_case("on") || v == "1")
+                    .unwrap_or(false);
+                if ui.checkbox(&mut is_on, "").accessible_name(name.clone()).changed() {
+                    out.push(CellAction::Set(
+                        control_idx,
+                        vec![if is_on { "on" } else { "off" }.to_string()],
+                    ));
+                }
+            }
+            _ => {
+                ui.label("...");
+            }
+        },
+        );
+        if self.paint_mode && matches!(control.kind, ControlKind::Integer { .. }) {
+            // The drag is owned by the knob it started on, so follow the raw pointer.
+            let (primary, secondary, pointer) = ui.input(|i| {
+                (
+                    i.pointer.primary_down(),
+                    i.pointer.secondary_down(),
+                    i.pointer.interact_pos(),
+                )
+            });
+            if (primary || secondary) && pointer.is_some_and(|p| cell.response.rect.contains(p)) {
+                out.push(CellAction::Paint(pos, control_idx, secondary && !primary));
+            }
+        }
+        if ui.rect_contains_pointer(cell.response.rect) {
+            out.push(CellAction::Hover(pos, control_idx));
+            if ui.input(|i| i.modifiers.command && i.pointer.primary_clicked()) {
+                out.push(CellAction::ToggleSelect(control_idx));
+            }
+        }
+        if Self::selection_band(ui.ctx()).is_some_and(|band| band.intersects(cell.response.rect)) {
+            out.push(CellAction::Select(control_idx));
+        }
+        if muted && self.palette.state_cues {
+            let stroke = Stroke::new(1.0, self.palette.muted.gamma_multiply(0.35));
+            Self::paint_hatch(ui.painter(), cell.response.rect.shrink(2.0), stroke);
+        }
+        if let Some(ceiling_db) = self.route_ceiling_db(control_idx) {
+            // A bar along the top of cells with an output ceiling, lit with
+            // "LIM" once the level sits on it.
+            let limited = control.kind.raw_at_db(ceiling_db).is_some_and(|ceiling| {
+                control
+                    .values
+                    .iter()
+                    .any(|v| v.parse::<i64>().is_ok_and(|raw| raw >= ceiling))
+            });
+            let color = if limited {
+                Color32::from_rgb(240, 120, 80)
+            } else {
+                Color32::from_rgba_unmultiplied(240, 120, 80, 90)
+            };
+            let rect = cell.response.rect.shrink(2.0);
+            ui.painter()
+                .line_segment([rect.left_top(), rect.right_top()], Stroke::new(2.0, color));
+            if limited {
+                ui.painter().text(
+                    rect.right_top() + vec2(-2.0, 3.0),
+                    egui::Align2::RIGHT_TOP,
+                    "LIM",
+                    egui::FontId::proportional(9.0),
+                    color,
+                );
+            }
+        }
+        if self.matrix_selection.contains(&control_idx) {
+            ui.painter().rect_stroke(
+                cell.response.rect.shrink(2.5),
+                4.0,
+                Stroke::new(1.5, Color32::from_rgb(240, 190, 90)),
+                egui::StrokeKind::Inside,
+            );
+        }
+        let crosshair = self
+            .matrix_hover
+            .is_some_and(|h| h.grid == pos.grid && (h.row == pos.row || h.col == pos.col));
+        if crosshair {
+            let alpha = if self.matrix_hover == Some(pos) { 46 } else { 22 };
+            ui.painter().set(
+                background,
+                egui::Shape::rect_filled(
+                    cell.response.rect,
+                    4.0,
+                    Color32::from_rgba_unmultiplied(90, 220, 220, alpha),
+                ),
+            );
+        }
+        if self.matrix_cursor == Some(pos) {
+            let rect = cell.response.rect;
+            ui.painter().rect_stroke(
+                rect.shrink(1.0),
+                4.0,
+                Stroke::new(1.5, Color32::from_rgb(90, 220, 220)),
+                egui::StrokeKind::Inside,
+            );
+            if self.scroll_to_cursor {
+                ui.scroll_to_rect(rect, None);
+            }
+        }
+        out
+    }
+
+    /// "AIn1 → Out1" with aliases, for matrix routes.
+    fn route_name(&self, control_idx: usize) -> Option<String> {
+        let (bus, route) = self.route_of(control_idx)?;
+        let source = match bus {
+            MatrixBus::Analog => RenameTarget::Ain(route.input),
+            MatrixBus::Digital => RenameTarget::Din(route.input),
+        };
+        Some(format!(
+            "{} → {}",
+            self.alias_text(source),
+            self.alias_text(RenameTarget::Out(route.output))
+        ))
+    }
+
+    /// Spoken name of a control: its route when it has one, else its ALSA name.
+    fn control_spoken_name(&self, control_idx: usize) -> String {
+        self.route_name(control_idx)
+            .or_else(|| self.controls.get(control_idx).map(|c| c.name.clone()))
+            .unwrap_or_default()
+    }
+
+    /// Route, control identity and the value as raw, dB and percent, so a
+    /// knob can be matched against `amixer` output.
+    fn route_tooltip(&self, control_idx: usize) -> String {
+        let Some(control) = self.controls.get(control_idx) else {
+            return String::new();
+        };
+        let mut lines = Vec::new();
+        lines.extend(self.route_name(control_idx));
+        lines.push(format!("{} (numid={})", control.name, control.numid));
+        if let Some(level) = self.integer_route_level(control_idx) {
+            lines.push(trf(
+                "Raw: {} (range {}..{})",
+                &[&control.values.join(", "), &level.min, &level.max],
+            ));
+            let db = level.db()
+                .map(|db| format!("{db:.2} dB · "))
+                .unwrap_or_default();
+            lines.push(format!(
+                "{db}{}%",
+                Self::control_percent(level.raw, level.min, level.max, level.db_range)
+            ));
+        }
+        if self.user_config.muted_routes.contains_key(&control.key()) {
+            lines.push(tr("Muted — right-click to unmute").to_string());
+        }
+        if let Some(db) = self.route_ceiling_db(control_idx) {
+            lines.push(trf("Limited to {} dB on this output", &[&format!("{db:+.1}")]));
+        }
+        if let Some(note) = self.control_note(control_idx) {
+            lines.push(format!("📝 {note}"));
+        }
+        lines.join("\n")
+    }
+
+    fn render_cell_menu(
+        &self,
+        ui: &mut egui::Ui,
+        control_idx: usize,
+        muted: bool,
+        out: &mut Vec<CellAction>,
+    ) {
+        let Some(control) = self.controls.get(control_idx) else {
+            return;
+        };
+        let ControlKind::Integer {
+            min,
+            max,
+            step,
+            channels,
+            db_range,
+        } = &control.kind
+        else {
+            return;
+        };
+        if control.kind.unity_value().is_some() {
+            let label = if db_range.is_some() {
+                "Set to 0 dB"
+            } else {
+                "Set to max"
+            };
+            if ui.button(tr(label)).clicked() {
+                out.push(CellAction::Reset(control_idx));
+                ui.close();
+            }
+        }
+        if ui.button(tr(if muted { "Unmute" } else { "Mute" })).clicked() {
+            out.push(CellAction::ToggleMute(control_idx));
+            ui.close();
+        }
+        if ui.button(tr("Enter exact value…")).clicked() {
+            out.push(CellAction::EditValue(control_idx));
+            ui.close();
+        }
+        let locked = self.is_locked(control);
+        if ui
+            .button(tr(if locked { "🔓 Unlock" } else { "🔒 Lock" }))
+            .on_hover_text(tr("Locked controls are skipped by Disable FX, quick actions and presets"))
+            .clicked()
+        {
+            out.push(CellAction::ToggleLock(control_idx));
+            ui.close();
+        }
+        if ui
+            .button(tr(if control.favorite { "★ Remove from favorites" } else { "☆ Add to favorites" }))
+            .clicked()
+        {
+            out.push(CellAction::ToggleFavorite(control_idx));
+            ui.close();
+        }
+        if ui.button(tr("📝 Note…")).clicked() {
+            out.push(CellAction::EditNote(control_idx));
+            ui.close();
+        }
+        let hidden = self.is_control_hidden(control_idx);
+        if ui
+            .button(tr(if hidden { "Unhide this control" } else { "Hide this control" }))
+            .on_hover_text(tr("Hidden controls leave the matrices and the All controls tab until \"Show all\""))
+            .clicked()
+        {
+            out.push(CellAction::ToggleControlHidden(control_idx));
+            ui.close();
+        }
+        ui.separator();
+        if ui.button(tr("Copy value")).clicked() {
+            out.push(CellAction::CopyValue(control_idx));
+            ui.close();
+        }
+        if ui
+            .add_enabled(self.cell_clipboard.is_some(), egui::Button::new(tr("Paste value")))
+            .clicked()
+        {
+            out.push(CellAction::PasteValue(control_idx));
+            ui.close();
+        }
+        let copy_label = if self.matrix_selection.len() > 1 && self.matrix_selection.contains(&control_idx) {
+            trf("Copy {} cells as JSON", &[&self.matrix_selection.len()])
+        } else {
+            tr("Copy as JSON").to_string()
+        };
+        if ui
+            .button(copy_label)
+            .on_hover_text(tr("Ctrl+V in another FTU mixer window applies it"))
+            .clicked()
+        {
+            out.push(CellAction::CopyJson(control_idx));
+            ui.close();
+        }
+        ui.separator();
+        ui.menu_button(tr("Control info"), |ui| {
+            ui.label(trf("numid={} {} (index {})", &[&control.numid, &control.name, &control.index]));
+            ui.label(trf("Integer {}..={}, step {}, {} ch", &[min, max, step, channels]));
+            match db_range {
+                Some((lo, hi)) => ui.label(trf(
+                    "dB range {}..={} dB",
+                    &[&format!("{:.2}", *lo as f64 / 100.0), &format!("{:.2}", *hi as f64 / 100.0)],
+                )),
+                None => ui.label(tr("No dB range")),
+            };
+            ui.label(trf("Raw values: {}", &[&control.values.join(", ")]));
+        });
+    }
+
+    fn render_cell_value_window(&mut self, ctx: &egui::Context) {
+        let Some(edit) = self.cell_value_edit.as_mut() else {
+            return;
+        };
+        let Some(control) = self.controls.get(edit.control_index) else {
+            self.cell_value_edit = None;
+            return;
+        };
+        let ControlKind::Integer {
+            min, max, channels, ..
+        } = control.kind
+        else {
+            self.cell_value_edit = None;
+            return;
+        };
+        let mut open = true;
+        let mut submit = false;
+        let mut cancel = false;
+        egui::Window::new(tr("Exact value"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(&control.name);
+                ui.label(RichText::new(trf("Raw value, {}..={}", &[&min, &max])).small().weak());
+                let resp = ui.text_edit_singleline(&mut edit.text);
+                if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    submit = true;
+                }
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Apply")).clicked() {
+                        submit = true;
+                    }
+                    if ui.button(tr("Cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+        if submit {
+            match edit.text.trim().parse::<i64>() {
+                Ok(raw) => {
+                    let raw = raw.clamp(min, max);
+                    let idx = edit.control_index;
+                    self.cell_value_edit = None;
+                    self.run_cell_actions(vec![CellAction::Set(idx, vec![raw.to_string(); channels])]);
+                }
+                Err(_) => {
+                    self.status_line = trf("Invalid value '{}'", &[&edit.text.trim()]);
+                }
+            }
+        } else if cancel || !open {
+            self.cell_value_edit = None;
+        }
+    }
+
+    fn control_note(&self, idx: usize) -> Option<&str> {
+        let control = self.controls.get(idx)?;
+        self.user_config.control_notes.get(&control.key()).map(String::as_str)
+    }
+
+    /// Note marker; shows the note on hover and returns `true` when clicked.
+    fn render_note_button(&self, ui: &mut egui::Ui, idx: usize) -> bool {
+        let note = self.control_note(idx);
+        let text = if note.is_some() {
+            RichText::new("📝").small()
+        } else {
+            RichText::new("📝").small().weak()
+        };
+        let hint = note.unwrap_or(tr("Add a note"));
+        ui.add(egui::Button::new(text).small().frame(false))
+            .accessible_name(hint)
+            .on_hover_text(hint)
+            .clicked()
+    }
+
+    fn render_note_window(&mut self, ctx: &egui::Context) {
+        let Some(edit) = self.note_edit.as_mut() else {
+            return;
+        };
+        let Some((name, key)) = self.controls.get(edit.control_index).map(|c| (c.name.clone(), c.key())) else {
+            self.note_edit = None;
+            return;
+        };
+        let mut open = true;
+        let mut save = false;
+        let mut cancel = false;
+        egui::Window::new(tr("Note"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(&name);
+                ui.add(
+                    egui::TextEdit::multiline(&mut edit.text)
+                        .hint_text(tr("e.g. kick drum mic — don't send to the singer's cue"))
+                        .desired_rows(3),
+                );
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Save")).clicked() {
+                        save = true;
+                    }
+                    if ui.button(tr("Cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
+                ui.small(tr("An empty note removes it."));
+            });
+        if save {
+            let note = edit.text.trim().to_string();
+            self.note_edit = None;
+            if note.is_empty() {
+                self.user_config.control_notes.remove(&key);
+            } else {
+                self.user_config.control_notes.insert(key, note);
+            }
+            self.save_user_config();
+        } else if cancel || !open {
+            self.note_edit = None;
+        }
+    }
+
+    fn reset_to_unity(&mut self, idx: usize) {
+        let Some(control) = self.controls.get(idx) else {
+            return;
+        };
+        let (Some(unity), ControlKind::Integer { channels, .. }) =
+            (control.kind.unity_value(), &control.kind)
+        else {
+            return;
+        };
+        let values = vec![unity.to_string(); *channels];
+        if control.values == values {
+            return;
+        }
+        let mut touched = vec![idx];
+        touched.extend(self.linked_route_partners(idx));
+        self.push_undo(&touched);
+        self.run_cell_actions(vec![CellAction::Set(idx, values)]);
+    }
 
-        let remaining: Vec<usize> = fx_indices
+    fn push_undo(&mut self, indices: &[usize]) {
+        let entry: Vec<(usize, Vec<String>)> = indices
             .iter()
-            .copied()
-            .filter(|idx| !used.contains(idx))
+            .filter_map(|&idx| self.controls.get(idx).map(|c| (idx, c.values.clone())))
             .collect();
-        if !remaining.is_empty() {
-            ui.separator();
-            ui.horizontal_wrapped(|ui| {
-                for idx in remaining {
-                    if let Some(values) = self.render_effect_tile(ui, idx) {
-                        actions.push((idx, values));
+        if entry.is_empty() {
+            return;
+        }
+        if self.undo_stack.len() >= Self::UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(entry);
+    }
+
+    fn undo(&mut self) {
+        let Some(entry) = self.undo_stack.pop() else {
+            self.status_line = tr("Nothing to undo").to_string();
+            return;
+        };
+        let count = entry.len();
+        for (idx, values) in entry {
+            self.apply_values_to_control(idx, values);
+        }
+        self.status_line = trf("Undo: restored {} control(s)", &[&count]);
+    }
+
+    fn run_cell_actions(&mut self, actions: Vec<CellAction>) {
+        for action in actions {
+            match action {
+                CellAction::Set(idx, values) => {
+                    // Moving a muted cell by hand replaces the remembered level.
+                    if let Some(key) = self.controls.get(idx).map(|c| c.key()) {
+                        if self.user_config.muted_routes.remove(&key).is_some() {
+                            self.save_user_config();
+                        }
                     }
+                    self.apply_route_values(idx, values);
                 }
-            });
+                CellAction::ToggleMute(idx) => self.toggle_route_mute(idx),
+                CellAction::ToggleSolo(input) => self.toggle_solo(input),
+                CellAction::CopyLine(line) => self.copy_line(line),
+                CellAction::PasteLine(line) => self.paste_line(line),
+                CellAction::TrimLine(line, db) => self.trim_line(line, db),
+                CellAction::ToggleHidden(target) => self.toggle_hidden(target),
+                CellAction::ToggleDim(output) => self.toggle_dim(output),
+                CellAction::Identify(output) => self.identify_output(output),
+                CellAction::Master(output, db, persist) => {
+                    self.set_output_master(output, db);
+                    if persist {
+                        self.save_user_config();
+                    }
+                }
+                CellAction::Paint(pos, idx, clear) => self.paint_cell(pos, idx, clear),
+                CellAction::Focus(pos) => self.matrix_cursor = Some(pos),
+                CellAction::ToggleSelect(idx) => {
+                    if !self.matrix_selection.remove(&idx) {
+                        self.matrix_selection.insert(idx);
+                    }
+                }
+                CellAction::Select(idx) => {
+                    self.matrix_selection.insert(idx);
+                }
+                CellAction::Gang(idx, raw) => self.gang_selection(idx, raw),
+                CellAction::Hover(pos, idx) => {
+                    self.matrix_hover_seen = true;
+                    if self.matrix_hover != Some(pos) {
+                        self.matrix_hover = Some(pos);
+                        self.announce_route(Some(idx));
+                    }
+                }
+                CellAction::Reset(idx) => self.reset_to_unity(idx),
+                CellAction::EditValue(idx) => {
+                    let text = self
+                        .controls
+                        .get(idx)
+                        .and_then(|c| c.values.first().cloned())
+                        .unwrap_or_default();
+                    self.cell_value_edit = Some(CellValueEdit {
+                        control_index: idx,
+                        text,
+                    });
+                }
+                CellAction::CopyValue(idx) => {
+                    if let Some(control) = self.controls.get(idx) {
+                        self.cell_clipboard = Some(control.values.clone());
+                        self.status_line = trf("Copied {}", &[&control.name]);
+                    }
+                }
+                CellAction::PasteValue(idx) => {
+                    if let Some(values) = self.cell_clipboard.clone() {
+                        self.run_cell_actions(vec![CellAction::Set(idx, values)]);
+                    }
+                }
+                CellAction::ToggleLock(idx) => self.toggle_lock(idx),
+                CellAction::ToggleFavorite(idx) => self.toggle_favorite(idx),
+                CellAction::ToggleControlHidden(idx) => self.toggle_control_hidden(idx),
+                CellAction::EditNote(idx) => {
+                    let text = self.control_note(idx).unwrap_or_default().to_string();
+                    self.note_edit = Some(CellValueEdit {
+                        control_index: idx,
+                        text,
+                    });
+                }
+                CellAction::CopyJson(idx) => {
+                    let indices: Vec<usize> = if self.matrix_selection.contains(&idx) {
+                        self.matrix_selection.iter().copied().collect()
+                    } else {
+                        vec![idx]
+                    };
+                    self.copy_controls_json(&indices);
+                }
+            }
         }
+    }
 
-        for (idx, values) in actions {
+    fn edit_action(&self, control_idx: usize, raw: i64) -> CellAction {
+        if self.matrix_selection.len() > 1 && self.matrix_selection.contains(&control_idx) {
+            CellAction::Gang(control_idx, raw)
+        } else {
+            CellAction::Set(control_idx, vec![raw.to_string()])
+        }
+    }
+
+    /// The rubber band of a Shift+drag in progress, in screen coordinates.
+    fn selection_band(ctx: &egui::Context) -> Option<egui::Rect> {
+        ctx.input(|i| {
+            if !(i.modifiers.shift && i.pointer.primary_down()) {
+                return None;
+            }
+            let origin = i.pointer.press_origin()?;
+            let current = i.pointer.interact_pos()?;
+            Some(egui::Rect::from_two_pos(origin, current))
+        })
+    }
+
+    fn integer_route_level(&self, idx: usize) -> Option<RouteLevel> {
+        Self::route_level_of(self.controls.get(idx)?)
+    }
+
+    fn route_level_of(control: &ControlDescriptor) -> Option<RouteLevel> {
+        let ControlKind::Integer {
+            min,
+            max,
+            channels,
+            db_range,
+            ..
+        } = control.kind
+        else {
+            return None;
+        };
+        let raw = control
+            .values
+            .first()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(min);
+        Some(RouteLevel {
+            raw,
+            min,
+            max,
+            channels,
+            db_range,
+        })
+    }
+
+    /// Writes several cells without stereo-link propagation (linked partners
+    /// are usually part of the same set). `manual` edits form one undo step and
+    /// replace a muted cell's remembered level; temporary overlays (master, dim,
+    /// mono, talkback) do neither.
+    fn apply_group(&mut self, targets: Vec<(usize, i64)>, manual: bool) {
+        let targets: Vec<(usize, Vec<String>)> = targets
+            .into_iter()
+            .filter(|(idx, _)| !self.is_locked_idx(*idx))
+            .filter_map(|(idx, raw)| {
+                let level = self.integer_route_level(idx)?;
+                (level.raw != raw).then(|| (idx, vec![raw.to_string(); level.channels]))
+            })
+            .collect();
+        if targets.is_empty() {
+            return;
+        }
+        let indices: Vec<usize> = targets.iter().map(|(idx, _)| *idx).collect();
+        if manual {
+            self.push_undo(&indices);
+        }
+        let mut unmuted = false;
+        for (idx, values) in targets {
+            if manual {
+                if let Some(key) = self.controls.get(idx).map(|c| c.key()) {
+                    unmuted |= self.user_config.muted_routes.remove(&key).is_some();
+                }
+            }
             self.apply_values_to_control(idx, values);
         }
+        if unmuted {
+            self.save_user_config();
+        }
+        self.status_line = trf("Adjusted {} routes", &[&indices.len()]);
     }
 
-    fn render_effect_tile(&self, ui: &mut egui::Ui, idx: usize) -> Option<Vec<String>> {
-        let control = self.controls.get(idx)?.clone();
-        let mut out = None;
-        ui.allocate_ui_with_layout(
-            vec2(124.0, 92.0),
-            egui::Layout::top_down(egui::Align::Center),
-            |ui| {
-                let display_name = Self::fx_display_name(&control.name);
-                ui.add_sized(
-                    vec2(118.0, 28.0),
-                    egui::Label::new(RichText::new(display_name).strong())
-                        .wrap()
-                        .sense(egui::Sense::hover()),
-                );
-                out = Self::render_effect_control_inline(ui, &control);
-            },
-        );
-        out
+    /// Moves every selected cell by `offset_db`; silent cells stay silent.
+    fn offset_selection(&mut self, offset_db: f64) {
+        let factor = 10f64.powf(offset_db / 20.0);
+        let targets = self
+            .matrix_selection
+            .iter()
+            .filter_map(|&idx| {
+                let level = self.integer_route_level(idx)?;
+                Some((idx, level.scaled(factor)))
+            })
+            .collect();
+        self.apply_group(targets, true);
     }
 
-    fn find_first_fx_with<F>(
-        &self,
-        fx_indices: &[usize],
-        used: &HashSet<usize>,
-        predicate: F,
-    ) -> Option<usize>
-    where
-        F: Fn(&str) -> bool,
-    {
-        fx_indices.iter().copied().find(|idx| {
-            if used.contains(idx) {
-                return false;
+    fn set_selection_level(&mut self, db: f64) {
+        let targets = self
+            .matrix_selection
+            .iter()
+            .filter_map(|&idx| {
+                let level = self.integer_route_level(idx)?;
+                let raw = level.raw_at_db(db).unwrap_or_else(|| {
+                    pan::raw_from_amplitude(10f64.powf(db / 20.0), level.min, level.max, level.db_range)
+                });
+                Some((idx, raw))
+            })
+            .collect();
+        self.apply_group(targets, true);
+    }
+
+    fn gang_selection(&mut self, idx: usize, raw: i64) {
+        let Some(level) = self.integer_route_level(idx) else {
+            return;
+        };
+        let old_amp = level.amplitude();
+        let new_amp = RouteLevel { raw, ..level }.amplitude();
+        if old_amp <= f64::EPSILON || new_amp <= f64::EPSILON {
+            // No meaningful ratio from or to silence: move this cell alone.
+            self.apply_group(vec![(idx, raw)], true);
+            return;
+        }
+        let factor = new_amp / old_amp;
+        let mut targets = vec![(idx, raw)];
+        for &other in self.matrix_selection.iter().filter(|&&o| o != idx) {
+            if let Some(level) = self.integer_route_level(other) {
+                targets.push((other, level.scaled(factor)));
             }
-            let lower = self.controls[*idx].name.to_lowercase();
-            predicate(&lower)
-        })
+        }
+        self.apply_group(targets, true);
     }
 
-    fn render_effect_control_inline(
-        ui: &mut egui::Ui,
-        control: &ControlDescriptor,
-    ) -> Option<Vec<String>> {
-        match &control.kind {
-            ControlKind::Integer {
-                min,
-                max,
-                channels,
-                db_range,
-                ..
-            } => {
-                let mut new_values = control.values.clone();
-                let mut changed = false;
+    fn render_selection_window(&mut self, ctx: &egui::Context) {
+        if self.matrix_selection.is_empty() {
+            return;
+        }
+        let mut open = true;
+        let mut offset = None;
+        let mut level = None;
+        let mut copy = false;
+        egui::Window::new(tr("Selection"))
+            .open(&mut open)
+            .resizable(false)
+            .default_width(280.0)
+            .show(ctx, |ui| {
+                ui.label(trf(
+                    "{} cells selected (Ctrl+click to toggle, Shift+drag to add, Esc to clear)",
+                    &[&self.matrix_selection.len()],
+                ));
+                ui.separator();
+                ui.label(RichText::new(tr("Relative trim")).strong());
                 ui.horizontal_wrapped(|ui| {
-                    for ch in 0..*channels {
-                        let mut v = control
-                            .values
-                            .get(ch)
-                            .and_then(|x| x.parse::<i64>().ok())
-                            .unwrap_or(*min);
-                        let ch_label = if *channels > 1 {
-                            Some(format!("Ch{}", ch + 1))
-                        } else {
-                            None
-                        };
-                        changed |= Self::render_knob(
-                            ui,
-                            &mut v,
-                            *min,
-                            *max,
-                            ch_label,
-                            *db_range,
-                        );
-                        if ch < new_values.len() {
-                            new_values[ch] = v.to_string();
-                        } else {
-                            new_values.push(v.to_string());
+                    for step in [-6.0, -3.0, -1.0, 1.0, 3.0, 6.0] {
+                        if ui.button(format!("{step:+} dB")).clicked() {
+                            offset = Some(step);
                         }
                     }
                 });
-                if changed {
-                    return Some(new_values);
-                }
-            }
-            ControlKind::Boolean { channels } => {
-                let mut new_values = control.values.clone();
-                let mut changed = false;
-                ui.horizontal_wrapped(|ui| {
-                    for ch in 0..*channels {
-                        let mut on = control
-                            .values
-                            .get(ch)
-                            .map(|v| v.eq_ignore_ascii_case("on") || v == "1")
-                            .unwrap_or(false);
-                        changed |= ui.checkbox(&mut on, format!("Ch{}", ch + 1)).changed();
-                        if ch < new_values.len() {
-                            new_values[ch] = if on { "on" } else { "off" }.to_string();
-                        } else {
-                            new_values.push(if on { "on" } else { "off" }.to_string());
-                        }
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut self.selection_offset_db)
+                            .range(-40.0..=40.0)
+                            .speed(0.1)
+                            .suffix(" dB"),
+                    );
+                    if ui.button(tr("Apply offset")).clicked() {
+                        offset = Some(self.selection_offset_db);
                     }
                 });
-                if changed {
-                    return Some(new_values);
+                ui.separator();
+                ui.label(RichText::new(tr("Absolute level")).strong());
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut self.selection_level_db)
+                            .range(-80.0..=12.0)
+                            .speed(0.1)
+                            .suffix(" dB"),
+                    );
+                    if ui.button(tr("Set all")).clicked() {
+                        level = Some(self.selection_level_db);
+                    }
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Copy as JSON")).clicked() {
+                        copy = true;
+                    }
+                    if ui.button(tr("Clear selection")).clicked() {
+                        self.matrix_selection.clear();
+                    }
+                });
+            });
+        if copy {
+            let indices: Vec<usize> = self.matrix_selection.iter().copied().collect();
+            self.copy_controls_json(&indices);
+        }
+        if let Some(db) = offset {
+            self.offset_selection(db);
+        }
+        if let Some(db) = level {
+            self.set_selection_level(db);
+        }
+        if !open {
+            self.matrix_selection.clear();
+        }
+    }
+
+    fn copy_controls_json(&mut self, indices: &[usize]) {
+        let snippet = ControlSnippet::capture(indices.iter().filter_map(|&idx| self.controls.get(idx)));
+        self.status_line = match snippet.ftu_controls.len() {
+            1 => trf(
+                "Copied {} as JSON",
+                &[&snippet.ftu_controls.keys().next().map(|k| k.name.as_str()).unwrap_or_default()],
+            ),
+            n => trf("Copied {} controls as JSON", &[&n]),
+        };
+        self.clipboard_out = Some(snippet.to_json());
+    }
+
+    /// Applies a snippet pasted with Ctrl+V, as one undo step. Locked and
+    /// unknown controls are skipped.
+    fn paste_controls(&mut self, snippet: ControlSnippet) {
+        let mut targets = Vec::new();
+        let mut skipped = Vec::new();
+        for (key, values) in snippet.ftu_controls {
+            match self.control_index(&key) {
+                Some(idx) if !self.is_locked_idx(idx) => targets.push((idx, values)),
+                _ => skipped.push(key.to_string()),
+            }
+        }
+        let indices: Vec<usize> = targets.iter().map(|(idx, _)| *idx).collect();
+        self.push_undo(&indices);
+        let count = targets.len();
+        for (idx, values) in targets {
+            self.apply_values_to_control(idx, values);
+        }
+        self.status_line = trf("Pasted {} controls from the clipboard", &[&count]);
+        if !skipped.is_empty() {
+            self.status_line
+                .push_str(&trf(" — skipped (locked or absent): {}", &[&skipped.join(", ")]));
+        }
+    }
+
+    fn route_of(&self, control_index: usize) -> Option<(MatrixBus, &RouteRef)> {
+        [MatrixBus::Analog, MatrixBus::Digital]
+            .into_iter()
+            .find_map(|bus| {
+                self.bus_routes(bus)
+                    .iter()
+                    .find(|r| r.control_index == control_index)
+                    .map(|r| (bus, r))
+            })
+    }
+
+    /// The first cell of a stroke picks the value (its own level, or the
+    /// minimum for a right-drag); later cells only take it on the same row.
+    fn paint_cell(&mut self, pos: GridPos, idx: usize, clear: bool) {
+        let row = (pos.grid, pos.row);
+        let Some(control) = self.controls.get(idx) else {
+            return;
+        };
+        let stroke = match &self.paint_stroke {
+            Some(stroke) => stroke.clone(),
+            None => {
+                let values = match (&control.kind, clear) {
+                    (ControlKind::Integer { min, channels, .. }, true) => {
+                        vec![min.to_string(); *channels]
+                    }
+                    _ => control.values.clone(),
+                };
+                let stroke = PaintStroke { row, values };
+                self.paint_stroke = Some(stroke.clone());
+                stroke
+            }
+        };
+        if stroke.row != row || control.values == stroke.values {
+            return;
+        }
+        if self.user_config.muted_routes.remove(&control.key()).is_some() {
+            self.save_user_config();
+        }
+        self.apply_route_values(idx, stroke.values);
+    }
+
+    fn handle_matrix_keys(&mut self, ctx: &egui::Context) {
+        let grids: &[MatrixGrid] = match self.selected_tab {
+            Tab::MixRouting => &[MatrixGrid::Monitoring, MatrixGrid::DigitalRoutes],
+            Tab::CueMix => &[MatrixGrid::CueMix],
+            Tab::Favorites | Tab::OutputMix | Tab::Inputs | Tab::Fx | Tab::Browser | Tab::Settings => return,
+        };
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let none = egui::Modifiers::NONE;
+        let (d_row, d_col, steps) = ctx.input_mut(|i| {
+            let mut count = |key| i.count_and_consume_key(none, key) as i32;
+            let d_row = count(egui::Key::ArrowDown) - count(egui::Key::ArrowUp);
+            let d_col = count(egui::Key::ArrowRight) - count(egui::Key::ArrowLeft);
+            let mut steps = Vec::new();
+            for (key, up, coarse) in [
+                (egui::Key::Plus, true, false),
+                (egui::Key::Equals, true, false),
+                (egui::Key::Minus, false, false),
+                (egui::Key::PageUp, true, true),
+                (egui::Key::PageDown, false, true),
+            ] {
+                for _ in 0..count(key) {
+                    steps.push((up, coarse));
                 }
             }
-            ControlKind::Enumerated { items, channels } => {
-                let mut new_values = control.values.clone();
-                let mut changed = false;
-                ui.horizontal_wrapped(|ui| {
-                    for ch in 0..*channels {
-                        let mut current = control
-                            .values
-                            .get(ch)
-                            .cloned()
-                            .unwrap_or_else(|| items.first().cloned().unwrap_or_default());
-                        egui::ComboBox::from_label(format!("Ch{}", ch + 1))
-                            .selected_text(current.clone())
-                            .show_ui(ui, |ui| {
-                                for item in items {
-                                    if ui.selectable_label(current == *item, item).clicked() {
-                                        current = item.clone();
-                                        changed = true;
-                                    }
-                                }
-                            });
-                        if ch < new_values.len() {
-                            new_values[ch] = current;
+            (d_row, d_col, steps)
+        });
+        if d_row == 0 && d_col == 0 && steps.is_empty() {
+            return;
+        }
+
+        let cursor = self.matrix_cursor.filter(|c| grids.contains(&c.grid));
+        let mut pos = cursor.unwrap_or(GridPos {
+            grid: grids[0],
+            row: 0,
+            col: 0,
+        });
+        let Some(cells) = self.matrix_cells.get(&pos.grid).filter(|c| !c.is_empty()) else {
+            return;
+        };
+        if cursor.is_some() {
+            pos.row = pos.row.saturating_add_signed(d_row as isize).min(cells.len() - 1);
+            let cols = cells[pos.row].len().max(1);
+            pos.col = pos.col.saturating_add_signed(d_col as isize).min(cols - 1);
+        }
+        let control_idx = cells[pos.row].get(pos.col).copied().flatten();
+        self.matrix_cursor = Some(pos);
+        self.scroll_to_cursor = true;
+
+        if let Some(idx) = control_idx {
+            if !steps.is_empty() {
+                if let Some(ControlKind::Integer {
+                    min,
+                    max,
+                    channels,
+                    db_range,
+                    ..
+                }) = self.controls.get(idx).map(|c| c.kind.clone())
+                {
+                    let current = self.controls[idx]
+                        .values
+                        .first()
+                        .and_then(|v| v.parse::<i64>().ok())
+                        .unwrap_or(min);
+                    let next = steps.iter().fold(current, |v, &(up, coarse)| {
+                        let modifiers = if coarse {
+                            egui::Modifiers::COMMAND
                         } else {
-                            new_values.push(current);
-                        }
-                    }
-                });
-                if changed {
-                    return Some(new_values);
+                            egui::Modifiers::NONE
+                        };
+                        Self::wheel_step(v, up, modifiers, min, max, db_range)
+                    });
+                    self.run_cell_actions(vec![CellAction::Set(idx, vec![next.to_string(); channels])]);
+                }
+            }
+        }
+        self.announce_route(control_idx);
+    }
+
+    fn announce_route(&mut self, control_idx: Option<usize>) {
+        let Some(idx) = control_idx else {
+            self.status_line = tr("No route at this position").to_string();
+            return;
+        };
+        let Some((bus, route)) = self.route_of(idx) else {
+            return;
+        };
+        let source = match bus {
+            MatrixBus::Analog => RenameTarget::Ain(route.input),
+            MatrixBus::Digital => RenameTarget::Din(route.input),
+        };
+        let route_text = format!(
+            "{} → {}",
+            self.alias_text(source),
+            self.alias_text(RenameTarget::Out(route.output))
+        );
+        let Some(control) = self.controls.get(idx) else {
+            return;
+        };
+        let level = match &control.kind {
+            ControlKind::Integer {
+                min, max, db_range, ..
+            } => {
+                let raw = control
+                    .values
+                    .first()
+                    .and_then(|v| v.parse::<i64>().ok())
+                    .unwrap_or(*min);
+                match control.kind.db_at_raw(raw) {
+                    Some(db) => format!("{db:.1} dB"),
+                    None => format!("{}%", Self::control_percent(raw, *min, *max, *db_range)),
                 }
             }
-            ControlKind::Unknown { .. } => {
-                return Self::render_control_editor(ui, control);
-            }
+            _ => control.values.join(", "),
+        };
+        self.status_line = format!("{route_text}: {level}");
+    }
+
+    fn bus_routes(&self, bus: MatrixBus) -> &[RouteRef] {
+        match bus {
+            MatrixBus::Analog => &self.routing_index.analog_routes,
+            MatrixBus::Digital => &self.routing_index.digital_routes,
         }
-        None
     }
 
-    fn fx_display_name(name: &str) -> String {
-        name.replace(" Capture Volume", "")
-            .replace(" Playback Volume", "")
-            .replace(" Switch", "")
-            .replace(" Volume", "")
+    /// Routes on a line as (index along the other axis, control index).
+    fn line_routes(&self, line: MatrixLine) -> Vec<(usize, usize)> {
+        self.bus_routes(line.bus)
+            .iter()
+            .filter_map(|r| match line.target {
+                RenameTarget::Ain(i) | RenameTarget::Din(i) => {
+                    (r.input == i).then_some((r.output, r.control_index))
+                }
+                RenameTarget::Out(o) => (r.output == o).then_some((r.input, r.control_index)),
+            })
+            .collect()
     }
 
-    fn render_route_matrix(&mut self, ui: &mut egui::Ui, analog: bool) {
-        let refs = if analog {
-            &self.routing_index.analog_routes
-        } else {
-            &self.routing_index.digital_routes
+    /// Rows paste onto rows and columns onto columns; columns only within the
+    /// same matrix, since their cells are indexed by that matrix's inputs.
+    fn can_paste_line(&self, line: MatrixLine) -> bool {
+        let Some(clip) = &self.line_clipboard else {
+            return false;
         };
-        if refs.is_empty() {
-            ui.label("No routes found for this group.");
+        let source_is_output = matches!(clip.source.target, RenameTarget::Out(_));
+        let target_is_output = matches!(line.target, RenameTarget::Out(_));
+        clip.source != line
+            && source_is_output == target_is_output
+            && (!target_is_output || clip.source.bus == line.bus)
+    }
+
+    fn copy_line(&mut self, line: MatrixLine) {
+        let values = self
+            .line_routes(line)
+            .into_iter()
+            .filter_map(|(key, idx)| self.controls.get(idx).map(|c| (key, c.values.clone())))
+            .collect();
+        self.line_clipboard = Some(LineClipboard {
+            source: line,
+            values,
+        });
+        self.status_line = trf("Copied {}", &[&self.alias_text(line.target)]);
+    }
+
+    fn paste_line(&mut self, line: MatrixLine) {
+        if !self.can_paste_line(line) {
+            return;
+        }
+        let Some(clip) = self.line_clipboard.clone() else {
             return;
+        };
+        let mut pasted = 0usize;
+        for (key, idx) in self.line_routes(line) {
+            if let Some(values) = clip.values.get(&key) {
+                self.apply_values_to_control(idx, values.clone());
+                pasted += 1;
+            }
         }
+        self.status_line = trf(
+            "Pasted {} onto {} ({} routes)",
+            &[&self.alias_text(clip.source.target), &self.alias_text(line.target), &pasted],
+        );
+    }
 
-        let max_input = refs.iter().map(|r| r.input).max().unwrap_or(0);
-        let max_output = refs.iter().map(|r| r.output).max().unwrap_or(0);
-        let mut by_pair: HashMap<(usize, usize), usize> = HashMap::new();
-        for r in refs {
-            if analog {
-                by_pair.insert((r.output, r.input), r.control_index);
+    fn render_line_header(
+        &mut self,
+        ui: &mut egui::Ui,
+        bus: MatrixBus,
+        target: RenameTarget,
+        strong: bool,
+        width: f32,
+        actions: &mut Vec<CellAction>,
+    ) {
+        let Some(resp) = self.render_alias_label(ui, target, strong, width) else {
+            return;
+        };
+        if let Some(meter) = match target {
+            RenameTarget::Ain(i) => self.input_meters.get(i),
+            RenameTarget::Out(i) => self.output_meters.get(i),
+            RenameTarget::Din(_) => None,
+        } {
+            if self.user_config.metering.leds {
+                Self::paint_led(ui, resp.rect, meter, &self.palette);
             } else {
-                by_pair.insert((r.input, r.output), r.control_index);
+                Self::paint_meter(ui, resp.rect, meter, &self.user_config.metering, &self.palette);
             }
         }
-
-        let mut actions: Vec<(usize, Vec<String>)> = Vec::new();
-        egui::Grid::new(if analog { "analog_grid" } else { "digital_grid" })
-            .striped(true)
-            .show(ui, |ui| {
-                if analog {
-                    ui.label("Out \\ AIn");
-                    for input in 0..=max_input {
-                        ui.allocate_ui_with_layout(
-                            vec2(Self::KNOB_CELL_W, 18.0),
-                            egui::Layout::top_down(egui::Align::Center),
-                            |ui| {
-                                self.render_alias_label(
-                                    ui,
-                                    RenameTarget::Ain(input),
-                                    false,
-                                    Self::KNOB_CELL_W,
-                                );
-                            },
-                        );
-                    }
+        // Shown while the header is hovered so it does not cover the name.
+        if let RenameTarget::Out(output) = target {
+            let button = egui::Rect::from_min_size(
+                egui::pos2(resp.rect.right() - 16.0, resp.rect.top() + 1.0),
+                vec2(16.0, 16.0),
+            );
+            if ui.rect_contains_pointer(resp.rect)
+                && ui
+                    .put(button, egui::Button::new(RichText::new("🔊").size(10.0)).small())
+                    .accessible_name(trf("Identify {}", &[&self.alias_text(target)]))
+                    .on_hover_text(tr("Identify: beeps as many times as the output number on this output only"))
+                    .clicked()
+            {
+                actions.push(CellAction::Identify(output));
+            }
+        }
+        let line = MatrixLine { bus, target };
+        // Named after the channel: Out lines are rows in a transposed matrix.
+        let noun = self.alias_text(target);
+        let can_paste = self.can_paste_line(line);
+        resp.context_menu(|ui| {
+            if ui.button(trf("Copy {} routes", &[&noun])).clicked() {
+                actions.push(CellAction::CopyLine(line));
+                ui.close();
+            }
+            if ui
+                .add_enabled(can_paste, egui::Button::new(trf("Paste onto {}", &[&noun])))
+                .clicked()
+            {
+                actions.push(CellAction::PasteLine(line));
+                ui.close();
+            }
+            let hidden = self.is_hidden(target);
+            if ui
+                .button(if hidden {
+                    trf("Unhide {}", &[&noun])
                 } else {
-                    ui.label("DIn \\ Out");
-                    for output in 0..=max_output {
-                        ui.allocate_ui_with_layout(
-                            vec2(Self::KNOB_CELL_W, 18.0),
-                            egui::Layout::top_down(egui::Align::Center),
-                            |ui| {
-                                self.render_alias_label(
-                                    ui,
-                                    RenameTarget::Out(output),
-                                    true,
-                                    Self::KNOB_CELL_W,
-                                );
-                            },
-                        );
-                    }
+                    trf("Hide {}", &[&noun])
+                })
+                .clicked()
+            {
+                actions.push(CellAction::ToggleHidden(target));
+                ui.close();
+            }
+            if let RenameTarget::Out(output) = target {
+                if ui.button(trf("Identify {} (beeps)", &[&noun])).clicked() {
+                    actions.push(CellAction::Identify(output));
+                    ui.close();
                 }
-                ui.end_row();
-
-                if analog {
-                    for output in 0..=max_output {
-                        ui.allocate_ui_with_layout(
-                            vec2(Self::ROW_LABEL_W, 18.0),
-                            egui::Layout::top_down(egui::Align::Min),
-                            |ui| {
-                                self.render_alias_label(ui, RenameTarget::Out(output), true, Self::ROW_LABEL_W);
-                            },
-                        );
-                        for input in 0..=max_input {
-                            if let Some(control_idx) = by_pair.get(&(output, input)).copied() {
-                                if let Some(control) = self.controls.get(control_idx) {
-                                    if let Some(values) = Self::render_route_cell(ui, control) {
-                                        actions.push((control_idx, values));
-                                    }
-                                }
-                            } else {
-                                ui.label("-");
-                            }
-                        }
-                        ui.end_row();
-                    }
-                } else {
-                    let din_send_map = self.find_fx_send_map(true);
-                    for input in 0..=max_input {
-                        ui.allocate_ui_with_layout(
-                            vec2(Self::ROW_LABEL_W, Self::KNOB_CELL_H),
-                            egui::Layout::top_down(egui::Align::Min),
-                            |ui| {
-                                self.render_input_row_header(
-                                    ui,
-                                    RenameTarget::Din(input),
-                                    din_send_map.get(&input).copied(),
-                                    &mut actions,
-                                );
-                            },
-                        );
-                        for output in 0..=max_output {
-                            if let Some(control_idx) = by_pair.get(&(input, output)).copied() {
-                                if let Some(control) = self.controls.get(control_idx) {
-                                    if let Some(values) = Self::render_route_cell(ui, control) {
-                                        actions.push((control_idx, values));
-                                    }
-                                }
-                            } else {
-                                ui.label("-");
-                            }
-                        }
-                        ui.end_row();
+            }
+            ui.separator();
+            // Stays open so the trim can be clicked repeatedly.
+            ui.horizontal(|ui| {
+                ui.label(tr("Trim"));
+                for db in [-3.0, 3.0] {
+                    if ui.button(format!("{db:+} dB")).clicked() {
+                        actions.push(CellAction::TrimLine(line, db));
                     }
                 }
             });
+        });
+    }
 
-        for (idx, values) in actions {
-            self.apply_values_to_control(idx, values);
+    /// Routes feeding `output` from either bus.
+    fn output_routes(&self, output: usize) -> Vec<usize> {
+        self.routing_index
+            .analog_routes
+            .iter()
+            .chain(self.routing_index.digital_routes.iter())
+            .filter(|r| r.output == output)
+            .map(|r| r.control_index)
+            .collect()
+    }
+
+    fn output_master_db(&self, output: usize) -> f64 {
+        self.user_config
+            .output_masters_db
+            .get(&output)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// The master is not a hardware control: moving it writes every route to
+    /// the output at its base level times the master.
+    fn set_output_master(&mut self, output: usize, db: f64) {
+        let previous = self.output_master_db(output);
+        if (db - previous).abs() < f64::EPSILON {
+            return;
+        }
+        let bases = self.user_config.output_master_bases.remove(&output).unwrap_or_default();
+        let bases = self.apply_master(self.output_routes(output), bases, previous, db);
+        if db == 0.0 {
+            self.user_config.output_masters_db.remove(&output);
+        } else {
+            self.user_config.output_masters_db.insert(output, db);
+            self.user_config.output_master_bases.insert(output, bases);
+        }
+        self.status_line = trf(
+            "Master {}: {} dB",
+            &[&self.alias_text(RenameTarget::Out(output)), &format!("{db:+.1}")],
+        );
+    }
+
+    /// Writes `indices` at their base amplitude times `db`, clamped, and
+    /// returns the bases to keep. A route moved since the master last wrote
+    /// it takes its current level, divided by `previous_db`, as its new base.
+    fn apply_master(
+        &mut self,
+        indices: Vec<usize>,
+        bases: BTreeMap<ControlKey, MasterBase>,
+        previous_db: f64,
+        db: f64,
+    ) -> BTreeMap<ControlKey, MasterBase> {
+        let previous_factor = 10f64.powf(previous_db / 20.0);
+        let factor = 10f64.powf(db / 20.0);
+        let mut kept = Vec::new();
+        let mut targets = Vec::new();
+        for idx in indices {
+            let Some(level) = self.integer_route_level(idx).filter(|_| !self.is_locked_idx(idx)) else {
+                continue;
+            };
+            let key = self.controls[idx].key();
+            let amplitude = match bases.get(&key) {
+                Some(base) if base.raw == level.raw => base.amplitude,
+                _ => level.amplitude() / previous_factor,
+            };
+            let raw = pan::raw_from_amplitude(amplitude * factor, level.min, level.max, level.db_range);
+            targets.push((idx, raw));
+            kept.push((idx, key, amplitude));
+        }
+        self.apply_group(targets, false);
+        // Record what the card holds after the write, ceilings included.
+        kept.into_iter()
+            .filter_map(|(idx, key, amplitude)| {
+                let raw = self.integer_route_level(idx)?.raw;
+                Some((key, MasterBase { amplitude, raw }))
+            })
+            .collect()
+    }
+
+    fn render_master_row(
+        &self,
+        ui: &mut egui::Ui,
+        outputs: &[usize],
+        cell_w: f32,
+        actions: &mut Vec<CellAction>,
+    ) {
+        ui.label(RichText::new("Master").small())
+            .on_hover_text(tr("Overall level of each output: scales every route feeding it"));
+        for &output in outputs {
+            self.render_master_cell(ui, output, cell_w, actions);
         }
+        ui.end_row();
     }
 
-    fn render_route_cell(ui: &mut egui::Ui, control: &ControlDescriptor) -> Option<Vec<String>> {
-        let mut out: Option<Vec<String>> = None;
+    fn render_master_cell(
+        &self,
+        ui: &mut egui::Ui,
+        output: usize,
+        cell_w: f32,
+        actions: &mut Vec<CellAction>,
+    ) {
         ui.allocate_ui_with_layout(
-            vec2(Self::KNOB_CELL_W, Self::KNOB_CELL_H),
+            vec2(cell_w, 18.0),
             egui::Layout::top_down(egui::Align::Center),
-            |ui| match &control.kind {
-            ControlKind::Integer {
-                min, max, db_range, ..
-            } => {
-                let mut v = control
-                    .values
-                    .first()
-                    .and_then(|x| x.parse::<i64>().ok())
-                    .unwrap_or(*min);
-                let changed = Self::render_knob(ui, &mut v, *min, *max, None, *db_range);
-                if changed {
-                    out = Some(vec![v.to_string()]);
+            |ui| {
+                let mut db = self.output_master_db(output);
+                let resp = ui
+                    .add(
+                        egui::DragValue::new(&mut db)
+                            .range(-40.0..=12.0)
+                            .speed(0.1)
+                            .fixed_decimals(1)
+                            .suffix(" dB"),
+                    )
+                    .accessible_name(trf("Master {}", &[&self.alias_text(RenameTarget::Out(output))]));
+                if resp.changed() {
+                    actions.push(CellAction::Master(output, db, !resp.dragged()));
+                } else if resp.drag_stopped() {
+                    actions.push(CellAction::Master(output, db, true));
                 }
+            },
+        );
+    }
+
+    /// Dimming saves every route feeding the output and lowers the audible ones
+    /// by `dim_db`; releasing writes the saved values back unchanged.
+    fn toggle_dim(&mut self, output: usize) {
+        let label = self.alias_text(RenameTarget::Out(output));
+        if let Some(saved) = self.user_config.dimmed_outputs.remove(&output) {
+            self.restore_saved_values(saved);
+            self.save_user_config();
+            self.status_line = trf("Dim released: {}", &[&label]);
+            return;
+        }
+        let routes = self.output_routes(output);
+        let saved = self.saved_values(&routes);
+        let factor = 10f64.powf(self.user_config.dim_db.min(0.0) / 20.0);
+        let targets: Vec<(usize, i64)> = routes
+            .into_iter()
+            .filter_map(|idx| {
+                let level = self.integer_route_level(idx)?;
+                (level.amplitude() > 0.0).then(|| (idx, level.scaled(factor)))
+            })
+            .collect();
+        self.apply_group(targets, false);
+        self.user_config.dimmed_outputs.insert(output, saved);
+        self.save_user_config();
+        self.status_line = trf("Dim {}: {} dB", &[&label, &format!("{:.0}", self.user_config.dim_db)]);
+    }
+
+    /// Current values of `indices`, by control, for a later `restore_saved_values`.
+    fn saved_values(&self, indices: &[usize]) -> BTreeMap<ControlKey, Vec<String>> {
+        indices
+            .iter()
+            .filter_map(|&idx| self.controls.get(idx).map(|c| (c.key(), c.values.clone())))
+            .collect()
+    }
+
+    fn restore_saved_values(&mut self, saved: BTreeMap<ControlKey, Vec<String>>) {
+        for (key, values) in saved {
+            if let Some(idx) = self.control_index(&key) {
+                self.apply_values_to_control(idx, values);
             }
-            ControlKind::Boolean { .. } => {
-                let mut is_on = control
-                    .values
-                    .first()
-                    .map(|v| v.eq_ignore_ascii_case("on") || v == "1")
-                    .unwrap_or(false);
-                if ui.checkbox(&mut is_on, "").changed() {
-                    out = Some(vec![if is_on { "on" } else { "off" }.to_string()]);
+        }
+    }
+
+    fn control_index(&self, key: &ControlKey) -> Option<usize> {
+        self.controls
+            .iter()
+            .position(|c| c.name == key.name && c.index == key.index)
+    }
+
+    /// Like Dim, but down to silence; releasing writes the saved values back.
+    fn toggle_output_mute(&mut self, output: usize) {
+        let label = self.alias_text(RenameTarget::Out(output));
+        if let Some(saved) = self.user_config.muted_outputs.remove(&output) {
+            self.restore_saved_values(saved);
+            self.save_user_config();
+            self.status_line = trf("Unmuted: {}", &[&label]);
+            return;
+        }
+        let routes = self.output_routes(output);
+        let saved = self.saved_values(&routes);
+        let targets: Vec<(usize, i64)> = routes
+            .into_iter()
+            .filter_map(|idx| {
+                let level = self.integer_route_level(idx)?;
+                (level.amplitude() > 0.0).then_some((idx, level.min))
+            })
+            .collect();
+        self.apply_group(targets, false);
+        self.user_config.muted_outputs.insert(output, saved);
+        self.save_user_config();
+        self.status_line = trf("Muted: {}", &[&label]);
+    }
+
+    /// Sets the L and R routes of every input feeding the pair to the average of
+    /// their amplitudes; releasing writes the saved stereo values back.
+    fn toggle_mono_sum(&mut self, pair: usize) {
+        let (left, right) = (pair * 2, pair * 2 + 1);
+        let label = format!(
+            "{} / {}",
+            self.alias_text(RenameTarget::Out(left)),
+            self.alias_text(RenameTarget::Out(right))
+        );
+        if let Some(saved) = self.user_config.mono_out_pairs.remove(&pair) {
+            self.restore_saved_values(saved);
+            self.save_user_config();
+            self.status_line = trf("Stereo restored: {}", &[&label]);
+            return;
+        }
+        let mut saved = BTreeMap::new();
+        let mut targets = Vec::new();
+        for routes in [&self.routing_index.analog_routes, &self.routing_index.digital_routes] {
+            for l in routes.iter().filter(|r| r.output == left) {
+                let Some(r) = routes.iter().find(|r| r.output == right && r.input == l.input) else {
+                    continue;
+                };
+                let (Some(l_level), Some(r_level)) = (
+                    self.integer_route_level(l.control_index),
+                    self.integer_route_level(r.control_index),
+                ) else {
+                    continue;
+                };
+                let mean = (l_level.amplitude() + r_level.amplitude()) / 2.0;
+                for (idx, level) in [(l.control_index, l_level), (r.control_index, r_level)] {
+                    let raw = pan::raw_from_amplitude(mean, level.min, level.max, level.db_range);
+                    targets.push((idx, raw));
+                    saved.insert(self.controls[idx].key(), self.controls[idx].values.clone());
                 }
             }
-            _ => {
-                ui.label("...");
+        }
+        self.apply_group(targets, false);
+        self.user_config.mono_out_pairs.insert(pair, saved);
+        self.save_user_config();
+        self.status_line = trf("Mono: {}", &[&label]);
+    }
+
+    fn render_dim_row(
+        &mut self,
+        ui: &mut egui::Ui,
+        outputs: &[usize],
+        cell_w: f32,
+        actions: &mut Vec<CellAction>,
+    ) {
+        self.render_dim_amount(ui);
+        for &output in outputs {
+            self.render_dim_button(ui, output, cell_w, actions);
+        }
+        ui.end_row();
+    }
+
+    fn render_dim_amount(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("Dim").small());
+            let resp = ui
+                .add(
+                    egui::DragValue::new(&mut self.user_config.dim_db)
+                        .range(-60.0..=0.0)
+                        .speed(0.5)
+                        .fixed_decimals(0)
+                        .suffix(" dB"),
+                )
+                .on_hover_text(tr("Attenuation of the Dim buttons"));
+            if resp.drag_stopped() || (resp.changed() && !resp.dragged()) {
+                self.save_user_config();
             }
-        },
+        });
+    }
+
+    fn render_dim_button(
+        &self,
+        ui: &mut egui::Ui,
+        output: usize,
+        cell_w: f32,
+        actions: &mut Vec<CellAction>,
+    ) {
+        let dimmed = self.user_config.dimmed_outputs.contains_key(&output);
+        ui.allocate_ui_with_layout(
+            vec2(cell_w, 18.0),
+            egui::Layout::top_down(egui::Align::Center),
+            |ui| {
+                let text = RichText::new("Dim").small();
+                let text = if dimmed {
+                    text.strong().color(Color32::from_rgb(240, 190, 90))
+                } else {
+                    text
+                };
+                if ui
+                    .add(egui::Button::new(text).selected(dimmed).small())
+                    .accessible_name(trf("Dim {}", &[&self.alias_text(RenameTarget::Out(output))]))
+                    .on_hover_text(tr(if dimmed {
+                        "Release: restore the routes to this output"
+                    } else {
+                        "Lower every route to this output by the Dim amount"
+                    }))
+                    .clicked()
+                {
+                    actions.push(CellAction::ToggleDim(output));
+                }
+            },
         );
-        out
     }
 
-    fn render_control_editor(ui: &mut egui::Ui, control: &ControlDescriptor) -> Option<Vec<String>> {
+    /// Scales the non-muted, audible routes of a line (and of its stereo-linked
+    /// partner line) so their relative balance is kept.
+    fn trim_line(&mut self, line: MatrixLine, offset_db: f64) {
+        let mut routes = self.line_routes(line);
+        if let Some(partner) = self.linked_partner(line.target) {
+            routes.extend(self.line_routes(MatrixLine {
+                target: partner,
+                ..line
+            }));
+        }
+        let factor = 10f64.powf(offset_db / 20.0);
+        let targets: Vec<(usize, i64)> = routes
+            .into_iter()
+            .filter(|(_, idx)| {
+                self.controls
+                    .get(*idx)
+                    .is_some_and(|c| !self.user_config.muted_routes.contains_key(&c.key()))
+            })
+            .filter_map(|(_, idx)| {
+                let level = self.integer_route_level(idx)?;
+                (level.amplitude() > 0.0).then(|| (idx, level.scaled(factor)))
+            })
+            .collect();
+        let count = targets.len();
+        self.apply_group(targets, true);
+        self.status_line = trf(
+            "Trimmed {} by {} dB ({} routes)",
+            &[&self.alias_text(line.target), &format!("{offset_db:+}"), &count],
+        );
+    }
+
+    /// Solos an analog input: other inputs' routes to the outputs it feeds (or to
+    /// every output, if it feeds none) are muted until the solo is released.
+    fn toggle_solo(&mut self, input: usize) {
+        let previous = self.release_solo();
+        if previous == Some(input) {
+            self.status_line = trf("Solo released: {}", &[&self.alias_text(RenameTarget::Ain(input))]);
+            return;
+        }
+        let routes = self.routing_index.analog_routes.clone();
+        let level = |app: &Self, idx: usize| {
+            app.controls
+                .get(idx)
+                .and_then(|c| c.values.first())
+                .and_then(|v| v.parse::<i64>().ok())
+        };
+        let mut outputs: HashSet<usize> = routes
+            .iter()
+            .filter(|r| r.input == input)
+            .filter(|r| {
+                matches!(
+                    (level(self, r.control_index), self.controls.get(r.control_index).map(|c| &c.kind)),
+                    (Some(v), Some(ControlKind::Integer { min, .. })) if v > *min
+                )
+            })
+            .map(|r| r.output)
+            .collect();
+        if outputs.is_empty() {
+            outputs = routes.iter().map(|r| r.output).collect();
+        }
+        let mut saved = Vec::new();
+        for route in routes {
+            if route.input == input || !outputs.contains(&route.output) {
+                continue;
+            }
+            if let Some(control) = self.controls.get(route.control_index) {
+                saved.push((route.control_index, control.values.clone()));
+            }
+            self.apply_integer_route(route.control_index, 0);
+        }
+        self.solo = Some(SoloState { input, saved });
+        self.status_line = trf("Solo: {}", &[&self.alias_text(RenameTarget::Ain(input))]);
+    }
+
+    /// Restores the routes muted by the active solo; returns the soloed input.
+    fn release_solo(&mut self) -> Option<usize> {
+        let solo = self.solo.take()?;
+        for (idx, values) in solo.saved {
+            self.apply_values_to_control(idx, values);
+        }
+        Some(solo.input)
+    }
+
+    /// Mutes a route while remembering its level in the config, or restores it.
+    fn toggle_route_mute(&mut self, idx: usize) {
+        let Some(control) = self.controls.get(idx).cloned() else {
+            return;
+        };
+        let ControlKind::Integer { min, channels, .. } = control.kind else {
+            return;
+        };
+        if let Some(previous) = self.user_config.muted_routes.remove(&control.key()) {
+            self.save_user_config();
+            self.apply_values_to_control(idx, previous);
+            self.status_line = trf("Unmuted {}", &[&control.name]);
+        } else {
+            self.user_config
+                .muted_routes
+                .insert(control.key(), control.values.clone());
+            self.save_user_config();
+            self.apply_values_to_control(idx, vec![min.to_string(); channels]);
+            self.status_line = trf("Muted {}", &[&control.name]);
+        }
+    }
+
+    fn render_control_editor(
+        ui: &mut egui::Ui,
+        drag: KnobDragConfig,
+        control: &ControlDescriptor,
+    ) -> Option<Vec<String>> {
         match &control.kind {
             ControlKind::Integer {
                 min,
@@ -837,12 +8307,15 @@ impl MixerApp {
                         ui.vertical(|ui| {
                             changed |= Self::render_knob(
                                 ui,
+                                drag,
                                 &mut v,
                                 *min,
                                 *max,
                                 Some(format!("Ch{}", ch + 1)),
                                 *db_range,
-                            );
+                            )
+                            .accessible_name(format!("{} Ch{}", control.name, ch + 1))
+                            .changed();
                         });
                         if ch < new_values.len() {
                             new_values[ch] = v.to_string();
@@ -905,7 +8378,7 @@ impl MixerApp {
                 }
             }
             ControlKind::Unknown { type_name, channels } => {
-                ui.label(format!("Type non mappé: {type_name}"));
+                ui.label(trf("Unmapped type: {}", &[type_name]));
                 let mut new_values = control.values.clone();
                 let mut changed = false;
                 for ch in 0..*channels {
@@ -931,15 +8404,17 @@ impl MixerApp {
     fn mute_hardware_routes(&mut self) {
         let routes: Vec<RouteRef> = self.routing_index.analog_routes.clone();
         for route in routes {
-            self.apply_integer_route(route.control_index, 0);
+            if !self.is_locked_idx(route.control_index) {
+                self.apply_integer_route(route.control_index, 0);
+            }
         }
-        self.status_line = "Mute analog monitoring applied".to_string();
+        self.status_line = tr("Mute analog monitoring applied").to_string();
     }
 
     fn pass_through_inputs(&mut self) {
         let routes: Vec<RouteRef> = self.routing_index.analog_routes.clone();
         for route in routes {
-            if route.output > 1 {
+            if route.output > 1 || self.is_locked_idx(route.control_index) {
                 continue;
             }
             let target = match self.controls.get(route.control_index).map(|c| &c.kind) {
@@ -948,7 +8423,7 @@ impl MixerApp {
             };
             self.apply_integer_route(route.control_index, target);
         }
-        self.status_line = "Pass-through analog monitoring to channel 1/2 applied".to_string();
+        self.status_line = tr("Pass-through analog monitoring to channel 1/2 applied").to_string();
     }
 
     fn disable_fx_controls(&mut self) {
@@ -958,7 +8433,7 @@ impl MixerApp {
             .enumerate()
             .filter_map(|(i, c)| {
                 let n = c.name.to_lowercase();
-                if n.contains("fx") || n.contains("effect") {
+                if (n.contains("fx") || n.contains("effect")) && !self.is_locked(c) {
                     Some(i)
                 } else {
                     None
@@ -977,17 +8452,17 @@ impl MixerApp {
             };
             self.apply_values_to_control(idx, values);
         }
-        self.status_line = "FX controls disabled".to_string();
+        self.status_line = tr("FX controls disabled").to_string();
     }
 
     fn mute_most_digital_routes(&mut self) {
         let routes: Vec<RouteRef> = self.routing_index.digital_routes.clone();
         for route in routes {
-            if route.input != route.output {
+            if route.input != route.output && !self.is_locked_idx(route.control_index) {
                 self.apply_integer_route(route.control_index, 0);
             }
         }
-        self.status_line = "Most digital routes muted".to_string();
+        self.status_line = tr("Most digital routes muted").to_string();
     }
 
     fn panic_mute(&mut self) {
@@ -995,88 +8470,273 @@ impl MixerApp {
         indexes.extend(self.routing_index.digital_routes.iter().map(|r| r.control_index));
         indexes.sort_unstable();
         indexes.dedup();
+        indexes.retain(|&idx| !self.is_locked_idx(idx));
         for idx in indexes {
             self.apply_integer_route(idx, 0);
         }
-        self.status_line = "Mute all monitoring applied".to_string();
+        self.status_line = tr("Mute all monitoring applied").to_string();
+        self.fire_event(MixerEvent::PanicMute, Vec::new());
+    }
+
+    fn apply_integer_route(&mut self, idx: usize, target: i64) {
+        let Some(ctrl) = self.controls.get(idx).cloned() else {
+            return;
+        };
+        if let ControlKind::Integer { channels, min, max, .. } = ctrl.kind {
+            let v = target.clamp(min, max).to_string();
+            self.apply_values_to_control(idx, vec![v; channels]);
+        }
+    }
+
+    fn is_locked(&self, control: &ControlDescriptor) -> bool {
+        self.user_config.locked_controls.contains(&control.key())
+    }
+
+    fn is_locked_idx(&self, idx: usize) -> bool {
+        self.controls.get(idx).is_some_and(|c| self.is_locked(c))
+    }
+
+    fn toggle_lock(&mut self, idx: usize) {
+        let Some((name, key)) = self.controls.get(idx).map(|c| (c.name.clone(), c.key())) else {
+            return;
+        };
+        let locked = !self.user_config.locked_controls.remove(&key);
+        if locked {
+            self.user_config.locked_controls.insert(key);
+        }
+        self.save_user_config();
+        self.status_line = trf(if locked { "{} locked" } else { "{} unlocked" }, &[&name]);
     }
 
-    fn apply_integer_route(&mut self, idx: usize, target: i64) {
-        let Some(ctrl) = self.controls.get(idx).cloned() else {
+    fn toggle_favorite(&mut self, idx: usize) {
+        let Some(control) = self.controls.get_mut(idx) else {
             return;
         };
-        if let ControlKind::Integer { channels, min, max, .. } = ctrl.kind {
-            let v = target.clamp(min, max).to_string();
-            self.apply_values_to_control(idx, vec![v; channels]);
+        control.favorite = !control.favorite;
+        let (name, favorite) = (control.name.clone(), control.favorite);
+        if favorite {
+            self.user_config.favorite_controls.insert(control.key());
+        } else {
+            self.user_config.favorite_controls.remove(&control.key());
         }
+        self.save_user_config();
+        self.status_line = trf(
+            if favorite { "{} added to favorites" } else { "{} removed from favorites" },
+            &[&name],
+        );
+    }
+
+    /// Star toggle; returns `true` when clicked.
+    fn render_favorite_button(&self, ui: &mut egui::Ui, idx: usize) -> bool {
+        let favorite = self.controls.get(idx).is_some_and(|c| c.favorite);
+        let text = if favorite {
+            RichText::new("★").small().color(Color32::from_rgb(240, 190, 90))
+        } else {
+            RichText::new("☆").small().weak()
+        };
+        let hint = tr(if favorite { "Remove from favorites" } else { "Add to favorites" });
+        ui.add(egui::Button::new(text).small().frame(false))
+            .accessible_name(hint)
+            .on_hover_text(hint)
+            .clicked()
+    }
+
+    /// Padlock toggle; returns `true` when clicked.
+    fn render_lock_button(&self, ui: &mut egui::Ui, idx: usize) -> bool {
+        let locked = self.is_locked_idx(idx);
+        let text = if locked {
+            RichText::new("🔒").small().color(Color32::from_rgb(240, 190, 90))
+        } else {
+            RichText::new("🔓").small().weak()
+        };
+        let hint = tr(if locked {
+            "Locked: skipped by Disable FX, quick actions and presets"
+        } else {
+            "Lock against Disable FX, quick actions and presets"
+        });
+        ui.add(egui::Button::new(text).small().frame(false))
+            .accessible_name(hint)
+            .on_hover_text(hint)
+            .clicked()
     }
 
     fn save_user_config(&mut self) {
         match self.user_config.save() {
             Ok(()) => {
-                self.status_line = "Configuration saved to ~/.ftu-mixer/config.json".to_string();
+                self.status_line = tr("Configuration saved to ~/.ftu-mixer/config.json").to_string();
             }
             Err(err) => {
-                self.status_line = format!("Config save failed: {err}");
+                self.status_line = trf("Config save failed: {}", &[&err]);
             }
         }
     }
 
-    fn render_input_row_header(
+    /// Input label with its solo button, as a row or column header.
+    fn render_input_header(
         &mut self,
         ui: &mut egui::Ui,
         target: RenameTarget,
-        send_control_index: Option<usize>,
-        actions: &mut Vec<(usize, Vec<String>)>,
+        width: f32,
+        actions: &mut Vec<CellAction>,
     ) {
         ui.horizontal(|ui| {
-            if let Some(send_idx) = send_control_index {
-                if let Some(control) = self.controls.get(send_idx).cloned() {
-                    if let ControlKind::Integer {
-                        min, max, db_range, ..
-                    } = control.kind
-                    {
-                        let mut v = control
-                            .values
-                            .first()
-                            .and_then(|x| x.parse::<i64>().ok())
-                            .unwrap_or(min);
-                        ui.vertical(|ui| {
-                            ui.label("FX");
-                            let changed = Self::render_knob(ui, &mut v, min, max, None, db_range);
-                            if changed {
-                                actions.push((send_idx, vec![v.to_string()]));
-                            }
-                        });
-                    } else {
-                        ui.label("FX");
-                    }
+            let mut label_w = width - 8.0;
+            if let RenameTarget::Ain(input) = target {
+                let soloed = self.solo.as_ref().map(|s| s.input) == Some(input);
+                let text = RichText::new("S").small();
+                let text = if soloed {
+                    text.strong().color(Color32::from_rgb(240, 220, 90))
+                } else {
+                    text
+                };
+                if ui
+                    .add(egui::Button::new(text).selected(soloed).small())
+                    .accessible_name(trf("Solo {}", &[&self.alias_text(target)]))
+                    .on_hover_text(tr("Solo: mute the other inputs on the outputs this input feeds"))
+                    .clicked()
+                {
+                    actions.push(CellAction::ToggleSolo(input));
                 }
-            } else {
-                ui.label(" ");
+                label_w -= 22.0;
             }
-            self.render_alias_label(ui, target, true, Self::ROW_LABEL_W - 64.0);
+            let bus = if matches!(target, RenameTarget::Din(_)) {
+                MatrixBus::Digital
+            } else {
+                MatrixBus::Analog
+            };
+            self.render_line_header(ui, bus, target, true, label_w.max(40.0), actions);
         });
     }
 
-    fn is_fx_control(&self, control: &ControlDescriptor) -> bool {
-        let lower = control.name.to_lowercase();
-        lower.contains("fx")
-            || lower.contains("effect")
-            || lower.contains("reverb")
-            || lower.contains("delay")
-            || lower.contains("chorus")
+    fn fx_send_overrides(&self, digital: bool) -> &BTreeMap<usize, Option<String>> {
+        if digital {
+            &self.user_config.din_fx_sends
+        } else {
+            &self.user_config.ain_fx_sends
+        }
     }
 
-    fn is_channel_fx_send(&self, control: &ControlDescriptor) -> bool {
-        let lower = control.name.to_lowercase();
-        let has_channel = lower.contains("ain") || lower.contains("din");
-        let send_like =
-            lower.contains("send") || lower.contains("aux") || lower.contains("to fx");
-        self.is_fx_control(control) && has_channel && send_like
+    /// FX send control of each input: the manual mapping first, then the guess.
+    fn find_fx_send_map(&self, digital: bool) -> HashMap<usize, usize> {
+        let mut map = self.guess_fx_send_map(digital);
+        for (input, name) in self.fx_send_overrides(digital) {
+            match name {
+                // A control missing from this card falls back to the guess.
+                Some(name) => {
+                    if let Some(idx) = self.controls.iter().position(|c| &c.name == name) {
+                        map.insert(*input, idx);
+                    }
+                }
+                None => {
+                    map.remove(input);
+                }
+            }
+        }
+        map
     }
 
-    fn find_fx_send_map(&self, digital: bool) -> HashMap<usize, usize> {
+    fn render_fx_send_mapping_window(&mut self, ctx: &egui::Context) {
+        if !self.fx_send_mapping_open {
+            return;
+        }
+        // Any integer control that is not a matrix route can be a send.
+        let route_controls: HashSet<usize> = self
+            .routing_index
+            .analog_routes
+            .iter()
+            .chain(&self.routing_index.digital_routes)
+            .map(|r| r.control_index)
+            .collect();
+        let candidates: Vec<String> = self
+            .controls
+            .iter()
+            .enumerate()
+            .filter(|(idx, c)| {
+                matches!(c.kind, ControlKind::Integer { .. }) && !route_controls.contains(idx)
+            })
+            .map(|(_, c)| c.name.clone())
+            .collect();
+        let mut open = true;
+        let mut edits: Vec<(bool, usize, Option<Option<String>>)> = Vec::new();
+        egui::Window::new(tr("FX send mapping"))
+            .open(&mut open)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                ui.small(tr("Auto uses the name heuristic; pick a control when it guesses wrong on your kernel."));
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    egui::Grid::new("fx_send_mapping_grid")
+                        .num_columns(2)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (digital, make, routes) in [
+                                (
+                                    false,
+                                    RenameTarget::Ain as fn(usize) -> RenameTarget,
+                                    &self.routing_index.analog_routes,
+                                ),
+                                (true, RenameTarget::Din, &self.routing_index.digital_routes),
+                            ] {
+                                let Some(max_input) = routes.iter().map(|r| r.input).max() else {
+                                    continue;
+                                };
+                                let guesses = self.guess_fx_send_map(digital);
+                                for input in 0..=max_input {
+                                    ui.label(self.alias_text(make(input)));
+                                    let current = self.fx_send_overrides(digital).get(&input).cloned();
+                                    let auto_text = match guesses.get(&input) {
+                                        Some(idx) => format!("Auto ({})", self.controls[*idx].name),
+                                        None => tr("Auto (none found)").to_string(),
+                                    };
+                                    let selected_text = match &current {
+                                        None => auto_text.clone(),
+                                        Some(None) => tr("No send").to_string(),
+                                        Some(Some(name)) => name.clone(),
+                                    };
+                                    let mut choice = current.clone();
+                                    egui::ComboBox::from_id_salt(("fx_send_map", digital, input))
+                                        .selected_text(selected_text)
+                                        .width(320.0)
+                                        .show_ui(ui, |ui| {
+                                            ui.selectable_value(&mut choice, None, auto_text);
+                                            ui.selectable_value(&mut choice, Some(None), tr("No send"));
+                                            ui.separator();
+                                            for name in &candidates {
+                                                ui.selectable_value(
+                                                    &mut choice,
+                                                    Some(Some(name.clone())),
+                                                    name,
+                                                );
+                                            }
+                                        });
+                                    if choice != current {
+                                        edits.push((digital, input, choice));
+                                    }
+                                    ui.end_row();
+                                }
+                            }
+                        });
+                });
+            });
+        self.fx_send_mapping_open = open;
+        if edits.is_empty() {
+            return;
+        }
+        for (digital, input, choice) in edits {
+            let overrides = if digital {
+                &mut self.user_config.din_fx_sends
+            } else {
+                &mut self.user_config.ain_fx_sends
+            };
+            match choice {
+                Some(mapping) => overrides.insert(input, mapping),
+                None => overrides.remove(&input),
+            };
+        }
+        self.save_user_config();
+        self.status_line = tr("FX send mapping saved").to_string();
+    }
+
+    fn guess_fx_send_map(&self, digital: bool) -> HashMap<usize, usize> {
         let mut map = HashMap::new();
         let max_idx = if digital {
             self.routing_index
@@ -1106,7 +8766,7 @@ impl MixerApp {
                     continue;
                 }
                 let lower = c.name.to_lowercase();
-                if !lower.contains(&token) || !self.is_fx_control(c) {
+                if !lower.contains(&token) || !fx::is_fx_control(c) {
                     continue;
                 }
                 let mut score = 0;
@@ -1139,7 +8799,7 @@ impl MixerApp {
         target: RenameTarget,
         strong: bool,
         width: f32,
-    ) {
+    ) -> Option<egui::Response> {
         let default_name = match target {
             RenameTarget::Ain(i) => format!("AIn{}", i + 1),
             RenameTarget::Din(i) => format!("DIn{}", i + 1),
@@ -1150,7 +8810,15 @@ impl MixerApp {
             RenameTarget::Din(i) => self.user_config.din_aliases.get(&i).cloned(),
             RenameTarget::Out(i) => self.user_config.out_aliases.get(&i).cloned(),
         };
-        let displayed = current_alias.unwrap_or(default_name);
+        let mut displayed = current_alias.unwrap_or(default_name);
+        if self.user_config.merge_linked_cells {
+            if let Some(partner) = self.linked_partner(target) {
+                displayed = format!("{displayed} + {}", self.alias_text(partner));
+            }
+        }
+        if self.palette.state_cues && self.linked_partner(target).is_some() {
+            displayed = format!("🔗 {displayed}");
+        }
 
         if self.rename_target == Some(target) {
             let mut commit = false;
@@ -1171,7 +8839,8 @@ impl MixerApp {
                         vec2(button_w, 20.0),
                         egui::Button::new(RichText::new("✓").size(15.0)),
                     )
-                    .on_hover_text("Valider")
+                    .accessible_name(tr("Confirm"))
+                    .on_hover_text(tr("Confirm"))
                     .clicked()
                 {
                     commit = true;
@@ -1181,7 +8850,8 @@ impl MixerApp {
                         vec2(button_w, 20.0),
                         egui::Button::new(RichText::new("✕").size(15.0)),
                     )
-                    .on_hover_text("Annuler")
+                    .accessible_name(tr("Cancel"))
+                    .on_hover_text(tr("Cancel"))
                     .clicked()
                 {
                     cancel = true;
@@ -1197,7 +8867,7 @@ impl MixerApp {
                 self.rename_target = None;
                 self.rename_buffer.clear();
             }
-            return;
+            return None;
         }
 
         let char_count = displayed.chars().count();
@@ -1216,6 +8886,12 @@ impl MixerApp {
         } else {
             RichText::new(shown_text).size(font_size)
         };
+        // Only reachable with "Show all" on.
+        let text = if self.is_hidden(target) {
+            text.italics().weak()
+        } else {
+            text
+        };
         let resp = ui.add_sized(
             vec2(width, 18.0),
             egui::Label::new(text)
@@ -1231,6 +8907,7 @@ impl MixerApp {
                 RenameTarget::Out(i) => self.user_config.out_aliases.get(&i).cloned().unwrap_or_default(),
             };
         }
+        Some(resp)
     }
 
     fn commit_alias_rename(&mut self, target: RenameTarget) {
@@ -1265,23 +8942,26 @@ impl MixerApp {
 
     fn render_knob(
         ui: &mut egui::Ui,
+        drag: KnobDragConfig,
         value: &mut i64,
         min: i64,
         max: i64,
         label: Option<String>,
         db_range: Option<(i64, i64)>,
-    ) -> bool {
+    ) -> egui::Response {
         *value = (*value).clamp(min, max);
         let desired_size = vec2(34.0, 34.0);
-        let (rect, response) = ui.allocate_exact_size(desired_size, egui::Sense::click_and_drag());
+        let (rect, mut response) = ui.allocate_exact_size(desired_size, egui::Sense::click_and_drag());
 
         let old = *value;
         if response.dragged() {
-            let dy = ui.input(|i| i.pointer.delta().y);
+            let step = ui.input(|i| drag.progress_delta(i.pointer.delta(), i.modifiers));
             let current = Self::knob_progress_from_value(*value, min, max, db_range);
-            let next = (current - (dy / 180.0)).clamp(0.0, 1.0);
+            let next = (current + step).clamp(0.0, 1.0);
             *value = Self::value_from_knob_progress(next, min, max, db_range);
         }
+        Self::handle_level_wheel(ui, &response, value, min, max, db_range);
+        Self::handle_level_actions(ui, &response, value, min, max, db_range);
 
         let t = Self::knob_progress_from_value(*value, min, max, db_range);
         let start_angle = -2.35_f32;
@@ -1323,13 +9003,319 @@ impl MixerApp {
         ui.painter()
             .line_segment([center, tip], Stroke::new(2.2, Color32::from_rgb(90, 220, 220)));
 
+        let name = label.clone().unwrap_or_default();
         if let Some(text) = label {
             ui.label(text);
         }
 
+        Self::render_level_readout(ui, response.id, value, min, max, db_range);
+        if old != *value {
+            response.mark_changed();
+        }
+        Self::describe_level(&response, &name, *value, min, max, db_range);
+        response
+    }
+
+    /// Matrix cell drawn as a horizontal slider or a vertical fader, with the
+    /// same value mapping, wheel steps and readout as [`Self::render_knob`].
+    fn render_cell_fader(
+        ui: &mut egui::Ui,
+        widget: CellWidget,
+        value: &mut i64,
+        min: i64,
+        max: i64,
+        db_range: Option<(i64, i64)>,
+    ) -> egui::Response {
+        *value = (*value).clamp(min, max);
+        let old = *value;
+        let vertical = widget == CellWidget::Fader;
+        // Leave room for the readout under the fader.
+        ui.spacing_mut().slider_width = if vertical {
+            (ui.available_height() - 24.0).clamp(34.0, 140.0)
+        } else {
+            (ui.available_width() - 10.0).clamp(34.0, 160.0)
+        };
+        let mut progress = Self::knob_progress_from_value(*value, min, max, db_range);
+        let slider = egui::Slider::new(&mut progress, 0.0..=1.0).show_value(false);
+        let mut response = ui.add(if vertical { slider.vertical() } else { slider });
+        if response.changed() {
+            *value = Self::value_from_knob_progress(progress, min, max, db_range);
+        }
+        Self::handle_level_wheel(ui, &response, value, min, max, db_range);
+        Self::render_level_readout(ui, response.id, value, min, max, db_range);
+        if old != *value {
+            response.mark_changed();
+        }
+        Self::describe_level(&response, "", *value, min, max, db_range);
+        response
+    }
+
+    /// Mouse wheel steps over a knob or fader.
+    fn handle_level_wheel(
+        ui: &mut egui::Ui,
+        response: &egui::Response,
+        value: &mut i64,
+        min: i64,
+        max: i64,
+        db_range: Option<(i64, i64)>,
+    ) {
+        if response.hovered() {
+            let notches = ui.input_mut(|i| {
+                // Keep the surrounding scroll area still while the wheel turns the knob.
+                i.smooth_scroll_delta = egui::Vec2::ZERO;
+                i.raw_scroll_delta = egui::Vec2::ZERO;
+                i.events
+                    .iter()
+                    .filter_map(|event| match event {
+                        egui::Event::MouseWheel {
+                            delta, modifiers, ..
+                        } => {
+                            let d = if delta.y != 0.0 { delta.y } else { delta.x };
+                            (d != 0.0).then_some((d > 0.0, *modifiers))
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+            });
+            for (up, modifiers) in notches {
+                *value = Self::wheel_step(*value, up, modifiers, min, max, db_range);
+            }
+        }
+    }
+
+    /// Screen reader Increment / Decrement requests, one wheel notch each.
+    fn handle_level_actions(
+        ui: &mut egui::Ui,
+        response: &egui::Response,
+        value: &mut i64,
+        min: i64,
+        max: i64,
+        db_range: Option<(i64, i64)>,
+    ) {
+        use egui::accesskit::Action;
+        let (up, down) = ui.input(|i| {
+            (
+                i.num_accesskit_action_requests(response.id, Action::Increment),
+                i.num_accesskit_action_requests(response.id, Action::Decrement),
+            )
+        });
+        for _ in 0..up {
+            *value = Self::wheel_step(*value, true, egui::Modifiers::NONE, min, max, db_range);
+        }
+        for _ in 0..down {
+            *value = Self::wheel_step(*value, false, egui::Modifiers::NONE, min, max, db_range);
+        }
+    }
+
+    /// Exposes a knob or fader as a slider whose value reads in dB (or
+    /// percent); callers name it with [`AccessibleName`].
+    fn describe_level(
+        response: &egui::Response,
+        name: &str,
+        value: i64,
+        min: i64,
+        max: i64,
+        db_range: Option<(i64, i64)>,
+    ) {
+        use egui::accesskit::Action;
+        let text = match ControlKind::integer(min, max, db_range).db_at_raw(value) {
+            Some(db) => format!("{db:.1} dB"),
+            None => format!("{}%", Self::control_percent(value, min, max, db_range)),
+        };
+        response.widget_info(|| egui::WidgetInfo::slider(response.enabled(), value as f64, name));
+        response.ctx.accesskit_node_builder(response.id, |node| {
+            node.set_value(text);
+            node.set_min_numeric_value(min as f64);
+            node.set_max_numeric_value(max as f64);
+            node.add_action(Action::Increment);
+            node.add_action(Action::Decrement);
+        });
+    }
+
+    /// The dB (or percent) readout under a knob or fader.
+    fn render_level_readout(
+        ui: &mut egui::Ui,
+        id: egui::Id,
+        value: &mut i64,
+        min: i64,
+        max: i64,
+        db_range: Option<(i64, i64)>,
+    ) {
         let percent = Self::control_percent(*value, min, max, db_range);
-        ui.label(format!("{percent}%"));
-        old != *value
+        let kind = ControlKind::integer(min, max, db_range);
+        match kind.db_at_raw(*value) {
+            Some(db) => {
+                if let Some(raw) = Self::render_db_readout(ui, id, db, percent) {
+                    *value = kind.raw_at_db(raw).unwrap_or(*value);
+                }
+            }
+            None => {
+                ui.label(format!("{percent}%"));
+            }
+        }
+    }
+
+    fn ui_scale(&self) -> f32 {
+        self.user_config.ui_scale.clamp(0.5, 3.0)
+    }
+
+    fn handle_zoom_keys(&mut self, ctx: &egui::Context) {
+        use egui::gui_zoom::kb_shortcuts;
+        let scale = if ctx.input_mut(|i| i.consume_shortcut(&kb_shortcuts::ZOOM_RESET)) {
+            1.0
+        } else if ctx.input_mut(|i| {
+            i.consume_shortcut(&kb_shortcuts::ZOOM_IN) || i.consume_shortcut(&kb_shortcuts::ZOOM_IN_SECONDARY)
+        }) {
+            self.ui_scale() + 0.1
+        } else if ctx.input_mut(|i| i.consume_shortcut(&kb_shortcuts::ZOOM_OUT)) {
+            self.ui_scale() - 0.1
+        } else {
+            return;
+        };
+        self.user_config.ui_scale = ((scale * 10.0).round() / 10.0).clamp(0.5, 3.0);
+        self.status_line = trf("UI scale {}%", &[&format!("{:.0}", self.user_config.ui_scale * 100.0)]);
+        self.save_user_config();
+    }
+
+    fn matrix_zoom(&self) -> f32 {
+        self.user_config.matrix_zoom.clamp(0.5, 2.0)
+    }
+
+    fn cell_size(&self) -> (f32, f32) {
+        let zoom = self.matrix_zoom();
+        if self.user_config.compact_matrix {
+            (Self::COMPACT_CELL_W * zoom, Self::COMPACT_CELL_H * zoom)
+        } else {
+            let (w, h, _) = self.user_config.matrix_density.cell_metrics();
+            (w * zoom, h * zoom)
+        }
+    }
+
+    fn row_label_w(&self) -> f32 {
+        self.user_config.matrix_density.cell_metrics().2 * self.matrix_zoom()
+    }
+
+    /// Compact stand-in for [`Self::render_knob`]: a drag/type number in dB,
+    /// or in percent when the control has no dB range.
+    fn render_compact_value(
+        ui: &mut egui::Ui,
+        value: &mut i64,
+        min: i64,
+        max: i64,
+        db_range: Option<(i64, i64)>,
+    ) -> egui::Response {
+        let old = *value;
+        let kind = ControlKind::integer(min, max, db_range);
+        let mut response = match (kind.db_at_raw(*value), db_range) {
+            (Some(mut db), Some((db_min, db_max))) => {
+                let resp = ui.add(
+                    egui::DragValue::new(&mut db)
+                        .range(db_min as f64 / 100.0..=db_max as f64 / 100.0)
+                        .speed(0.1)
+                        .fixed_decimals(1),
+                );
+                if resp.changed() {
+                    *value = kind.raw_at_db(db).unwrap_or(*value);
+                }
+                resp
+            }
+            _ => {
+                let mut percent = Self::control_percent(*value, min, max, db_range);
+                let resp = ui.add(
+                    egui::DragValue::new(&mut percent)
+                        .range(0..=100)
+                        .suffix("%"),
+                );
+                if resp.changed() {
+                    *value = Self::value_from_knob_progress(percent as f32 / 100.0, min, max, db_range);
+                }
+                resp
+            }
+        };
+        if old != *value {
+            response.mark_changed();
+        }
+        response
+    }
+
+    /// One wheel notch: 1 dB (Shift 0.1 dB, Ctrl 6 dB) on controls with a dB
+    /// range, otherwise 1% of the range (Shift one raw unit, Ctrl 10%).
+    fn wheel_step(
+        value: i64,
+        up: bool,
+        modifiers: egui::Modifiers,
+        min: i64,
+        max: i64,
+        db_range: Option<(i64, i64)>,
+    ) -> i64 {
+        let sign = if up { 1 } else { -1 };
+        let kind = ControlKind::integer(min, max, db_range);
+        let next = match kind.db_at_raw(value) {
+            Some(db) => {
+                let step_db = if modifiers.shift {
+                    0.1
+                } else if modifiers.command {
+                    6.0
+                } else {
+                    1.0
+                };
+                kind.raw_at_db(db + sign as f64 * step_db).unwrap_or(value)
+            }
+            None => {
+                let span = (max - min).max(1);
+                let step = if modifiers.shift {
+                    1
+                } else if modifiers.command {
+                    (span / 10).max(1)
+                } else {
+                    (span / 100).max(1)
+                };
+                value + sign * step
+            }
+        };
+        // A fine step can be smaller than one raw unit; always move at least one.
+        let next = if next == value { value + sign } else { next };
+        next.clamp(min, max)
+    }
+
+    /// Shows the knob level in dB; clicking it turns it into a text field.
+    /// Returns the dB value entered, once confirmed with Enter.
+    fn render_db_readout(ui: &mut egui::Ui, knob_id: egui::Id, db: f64, percent: i64) -> Option<f64> {
+        let edit_id = knob_id.with("db_entry");
+        let Some(mut text) = ui.data_mut(|d| d.get_temp::<String>(edit_id)) else {
+            let clicked = ui
+                .add(egui::Label::new(format!("{db:.1} dB")).sense(egui::Sense::click()))
+                .on_hover_text(trf("{}% — click to type a dB value", &[&percent]))
+                .clicked();
+            if clicked {
+                ui.data_mut(|d| d.insert_temp(edit_id, format!("{db:.1}")));
+            }
+            return None;
+        };
+        let resp = ui.add(egui::TextEdit::singleline(&mut text).desired_width(56.0));
+        if !resp.has_focus() && !resp.lost_focus() {
+            resp.request_focus();
+        }
+        let mut entered = None;
+        if resp.lost_focus() {
+            if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                entered = Self::parse_db(&text);
+            }
+            ui.data_mut(|d| d.remove::<String>(edit_id));
+        } else {
+            ui.data_mut(|d| d.insert_temp(edit_id, text));
+        }
+        entered
+    }
+
+    /// Accepts "-12.5", "-12.5 dB" and "-inf".
+    fn parse_db(text: &str) -> Option<f64> {
+        let text = text.trim().to_ascii_lowercase();
+        let text = text.strip_suffix("db").unwrap_or(&text).trim();
+        if matches!(text, "-inf" | "-∞" | "off") {
+            return Some(f64::NEG_INFINITY);
+        }
+        text.replace(',', ".").parse::<f64>().ok().filter(|db| !db.is_nan())
     }
 
     fn knob_progress_from_value(value: i64, min: i64, max: i64, db_range: Option<(i64, i64)>) -> f32 {
@@ -1396,7 +9382,7 @@ impl MixerApp {
         ((pos * 100) / span).clamp(0, 100) as i64
     }
 
-    fn apply_studio_theme(&self, ctx: &egui::Context) {
+    fn apply_studio_theme(&mut self, ctx: &egui::Context) {
         self.apply_font_fallbacks(ctx);
 
         let mut style = (*ctx.style()).clone();
@@ -1405,26 +9391,14 @@ impl MixerApp {
         style.spacing.interact_size = vec2(20.0, 18.0);
         style.spacing.window_margin = egui::Margin::same(6);
         ctx.set_style(style);
+        self.apply_theme_colors(ctx);
+    }
 
-        let mut visuals = egui::Visuals::dark();
-        visuals.override_text_color = Some(Color32::from_rgb(232, 236, 240));
-        visuals.panel_fill = Color32::from_rgb(14, 16, 20);
-        visuals.window_fill = Color32::from_rgb(14, 16, 20);
-        visuals.extreme_bg_color = Color32::from_rgb(20, 23, 28);
-        visuals.faint_bg_color = Color32::from_rgb(30, 33, 40);
-        visuals.selection.bg_fill = Color32::from_rgb(54, 168, 178);
-        visuals.selection.stroke = Stroke::new(1.0, Color32::from_rgb(180, 245, 250));
-        visuals.widgets.inactive.bg_fill = Color32::from_rgb(28, 32, 38);
-        visuals.widgets.inactive.weak_bg_fill = Color32::from_rgb(24, 27, 33);
-        visuals.widgets.hovered.bg_fill = Color32::from_rgb(44, 50, 58);
-        visuals.widgets.active.bg_fill = Color32::from_rgb(57, 66, 76);
-        visuals.widgets.open.bg_fill = Color32::from_rgb(40, 46, 54);
-        visuals.widgets.noninteractive.bg_stroke =
-            Stroke::new(1.0, Color32::from_rgb(52, 57, 66));
-        visuals.widgets.inactive.fg_stroke = Stroke::new(1.0, Color32::from_rgb(210, 214, 220));
-        visuals.widgets.hovered.fg_stroke = Stroke::new(1.0, Color32::from_rgb(235, 240, 244));
-        visuals.widgets.active.fg_stroke = Stroke::new(1.0, Color32::from_rgb(245, 250, 252));
-        ctx.set_visuals(visuals);
+    /// Visuals and hand-painted colors of `user_config.theme`; called again
+    /// when the theme changes in the settings.
+    fn apply_theme_colors(&mut self, ctx: &egui::Context) {
+        self.palette = self.user_config.theme.palette();
+        ctx.set_visuals(self.user_config.theme.visuals());
     }
 
     fn apply_font_fallbacks(&self, ctx: &egui::Context) {
@@ -1460,19 +9434,112 @@ impl eframe::App for MixerApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         if !self.theme_initialized {
             self.apply_studio_theme(ctx);
+            // Ctrl+= / Ctrl+- are handled here so the zoom is saved.
+            ctx.options_mut(|o| o.zoom_with_keyboard = false);
+            if self.user_config.mini_mode {
+                ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(self.mini_window_size()));
+            }
+            if self.user_config.always_on_top {
+                ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(egui::WindowLevel::AlwaysOnTop));
+            }
+            if self.user_config.start_minimized || self.minimize_at_start {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+            }
             self.theme_initialized = true;
         }
+        self.handle_zoom_keys(ctx);
+        ctx.set_pixels_per_point(ctx.native_pixels_per_point().unwrap_or(1.0) * self.ui_scale());
         if !self.event_listener_initialized {
             self.event_listener_initialized = true;
             let egui_ctx = ctx.clone();
             self.alsa_event_rx = self
                 .backend
                 .start_event_listener(move || egui_ctx.request_repaint());
+            if self.user_config.midi_presets.enabled {
+                let egui_ctx = ctx.clone();
+                match midi::start_program_listener(move || egui_ctx.request_repaint()) {
+                    Ok(rx) => self.midi_rx = Some(rx),
+                    Err(err) => self.status_line = trf("MIDI preset select disabled: {}", &[&format!("{err:#}")]),
+                }
+            }
+            if self.user_config.metering.inputs {
+                self.start_input_meters(ctx);
+            }
+            if self.user_config.metering.outputs {
+                self.start_output_meters(ctx);
+            }
+            if self.user_config.gamepad.enabled {
+                let egui_ctx = ctx.clone();
+                match gamepad::start(move || egui_ctx.request_repaint()) {
+                    Ok(rx) => self.gamepad_rx = Some(rx),
+                    Err(err) => self.status_line = trf("Gamepad input disabled: {}", &[&format!("{err:#}")]),
+                }
+            }
+            if self.user_config.mqtt.enabled {
+                let egui_ctx = ctx.clone();
+                self.start_mqtt(move || egui_ctx.request_repaint());
+            }
+            if self.user_config.ipc_socket {
+                let egui_ctx = ctx.clone();
+                match ipc::start(move || egui_ctx.request_repaint()) {
+                    Ok(server) => self.ipc = Some(server),
+                    Err(err) => self.status_line = trf("IPC socket disabled: {}", &[&format!("{err:#}")]),
+                }
+            }
+        }
+        self.handle_snapshot_shortcuts(ctx);
+        if !ctx.wants_keyboard_input()
+            && ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z))
+        {
+            self.undo();
+        }
+        self.handle_matrix_keys(ctx);
+        if !ctx.wants_keyboard_input() {
+            let pasted = ctx.input(|i| {
+                i.events.iter().find_map(|e| match e {
+                    egui::Event::Paste(text) => ControlSnippet::parse(text),
+                    _ => None,
+                })
+            });
+            if let Some(snippet) = pasted {
+                self.paste_controls(snippet);
+            }
+        }
+        if let Some(text) = self.clipboard_out.take() {
+            ctx.copy_text(text);
+        }
+        if !self.matrix_selection.is_empty()
+            && !ctx.wants_keyboard_input()
+            && ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape))
+        {
+            self.matrix_selection.clear();
+        }
+        if !ctx.input(|i| i.pointer.any_down()) {
+            self.paint_stroke = None;
+        }
+        if std::mem::take(&mut self.http_api_pending) {
+            let egui_ctx = ctx.clone();
+            self.start_http_api(move || egui_ctx.request_repaint());
+        }
+        if std::mem::take(&mut self.global_hotkeys_pending) {
+            let egui_ctx = ctx.clone();
+            self.global_hotkeys_rx = Some(global_hotkeys::start(move || egui_ctx.request_repaint()));
+        }
+        self.poll_global_hotkeys();
+        self.poll_device_usage();
+        self.poll_midi_programs();
+        self.poll_gamepad(ctx);
+        self.poll_meters();
+        self.poll_hot_folder();
+        self.poll_app_triggers();
+        self.poll_http_api();
+        self.poll_mqtt_status();
+        self.poll_fx_tail_restore(ctx);
+        if self.poll_preset_ramp() {
+            ctx.request_repaint_after(Duration::from_millis(15));
         }
 
-        const AUTO_REFRESH_INTERVAL: Duration = Duration::from_millis(220);
-        const EVENT_FALLBACK_INTERVAL: Duration = Duration::from_millis(500);
-        const FULL_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+        let refresh = self.user_config.refresh;
         let is_interacting = ctx.input(|i| i.pointer.any_down());
         let mut should_repaint = is_interacting;
         let has_event_listener = self.alsa_event_rx.is_some();
@@ -1483,67 +9550,111 @@ impl eframe::App for MixerApp {
             }
         }
 
-        if !is_interacting && got_alsa_event {
-            should_repaint |= self.refresh_live_values_only();
-            self.last_auto_refresh = Instant::now();
-        } else if !is_interacting && !has_event_listener && self.last_auto_refresh.elapsed() >= AUTO_REFRESH_INTERVAL {
-            should_repaint |= self.refresh_live_values_only();
-            self.last_auto_refresh = Instant::now();
-        } else if !is_interacting
-            && has_event_listener
-            && self.last_auto_refresh.elapsed() >= EVENT_FALLBACK_INTERVAL
-        {
+        let poll_interval = if has_event_listener {
+            refresh.event_fallback()
+        } else {
+            refresh.auto_refresh()
+        };
+        if !is_interacting && (got_alsa_event || self.last_auto_refresh.elapsed() >= poll_interval) {
             should_repaint |= self.refresh_live_values_only();
             self.last_auto_refresh = Instant::now();
+            self.check_overridden_writes();
         }
-        if !is_interacting && self.last_full_refresh.elapsed() >= FULL_REFRESH_INTERVAL {
+        if !is_interacting && self.last_full_refresh.elapsed() >= refresh.full_refresh() {
             should_repaint |= self.refresh_controls_with_status(false);
         }
+        self.poll_script();
+        self.broadcast_osc();
+        self.publish_api_changes();
         if should_repaint {
             ctx.request_repaint();
         } else {
-            let wake_after = if has_event_listener {
-                EVENT_FALLBACK_INTERVAL
-            } else {
-                AUTO_REFRESH_INTERVAL
-            };
-            ctx.request_repaint_after(wake_after);
+            let next_timer = self.script.as_ref().and_then(|s| s.next_timer_in());
+            ctx.request_repaint_after(next_timer.map_or(poll_interval, |t| t.min(poll_interval)));
         }
 
-        egui::TopBottomPanel::top("toolbar")
-            .frame(
-                egui::Frame::new()
-                    .fill(Color32::from_rgb(20, 23, 29))
-                    .stroke(Stroke::new(1.0, Color32::from_rgb(44, 50, 60)))
-                    .inner_margin(egui::Margin::symmetric(8, 6)),
-            )
-            .show(ctx, |ui| {
-                self.render_toolbar(ui);
-            });
+        self.render_meter_bridge(ctx);
+        if self.user_config.mini_mode {
+            self.render_mini_mode(ctx);
+        } else {
+            egui::TopBottomPanel::top("toolbar")
+                .frame(
+                    egui::Frame::new()
+                        .fill(Color32::from_rgb(20, 23, 29))
+                        .stroke(Stroke::new(1.0, Color32::from_rgb(44, 50, 60)))
+                        .inner_margin(egui::Margin::symmetric(8, 6)),
+                )
+                .show(ctx, |ui| {
+                    self.render_toolbar(ui);
+                });
+            self.render_preset_save_window(ctx);
+            self.render_preset_library_window(ctx);
+            self.render_preset_changes_window(ctx);
+            self.render_app_triggers_window(ctx);
+            self.render_generator_window(ctx);
+            self.render_loopback_window(ctx);
+            self.render_scratch_window(ctx);
+            self.render_preset_preview_window(ctx);
+            self.render_templates_window(ctx);
+            self.render_cell_value_window(ctx);
+            self.render_note_window(ctx);
+            self.render_selection_window(ctx);
+            self.render_fx_send_mapping_window(ctx);
 
-        egui::TopBottomPanel::bottom("status")
-            .frame(
-                egui::Frame::new()
-                    .fill(Color32::from_rgb(18, 21, 26))
-                    .stroke(Stroke::new(1.0, Color32::from_rgb(44, 50, 60)))
-                    .inner_margin(egui::Margin::symmetric(8, 4)),
-            )
-            .show(ctx, |ui| {
-                ui.label(RichText::new(&self.status_line).size(12.0));
-            });
+            egui::TopBottomPanel::bottom("status")
+                .frame(
+                    egui::Frame::new()
+                        .fill(Color32::from_rgb(18, 21, 26))
+                        .stroke(Stroke::new(1.0, Color32::from_rgb(44, 50, 60)))
+                        .inner_margin(egui::Margin::symmetric(8, 4)),
+                )
+                .show(ctx, |ui| {
+                    ui.label(RichText::new(&self.status_line).size(12.0));
+                });
 
-        egui::CentralPanel::default()
-            .frame(
-                egui::Frame::new()
-                    .fill(Color32::from_rgb(12, 14, 18))
-                    .inner_margin(egui::Margin::symmetric(8, 6)),
-            )
-            .show(ctx, |ui| {
-                egui::ScrollArea::both()
-                    .auto_shrink([false, false])
-                    .show(ui, |ui| match self.selected_tab {
-                        Tab::MixRouting => self.render_mix_routing_tab(ui),
+            egui::CentralPanel::default()
+                .frame(
+                    egui::Frame::new()
+                        .fill(self.palette.background)
+                        .inner_margin(egui::Margin::symmetric(8, 6)),
+                )
+                .show(ctx, |ui| {
+                    self.matrix_hover_seen = false;
+                    self.render_tab_bar(ui);
+                    egui::ScrollArea::both()
+                        .auto_shrink([false, false])
+                        .show(ui, |ui| match self.selected_tab {
+                            Tab::Favorites => self.render_favorites_tab(ui),
+                            Tab::MixRouting => self.render_mix_routing_tab(ui),
+                            Tab::OutputMix => self.render_output_mix_tab(ui),
+                            Tab::CueMix => self.render_cue_mix_tab(ui),
+                            Tab::Inputs => self.render_inputs_tab(ui),
+                            Tab::Fx => self.render_fx_tab(ui),
+                            Tab::Browser => self.render_browser_tab(ui),
+                            Tab::Settings => self.render_settings_tab(ui),
+                        });
                     });
-                });
+        }
+        self.scroll_to_cursor = false;
+        let talk_key = !ctx.wants_keyboard_input()
+            && ctx.input(|i| i.modifiers.is_none() && i.key_down(egui::Key::T));
+        self.update_talkback(self.talk_button_held || talk_key || self.global_talk_held || self.gamepad_talk_held);
+        self.talk_button_held = false;
+        if !self.matrix_hover_seen {
+            self.matrix_hover = None;
+        }
+        if let Some(band) = Self::selection_band(ctx) {
+            ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("matrix_selection_band"),
+            ))
+            .rect(
+                band,
+                0.0,
+                Color32::from_rgba_unmultiplied(240, 190, 90, 24),
+                Stroke::new(1.0, Color32::from_rgb(240, 190, 90)),
+                egui::StrokeKind::Inside,
+            );
+        }
     }
 }
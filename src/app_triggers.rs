@@ -0,0 +1,145 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::PathBuf,
+    process::{self, Command},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+const SCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppTrigger {
+    /// Case-insensitive substring matched against process and PipeWire node names.
+    pub pattern: String,
+    pub preset: PathBuf,
+}
+
+impl AppTrigger {
+    /// Only triggers with a pattern and an existing preset file take part.
+    pub fn is_armed(&self) -> bool {
+        !self.pattern.trim().is_empty() && self.preset.is_file()
+    }
+}
+
+/// Names of the audio clients currently visible on the system, lowercased.
+#[derive(Debug, Clone, Default)]
+pub struct ActiveClients {
+    pub names: HashSet<String>,
+}
+
+impl ActiveClients {
+    pub fn matches(&self, pattern: &str) -> bool {
+        let pattern = pattern.trim().to_lowercase();
+        !pattern.is_empty() && self.names.iter().any(|n| n.contains(&pattern))
+    }
+}
+
+/// Returns the first armed trigger whose pattern matches a running client.
+pub fn pick_trigger<'a>(triggers: &'a [AppTrigger], clients: &ActiveClients) -> Option<&'a AppTrigger> {
+    triggers.iter().find(|t| t.is_armed() && clients.matches(&t.pattern))
+}
+
+/// Polls in the background for processes holding the card's PCM devices,
+/// running process names and PipeWire nodes (through `pw-dump`, when installed).
+pub fn start_client_monitor(card_index: u32) -> Receiver<ActiveClients> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || loop {
+        let mut names = HashSet::new();
+        collect_pcm_owners(card_index, &mut names);
+        collect_process_names(&mut names);
+        collect_pipewire_nodes(&mut names);
+        if tx.send(ActiveClients { names }).is_err() {
+            break;
+        }
+        thread::sleep(SCAN_INTERVAL);
+    });
+    rx
+}
+
+/// Name of another process; the mixer itself never counts as a client.
+fn process_name(pid: &str) -> Option<String> {
+    if pid == process::id().to_string() {
+        return None;
+    }
+    fs::read_to_string(format!("/proc/{pid}/comm"))
+        .ok()
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+}
+
+fn collect_pcm_owners(card_index: u32, names: &mut HashSet<String>) {
+    let Ok(card_dir) = fs::read_dir(format!("/proc/asound/card{card_index}")) else {
+        return;
+    };
+    for pcm in card_dir.flatten() {
+        if !pcm.file_name().to_string_lossy().starts_with("pcm") {
+            continue;
+        }
+        let Ok(subs) = fs::read_dir(pcm.path()) else {
+            continue;
+        };
+        for sub in subs.flatten() {
+            let Ok(status) = fs::read_to_string(sub.path().join("status")) else {
+                continue;
+            };
+            let owner = status
+                .lines()
+                .find_map(|l| l.strip_prefix("owner_pid"))
+                .and_then(|rest| rest.split(':').nth(1))
+                .map(str::trim);
+            if let Some(name) = owner.and_then(process_name) {
+                names.insert(name);
+            }
+        }
+    }
+}
+
+fn collect_process_names(names: &mut HashSet<String>) {
+    let Ok(proc_dir) = fs::read_dir("/proc") else {
+        return;
+    };
+    for entry in proc_dir.flatten() {
+        let pid = entry.file_name().to_string_lossy().into_owned();
+        if !pid.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        if let Some(name) = process_name(&pid) {
+            names.insert(name);
+        }
+    }
+}
+
+fn collect_pipewire_nodes(names: &mut HashSet<String>) {
+    let Ok(output) = Command::new("pw-dump").output() else {
+        return;
+    };
+    if !output.status.success() {
+        return;
+    }
+    let Ok(objects) = serde_json::from_slice::<Vec<serde_json::Value>>(&output.stdout) else {
+        return;
+    };
+    for obj in objects {
+        if obj.get("type").and_then(|t| t.as_str()) != Some("PipeWire:Interface:Node") {
+            continue;
+        }
+        let Some(props) = obj.pointer("/info/props") else {
+            continue;
+        };
+        let pid = props.get("application.process.id");
+        let pid = pid.and_then(|v| v.as_u64()).or_else(|| pid?.as_str()?.parse().ok());
+        if pid == Some(process::id() as u64) {
+            continue;
+        }
+        for key in ["node.name", "application.name", "application.process.binary"] {
+            if let Some(value) = props.get(key).and_then(|v| v.as_str()) {
+                names.insert(value.to_lowercase());
+            }
+        }
+    }
+}
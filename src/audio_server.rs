@@ -0,0 +1,163 @@
+use std::{
+    fs,
+    process::Command,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+const SCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// An open PCM substream of the card, from `/proc/asound/cardN/pcm*/sub*`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PcmStream {
+    /// e.g. `pcm0p/sub0`.
+    pub device: String,
+    pub pid: u32,
+    /// Process name of the owner, lowercased.
+    pub owner: String,
+    pub rate: Option<u32>,
+    pub period_size: Option<u32>,
+    pub buffer_size: Option<u32>,
+    pub running: bool,
+}
+
+/// Who has the card's PCM devices open, rescanned in the background.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceUsage {
+    pub streams: Vec<PcmStream>,
+    /// PipeWire owns the card and some of its clients use the JACK API.
+    pub pipewire_jack: bool,
+}
+
+impl DeviceUsage {
+    /// Sound server holding the card, by owner process.
+    pub fn server(&self) -> Option<&'static str> {
+        let owner = self.streams.first()?.owner.as_str();
+        Some(match owner {
+            "jackd" | "jackdbus" => "JACK",
+            "pipewire" if self.pipewire_jack => "PipeWire-JACK",
+            "pipewire" => "PipeWire",
+            "pulseaudio" => "PulseAudio",
+            _ => "",
+        })
+        .filter(|s| !s.is_empty())
+    }
+
+    /// Server (or owner process), sample rate and period, for the toolbar,
+    /// e.g. "JACK 48000 Hz · 256×2 (5.3 ms)".
+    pub fn summary(&self) -> Option<String> {
+        let stream = self.streams.iter().find(|s| s.running).or(self.streams.first())?;
+        let mut text = self.server().map(str::to_string).unwrap_or_else(|| stream.owner.clone());
+        if let Some(rate) = stream.rate {
+            text.push_str(&format!(" {rate} Hz"));
+            if let Some(period) = stream.period_size {
+                let periods = stream.buffer_size.map(|b| b / period.max(1)).unwrap_or(1);
+                let ms = period as f64 * 1000.0 / rate.max(1) as f64;
+                text.push_str(&format!(" · {period}×{periods} ({ms:.1} ms)"));
+            }
+        }
+        Some(text)
+    }
+
+    /// One line per open substream, for the toolbar tooltip.
+    pub fn details(&self) -> String {
+        self.streams
+            .iter()
+            .map(|s| {
+                let state = if s.running { "running" } else { "open" };
+                format!("{}: {} (pid {}), {state}", s.device, s.owner, s.pid)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+pub fn scan(card_index: u32) -> DeviceUsage {
+    let mut streams = Vec::new();
+    let Ok(card_dir) = fs::read_dir(format!("/proc/asound/card{card_index}")) else {
+        return DeviceUsage::default();
+    };
+    for pcm in card_dir.flatten() {
+        let pcm_name = pcm.file_name().to_string_lossy().into_owned();
+        if !pcm_name.starts_with("pcm") {
+            continue;
+        }
+        let Ok(subs) = fs::read_dir(pcm.path()) else {
+            continue;
+        };
+        for sub in subs.flatten() {
+            let sub_name = sub.file_name().to_string_lossy().into_owned();
+            if !sub_name.starts_with("sub") {
+                continue;
+            }
+            let Ok(status) = fs::read_to_string(sub.path().join("status")) else {
+                continue;
+            };
+            let Some(pid) = field(&status, "owner_pid").and_then(|v| v.parse::<u32>().ok()) else {
+                continue;
+            };
+            let hw_params = fs::read_to_string(sub.path().join("hw_params")).unwrap_or_default();
+            let number = |name: &str| {
+                field(&hw_params, name).and_then(|v| v.split_whitespace().next()?.parse().ok())
+            };
+            streams.push(PcmStream {
+                device: format!("{pcm_name}/{sub_name}"),
+                pid,
+                owner: fs::read_to_string(format!("/proc/{pid}/comm"))
+                    .map(|s| s.trim().to_lowercase())
+                    .unwrap_or_else(|_| format!("pid {pid}")),
+                rate: number("rate"),
+                period_size: number("period_size"),
+                buffer_size: number("buffer_size"),
+                running: field(&status, "state") == Some("RUNNING"),
+            });
+        }
+    }
+    streams.sort_by(|a, b| a.device.cmp(&b.device));
+    let pipewire_jack = streams.iter().any(|s| s.owner == "pipewire") && has_pipewire_jack_clients();
+    DeviceUsage {
+        streams,
+        pipewire_jack,
+    }
+}
+
+/// `name: value` or `name   : value` line of a procfs file.
+fn field<'a>(text: &'a str, name: &str) -> Option<&'a str> {
+    text.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == name).then(|| value.trim())
+    })
+}
+
+fn has_pipewire_jack_clients() -> bool {
+    let Ok(output) = Command::new("pw-dump").output() else {
+        return false;
+    };
+    let Ok(objects) = serde_json::from_slice::<Vec<serde_json::Value>>(&output.stdout) else {
+        return false;
+    };
+    objects.iter().any(|obj| {
+        obj.pointer("/info/props/client.api").and_then(|v| v.as_str()) == Some("jack")
+    })
+}
+
+/// Rescans the card's PCM owners every couple of seconds, sending the result
+/// when it changes.
+pub fn start_usage_monitor(card_index: u32) -> Receiver<DeviceUsage> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut last = None;
+        loop {
+            let usage = scan(card_index);
+            if last.as_ref() != Some(&usage) {
+                if tx.send(usage.clone()).is_err() {
+                    break;
+                }
+                last = Some(usage);
+            }
+            thread::sleep(SCAN_INTERVAL);
+        }
+    });
+    rx
+}
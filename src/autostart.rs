@@ -0,0 +1,67 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+/// `$XDG_CONFIG_HOME/autostart/ftu-rust-mixer.desktop`, `~/.config/...` by default.
+pub fn entry_path() -> Result<PathBuf> {
+    let config = match env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let home = env::var("HOME").context("HOME environment variable is not set")?;
+            Path::new(&home).join(".config")
+        }
+    };
+    Ok(config.join("autostart").join("ftu-rust-mixer.desktop"))
+}
+
+pub fn is_installed() -> bool {
+    entry_path().is_ok_and(|path| path.exists())
+}
+
+/// Writes a login entry that starts this executable minimized.
+pub fn install() -> Result<PathBuf> {
+    let exe = env::current_exe().context("Failed to locate the mixer executable")?;
+    let path = entry_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let entry = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Fast Track Ultra Mixer\n\
+         Comment=Restore the Fast Track Ultra mixer state at login\n\
+         Exec={} --minimized\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n",
+        exec_arg(&exe.to_string_lossy())
+    );
+    fs::write(&path, entry).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+pub fn remove() -> Result<()> {
+    let path = entry_path()?;
+    fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))
+}
+
+/// Quotes a path for the `Exec` key when it holds spaces or reserved
+/// characters. The key's string escaping comes on top of the quoting, so
+/// backslashes are doubled once more and `%` (field codes) is doubled.
+fn exec_arg(path: &str) -> String {
+    let path = path.replace('%', "%%");
+    if !path.contains(|c: char| c.is_whitespace() || "\"'\\`$<>|&;*?#()".contains(c)) {
+        return path;
+    }
+    let mut quoted = String::from("\"");
+    for c in path.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted.replace('\\', "\\\\")
+}
@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    alsa_backend::AlsaBackend,
+    config::AppUserConfig,
+    fx::{self, FxModel},
+    models::{ControlDescriptor, ControlKind},
+};
+
+/// Raw ceiling of the Effect Feedback control, from the cap in percent.
+pub fn fx_feedback_ceiling(control: &ControlDescriptor, cap_pct: f64) -> Option<i64> {
+    let ControlKind::Integer { min, max, .. } = control.kind else {
+        return None;
+    };
+    let pct = cap_pct.clamp(0.0, 100.0);
+    Some(min + ((max - min) as f64 * pct / 100.0).round() as i64)
+}
+
+/// Outputs an FX return feeds: a stereo pair per return when the card has
+/// enough outputs, else the output with the return's number.
+pub fn fx_return_outputs(key: usize, count: usize, output_count: usize) -> Vec<usize> {
+    if count * 2 <= output_count {
+        vec![key * 2, key * 2 + 1]
+    } else {
+        vec![key]
+    }
+}
+
+/// Lowest ceiling among `outputs`, in dB.
+pub fn outputs_ceiling_db(ceilings_db: &BTreeMap<usize, f64>, outputs: &[usize]) -> Option<f64> {
+    outputs
+        .iter()
+        .filter_map(|o| ceilings_db.get(o).copied())
+        .reduce(f64::min)
+}
+
+/// Raw ceilings by control index: AIn/DIn routes and FX returns at the
+/// ceiling of their outputs, the Effect Feedback at its cap. Controls whose
+/// outputs have no ceiling get `None`, which lifts an earlier cap.
+pub fn value_ceilings(controls: &[ControlDescriptor], config: &AppUserConfig) -> Vec<(usize, Option<i64>)> {
+    let mut out = Vec::new();
+    let routing = AlsaBackend::build_routing_index(controls);
+    for route in routing.analog_routes.iter().chain(&routing.digital_routes) {
+        let Some(control) = controls.get(route.control_index) else {
+            continue;
+        };
+        let ceiling = config
+            .output_ceilings_db
+            .get(&route.output)
+            .and_then(|db| control.kind.raw_at_db(*db));
+        out.push((route.control_index, ceiling));
+    }
+
+    let output_count = routing
+        .analog_routes
+        .iter()
+        .chain(&routing.digital_routes)
+        .map(|r| r.output + 1)
+        .max()
+        .unwrap_or(0);
+    let returns = fx::return_map(controls);
+    for (&key, &idx) in &returns {
+        let outputs = fx_return_outputs(key, returns.len(), output_count);
+        let ceiling = outputs_ceiling_db(&config.output_ceilings_db, &outputs)
+            .and_then(|db| controls[idx].kind.raw_at_db(db));
+        out.push((idx, ceiling));
+    }
+
+    if let Some(idx) = FxModel::from_controls(controls).feedback {
+        out.push((idx, fx_feedback_ceiling(&controls[idx], config.fx_feedback_cap_pct)));
+    }
+    out
+}
+
+/// Hands every ceiling to the backend so all write paths are clamped: the
+/// UI, presets, scenes, the socket, the HTTP API, scripts and `converge`.
+pub fn install(backend: &AlsaBackend, controls: &[ControlDescriptor], config: &AppUserConfig) {
+    for (idx, ceiling) in value_ceilings(controls, config) {
+        backend.set_value_ceiling(controls[idx].numid, ceiling);
+    }
+}
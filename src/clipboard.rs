@@ -0,0 +1,38 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{ControlDescriptor, ControlKey};
+
+/// Control values shared as text, e.g. pasted in a chat:
+/// `{"ftu_controls": {"Effect Return 1 Playback Volume": ["90", "90"]}}`.
+/// Controls are keyed by name, with `,index` for same-named controls, since
+/// numids differ between machines.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ControlSnippet {
+    pub ftu_controls: BTreeMap<ControlKey, Vec<String>>,
+}
+
+impl ControlSnippet {
+    pub fn capture<'a>(controls: impl IntoIterator<Item = &'a ControlDescriptor>) -> Self {
+        Self {
+            ftu_controls: controls
+                .into_iter()
+                .map(|c| (c.key(), c.values.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Finds a snippet in pasted text, tolerating chat decoration around it
+    /// (a code fence, a leading message).
+    pub fn parse(text: &str) -> Option<Self> {
+        let start = text.find('{')?;
+        let end = text.rfind('}')?;
+        let snippet: Self = serde_json::from_str(text.get(start..=end)?).ok()?;
+        (!snippet.ftu_controls.is_empty()).then_some(snippet)
+    }
+}
@@ -1,18 +1,506 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     env, fs,
+    io::Write,
+    os::unix::fs::{OpenOptionsExt, PermissionsExt},
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 use anyhow::{Context, Result};
+use eframe::egui;
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    app_triggers::AppTrigger, event_hooks::EventHook, gamepad::GamepadConfig, generator::GeneratorConfig,
+    http_api::HttpApiConfig, i18n::{Language, tr}, metering::MeteringConfig, midi::MidiPresetConfig, models::ControlKey, mqtt::MqttConfig, pan::PanLaw,
+    theme::ThemeConfig,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PresetCardCheck {
+    Ignore,
+    #[default]
+    Warn,
+    Refuse,
+}
+
+impl PresetCardCheck {
+    pub const ALL: [PresetCardCheck; 3] = [PresetCardCheck::Ignore, PresetCardCheck::Warn, PresetCardCheck::Refuse];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PresetCardCheck::Ignore => tr("Ignore"),
+            PresetCardCheck::Warn => tr("Warn"),
+            PresetCardCheck::Refuse => tr("Refuse"),
+        }
+    }
+}
+
+/// Base size of the routing matrix cells, before `matrix_zoom`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatrixDensity {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl MatrixDensity {
+    pub const ALL: [MatrixDensity; 3] = [MatrixDensity::Small, MatrixDensity::Medium, MatrixDensity::Large];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MatrixDensity::Small => tr("Small"),
+            MatrixDensity::Medium => tr("Medium"),
+            MatrixDensity::Large => tr("Large"),
+        }
+    }
+
+    /// Knob cell width, knob cell height and row label width.
+    pub fn cell_metrics(self) -> (f32, f32, f32) {
+        match self {
+            MatrixDensity::Small => (70.0, 66.0, 120.0),
+            MatrixDensity::Medium => (82.0, 74.0, 150.0),
+            MatrixDensity::Large => (100.0, 90.0, 180.0),
+        }
+    }
+}
+
+/// Widget drawn in the level cells of a routing matrix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CellWidget {
+    #[default]
+    Knob,
+    Slider,
+    Fader,
+}
+
+impl CellWidget {
+    pub const ALL: [CellWidget; 3] = [CellWidget::Knob, CellWidget::Slider, CellWidget::Fader];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CellWidget::Knob => tr("Knob"),
+            CellWidget::Slider => tr("Horizontal slider"),
+            CellWidget::Fader => tr("Vertical fader"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DragAxis {
+    #[default]
+    Vertical,
+    Horizontal,
+}
+
+impl DragAxis {
+    pub const ALL: [DragAxis; 2] = [DragAxis::Vertical, DragAxis::Horizontal];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DragAxis::Vertical => tr("Vertical"),
+            DragAxis::Horizontal => tr("Horizontal"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FineModifier {
+    #[default]
+    Alt,
+    Ctrl,
+    Shift,
+}
+
+impl FineModifier {
+    pub const ALL: [FineModifier; 3] = [FineModifier::Alt, FineModifier::Ctrl, FineModifier::Shift];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FineModifier::Alt => "Alt",
+            FineModifier::Ctrl => "Ctrl",
+            FineModifier::Shift => "Shift",
+        }
+    }
+
+    pub fn held(self, modifiers: egui::Modifiers) -> bool {
+        match self {
+            FineModifier::Alt => modifiers.alt,
+            FineModifier::Ctrl => modifiers.command,
+            FineModifier::Shift => modifiers.shift,
+        }
+    }
+}
+
+/// How a mouse drag turns a knob.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KnobDragConfig {
+    /// Pointer travel across the whole range, in points.
+    pub range_px: f32,
+    pub axis: DragAxis,
+    /// Down / left turns the knob up instead.
+    pub inverted: bool,
+    /// Held during a drag to move ten times slower.
+    pub fine_modifier: FineModifier,
+}
+
+impl Default for KnobDragConfig {
+    fn default() -> Self {
+        Self {
+            range_px: 180.0,
+            axis: DragAxis::Vertical,
+            inverted: false,
+            fine_modifier: FineModifier::Alt,
+        }
+    }
+}
+
+impl KnobDragConfig {
+    /// Progress change (of the 0..1 range) for a pointer movement.
+    pub fn progress_delta(&self, delta: egui::Vec2, modifiers: egui::Modifiers) -> f32 {
+        let travel = match self.axis {
+            DragAxis::Vertical => -delta.y,
+            DragAxis::Horizontal => delta.x,
+        };
+        let travel = if self.inverted { -travel } else { travel };
+        let range = self.range_px.max(10.0) * if self.fine_modifier.held(modifiers) { 10.0 } else { 1.0 };
+        travel / range
+    }
+}
+
+/// How often the mixer polls the card; longer periods save CPU and battery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RefreshConfig {
+    /// Live value poll when ALSA events are unavailable.
+    pub auto_refresh_ms: u64,
+    /// Live value poll alongside ALSA events, in case one is missed.
+    pub event_fallback_ms: u64,
+    /// Reload of the whole control list.
+    pub full_refresh_secs: u64,
+}
+
+impl Default for RefreshConfig {
+    fn default() -> Self {
+        Self {
+            auto_refresh_ms: 220,
+            event_fallback_ms: 500,
+            full_refresh_secs: 10,
+        }
+    }
+}
+
+impl RefreshConfig {
+    pub const BATTERY_SAVER: RefreshConfig = RefreshConfig {
+        auto_refresh_ms: 1000,
+        event_fallback_ms: 3000,
+        full_refresh_secs: 60,
+    };
+
+    pub fn auto_refresh(&self) -> Duration {
+        Duration::from_millis(self.auto_refresh_ms.max(50))
+    }
+
+    pub fn event_fallback(&self) -> Duration {
+        Duration::from_millis(self.event_fallback_ms.max(50))
+    }
+
+    pub fn full_refresh(&self) -> Duration {
+        Duration::from_secs(self.full_refresh_secs.max(1))
+    }
+}
+
+/// Momentary routing of a microphone input to the cue outputs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TalkbackConfig {
+    /// Analog input carrying the talkback mic.
+    #[serde(default)]
+    pub input: Option<usize>,
+    #[serde(default)]
+    pub cue_outputs: BTreeSet<usize>,
+    #[serde(default)]
+    pub level_db: f64,
+    /// Lower the other routes to the cue outputs by `dim_db` while talking.
+    #[serde(default = "default_true")]
+    pub dim_others: bool,
+}
+
+impl Default for TalkbackConfig {
+    fn default() -> Self {
+        Self {
+            input: None,
+            cue_outputs: BTreeSet::new(),
+            level_db: 0.0,
+            dim_others: true,
+        }
+    }
+}
+
+/// Bounds of the FX "Surprise me" randomizer; percentages are of each control's range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FxRandomConfig {
+    /// Program values it may pick; empty means any.
+    pub programs: BTreeSet<i64>,
+    pub duration_min_pct: f64,
+    pub duration_max_pct: f64,
+    pub feedback_min_pct: f64,
+    pub feedback_max_pct: f64,
+    /// FX sends and returns above this level are brought down to it.
+    pub level_cap_db: f64,
+}
+
+impl Default for FxRandomConfig {
+    fn default() -> Self {
+        Self {
+            programs: BTreeSet::new(),
+            duration_min_pct: 10.0,
+            duration_max_pct: 70.0,
+            feedback_min_pct: 0.0,
+            feedback_max_pct: 60.0,
+            level_cap_db: -6.0,
+        }
+    }
+}
+
+/// A route under an app-level master: its amplitude at 0 dB and the raw
+/// value the master last wrote, so a route pushed to the bottom or into a
+/// limit comes back when the master does.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MasterBase {
+    pub amplitude: f64,
+    pub raw: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppUserConfig {
     pub schema_version: u32,
     pub ain_aliases: HashMap<usize, String>,
     pub din_aliases: HashMap<usize, String>,
     pub out_aliases: HashMap<usize, String>,
+    /// Card picked at startup when `--card` is not given, matched against the
+    /// card name; the FTU name heuristic applies when unset or absent.
+    #[serde(default)]
+    pub default_card: Option<String>,
+    #[serde(default)]
+    pub preset_library_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub preset_author: Option<String>,
+    #[serde(default)]
+    pub preset_card_check: PresetCardCheck,
+    #[serde(default)]
+    pub hot_folder_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub app_triggers: Vec<AppTrigger>,
+    #[serde(default)]
+    pub app_trigger_fallback: Option<PathBuf>,
+    /// `host:port` of remote surfaces that receive control changes over OSC.
+    #[serde(default)]
+    pub osc_clients: Vec<String>,
+    #[serde(default)]
+    pub http_api: HttpApiConfig,
+    /// HTTP API token of the `--headless` instance that `--remote` connects to.
+    #[serde(default)]
+    pub remote_token: Option<String>,
+    /// Accept commands on the local Unix socket (`ftu-rust-mixer get|set|apply…`).
+    #[serde(default = "default_true")]
+    pub ipc_socket: bool,
+    #[serde(default)]
+    pub midi_presets: MidiPresetConfig,
+    #[serde(default)]
+    pub gamepad: GamepadConfig,
+    #[serde(default)]
+    pub mqtt: MqttConfig,
+    #[serde(default)]
+    pub metering: MeteringConfig,
+    #[serde(default)]
+    pub generator: GeneratorConfig,
+    /// Register panic mute, talkback and snapshot recall as system-wide shortcuts.
+    #[serde(default)]
+    pub global_hotkeys: bool,
+    /// Shell commands run on mixer events, with the context in `FTU_*` variables.
+    #[serde(default)]
+    pub event_hooks: Vec<EventHook>,
+    /// Rhai script with event hooks; `~/.ftu-mixer/script.rhai` is used when unset.
+    #[serde(default)]
+    pub script_path: Option<PathBuf>,
+    /// Where "Record 10 s" puts its WAV files; `~/.ftu-mixer/scratch` when unset.
+    #[serde(default)]
+    pub scratch_dir: Option<PathBuf>,
+    /// Stereo-linked pairs, by pair index (0 = channels 1+2).
+    #[serde(default)]
+    pub linked_ain_pairs: BTreeSet<usize>,
+    #[serde(default)]
+    pub linked_din_pairs: BTreeSet<usize>,
+    #[serde(default)]
+    pub linked_out_pairs: BTreeSet<usize>,
+    /// Linked pairs of per-output FX returns.
+    #[serde(default)]
+    pub linked_fx_return_pairs: BTreeSet<usize>,
+    #[serde(default)]
+    pub merge_linked_cells: bool,
+    /// Show outputs as rows in the monitoring / digital matrix.
+    #[serde(default)]
+    pub transpose_monitoring: bool,
+    #[serde(default)]
+    pub transpose_digital: bool,
+    /// Channels collapsed out of the matrices, 0-based.
+    #[serde(default)]
+    pub hidden_ain: BTreeSet<usize>,
+    #[serde(default)]
+    pub hidden_din: BTreeSet<usize>,
+    #[serde(default)]
+    pub hidden_out: BTreeSet<usize>,
+    /// Single controls taken out of the matrices and the All controls tab.
+    #[serde(default)]
+    pub hidden_controls: BTreeSet<ControlKey>,
+    /// Temporarily bring hidden channels and controls back without forgetting them.
+    #[serde(default)]
+    pub show_hidden: bool,
+    #[serde(default)]
+    pub pan_law: PanLaw,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    #[serde(default)]
+    pub language: Language,
+    /// Scale of the whole UI on top of the monitor's own HiDPI factor.
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+    /// Show only the small strip with output masters, panic mute, talkback
+    /// and the active preset instead of the full mixer.
+    #[serde(default)]
+    pub mini_mode: bool,
+    /// Keep the main window above other windows, e.g. a fullscreen DAW.
+    #[serde(default)]
+    pub always_on_top: bool,
+    #[serde(default)]
+    pub start_minimized: bool,
+    #[serde(default)]
+    pub knob_drag: KnobDragConfig,
+    #[serde(default)]
+    pub refresh: RefreshConfig,
+    /// Levels of muted matrix cells, by control, restored on unmute.
+    #[serde(default)]
+    pub muted_routes: BTreeMap<ControlKey, Vec<String>>,
+    /// Show matrix cells as small numbers instead of knobs.
+    #[serde(default)]
+    pub compact_matrix: bool,
+    #[serde(default)]
+    pub matrix_density: MatrixDensity,
+    /// Cell widget of the monitoring, digital routing and cue mix matrices.
+    #[serde(default)]
+    pub monitoring_widget: CellWidget,
+    #[serde(default)]
+    pub digital_widget: CellWidget,
+    #[serde(default)]
+    pub cue_mix_widget: CellWidget,
+    #[serde(default = "default_matrix_zoom")]
+    pub matrix_zoom: f32,
+    /// App-level master of each output column, in dB; 0 dB when absent.
+    #[serde(default)]
+    pub output_masters_db: BTreeMap<usize, f64>,
+    /// Base levels of the routes under each output master away from 0 dB.
+    #[serde(default)]
+    pub output_master_bases: BTreeMap<usize, BTreeMap<ControlKey, MasterBase>>,
+    /// Highest level any route or FX return may reach on an output, in dB,
+    /// by output; writes above it are clamped.
+    #[serde(default)]
+    pub output_ceilings_db: BTreeMap<usize, f64>,
+    /// Attenuation applied by an output's Dim button.
+    #[serde(default = "default_dim_db")]
+    pub dim_db: f64,
+    /// Route levels saved by each active Dim, by output then control.
+    #[serde(default)]
+    pub dimmed_outputs: BTreeMap<usize, BTreeMap<ControlKey, Vec<String>>>,
+    /// Route levels saved by each active output mute (meter bridge), by
+    /// output then control.
+    #[serde(default)]
+    pub muted_outputs: BTreeMap<usize, BTreeMap<ControlKey, Vec<String>>>,
+    /// Stereo route levels saved by each active Mono, by output pair then control.
+    #[serde(default)]
+    pub mono_out_pairs: BTreeMap<usize, BTreeMap<ControlKey, Vec<String>>>,
+    #[serde(default)]
+    pub talkback: TalkbackConfig,
+    /// FX send and return values saved by an active Bypass, by control.
+    #[serde(default)]
+    pub fx_bypass: Option<BTreeMap<ControlKey, Vec<String>>>,
+    /// App-level wet amount applied to every FX send and return, in dB.
+    #[serde(default)]
+    pub fx_macro_db: f64,
+    /// Base levels of the FX sends and returns while the macro is away from 0 dB.
+    #[serde(default)]
+    pub fx_macro_bases: BTreeMap<ControlKey, MasterBase>,
+    /// Mute the FX returns around a program change so the old tail is cut.
+    #[serde(default)]
+    pub fx_clear_tail: bool,
+    #[serde(default = "default_fx_tail_ms")]
+    pub fx_tail_ms: u64,
+    /// Folded state of the effect processor frame and of its extra controls.
+    #[serde(default)]
+    pub fx_section_collapsed: bool,
+    #[serde(default)]
+    pub fx_other_collapsed: bool,
+    #[serde(default)]
+    pub fx_random: FxRandomConfig,
+    /// Controls left alone by Disable FX, quick actions and presets.
+    #[serde(default)]
+    pub locked_controls: BTreeSet<ControlKey>,
+    /// Free-text notes on controls.
+    #[serde(default)]
+    pub control_notes: BTreeMap<ControlKey, String>,
+    /// Folded `grouped_label` sections of the All controls tab.
+    #[serde(default)]
+    pub collapsed_groups: BTreeSet<String>,
+    /// Controls starred into the Favorites tab.
+    #[serde(default)]
+    pub favorite_controls: BTreeSet<ControlKey>,
+    /// Highest Effect Feedback allowed, in percent of its range; full-scale
+    /// feedback makes the delay self-oscillate.
+    #[serde(default = "default_fx_feedback_cap")]
+    pub fx_feedback_cap_pct: f64,
+    /// FX send control chosen by hand per input, by control name; `None` means
+    /// the input has no send. Inputs absent here use the name heuristic.
+    #[serde(default)]
+    pub ain_fx_sends: BTreeMap<usize, Option<String>>,
+    #[serde(default)]
+    pub din_fx_sends: BTreeMap<usize, Option<String>>,
+    /// Outputs shown in the cue mix tab (0-based; Out3/4 carry the FTU headphones).
+    #[serde(default = "default_cue_mix_outputs")]
+    pub cue_mix_outputs: BTreeSet<usize>,
+}
+
+fn default_matrix_zoom() -> f32 {
+    1.0
+}
+
+fn default_ui_scale() -> f32 {
+    1.0
+}
+
+fn default_dim_db() -> f64 {
+    -20.0
+}
+
+fn default_fx_tail_ms() -> u64 {
+    300
+}
+
+fn default_fx_feedback_cap() -> f64 {
+    80.0
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_cue_mix_outputs() -> BTreeSet<usize> {
+    BTreeSet::from([2, 3])
 }
 
 impl Default for AppUserConfig {
@@ -22,6 +510,78 @@ impl Default for AppUserConfig {
             ain_aliases: HashMap::new(),
             din_aliases: HashMap::new(),
             out_aliases: HashMap::new(),
+            default_card: None,
+            preset_library_dir: None,
+            preset_author: None,
+            preset_card_check: PresetCardCheck::default(),
+            hot_folder_dir: None,
+            app_triggers: Vec::new(),
+            app_trigger_fallback: None,
+            osc_clients: Vec::new(),
+            http_api: HttpApiConfig::default(),
+            remote_token: None,
+            ipc_socket: true,
+            global_hotkeys: false,
+            midi_presets: MidiPresetConfig::default(),
+            gamepad: GamepadConfig::default(),
+            mqtt: MqttConfig::default(),
+            metering: MeteringConfig::default(),
+            generator: GeneratorConfig::default(),
+            event_hooks: Vec::new(),
+            script_path: None,
+            scratch_dir: None,
+            linked_ain_pairs: BTreeSet::new(),
+            linked_din_pairs: BTreeSet::new(),
+            linked_out_pairs: BTreeSet::new(),
+            linked_fx_return_pairs: BTreeSet::new(),
+            merge_linked_cells: false,
+            transpose_monitoring: false,
+            transpose_digital: false,
+            hidden_ain: BTreeSet::new(),
+            hidden_din: BTreeSet::new(),
+            hidden_out: BTreeSet::new(),
+            show_hidden: false,
+            pan_law: PanLaw::default(),
+            theme: ThemeConfig::default(),
+            language: Language::default(),
+            ui_scale: default_ui_scale(),
+            mini_mode: false,
+            always_on_top: false,
+            start_minimized: false,
+            knob_drag: KnobDragConfig::default(),
+            refresh: RefreshConfig::default(),
+            muted_routes: BTreeMap::new(),
+            compact_matrix: false,
+            matrix_density: MatrixDensity::default(),
+            monitoring_widget: CellWidget::default(),
+            digital_widget: CellWidget::default(),
+            cue_mix_widget: CellWidget::default(),
+            matrix_zoom: default_matrix_zoom(),
+            output_masters_db: BTreeMap::new(),
+            output_master_bases: BTreeMap::new(),
+            output_ceilings_db: BTreeMap::new(),
+            dim_db: default_dim_db(),
+            dimmed_outputs: BTreeMap::new(),
+            muted_outputs: BTreeMap::new(),
+            mono_out_pairs: BTreeMap::new(),
+            talkback: TalkbackConfig::default(),
+            fx_bypass: None,
+            fx_macro_db: 0.0,
+            fx_macro_bases: BTreeMap::new(),
+            fx_clear_tail: false,
+            fx_tail_ms: default_fx_tail_ms(),
+            fx_random: FxRandomConfig::default(),
+            locked_controls: BTreeSet::new(),
+            favorite_controls: BTreeSet::new(),
+            collapsed_groups: BTreeSet::new(),
+            control_notes: BTreeMap::new(),
+            hidden_controls: BTreeSet::new(),
+            fx_feedback_cap_pct: default_fx_feedback_cap(),
+            fx_section_collapsed: false,
+            fx_other_collapsed: false,
+            ain_fx_sends: BTreeMap::new(),
+            din_fx_sends: BTreeMap::new(),
+            cue_mix_outputs: default_cue_mix_outputs(),
         }
     }
 }
@@ -47,7 +607,17 @@ impl AppUserConfig {
         fs::create_dir_all(dir)
             .with_context(|| format!("Failed to create config dir {}", dir.display()))?;
         let text = serde_json::to_string_pretty(self)?;
-        fs::write(&path, text)
+        // Holds the HTTP API token and the MQTT password: owner-only.
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&path)
+            .with_context(|| format!("Failed to write config file {}", path.display()))?;
+        file.set_permissions(fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to restrict config file {}", path.display()))?;
+        file.write_all(text.as_bytes())
             .with_context(|| format!("Failed to write config file {}", path.display()))?;
         Ok(())
     }
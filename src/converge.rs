@@ -0,0 +1,366 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    alsa_backend::AlsaBackend,
+    ceilings,
+    config::AppUserConfig,
+    fx::FxModel,
+    models::{ControlDescriptor, ControlKind},
+    templates::{FTU_FX_PROGRAMS, Level},
+};
+
+/// Desired mixer state, as written by hand or by Ansible.
+///
+/// ```yaml
+/// tolerance_db: 0.5
+/// mute_unlisted: true
+/// routes:
+///   - { input: AIn1, output: Out1, level: -6 }
+///   - { input: Vocal, output: Monitors L, level: unity }
+/// fx: { program: Hall 1, volume: -10, duration: 1500, feedback: 30 }
+/// controls:
+///   "Digital Playback Switch": on
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DesiredState {
+    /// Route levels this close to the target count as converged.
+    pub tolerance_db: Option<f64>,
+    /// Mute every route not listed under `routes`.
+    pub mute_unlisted: bool,
+    pub routes: Vec<DesiredRoute>,
+    pub fx: DesiredFx,
+    /// Raw values by ALSA control name: one value for every channel, or a list.
+    pub controls: BTreeMap<String, RawValues>,
+}
+
+/// `input`/`output` are `AIn3`, `DIn1`, `Out2` or their aliases.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredRoute {
+    pub input: String,
+    pub output: String,
+    pub level: LevelSpec,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DesiredFx {
+    /// Program name ("Hall 1") or index.
+    pub program: Option<RawValue>,
+    pub volume: Option<LevelSpec>,
+    /// Raw Effect Duration (milliseconds on the Fast Track Ultra).
+    pub duration: Option<i64>,
+    /// Percent of the Feedback range.
+    pub feedback: Option<f64>,
+}
+
+/// A dB value, or `unity` / `off`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum LevelSpec {
+    Db(f64),
+    Word(String),
+}
+
+impl LevelSpec {
+    fn level(&self) -> Result<Level> {
+        match self {
+            LevelSpec::Db(db) => Ok(Level::Db(*db)),
+            LevelSpec::Word(word) => match word.trim().to_ascii_lowercase().as_str() {
+                "unity" | "0db" => Ok(Level::Unity),
+                "off" | "-inf" | "mute" => Ok(Level::Off),
+                other => anyhow::bail!("Unknown level \"{other}\" (use dB, unity or off)"),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum RawValue {
+    Number(i64),
+    Bool(bool),
+    Text(String),
+}
+
+impl RawValue {
+    fn text(&self) -> String {
+        match self {
+            RawValue::Number(n) => n.to_string(),
+            RawValue::Bool(b) => if *b { "on" } else { "off" }.to_string(),
+            RawValue::Text(t) => t.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum RawValues {
+    List(Vec<RawValue>),
+    One(RawValue),
+}
+
+/// One control that differs from the desired state.
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    /// Route, FX role or control name, for the report.
+    pub target: String,
+    pub numid: u32,
+    pub from: String,
+    pub to: String,
+    #[serde(skip)]
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Plan {
+    pub changes: Vec<Change>,
+    /// Controls already in the desired state.
+    pub ok: usize,
+    /// Raw ceilings the backend clamps writes to, by numid; targets are
+    /// clamped the same way so a capped control does not show as changed.
+    #[serde(skip)]
+    ceilings: HashMap<u32, i64>,
+}
+
+pub fn load(path: &Path) -> Result<DesiredState> {
+    let text = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_yaml::from_str(&text).with_context(|| format!("Invalid desired state in {}", path.display()))
+}
+
+/// Diffs the desired state against the live controls. Every name must
+/// resolve, so a typo fails the run before anything is written.
+pub fn plan(state: &DesiredState, controls: &[ControlDescriptor], config: &AppUserConfig) -> Result<Plan> {
+    let tolerance = state.tolerance_db.unwrap_or(0.5).max(0.0);
+    let routing = AlsaBackend::build_routing_index(controls);
+    let mut plan = Plan {
+        ceilings: ceilings::value_ceilings(controls, config)
+            .into_iter()
+            .filter_map(|(idx, ceiling)| Some((controls[idx].numid, ceiling?)))
+            .collect(),
+        ..Default::default()
+    };
+    let mut listed = HashSet::new();
+
+    for route in &state.routes {
+        let (bus, input) = resolve_input(&route.input, config)?;
+        let output = resolve_output(&route.output, config)?;
+        let routes = if bus == "AIn" { &routing.analog_routes } else { &routing.digital_routes };
+        let control_index = routes
+            .iter()
+            .find(|r| r.input == input && r.output == output)
+            .map(|r| r.control_index)
+            .with_context(|| format!("This card has no {} → {} route", route.input, route.output))?;
+        listed.insert(control_index);
+        let target = format!("{bus}{} → Out{}", input + 1, output + 1);
+        let level = route.level.level().with_context(|| target.clone())?;
+        plan.level(&target, &controls[control_index], level, tolerance)?;
+    }
+    if state.mute_unlisted {
+        for route in routing.analog_routes.iter().chain(&routing.digital_routes) {
+            if listed.contains(&route.control_index) {
+                continue;
+            }
+            let control = &controls[route.control_index];
+            plan.level(&control.name, control, Level::Off, tolerance)?;
+        }
+    }
+
+    let fx = FxModel::from_controls(controls);
+    let fx_control = |role: Option<usize>, name: &str| {
+        role.map(|idx| &controls[idx])
+            .with_context(|| format!("This card has no FX {name} control"))
+    };
+    if let Some(program) = &state.fx.program {
+        let control = fx_control(fx.program, "program")?;
+        let index = match program {
+            RawValue::Number(n) => *n,
+            other => {
+                let name = other.text();
+                FTU_FX_PROGRAMS
+                    .iter()
+                    .position(|p| p.eq_ignore_ascii_case(name.trim()))
+                    .with_context(|| format!("Unknown FX program \"{name}\" ({})", FTU_FX_PROGRAMS.join(", ")))?
+                    as i64
+            }
+        };
+        plan.raw("FX program", control, index.to_string());
+    }
+    if let Some(volume) = &state.fx.volume {
+        let control = fx_control(fx.volume, "volume")?;
+        plan.level("FX volume", control, volume.level().context("FX volume")?, tolerance)?;
+    }
+    if let Some(duration) = state.fx.duration {
+        let control = fx_control(fx.duration, "duration")?;
+        plan.raw("FX duration", control, duration.to_string());
+    }
+    if let Some(percent) = state.fx.feedback {
+        let control = fx_control(fx.feedback, "feedback")?;
+        let ControlKind::Integer { min, max, .. } = control.kind else {
+            anyhow::bail!("{} is not a level control", control.name);
+        };
+        let raw = min as f64 + percent.clamp(0.0, 100.0) / 100.0 * (max - min) as f64;
+        plan.raw("FX feedback", control, (raw.round() as i64).to_string());
+    }
+
+    let by_name: HashMap<&str, &ControlDescriptor> = controls.iter().map(|c| (c.name.as_str(), c)).collect();
+    for (name, values) in &state.controls {
+        let control = by_name
+            .get(name.as_str())
+            .with_context(|| format!("This card has no control named \"{name}\""))?;
+        let values: Vec<String> = match values {
+            RawValues::List(list) => list.iter().map(RawValue::text).collect(),
+            RawValues::One(value) => vec![value.text(); control.values.len().max(1)],
+        };
+        plan.values(name, control, values);
+    }
+    Ok(plan)
+}
+
+impl Plan {
+    fn level(&mut self, target: &str, control: &ControlDescriptor, level: Level, tolerance: f64) -> Result<()> {
+        let ControlKind::Integer { min, .. } = control.kind else {
+            anyhow::bail!("{} is not a level control", control.name);
+        };
+        let raw = level
+            .raw(&control.kind)
+            .with_context(|| format!("{} has no dB scale; use unity or off", control.name))?;
+        let raw = self.ceilings.get(&control.numid).map_or(raw, |&ceiling| raw.min(ceiling));
+        let db = |raw: i64| control.kind.db_at_raw(raw);
+        let label = |raw: i64| match db(raw) {
+            _ if raw <= min => "off".to_string(),
+            Some(db) => format!("{db:.1} dB"),
+            None => raw.to_string(),
+        };
+        let current: Vec<i64> = control.values.iter().map(|v| v.parse().unwrap_or(min)).collect();
+        let converged = current.iter().all(|&value| match (level, db(value), db(raw)) {
+            (Level::Off, ..) => value <= min,
+            (_, Some(now), Some(want)) => value > min && (now - want).abs() <= tolerance,
+            _ => value == raw,
+        });
+        if converged {
+            self.ok += 1;
+            return Ok(());
+        }
+        self.changes.push(Change {
+            target: target.to_string(),
+            numid: control.numid,
+            from: current.iter().map(|&v| label(v)).collect::<Vec<_>>().join(", "),
+            to: label(raw),
+            values: vec![raw.to_string(); control.values.len().max(1)],
+        });
+        Ok(())
+    }
+
+    fn raw(&mut self, target: &str, control: &ControlDescriptor, value: String) {
+        let values = vec![value; control.values.len().max(1)];
+        self.values(target, control, values);
+    }
+
+    fn values(&mut self, target: &str, control: &ControlDescriptor, values: Vec<String>) {
+        let values: Vec<String> = match self.ceilings.get(&control.numid) {
+            Some(&ceiling) => values
+                .into_iter()
+                .map(|v| match v.trim().parse::<i64>() {
+                    Ok(raw) if raw > ceiling => ceiling.to_string(),
+                    _ => v,
+                })
+                .collect(),
+            None => values,
+        };
+        let same = |a: &str, b: &str| normalize(a) == normalize(b);
+        if control.values.len() == values.len() && control.values.iter().zip(&values).all(|(a, b)| same(a, b)) {
+            self.ok += 1;
+            return;
+        }
+        self.changes.push(Change {
+            target: target.to_string(),
+            numid: control.numid,
+            from: control.values.join(", "),
+            to: values.join(", "),
+            values,
+        });
+    }
+}
+
+/// Switch values are compared as on/off whatever their spelling.
+fn normalize(value: &str) -> String {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "on" | "true" | "yes" => "on".to_string(),
+        "off" | "false" | "no" => "off".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// `AIn3`, `DIn1` or an input alias → bus prefix and 0-based channel.
+fn resolve_input(name: &str, config: &AppUserConfig) -> Result<(&'static str, usize)> {
+    for (bus, aliases) in [("AIn", &config.ain_aliases), ("DIn", &config.din_aliases)] {
+        if let Some(n) = channel_number(name, bus) {
+            return Ok((bus, n));
+        }
+        if let Some((&idx, _)) = aliases.iter().find(|(_, alias)| alias.eq_ignore_ascii_case(name.trim())) {
+            return Ok((bus, idx));
+        }
+    }
+    anyhow::bail!("Unknown input \"{name}\" (AInN, DInN or an alias)")
+}
+
+fn resolve_output(name: &str, config: &AppUserConfig) -> Result<usize> {
+    channel_number(name, "Out")
+        .or_else(|| {
+            config
+                .out_aliases
+                .iter()
+                .find(|(_, alias)| alias.eq_ignore_ascii_case(name.trim()))
+                .map(|(&idx, _)| idx)
+        })
+        .with_context(|| format!("Unknown output \"{name}\" (OutN or an alias)"))
+}
+
+/// 0-based channel of `AIn3`-style names, case-insensitive.
+fn channel_number(name: &str, prefix: &str) -> Option<usize> {
+    let name = name.trim();
+    let rest = name.get(..prefix.len())?.eq_ignore_ascii_case(prefix).then(|| &name[prefix.len()..])?;
+    rest.trim().parse::<usize>().ok()?.checked_sub(1)
+}
+
+/// Applies the plan unless `check` is set, then prints what changed, as
+/// text or as one JSON object for `changed_when` in Ansible.
+pub fn run(path: &Path, backend: &AlsaBackend, check: bool, json: bool) -> Result<()> {
+    let state = load(path)?;
+    let config = AppUserConfig::load_or_default()?;
+    let controls = backend.list_controls()?;
+    ceilings::install(backend, &controls, &config);
+    let plan = plan(&state, &controls, &config)?;
+    if !check {
+        for change in &plan.changes {
+            backend
+                .apply_values(change.numid, &change.values)
+                .with_context(|| format!("Failed to set {}", change.target))?;
+        }
+    }
+    if json {
+        let report = serde_json::json!({
+            "changed": !plan.changes.is_empty(),
+            "check": check,
+            "changes": plan.changes,
+            "ok": plan.ok,
+        });
+        println!("{report}");
+        return Ok(());
+    }
+    let verb = if check { "would change" } else { "changed" };
+    for change in &plan.changes {
+        println!("{verb}: {}: {} → {}", change.target, change.from, change.to);
+    }
+    println!("{} {verb}, {} ok", plan.changes.len(), plan.ok);
+    Ok(())
+}
@@ -0,0 +1,354 @@
+use std::{fs, path::Path};
+
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+use anyhow::{Context, Result};
+use image::{Rgba, RgbaImage};
+
+const MARGIN: f32 = 16.0;
+const TITLE_H: f32 = 34.0;
+const GRID_TITLE_H: f32 = 26.0;
+const HEADER_H: f32 = 26.0;
+const LABEL_W: f32 = 150.0;
+const CELL_W: f32 = 72.0;
+const CELL_H: f32 = 26.0;
+const GRID_GAP: f32 = 22.0;
+
+const BACKGROUND: [u8; 3] = [255, 255, 255];
+const HEADER_FILL: [u8; 3] = [232, 236, 241];
+const EMPTY_FILL: [u8; 3] = [246, 247, 249];
+const SILENT_FILL: [u8; 3] = [238, 238, 238];
+const LIVE_FILL: [u8; 3] = [74, 170, 110];
+const GRID_LINE: [u8; 3] = [200, 205, 212];
+const TEXT: [u8; 3] = [30, 34, 40];
+const MUTED_TEXT: [u8; 3] = [150, 150, 150];
+
+/// One route of a diagram grid: its level as text and its amplitude (0..=1)
+/// for the cell shading.
+#[derive(Debug, Clone)]
+pub struct DiagramCell {
+    pub text: String,
+    pub level: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiagramRow {
+    pub label: String,
+    pub cells: Vec<Option<DiagramCell>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiagramGrid {
+    pub title: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<DiagramRow>,
+}
+
+/// Routing matrices laid out for export, independent of the egui widgets.
+#[derive(Debug, Clone)]
+pub struct Diagram {
+    pub title: String,
+    pub grids: Vec<DiagramGrid>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Anchor {
+    Start,
+    Middle,
+}
+
+enum Shape {
+    Rect {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        fill: [u8; 3],
+    },
+    /// `y` is the text baseline.
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        bold: bool,
+        anchor: Anchor,
+        color: [u8; 3],
+        text: String,
+    },
+}
+
+impl Diagram {
+    /// Writes an SVG or PNG file depending on the extension of `path`.
+    /// `font` is only used for PNG output.
+    pub fn save(&self, path: &Path, font: &[u8]) -> Result<()> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match ext.as_str() {
+            "png" => self
+                .to_png(font)?
+                .save(path)
+                .with_context(|| format!("Failed to write {}", path.display())),
+            "svg" => fs::write(path, self.to_svg())
+                .with_context(|| format!("Failed to write {}", path.display())),
+            _ => anyhow::bail!("Unsupported diagram format \"{ext}\" (use .svg or .png)"),
+        }
+    }
+
+    pub fn to_svg(&self) -> String {
+        let (shapes, width, height) = self.layout();
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\" font-family=\"sans-serif\">\n"
+        );
+        for shape in shapes {
+            match shape {
+                Shape::Rect { x, y, w, h, fill } => out.push_str(&format!(
+                    "  <rect x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" fill=\"{}\" \
+                     stroke=\"{}\" stroke-width=\"0.5\"/>\n",
+                    svg_color(fill),
+                    svg_color(GRID_LINE)
+                )),
+                Shape::Text {
+                    x,
+                    y,
+                    size,
+                    bold,
+                    anchor,
+                    color,
+                    text,
+                } => out.push_str(&format!(
+                    "  <text x=\"{x}\" y=\"{y}\" font-size=\"{size}\"{}{} fill=\"{}\">{}</text>\n",
+                    if bold { " font-weight=\"bold\"" } else { "" },
+                    match anchor {
+                        Anchor::Start => "",
+                        Anchor::Middle => " text-anchor=\"middle\"",
+                    },
+                    svg_color(color),
+                    xml_escape(&text)
+                )),
+            }
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    pub fn to_png(&self, font: &[u8]) -> Result<RgbaImage> {
+        let font = FontRef::try_from_slice(font).context("Invalid diagram font")?;
+        let (shapes, width, height) = self.layout();
+        let mut img = RgbaImage::from_pixel(
+            width.ceil() as u32,
+            height.ceil() as u32,
+            rgba(BACKGROUND),
+        );
+        for shape in shapes {
+            match shape {
+                Shape::Rect { x, y, w, h, fill } => {
+                    fill_rect(&mut img, x, y, w, h, GRID_LINE);
+                    fill_rect(&mut img, x + 0.5, y + 0.5, w - 1.0, h - 1.0, fill);
+                }
+                Shape::Text {
+                    x,
+                    y,
+                    size,
+                    bold,
+                    anchor,
+                    color,
+                    text,
+                } => {
+                    // The bundled font has no bold face; a 1px double strike stands in for it.
+                    for dx in if bold { &[0.0, 0.6][..] } else { &[0.0][..] } {
+                        draw_text(&mut img, &font, x + dx, y, size, anchor, color, &text);
+                    }
+                }
+            }
+        }
+        Ok(img)
+    }
+
+    fn layout(&self) -> (Vec<Shape>, f32, f32) {
+        let mut shapes = Vec::new();
+        let mut width: f32 = 2.0 * MARGIN + LABEL_W;
+        let mut y = MARGIN;
+        shapes.push(Shape::Text {
+            x: MARGIN,
+            y: y + 20.0,
+            size: 18.0,
+            bold: true,
+            anchor: Anchor::Start,
+            color: TEXT,
+            text: self.title.clone(),
+        });
+        y += TITLE_H;
+
+        for grid in &self.grids {
+            shapes.push(Shape::Text {
+                x: MARGIN,
+                y: y + 17.0,
+                size: 14.0,
+                bold: true,
+                anchor: Anchor::Start,
+                color: TEXT,
+                text: grid.title.clone(),
+            });
+            y += GRID_TITLE_H;
+
+            shapes.push(Shape::Rect {
+                x: MARGIN,
+                y,
+                w: LABEL_W,
+                h: HEADER_H,
+                fill: HEADER_FILL,
+            });
+            for (col, label) in grid.columns.iter().enumerate() {
+                let x = MARGIN + LABEL_W + col as f32 * CELL_W;
+                shapes.push(Shape::Rect {
+                    x,
+                    y,
+                    w: CELL_W,
+                    h: HEADER_H,
+                    fill: HEADER_FILL,
+                });
+                shapes.push(Shape::Text {
+                    x: x + CELL_W / 2.0,
+                    y: y + 17.0,
+                    size: 11.0,
+                    bold: true,
+                    anchor: Anchor::Middle,
+                    color: TEXT,
+                    text: label.clone(),
+                });
+            }
+            width = width.max(2.0 * MARGIN + LABEL_W + grid.columns.len() as f32 * CELL_W);
+            y += HEADER_H;
+
+            for row in &grid.rows {
+                shapes.push(Shape::Rect {
+                    x: MARGIN,
+                    y,
+                    w: LABEL_W,
+                    h: CELL_H,
+                    fill: HEADER_FILL,
+                });
+                shapes.push(Shape::Text {
+                    x: MARGIN + 6.0,
+                    y: y + 17.0,
+                    size: 11.0,
+                    bold: true,
+                    anchor: Anchor::Start,
+                    color: TEXT,
+                    text: row.label.clone(),
+                });
+                for (col, cell) in row.cells.iter().enumerate() {
+                    let x = MARGIN + LABEL_W + col as f32 * CELL_W;
+                    let fill = match cell {
+                        None => EMPTY_FILL,
+                        Some(c) if c.level <= 0.0 => SILENT_FILL,
+                        Some(c) => mix(SILENT_FILL, LIVE_FILL, c.level.clamp(0.0, 1.0).sqrt()),
+                    };
+                    shapes.push(Shape::Rect {
+                        x,
+                        y,
+                        w: CELL_W,
+                        h: CELL_H,
+                        fill,
+                    });
+                    if let Some(cell) = cell {
+                        shapes.push(Shape::Text {
+                            x: x + CELL_W / 2.0,
+                            y: y + 17.0,
+                            size: 11.0,
+                            bold: false,
+                            anchor: Anchor::Middle,
+                            color: if cell.level > 0.0 { TEXT } else { MUTED_TEXT },
+                            text: cell.text.clone(),
+                        });
+                    }
+                }
+                y += CELL_H;
+            }
+            y += GRID_GAP;
+        }
+        (shapes, width, y - GRID_GAP + MARGIN)
+    }
+}
+
+fn svg_color([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn rgba([r, g, b]: [u8; 3]) -> Rgba<u8> {
+    Rgba([r, g, b, 255])
+}
+
+fn mix(a: [u8; 3], b: [u8; 3], t: f32) -> [u8; 3] {
+    std::array::from_fn(|i| (a[i] as f32 + (b[i] as f32 - a[i] as f32) * t).round() as u8)
+}
+
+fn fill_rect(img: &mut RgbaImage, x: f32, y: f32, w: f32, h: f32, color: [u8; 3]) {
+    let x0 = x.round().max(0.0) as u32;
+    let y0 = y.round().max(0.0) as u32;
+    let x1 = ((x + w).round() as u32).min(img.width());
+    let y1 = ((y + h).round() as u32).min(img.height());
+    for py in y0..y1 {
+        for px in x0..x1 {
+            img.put_pixel(px, py, rgba(color));
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_text(
+    img: &mut RgbaImage,
+    font: &FontRef,
+    x: f32,
+    baseline: f32,
+    size: f32,
+    anchor: Anchor,
+    color: [u8; 3],
+    text: &str,
+) {
+    let scale = font.pt_to_px_scale(size).unwrap_or(PxScale::from(size));
+    let scaled = font.as_scaled(scale);
+    let mut glyphs = Vec::new();
+    let mut caret = 0.0;
+    let mut previous = None;
+    for ch in text.chars() {
+        let id = scaled.glyph_id(ch);
+        if let Some(prev) = previous {
+            caret += scaled.kern(prev, id);
+        }
+        glyphs.push((id, caret));
+        caret += scaled.h_advance(id);
+        previous = Some(id);
+    }
+    let start = match anchor {
+        Anchor::Start => x,
+        Anchor::Middle => x - caret / 2.0,
+    };
+    for (id, offset) in glyphs {
+        let glyph = id.with_scale_and_position(scaled.scale(), ab_glyph::point(start + offset, baseline));
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i64 + gx as i64;
+            let py = bounds.min.y as i64 + gy as i64;
+            if px < 0 || py < 0 || px >= img.width() as i64 || py >= img.height() as i64 {
+                return;
+            }
+            let pixel = img.get_pixel_mut(px as u32, py as u32);
+            let under = [pixel[0], pixel[1], pixel[2]];
+            *pixel = rgba(mix(under, color, coverage.clamp(0.0, 1.0)));
+        });
+    }
+}
@@ -0,0 +1,64 @@
+use std::{
+    process::{Command, Stdio},
+    thread,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MixerEvent {
+    PresetApplied,
+    DeviceConnected,
+    DeviceLost,
+    PanicMute,
+}
+
+impl MixerEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            MixerEvent::PresetApplied => "preset-applied",
+            MixerEvent::DeviceConnected => "device-connected",
+            MixerEvent::DeviceLost => "device-lost",
+            MixerEvent::PanicMute => "panic-mute",
+        }
+    }
+}
+
+/// Shell command run (through `sh -c`) whenever `event` happens.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventHook {
+    pub event: MixerEvent,
+    pub command: String,
+}
+
+/// Starts the hooks registered for `event` without waiting for them. They get
+/// `FTU_EVENT` plus `env` (e.g. `FTU_CARD`, `FTU_PRESET`) in their environment.
+/// A hook that fails to start does not prevent the others; the first error is returned.
+pub fn run_hooks(hooks: &[EventHook], event: MixerEvent, env: &[(&str, String)]) -> Result<()> {
+    let mut failure = None;
+    for hook in hooks.iter().filter(|h| h.event == event && !h.command.trim().is_empty()) {
+        let spawned = Command::new("sh")
+            .arg("-c")
+            .arg(&hook.command)
+            .env("FTU_EVENT", event.name())
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .stdin(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to run {} hook \"{}\"", event.name(), hook.command));
+        match spawned {
+            // Reap it in the background so slow hooks never hold up the UI.
+            Ok(mut child) => {
+                thread::spawn(move || child.wait());
+            }
+            Err(err) => {
+                failure.get_or_insert(err);
+            }
+        }
+    }
+    match failure {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
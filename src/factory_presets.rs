@@ -0,0 +1,128 @@
+use crate::{
+    models::{
+        CardIdentity, ControlDescriptor, ControlKind, PresetControlValue, PresetFile,
+        PresetMetadata, RoutingIndex,
+    },
+    templates::{Bus, Channels, Level, Outputs, PresetTemplate, RouteRule, TemplateParams},
+};
+
+/// Built-in starting points. They are generated against the current routing
+/// index rather than stored as JSON, because numids differ between the FTU and
+/// the FTU 8R.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FactoryPreset {
+    DirectMonitoring,
+    PlaybackOnly,
+    AllMuted,
+    FxOffTracking,
+}
+
+impl FactoryPreset {
+    pub const ALL: [FactoryPreset; 4] = [
+        FactoryPreset::DirectMonitoring,
+        FactoryPreset::PlaybackOnly,
+        FactoryPreset::AllMuted,
+        FactoryPreset::FxOffTracking,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FactoryPreset::DirectMonitoring => "Direct monitoring 1:1",
+            FactoryPreset::PlaybackOnly => "Playback only",
+            FactoryPreset::AllMuted => "All muted",
+            FactoryPreset::FxOffTracking => "Tracking (FX off)",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            FactoryPreset::DirectMonitoring => {
+                "AInN -> OutN and DInN -> OutN at unity, every other route muted."
+            }
+            FactoryPreset::PlaybackOnly => {
+                "DInN -> OutN at unity, analog monitoring muted."
+            }
+            FactoryPreset::AllMuted => "Every analog and digital route muted.",
+            FactoryPreset::FxOffTracking => {
+                "Direct monitoring 1:1 with every FX control at zero."
+            }
+        }
+    }
+
+    /// The routing part of the preset. The built-in templates reuse these, so
+    /// the matrix rules live in one place.
+    pub fn template(self) -> PresetTemplate {
+        let one_to_one = |bus| RouteRule {
+            bus,
+            inputs: Channels::All,
+            outputs: Outputs::Same,
+            level: Level::Unity,
+        };
+        let rules = match self {
+            FactoryPreset::DirectMonitoring | FactoryPreset::FxOffTracking => {
+                vec![one_to_one(Bus::Analog), one_to_one(Bus::Digital)]
+            }
+            FactoryPreset::PlaybackOnly => vec![one_to_one(Bus::Digital)],
+            FactoryPreset::AllMuted => Vec::new(),
+        };
+        PresetTemplate {
+            name: self.name().to_string(),
+            description: self.description().to_string(),
+            min_inputs: 1,
+            min_outputs: 1,
+            mute_unmatched: true,
+            rules,
+        }
+    }
+
+    pub fn build(
+        self,
+        card_name: &str,
+        card_identity: &CardIdentity,
+        controls: &[ControlDescriptor],
+        routing: &RoutingIndex,
+    ) -> PresetFile {
+        let full = TemplateParams {
+            inputs: usize::MAX,
+            outputs: usize::MAX,
+        };
+        let template = self.template();
+        let mut preset = template.instantiate(full, card_name, card_identity, controls, routing);
+
+        if self == FactoryPreset::FxOffTracking {
+            for control in controls {
+                let n = control.name.to_lowercase();
+                if !(n.contains("fx") || n.contains("effect")) {
+                    continue;
+                }
+                if preset.controls.iter().any(|c| c.numid == control.numid) {
+                    continue;
+                }
+                let values = match &control.kind {
+                    ControlKind::Integer { channels, .. } => vec!["0".to_string(); *channels],
+                    ControlKind::Boolean { channels } => vec!["off".to_string(); *channels],
+                    _ => continue,
+                };
+                preset.controls.push(PresetControlValue {
+                    numid: control.numid,
+                    values,
+                });
+            }
+            preset.controls.sort_by_key(|c| {
+                controls
+                    .iter()
+                    .position(|control| control.numid == c.numid)
+                    .unwrap_or(usize::MAX)
+            });
+        }
+
+        preset.metadata = PresetMetadata {
+            name: Some(self.name().to_string()),
+            description: Some(self.description().to_string()),
+            author: Some("Factory".to_string()),
+            tags: vec!["factory".to_string()],
+            created: None,
+        };
+        preset
+    }
+}
@@ -0,0 +1,31 @@
+/// Fuzzy match of `query` against `text`, case-insensitive. Every
+/// whitespace-separated term must appear in order as a subsequence; runs of
+/// consecutive characters and matches at word starts score higher. `None`
+/// when a term does not match; an empty query matches everything with 0.
+pub fn score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut total = 0;
+    for term in query.split_whitespace() {
+        total += term_score(&term.to_lowercase(), &text)?;
+    }
+    Some(total)
+}
+
+fn term_score(term: &str, text: &[char]) -> Option<u32> {
+    let mut score = 0;
+    let mut pos = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in term.chars() {
+        let found = pos + text[pos..].iter().position(|c| *c == wanted)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 4;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        pos = found + 1;
+    }
+    Some(score)
+}
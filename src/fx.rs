@@ -0,0 +1,211 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::AppUserConfig,
+    i18n::tr,
+    models::{ControlDescriptor, ControlKey},
+};
+
+/// Family of an effect program; it decides which parameters matter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FxKind {
+    Reverb,
+    Delay,
+}
+
+impl FxKind {
+    pub fn of_program(name: &str) -> Self {
+        let lower = name.to_lowercase();
+        if lower.contains("delay") || lower.contains("echo") {
+            FxKind::Delay
+        } else {
+            FxKind::Reverb
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FxKind::Reverb => tr("Reverb"),
+            FxKind::Delay => tr("Delay"),
+        }
+    }
+
+    /// What the Duration control means for this effect.
+    pub fn duration_label(self) -> &'static str {
+        match self {
+            FxKind::Reverb => tr("Decay"),
+            FxKind::Delay => tr("Delay time"),
+        }
+    }
+
+    /// Reverb programs ignore Feedback.
+    pub fn uses_feedback(self) -> bool {
+        self == FxKind::Delay
+    }
+}
+
+/// The card's global effect controls by role, classified once from the
+/// control list. Per-channel sends and the returns are not part of it.
+#[derive(Debug, Clone, Default)]
+pub struct FxModel {
+    pub program: Option<usize>,
+    pub volume: Option<usize>,
+    pub duration: Option<usize>,
+    pub feedback: Option<usize>,
+    /// FX controls with no known role, shown as generic tiles.
+    pub other: Vec<usize>,
+}
+
+impl FxModel {
+    pub fn from_controls(controls: &[ControlDescriptor]) -> Self {
+        let mut model = FxModel::default();
+        for (idx, control) in controls.iter().enumerate() {
+            if !is_fx_control(control) || is_channel_fx_send(control) || is_fx_return(control) {
+                continue;
+            }
+            let lower = control.name.to_lowercase();
+            let slot = if lower.contains("program") {
+                &mut model.program
+            } else if lower.contains("duration") {
+                &mut model.duration
+            } else if lower.contains("feedback") {
+                &mut model.feedback
+            } else if lower.contains("effect") {
+                &mut model.volume
+            } else {
+                model.other.push(idx);
+                continue;
+            };
+            if slot.is_none() {
+                *slot = Some(idx);
+            } else {
+                model.other.push(idx);
+            }
+        }
+        model
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.program.is_none()
+            && self.volume.is_none()
+            && self.duration.is_none()
+            && self.feedback.is_none()
+            && self.other.is_empty()
+    }
+}
+
+pub fn is_fx_control(control: &ControlDescriptor) -> bool {
+    let lower = control.name.to_lowercase();
+    lower.contains("fx")
+        || lower.contains("effect")
+        || lower.contains("reverb")
+        || lower.contains("delay")
+        || lower.contains("chorus")
+}
+
+pub fn is_channel_fx_send(control: &ControlDescriptor) -> bool {
+    let lower = control.name.to_lowercase();
+    let has_channel = lower.contains("ain") || lower.contains("din");
+    let send_like = lower.contains("send") || lower.contains("aux") || lower.contains("to fx");
+    is_fx_control(control) && has_channel && send_like
+}
+
+pub fn is_fx_return(control: &ControlDescriptor) -> bool {
+    is_fx_control(control) && control.name.to_lowercase().contains("return")
+}
+
+/// Effect return controls by return number, from the number in their name
+/// ("Effect Return 2 Playback Volume" -> 1), else in name order.
+pub fn return_map(controls: &[ControlDescriptor]) -> BTreeMap<usize, usize> {
+    let number_re = regex::Regex::new(r"(?i)return\D*(\d+)").expect("valid regex");
+    let mut map = BTreeMap::new();
+    let mut unnumbered = Vec::new();
+    for (idx, control) in controls.iter().enumerate() {
+        if !is_fx_return(control) {
+            continue;
+        }
+        match number_re
+            .captures(&control.name)
+            .and_then(|cap| cap[1].parse::<usize>().ok())
+        {
+            Some(n) if n > 0 && !map.contains_key(&(n - 1)) => {
+                map.insert(n - 1, idx);
+            }
+            _ => unnumbered.push(idx),
+        }
+    }
+    let mut output = 0;
+    for idx in unnumbered {
+        while map.contains_key(&output) {
+            output += 1;
+        }
+        map.insert(output, idx);
+    }
+    map
+}
+
+/// Effect settings alone (program, parameters, sends, returns), by control
+/// name and index so they survive a numid reshuffle; routing is left untouched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FxPreset {
+    pub name: String,
+    pub controls: BTreeMap<ControlKey, Vec<String>>,
+}
+
+impl FxPreset {
+    pub fn capture(name: &str, controls: &[ControlDescriptor]) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            controls: controls
+                .iter()
+                .filter(|c| is_fx_control(c))
+                .map(|c| (c.key(), c.values.clone()))
+                .collect(),
+        }
+    }
+}
+
+pub fn fx_presets_dir() -> Result<PathBuf> {
+    let config_path = AppUserConfig::config_file_path()?;
+    let dir = config_path
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Invalid config path {}", config_path.display()))?;
+    Ok(dir.join("fx-presets"))
+}
+
+/// Loads `*.json` FX presets from `~/.ftu-mixer/fx-presets`; a missing folder is not an error.
+pub fn load_fx_presets() -> Result<Vec<FxPreset>> {
+    let dir = fx_presets_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut out = Vec::new();
+    let read_dir = fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if path.extension().map(|e| e != "json").unwrap_or(true) {
+            continue;
+        }
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read FX preset {}", path.display()))?;
+        let preset = serde_json::from_str::<FxPreset>(&text)
+            .with_context(|| format!("Failed to parse FX preset {}", path.display()))?;
+        out.push(preset);
+    }
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(out)
+}
+
+/// Writes `preset` as `<file_name>` in the FX preset folder, replacing a
+/// preset saved under the same name.
+pub fn save_fx_preset(preset: &FxPreset, file_name: &str) -> Result<PathBuf> {
+    let dir = fx_presets_dir()?;
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(file_name);
+    let text = serde_json::to_string_pretty(preset)?;
+    fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
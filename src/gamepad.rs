@@ -0,0 +1,172 @@
+use std::{
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use anyhow::{Context, Result};
+use gilrs::{Axis, Button, EventType, Gilrs, ev::Code};
+use serde::{Deserialize, Serialize};
+
+/// Gamepad and jog-wheel input bound to controls and actions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GamepadConfig {
+    pub enabled: bool,
+    /// Stick deflection ignored around the centre, 0-1.
+    pub deadzone: f32,
+    pub mappings: Vec<GamepadMapping>,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            deadzone: 0.15,
+            mappings: Vec::new(),
+        }
+    }
+}
+
+/// `input` is the name shown in the status bar when it is moved, e.g.
+/// `left-stick-y`, `right-trigger2`, `south` or `code-65539` for elements
+/// without a standard mapping.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GamepadMapping {
+    pub input: String,
+    pub target: GamepadTarget,
+    #[serde(default)]
+    pub mode: AxisMode,
+    /// Flips the axis direction (e.g. for sticks that report up as negative).
+    #[serde(default)]
+    pub invert: bool,
+    /// Fraction of the control range covered per second at full deflection,
+    /// in `rate` mode.
+    #[serde(default = "default_speed")]
+    pub speed: f32,
+}
+
+fn default_speed() -> f32 {
+    0.5
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GamepadTarget {
+    /// Integer controls follow axes, boolean controls toggle on a button press.
+    Control(String),
+    PanicMute,
+    /// Held while the button is down.
+    Talkback,
+    /// 1-8, like the snapshot bar.
+    Snapshot(usize),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AxisMode {
+    /// The deflection moves the level, which stays put when the stick springs
+    /// back to the centre.
+    #[default]
+    Rate,
+    /// The axis position is the level, for triggers, faders and dials.
+    Absolute,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GamepadEvent {
+    Connected(String),
+    Disconnected(String),
+    /// -1..1 for sticks, 0..1 for analog triggers.
+    Axis { input: String, value: f32 },
+    Button { input: String, pressed: bool },
+}
+
+/// Reads every connected gamepad on a background thread.
+pub fn start(notify: impl Fn() + Send + 'static) -> Result<Receiver<GamepadEvent>> {
+    let (tx, rx) = mpsc::channel();
+    let (ready_tx, ready_rx) = mpsc::channel();
+    // Gilrs is not Send, so it lives on its thread from the start.
+    thread::spawn(move || {
+        let mut gilrs = match Gilrs::new() {
+            Ok(gilrs) => {
+                let _ = ready_tx.send(Ok(()));
+                gilrs
+            }
+            Err(err) => {
+                let _ = ready_tx.send(Err(anyhow::anyhow!("{err}")));
+                return;
+            }
+        };
+        for (_, pad) in gilrs.gamepads() {
+            let _ = tx.send(GamepadEvent::Connected(pad.name().to_string()));
+        }
+        notify();
+        loop {
+            let Some(event) = gilrs.next_event_blocking(None) else {
+                continue;
+            };
+            let pad = gilrs.gamepad(event.id).name().to_string();
+            let event = match event.event {
+                EventType::Connected => GamepadEvent::Connected(pad),
+                EventType::Disconnected => GamepadEvent::Disconnected(pad),
+                EventType::AxisChanged(axis, value, code) => GamepadEvent::Axis {
+                    input: axis_name(axis, code),
+                    value,
+                },
+                // Analog triggers report their travel here besides press/release.
+                EventType::ButtonChanged(button, value, code) => GamepadEvent::Axis {
+                    input: button_name(button, code),
+                    value,
+                },
+                EventType::ButtonPressed(button, code) => GamepadEvent::Button {
+                    input: button_name(button, code),
+                    pressed: true,
+                },
+                EventType::ButtonReleased(button, code) => GamepadEvent::Button {
+                    input: button_name(button, code),
+                    pressed: false,
+                },
+                _ => continue,
+            };
+            if tx.send(event).is_err() {
+                break;
+            }
+            notify();
+        }
+    });
+    ready_rx
+        .recv()
+        .context("Gamepad thread exited")?
+        .context("Failed to open gamepads")?;
+    Ok(rx)
+}
+
+fn axis_name(axis: Axis, code: Code) -> String {
+    match axis {
+        Axis::Unknown => format!("code-{}", code.into_u32()),
+        _ => kebab_case(&format!("{axis:?}")),
+    }
+}
+
+fn button_name(button: Button, code: Code) -> String {
+    match button {
+        Button::Unknown => format!("code-{}", code.into_u32()),
+        _ => kebab_case(&format!("{button:?}")),
+    }
+}
+
+/// `LeftStickY` → `left-stick-y`, `LeftTrigger2` → `left-trigger2`.
+fn kebab_case(name: &str) -> String {
+    let mut out = String::new();
+    for ch in name.chars() {
+        if ch.is_ascii_uppercase() {
+            if !out.is_empty() {
+                out.push('-');
+            }
+            out.push(ch.to_ascii_lowercase());
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
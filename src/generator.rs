@@ -0,0 +1,350 @@
+use std::{
+    f32::consts::{PI, TAU},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use alsa::{
+    Direction, ValueOr,
+    pcm::{Access, Format, HwParams, PCM},
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+const RATE: u32 = 48000;
+const PERIOD_FRAMES: i64 = 256;
+const BUFFER_FRAMES: i64 = PERIOD_FRAMES * 4;
+/// Gain steps per sample towards the target, so level changes, start and
+/// stop do not click (~10 ms for a full swing).
+const GAIN_SLEW: f32 = 1.0 / 480.0;
+
+/// Test signal sent to playback channels, to check cabling and routing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeneratorConfig {
+    /// ALSA playback device; `plughw:<card>,0` when unset. Point it at the
+    /// sound server's ALSA plugin (e.g. `pipewire`) when that holds the card.
+    pub playback_device: Option<String>,
+    pub signal: Signal,
+    pub frequency_hz: f32,
+    /// Peak level of the sine, RMS level of the noise.
+    pub level_dbfs: f32,
+    /// Play on both outputs of the selected pair.
+    pub pair: bool,
+    /// Pink noise stops by itself after this long, in case it is forgotten
+    /// on loud speakers.
+    pub noise_timeout_secs: u64,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            playback_device: None,
+            signal: Signal::Sine,
+            frequency_hz: 1000.0,
+            level_dbfs: -20.0,
+            pair: false,
+            noise_timeout_secs: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Signal {
+    #[default]
+    Sine,
+    /// Pink noise band-limited to 500 Hz-2 kHz, as in SMPTE RP 200, so
+    /// speakers can be matched with an SPL meter at the listening position.
+    PinkNoise,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignalSettings {
+    pub signal: Signal,
+    pub frequency_hz: f32,
+    pub level_dbfs: f32,
+}
+
+/// Playback stream written on a background thread until dropped.
+pub struct Generator {
+    pub device: String,
+    settings: Arc<Mutex<SignalSettings>>,
+    error: Arc<Mutex<Option<String>>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Generator {
+    /// Takes effect within a period, with the level ramped.
+    pub fn set(&self, settings: SignalSettings) {
+        if let Ok(mut current) = self.settings.lock() {
+            *current = settings;
+        }
+    }
+
+    pub fn error(&self) -> Option<String> {
+        self.error.lock().ok().and_then(|e| e.clone())
+    }
+}
+
+impl Drop for Generator {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+pub fn start(
+    device: &str,
+    channels: u32,
+    targets: Vec<usize>,
+    settings: SignalSettings,
+    notify: impl Fn() + Send + 'static,
+) -> Result<Generator> {
+    let settings = Arc::new(Mutex::new(settings));
+    let error = Arc::new(Mutex::new(None));
+    let stop = Arc::new(AtomicBool::new(false));
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let generator = Generator {
+        device: device.to_string(),
+        settings: settings.clone(),
+        error: error.clone(),
+        stop: stop.clone(),
+    };
+    let device = device.to_string();
+    thread::spawn(move || {
+        let opened = open_playback(&device, channels, &targets, RATE);
+        let ready = opened.as_ref().map(|_| ()).map_err(|err| anyhow::anyhow!("{err:#}"));
+        let _ = ready_tx.send(ready);
+        let Ok((pcm, format, channels)) = opened else {
+            return;
+        };
+        if let Err(err) = write_loop(&pcm, format, channels as usize, &targets, &settings, &stop) {
+            if let Ok(mut slot) = error.lock() {
+                *slot = Some(format!("{err:#}"));
+            }
+            notify();
+        }
+    });
+    ready_rx.recv().context("Generator thread exited")??;
+    Ok(generator)
+}
+
+/// Plays one beep per `count` on a playback channel, then closes the
+/// stream, so an output can be told apart by ear (four beeps for Out4).
+pub fn identify(device: &str, channels: u32, target: usize, count: usize) -> Result<()> {
+    const BEEP: f32 = 0.09;
+    const GAP: f32 = 0.11;
+    const RAMP: f32 = 0.005;
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let device = device.to_string();
+    thread::spawn(move || {
+        let opened = open_playback(&device, channels, &[target], RATE);
+        let ready = opened.as_ref().map(|_| ()).map_err(|err| anyhow::anyhow!("{err:#}"));
+        let _ = ready_tx.send(ready);
+        let Ok((pcm, format, channels)) = opened else {
+            return;
+        };
+        let Ok(rate) = pcm.hw_params_current().and_then(|hwp| hwp.get_rate()) else {
+            return;
+        };
+        let rate = rate as f32;
+        let frames = ((BEEP + GAP) * rate) as usize * count.max(1);
+        let mut float = vec![0f32; frames * channels as usize];
+        for (i, frame) in float.chunks_exact_mut(channels as usize).enumerate() {
+            let t = (i as f32 / rate) % (BEEP + GAP);
+            if t < BEEP {
+                let envelope = (t / RAMP).min((BEEP - t) / RAMP).min(1.0);
+                frame[target] = 0.25 * envelope * (TAU * 1000.0 * i as f32 / rate).sin();
+            }
+        }
+        let _ = write_frames(&pcm, format, channels as usize, &float);
+        let _ = pcm.drain();
+    });
+    ready_rx.recv().context("Generator thread exited")?
+}
+
+/// Opens the device with at least the channels `targets` point at.
+pub fn open_playback(device: &str, channels: u32, targets: &[usize], rate: u32) -> Result<(PCM, Format, u32)> {
+    let pcm = PCM::new(device, Direction::Playback, false).with_context(|| format!("Failed to open {device}"))?;
+    let format = {
+        let hwp = HwParams::any(&pcm).context("No hardware parameters")?;
+        hwp.set_access(Access::RWInterleaved)?;
+        let format = [Format::float(), Format::s32(), Format::s16()]
+            .into_iter()
+            .find(|f| hwp.set_format(*f).is_ok())
+            .with_context(|| format!("{device} offers no float, 32-bit or 16-bit format"))?;
+        hwp.set_channels_near(channels)?;
+        hwp.set_rate_near(rate, ValueOr::Nearest)?;
+        hwp.set_period_size_near(PERIOD_FRAMES, ValueOr::Nearest)?;
+        hwp.set_buffer_size_near(BUFFER_FRAMES)?;
+        pcm.hw_params(&hwp).with_context(|| format!("{device} refused the playback settings"))?;
+        format
+    };
+    let channels = pcm.hw_params_current()?.get_channels()?;
+    if let Some(missing) = targets.iter().find(|&&t| t >= channels as usize) {
+        anyhow::bail!("{device} has {channels} playback channels, channel {} requested", missing + 1);
+    }
+    Ok((pcm, format, channels))
+}
+
+fn write_loop(
+    pcm: &PCM,
+    format: Format,
+    channels: usize,
+    targets: &[usize],
+    settings: &Mutex<SignalSettings>,
+    stop: &AtomicBool,
+) -> Result<()> {
+    let rate = pcm.hw_params_current()?.get_rate()? as f32;
+    let mut float = vec![0f32; PERIOD_FRAMES as usize * channels];
+    let mut phase = 0f32;
+    let mut noise = PinkNoise::new(rate);
+    let mut gain = 0f32;
+    // Keeps writing after a stop request until the level has faded out.
+    loop {
+        let stopping = stop.load(Ordering::Relaxed);
+        if stopping && gain == 0.0 {
+            break;
+        }
+        let current = settings.lock().map(|s| *s).unwrap_or(SignalSettings {
+            signal: Signal::Sine,
+            frequency_hz: 1000.0,
+            level_dbfs: -120.0,
+        });
+        let target = if stopping {
+            0.0
+        } else {
+            10f32.powf(current.level_dbfs.min(0.0) / 20.0)
+        };
+        let step = current.frequency_hz.clamp(10.0, rate / 2.0) / rate;
+        for frame in float.chunks_exact_mut(channels) {
+            gain = if gain < target {
+                (gain + GAIN_SLEW).min(target)
+            } else {
+                (gain - GAIN_SLEW).max(target)
+            };
+            let sample = match current.signal {
+                Signal::Sine => {
+                    phase = (phase + step).fract();
+                    (TAU * phase).sin()
+                }
+                Signal::PinkNoise => noise.next(),
+            };
+            let sample = (gain * sample).clamp(-1.0, 1.0);
+            for &target in targets {
+                frame[target] = sample;
+            }
+        }
+        write_frames(pcm, format, channels, &float).context("Playback stream failed")?;
+    }
+    pcm.drain()?;
+    Ok(())
+}
+
+/// Band-limited pink noise with an RMS of 1 (0 dBFS RMS before the gain).
+struct PinkNoise {
+    seed: u32,
+    /// Paul Kellet's economy pinking filter.
+    pink: [f32; 3],
+    high_pass: Biquad,
+    low_pass: Biquad,
+    scale: f32,
+}
+
+impl PinkNoise {
+    fn new(rate: f32) -> Self {
+        let mut noise = Self {
+            seed: 0x9e37_79b9,
+            pink: [0.0; 3],
+            high_pass: Biquad::high_pass(500.0, rate),
+            low_pass: Biquad::low_pass(2000.0, rate),
+            scale: 1.0,
+        };
+        // Calibrates on a few seconds of output, which also settles the filters.
+        let count = (rate * 4.0) as usize;
+        let sum_sq: f64 = (0..count).map(|_| (noise.next() as f64).powi(2)).sum();
+        noise.scale = (count as f64 / sum_sq.max(1e-12)).sqrt() as f32;
+        noise
+    }
+
+    fn next(&mut self) -> f32 {
+        // xorshift32
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        let white = self.seed as f32 / u32::MAX as f32 * 2.0 - 1.0;
+        let [b0, b1, b2] = &mut self.pink;
+        *b0 = 0.99765 * *b0 + white * 0.0990460;
+        *b1 = 0.96300 * *b1 + white * 0.2965164;
+        *b2 = 0.57000 * *b2 + white * 1.0526913;
+        let pink = *b0 + *b1 + *b2 + white * 0.1848;
+        self.low_pass.process(self.high_pass.process(pink)) * self.scale
+    }
+}
+
+/// Second-order Butterworth section (RBJ cookbook), direct form I.
+struct Biquad {
+    b: [f32; 3],
+    a: [f32; 2],
+    state: [f32; 4],
+}
+
+impl Biquad {
+    fn high_pass(cutoff: f32, rate: f32) -> Self {
+        let (cos, alpha) = Self::prewarp(cutoff, rate);
+        Self::normalized([(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0], cos, alpha)
+    }
+
+    fn low_pass(cutoff: f32, rate: f32) -> Self {
+        let (cos, alpha) = Self::prewarp(cutoff, rate);
+        Self::normalized([(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0], cos, alpha)
+    }
+
+    fn prewarp(cutoff: f32, rate: f32) -> (f32, f32) {
+        let w0 = 2.0 * PI * cutoff / rate;
+        (w0.cos(), w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2))
+    }
+
+    fn normalized(b: [f32; 3], cos: f32, alpha: f32) -> Self {
+        let a0 = 1.0 + alpha;
+        Self {
+            b: b.map(|b| b / a0),
+            a: [-2.0 * cos / a0, (1.0 - alpha) / a0],
+            state: [0.0; 4],
+        }
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        let [x1, x2, y1, y2] = self.state;
+        let y = self.b[0] * x + self.b[1] * x1 + self.b[2] * x2 - self.a[0] * y1 - self.a[1] * y2;
+        self.state = [x, x1, y, y1];
+        y
+    }
+}
+
+/// Blocks until the interleaved samples are all queued.
+pub fn write_frames(pcm: &PCM, format: Format, channels: usize, float: &[f32]) -> Result<()> {
+    let mut offset = 0;
+    while offset < float.len() {
+        let rest = &float[offset..];
+        let written = if format == Format::float() {
+            pcm.io_f32()?.writei(rest)
+        } else if format == Format::s32() {
+            let int: Vec<i32> = rest.iter().map(|s| (s * i32::MAX as f32) as i32).collect();
+            pcm.io_i32()?.writei(&int)
+        } else {
+            let int: Vec<i16> = rest.iter().map(|s| (s * i16::MAX as f32) as i16).collect();
+            pcm.io_i16()?.writei(&int)
+        };
+        match written {
+            Ok(frames) => offset += frames * channels,
+            // Underruns happen when the machine stalls; just pick up again.
+            Err(err) => pcm.try_recover(err, true)?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+use anyhow::{Context, Result};
+use ashpd::desktop::global_shortcuts::{GlobalShortcuts, NewShortcut};
+use futures_lite::{StreamExt, future};
+use x11rb::{
+    connection::Connection,
+    protocol::{
+        Event,
+        xproto::{ConnectionExt, GrabMode, ModMask},
+    },
+};
+
+/// Actions reachable from anywhere on the desktop, with their default trigger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    PanicMute,
+    /// Held like the Talk button: active between press and release.
+    Talkback,
+    RecallSnapshot(usize),
+}
+
+impl HotkeyAction {
+    pub fn all() -> Vec<HotkeyAction> {
+        let mut all = vec![HotkeyAction::PanicMute, HotkeyAction::Talkback];
+        all.extend((0..8).map(HotkeyAction::RecallSnapshot));
+        all
+    }
+
+    fn id(self) -> String {
+        match self {
+            HotkeyAction::PanicMute => "panic-mute".to_string(),
+            HotkeyAction::Talkback => "talkback".to_string(),
+            HotkeyAction::RecallSnapshot(slot) => format!("snapshot-{}", slot + 1),
+        }
+    }
+
+    fn description(self) -> String {
+        match self {
+            HotkeyAction::PanicMute => "Mute all monitoring".to_string(),
+            HotkeyAction::Talkback => "Talkback (hold)".to_string(),
+            HotkeyAction::RecallSnapshot(slot) => format!("Recall snapshot {}", slot + 1),
+        }
+    }
+
+    /// X11 keysym of the key pressed with Ctrl+Alt.
+    fn keysym(self) -> u32 {
+        match self {
+            HotkeyAction::PanicMute => 0x6d, // m
+            HotkeyAction::Talkback => 0x74,  // t
+            HotkeyAction::RecallSnapshot(slot) => 0x31 + slot as u32,
+        }
+    }
+
+    /// Preferred trigger in the XDG "shortcuts" syntax.
+    fn trigger(self) -> String {
+        let key = char::from_u32(self.keysym()).unwrap_or('?');
+        format!("CTRL+ALT+{key}")
+    }
+
+    fn from_id(id: &str) -> Option<HotkeyAction> {
+        HotkeyAction::all().into_iter().find(|a| a.id() == id)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HotkeyEvent {
+    pub action: HotkeyAction,
+    pub pressed: bool,
+}
+
+/// Result of the registration, reported once before any key event.
+#[derive(Debug, Clone)]
+pub enum HotkeyMessage {
+    Registered(&'static str),
+    Failed(String),
+    Key(HotkeyEvent),
+}
+
+/// Registers the shortcuts through the desktop portal, or with key grabs on
+/// the X11 root window when no portal offers GlobalShortcuts.
+pub fn start(notify: impl Fn() + Send + 'static) -> Receiver<HotkeyMessage> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let portal_error = match future::block_on(run_portal(&tx, &notify)) {
+            Ok(()) => return,
+            Err(err) => err,
+        };
+        if let Err(err) = run_x11(&tx, &notify) {
+            let _ = tx.send(HotkeyMessage::Failed(format!(
+                "portal: {portal_error:#}; X11: {err:#}"
+            )));
+            notify();
+        }
+    });
+    rx
+}
+
+async fn run_portal(tx: &Sender<HotkeyMessage>, notify: &impl Fn()) -> Result<()> {
+    let portal = GlobalShortcuts::new()
+        .await
+        .context("GlobalShortcuts portal unavailable")?;
+    let session = portal.create_session().await.context("CreateSession failed")?;
+    let shortcuts: Vec<NewShortcut> = HotkeyAction::all()
+        .into_iter()
+        .map(|a| NewShortcut::new(a.id(), a.description()).preferred_trigger(a.trigger().as_str()))
+        .collect();
+    portal
+        .bind_shortcuts(&session, &shortcuts, None)
+        .await
+        .context("BindShortcuts failed")?
+        .response()
+        .context("Shortcuts were not granted")?;
+    let activated = portal
+        .receive_activated()
+        .await?
+        .map(|a| (a.shortcut_id().to_string(), true));
+    let deactivated = portal
+        .receive_deactivated()
+        .await?
+        .map(|d| (d.shortcut_id().to_string(), false));
+    let _ = tx.send(HotkeyMessage::Registered("portal"));
+    notify();
+    let mut events = activated.or(deactivated);
+    while let Some((id, pressed)) = events.next().await {
+        let Some(action) = HotkeyAction::from_id(&id) else {
+            continue;
+        };
+        if tx.send(HotkeyMessage::Key(HotkeyEvent { action, pressed })).is_err() {
+            break;
+        }
+        notify();
+    }
+    Ok(())
+}
+
+fn run_x11(tx: &Sender<HotkeyMessage>, notify: &impl Fn()) -> Result<()> {
+    let (conn, screen) = x11rb::connect(None).context("No X11 display")?;
+    let root = conn.setup().roots[screen].root;
+    let min = conn.setup().min_keycode;
+    let max = conn.setup().max_keycode;
+    let mapping = conn
+        .get_keyboard_mapping(min, max - min + 1)?
+        .reply()
+        .context("Failed to read the keyboard mapping")?;
+    let per_code = mapping.keysyms_per_keycode.max(1) as usize;
+    let keycode_of = |keysym: u32| {
+        mapping
+            .keysyms
+            .chunks(per_code)
+            .position(|syms| syms.contains(&keysym))
+            .map(|i| min + i as u8)
+    };
+    let base = ModMask::CONTROL | ModMask::M1;
+    let mut bound = Vec::new();
+    for action in HotkeyAction::all() {
+        let Some(code) = keycode_of(action.keysym()) else {
+            continue;
+        };
+        // Grab with every Caps Lock / Num Lock combination, or the key only
+        // fires while both are off.
+        for extra in [ModMask::from(0u16), ModMask::LOCK, ModMask::M2, ModMask::LOCK | ModMask::M2] {
+            conn.grab_key(true, root, base | extra, code, GrabMode::ASYNC, GrabMode::ASYNC)?
+                .check()
+                .with_context(|| format!("{} is taken by another program", action.trigger()))?;
+        }
+        bound.push((code, action));
+    }
+    conn.flush()?;
+    let _ = tx.send(HotkeyMessage::Registered("X11"));
+    notify();
+    let mut pending = None;
+    loop {
+        let event = match pending.take() {
+            Some(event) => event,
+            None => conn.wait_for_event()?,
+        };
+        let (code, pressed, time) = match event {
+            Event::KeyPress(e) => (e.detail, true, e.time),
+            Event::KeyRelease(e) => (e.detail, false, e.time),
+            _ => continue,
+        };
+        // Auto-repeat shows up as a release immediately followed by a press
+        // with the same timestamp; skip both so a held talkback stays held.
+        if !pressed {
+            match conn.poll_for_event()? {
+                Some(Event::KeyPress(next)) if next.detail == code && next.time == time => continue,
+                next => pending = next,
+            }
+        }
+        let Some(&(_, action)) = bound.iter().find(|(c, _)| *c == code) else {
+            continue;
+        };
+        if tx.send(HotkeyMessage::Key(HotkeyEvent { action, pressed })).is_err() {
+            return Ok(());
+        }
+        notify();
+    }
+}
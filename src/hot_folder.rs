@@ -0,0 +1,110 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+const SCAN_INTERVAL: Duration = Duration::from_millis(500);
+const DEBOUNCE: Duration = Duration::from_millis(750);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+/// Polls a directory for new or rewritten `*.json` presets.
+///
+/// A file is reported once its size and mtime have stayed unchanged for
+/// `DEBOUNCE`, so half-written files pushed over the network are not picked up.
+/// Files already present when watching starts are ignored.
+pub struct HotFolderWatcher {
+    dir: PathBuf,
+    applied: HashMap<PathBuf, FileStamp>,
+    pending: HashMap<PathBuf, (FileStamp, Instant)>,
+    last_scan: Instant,
+}
+
+impl HotFolderWatcher {
+    pub fn new(dir: PathBuf) -> Self {
+        let applied = Self::scan(&dir);
+        Self {
+            dir,
+            applied,
+            pending: HashMap::new(),
+            last_scan: Instant::now(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns files that settled since the last call, oldest change first.
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        if self.last_scan.elapsed() < SCAN_INTERVAL {
+            return Vec::new();
+        }
+        self.last_scan = Instant::now();
+
+        let current = Self::scan(&self.dir);
+        self.applied.retain(|path, _| current.contains_key(path));
+        self.pending.retain(|path, _| current.contains_key(path));
+
+        let mut ready = Vec::new();
+        for (path, stamp) in current {
+            if self.applied.get(&path) == Some(&stamp) {
+                continue;
+            }
+            match self.pending.get(&path) {
+                Some((pending_stamp, since)) if *pending_stamp == stamp => {
+                    if since.elapsed() >= DEBOUNCE {
+                        ready.push((stamp.modified, path.clone()));
+                        self.pending.remove(&path);
+                        self.applied.insert(path, stamp);
+                    }
+                }
+                _ => {
+                    self.pending.insert(path, (stamp, Instant::now()));
+                }
+            }
+        }
+        ready.sort();
+        ready.into_iter().map(|(_, path)| path).collect()
+    }
+
+    fn scan(dir: &Path) -> HashMap<PathBuf, FileStamp> {
+        let mut out = HashMap::new();
+        let Ok(read_dir) = fs::read_dir(dir) else {
+            return out;
+        };
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            let is_json = path
+                .extension()
+                .map(|e| e.eq_ignore_ascii_case("json"))
+                .unwrap_or(false);
+            if !is_json {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if !meta.is_file() {
+                continue;
+            }
+            let Ok(modified) = meta.modified() else {
+                continue;
+            };
+            out.insert(
+                path,
+                FileStamp {
+                    modified,
+                    len: meta.len(),
+                },
+            );
+        }
+        out
+    }
+}
@@ -0,0 +1,470 @@
+use std::{
+    io::{BufRead, BufReader, ErrorKind, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tungstenite::{
+    Message, WebSocket,
+    handshake::derive_accept_key,
+    protocol::{Role, WebSocketConfig},
+};
+
+pub const DEFAULT_BIND: &str = "127.0.0.1:7878";
+
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a request waits for the UI thread, which owns the card.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_BODY_BYTES: usize = 1 << 20;
+/// How often a WebSocket session stops reading to send queued events.
+const WS_POLL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpApiConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_bind")]
+    pub bind: String,
+    /// Bearer token every request must carry; generated on first start.
+    #[serde(default)]
+    pub token: String,
+}
+
+fn default_bind() -> String {
+    DEFAULT_BIND.to_string()
+}
+
+impl Default for HttpApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: default_bind(),
+            token: String::new(),
+        }
+    }
+}
+
+/// 128 bits from the OS random source, as hex.
+pub fn generate_token() -> Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes)
+        .map_err(|err| anyhow::anyhow!("Failed to read the OS random source: {err}"))?;
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// Compares in time that only depends on the length, so the token cannot be
+/// guessed byte by byte from response times.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Bulk actions of the "Quick actions" bar, by URL name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickAction {
+    MuteAnalog,
+    PassThrough,
+    DisableFx,
+    MuteDigital,
+    MuteAll,
+}
+
+impl QuickAction {
+    pub const ALL: [QuickAction; 5] = [
+        QuickAction::MuteAnalog,
+        QuickAction::PassThrough,
+        QuickAction::DisableFx,
+        QuickAction::MuteDigital,
+        QuickAction::MuteAll,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            QuickAction::MuteAnalog => "mute-analog",
+            QuickAction::PassThrough => "pass-through",
+            QuickAction::DisableFx => "disable-fx",
+            QuickAction::MuteDigital => "mute-digital",
+            QuickAction::MuteAll => "mute-all",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ApiRequest {
+    ListControls,
+    GetControl(u32),
+    SetControl(u32, Vec<String>),
+    /// File name of a preset in the library folder.
+    ApplyPreset(String),
+    QuickAction(QuickAction),
+    Metrics,
+    CardInfo,
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: Value,
+    /// Plain-text body sent instead of `body` (Prometheus metrics).
+    pub text: Option<String>,
+}
+
+impl ApiResponse {
+    pub fn ok(body: Value) -> Self {
+        Self {
+            status: 200,
+            body,
+            text: None,
+        }
+    }
+
+    pub fn text(text: String) -> Self {
+        Self {
+            status: 200,
+            body: Value::Null,
+            text: Some(text),
+        }
+    }
+
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+            text: None,
+        }
+    }
+}
+
+/// A request handed to the UI thread; the answer goes back through `reply`.
+pub struct ApiCall {
+    pub request: ApiRequest,
+    reply: Sender<ApiResponse>,
+}
+
+impl ApiCall {
+    pub fn respond(self, response: ApiResponse) {
+        let _ = self.reply.send(response);
+    }
+}
+
+#[derive(Deserialize)]
+struct SetBody {
+    values: Vec<String>,
+}
+
+/// Commands accepted on the WebSocket, as JSON text frames.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum WsCommand {
+    List,
+    Set { numid: u32, values: Vec<String> },
+}
+
+pub type Notify = Arc<dyn Fn() + Send + Sync>;
+type Subscribers = Arc<Mutex<Vec<Sender<String>>>>;
+
+/// Handle on the running server: API calls to answer on the UI thread and the
+/// WebSocket clients listening for changes.
+pub struct HttpApi {
+    pub calls: Receiver<ApiCall>,
+    subscribers: Subscribers,
+}
+
+impl HttpApi {
+    pub fn has_subscribers(&self) -> bool {
+        self.subscribers.lock().map(|s| !s.is_empty()).unwrap_or(false)
+    }
+
+    /// Sends `event` to every WebSocket client, dropping those that left.
+    pub fn publish(&self, event: &Value) {
+        let text = event.to_string();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|s| s.send(text.clone()).is_ok());
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct PresetBody {
+    name: String,
+}
+
+/// Serves the JSON API on `bind` from a background thread. Requests are
+/// authenticated, parsed, and forwarded to `HttpApi::calls`; `notify` wakes
+/// the UI so they are answered promptly. `GET /api/ws` upgrades to a
+/// WebSocket that gets its own thread.
+pub fn start<F>(bind: &str, token: String, notify: F) -> Result<HttpApi>
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind(bind).with_context(|| format!("Failed to listen on {bind}"))?;
+    let (tx, rx) = mpsc::channel();
+    let subscribers: Subscribers = Arc::new(Mutex::new(Vec::new()));
+    let notify: Notify = Arc::new(notify);
+    let server_subscribers = subscribers.clone();
+    let token: Arc<str> = token.into();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = stream.set_read_timeout(Some(IO_TIMEOUT));
+            let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+            let token = token.clone();
+            let tx = tx.clone();
+            let notify = notify.clone();
+            let subscribers = server_subscribers.clone();
+            thread::spawn(move || serve(stream, &token, &tx, &notify, &subscribers));
+        }
+    });
+    Ok(HttpApi {
+        calls: rx,
+        subscribers,
+    })
+}
+
+/// Answers one connection, on its own thread so a slow client or a busy UI
+/// thread does not hold up the others.
+fn serve(
+    mut stream: TcpStream,
+    token: &str,
+    tx: &Sender<ApiCall>,
+    notify: &Notify,
+    subscribers: &Subscribers,
+) {
+    let response = match read_request(&stream) {
+        Ok(request) if request.websocket_key.is_some() && request.path_only() == "/api/ws" => {
+            if !request.authorized(token) {
+                Some(ApiResponse::error(401, "Missing or wrong token"))
+            } else {
+                let tx = tx.clone();
+                let notify = notify.clone();
+                let subscribers = subscribers.clone();
+                websocket_session(stream, request, tx, notify, subscribers);
+                return;
+            }
+        }
+        Ok(request) => route(request, token, tx, notify),
+        Err(err) => Some(ApiResponse::error(400, err.to_string())),
+    };
+    let response =
+        response.unwrap_or_else(|| ApiResponse::error(503, "The mixer is shutting down"));
+    let _ = write_response(&mut stream, &response);
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    websocket_key: Option<String>,
+    body: Vec<u8>,
+}
+
+impl HttpRequest {
+    fn path_only(&self) -> &str {
+        self.path.split('?').next().unwrap_or_default()
+    }
+
+    /// Bearer header, or `?token=` for browsers, which cannot set headers on
+    /// a WebSocket.
+    fn authorized(&self, token: &str) -> bool {
+        let bearer = self
+            .authorization
+            .as_deref()
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::trim);
+        let query = self
+            .path
+            .split_once('?')
+            .and_then(|(_, query)| query.split('&').find_map(|kv| kv.strip_prefix("token=")));
+        bearer
+            .or(query)
+            .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+    }
+}
+
+fn read_request(stream: &TcpStream) -> Result<HttpRequest> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).context("Failed to read the request line")?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().context("Empty request")?.to_string();
+    let path = parts.next().context("Missing request path")?.to_string();
+
+    let mut content_length = 0usize;
+    let mut authorization = None;
+    let mut websocket_key = None;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).context("Failed to read headers")?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => content_length = value.parse().context("Bad Content-Length")?,
+            "authorization" => authorization = Some(value.to_string()),
+            "sec-websocket-key" => websocket_key = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    anyhow::ensure!(content_length <= MAX_BODY_BYTES, "Request body too large");
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).context("Truncated request body")?;
+    Ok(HttpRequest {
+        method,
+        path,
+        authorization,
+        websocket_key,
+        body,
+    })
+}
+
+/// `None` when the UI thread has gone away.
+fn route(
+    request: HttpRequest,
+    token: &str,
+    tx: &Sender<ApiCall>,
+    notify: &Notify,
+) -> Option<ApiResponse> {
+    if !request.authorized(token) {
+        return Some(ApiResponse::error(401, "Missing or wrong bearer token"));
+    }
+    let path = request.path_only();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let parsed = match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["metrics"]) => Ok(ApiRequest::Metrics),
+        ("GET", ["api", "card"]) => Ok(ApiRequest::CardInfo),
+        ("GET", ["api", "controls"]) => Ok(ApiRequest::ListControls),
+        ("GET", ["api", "controls", numid]) => parse_numid(numid).map(ApiRequest::GetControl),
+        ("PUT", ["api", "controls", numid]) => parse_numid(numid).and_then(|numid| {
+            serde_json::from_slice::<SetBody>(&request.body)
+                .map(|body| ApiRequest::SetControl(numid, body.values))
+                .map_err(|err| ApiResponse::error(400, format!("Expected {{\"values\": [...]}}: {err}")))
+        }),
+        ("POST", ["api", "presets", "apply"]) => serde_json::from_slice::<PresetBody>(&request.body)
+            .map(|body| ApiRequest::ApplyPreset(body.name))
+            .map_err(|err| ApiResponse::error(400, format!("Expected {{\"name\": \"...\"}}: {err}"))),
+        ("POST", ["api", "actions", name]) => QuickAction::ALL
+            .into_iter()
+            .find(|a| a.name() == *name)
+            .map(ApiRequest::QuickAction)
+            .ok_or_else(|| ApiResponse::error(404, format!("Unknown action \"{name}\""))),
+        _ => Err(ApiResponse::error(404, format!("No route for {} {path}", request.method))),
+    };
+    match parsed {
+        Ok(request) => call(request, tx, notify),
+        Err(response) => Some(response),
+    }
+}
+
+/// Hands `request` to the UI thread and waits for its answer; `None` when
+/// the UI thread has gone away.
+pub fn call(request: ApiRequest, tx: &Sender<ApiCall>, notify: &Notify) -> Option<ApiResponse> {
+    let (reply, answer) = mpsc::channel();
+    tx.send(ApiCall { request, reply }).ok()?;
+    notify();
+    Some(
+        answer
+            .recv_timeout(REPLY_TIMEOUT)
+            .unwrap_or_else(|_| ApiResponse::error(503, "The mixer did not answer in time")),
+    )
+}
+
+/// Runs one WebSocket connection. Reads time out every `WS_POLL` so change
+/// events and command replies go out from this same thread.
+fn websocket_session(
+    mut stream: TcpStream,
+    request: HttpRequest,
+    tx: Sender<ApiCall>,
+    notify: Notify,
+    subscribers: Subscribers,
+) {
+    let key = request.websocket_key.unwrap_or_default();
+    let accept = derive_accept_key(key.as_bytes());
+    let handshake = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {accept}\r\n\r\n"
+    );
+    if stream.write_all(handshake.as_bytes()).is_err() {
+        return;
+    }
+    let (out_tx, out_rx) = mpsc::channel();
+    if let Ok(mut subscribers) = subscribers.lock() {
+        subscribers.push(out_tx);
+    }
+    let _ = stream.set_read_timeout(Some(WS_POLL));
+    let config = WebSocketConfig::default().max_message_size(Some(MAX_BODY_BYTES));
+    let mut socket = WebSocket::from_raw_socket(stream, Role::Server, Some(config));
+
+    loop {
+        while let Ok(text) = out_rx.try_recv() {
+            if socket.send(Message::text(text)).is_err() {
+                return;
+            }
+        }
+        let text = match socket.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(_) => continue,
+            Err(tungstenite::Error::Io(err))
+                if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+            {
+                continue;
+            }
+            Err(_) => break,
+        };
+        let response = match serde_json::from_str::<WsCommand>(text.as_str()) {
+            Ok(WsCommand::List) => call(ApiRequest::ListControls, &tx, &notify),
+            Ok(WsCommand::Set { numid, values }) => {
+                call(ApiRequest::SetControl(numid, values), &tx, &notify)
+            }
+            Err(err) => Some(ApiResponse::error(400, format!("Bad command: {err}"))),
+        };
+        let Some(response) = response else {
+            break;
+        };
+        let reply = json!({ "type": "result", "status": response.status, "body": response.body });
+        if socket.send(Message::text(reply.to_string())).is_err() {
+            break;
+        }
+    }
+    let _ = socket.close(None);
+    let _ = socket.flush();
+}
+
+fn parse_numid(text: &str) -> Result<u32, ApiResponse> {
+    text.parse()
+        .map_err(|_| ApiResponse::error(400, format!("\"{text}\" is not a control numid")))
+}
+
+fn write_response(stream: &mut TcpStream, response: &ApiResponse) -> Result<()> {
+    let (body, content_type) = match &response.text {
+        Some(text) => (text.clone().into_bytes(), "text/plain; version=0.0.4"),
+        None => (serde_json::to_vec_pretty(&response.body)?, "application/json"),
+    };
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {reason}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()?;
+    Ok(())
+}
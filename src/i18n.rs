@@ -0,0 +1,640 @@
+use std::{
+    collections::HashMap,
+    env,
+    fmt::Display,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Language {
+    /// From `LC_ALL`, `LC_MESSAGES` or `LANG`.
+    #[default]
+    Auto,
+    English,
+    French,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::Auto, Language::English, Language::French];
+
+    /// In the language itself, so it can be found whatever the current one.
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::Auto => "Auto",
+            Language::English => "English",
+            Language::French => "Français",
+        }
+    }
+
+    pub fn resolve(self) -> Language {
+        match self {
+            Language::Auto => detect(),
+            language => language,
+        }
+    }
+}
+
+fn detect() -> Language {
+    // Same precedence as setlocale(); "C" and "POSIX" mean no preference.
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    if locale.to_lowercase().starts_with("fr") {
+        Language::French
+    } else {
+        Language::English
+    }
+}
+
+static FRENCH_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_language(language: Language) {
+    FRENCH_ACTIVE.store(language.resolve() == Language::French, Ordering::Relaxed);
+}
+
+/// `text` (the English UI string, which is also the catalog key) in the
+/// current language. Strings missing from a catalog are shown as is.
+pub fn tr(text: &'static str) -> &'static str {
+    if !FRENCH_ACTIVE.load(Ordering::Relaxed) {
+        return text;
+    }
+    static CATALOG: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    CATALOG
+        .get_or_init(|| FRENCH.iter().copied().collect())
+        .get(text)
+        .copied()
+        .unwrap_or(text)
+}
+
+/// [`tr`] for a template: each `{}` in the translation takes the next of
+/// `args`, so a translation must keep the placeholders in the same order.
+pub fn trf(template: &'static str, args: &[&dyn Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    let mut parts = tr(template).split("{}");
+    if let Some(first) = parts.next() {
+        out.push_str(first);
+    }
+    for part in parts {
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+/// English → French.
+const FRENCH: &[(&str, &str)] = &[
+    ("Changes since \"{}\"", "Modifications depuis \"{}\""),
+    ("No control differs from the loaded preset.", "Aucun contrôle ne diffère du preset chargé."),
+    ("Revert all", "Tout rétablir"),
+    ("Control", "Contrôle"),
+    ("Current", "Actuel"),
+    ("Revert", "Rétablir"),
+    ("Snapshots:", "Instantanés :"),
+    ("Click: recall (Ctrl+{})\nShift+click: store current state (Ctrl+Shift+{})", "Clic : rappeler (Ctrl+{})\nMaj+clic : mémoriser l'état actuel (Ctrl+Maj+{})"),
+    ("Card: hw:{} ({})", "Carte : hw:{} ({})"),
+    ("Refresh", "Rafraîchir"),
+    ("Undo", "Annuler"),
+    ("Save preset", "Enregistrer le preset"),
+    ("Load preset", "Charger un preset"),
+    ("Factory presets", "Presets d'usine"),
+    ("Templates", "Modèles"),
+    ("Preview preset", "Aperçu d'un preset"),
+    ("Import ALSA state", "Importer un état ALSA"),
+    ("Import an `alsactl store` file or saved `amixer contents` output", "Importer un fichier `alsactl store` ou une sortie `amixer contents` enregistrée"),
+    ("ALSA state", "État ALSA"),
+    ("All files", "Tous les fichiers"),
+    ("Export diagram", "Exporter le schéma"),
+    ("Save the analog and digital routing, with aliases and levels, as SVG or PNG", "Enregistrer le routage analogique et digital, avec alias et niveaux, en SVG ou PNG"),
+    ("App triggers", "Déclencheurs d'applis"),
+    ("Tone generator", "Générateur de signal"),
+    ("Loopback test", "Test de bouclage"),
+    ("Scratch recording", "Enregistrement rapide"),
+    ("Preset library", "Bibliothèque de presets"),
+    ("● {} (modified: {})", "● {} (modifié : {})"),
+    ("Show controls changed since the preset was loaded", "Afficher les contrôles modifiés depuis le chargement du preset"),
+    ("off", "coupé"),
+    ("Preset templates", "Modèles de presets"),
+    ("Device profile: {}", "Profil de l'appareil : {}"),
+    ("Apply to", "Appliquer à"),
+    ("inputs ×", "entrées ×"),
+    ("outputs", "sorties"),
+    ("User templates: {}", "Modèles utilisateur : {}"),
+    ("Apply", "Appliquer"),
+    ("Needs at least {} inputs and {} outputs", "Nécessite au moins {} entrées et {} sorties"),
+    ("FTU meters", "Vumètres FTU"),
+    ("Mute every route to this output", "Couper toutes les routes vers cette sortie"),
+    ("Input meters", "Vumètres d'entrée"),
+    ("Output meters", "Vumètres de sortie"),
+    ("Output", "Sortie"),
+    ("Pair", "Paire"),
+    ("Sine", "Sinus"),
+    ("Pink noise", "Bruit rose"),
+    ("Band-limited 500 Hz-2 kHz, level is RMS: -20 dBFS is the usual reference for 85 dB SPL (C-weighted, slow) per speaker", "Bande limitée 500 Hz-2 kHz, niveau RMS : -20 dBFS est la référence habituelle pour 85 dB SPL (pondération C, lent) par enceinte"),
+    ("Frequency", "Fréquence"),
+    ("Level", "Niveau"),
+    ("Auto stop", "Arrêt auto"),
+    ("■ Stop", "■ Arrêt"),
+    ("▶ Play", "▶ Lecture"),
+    ("stops in {} s", "arrêt dans {} s"),
+    ("{} is not routed here", "{} n'est pas routé ici"),
+    ("Inputs to record", "Entrées à enregistrer"),
+    ("⏺ Record {} s", "⏺ Enregistrer {} s"),
+    ("On the first two playback channels (DIn1/2)", "Sur les deux premiers canaux de lecture (DIn1/2)"),
+    ("Connect an output to an input with a cable. Turn speakers and headphones down: the test plays a click.", "Reliez une sortie à une entrée avec un câble. Baissez enceintes et casques : le test joue un clic."),
+    ("Input", "Entrée"),
+    ("Period", "Période"),
+    ("{} frames", "{} trames"),
+    ("Measure latency", "Mesurer la latence"),
+    ("Sweep 20 Hz-20 kHz", "Balayage 20 Hz-20 kHz"),
+    ("4 s logarithmic sine sweep at -12 dBFS, recorded at the same time", "Balayage sinusoïdal logarithmique de 4 s à -12 dBFS, enregistré en même temps"),
+    ("{} samples = {} ms @ {} Hz", "{} échantillons = {} ms à {} Hz"),
+    ("Converters, USB and DSP, without any buffering.", "Convertisseurs, USB et DSP, sans aucun tampon."),
+    ("With a {}-frame period (2 per buffer): {} samples = {} ms round trip", "Avec une période de {} trames (2 par tampon) : {} échantillons = {} ms aller-retour"),
+    ("Streams could not be linked; lined up with their start timestamps (±1 sample).", "Les flux n'ont pas pu être liés ; alignés sur leurs horodatages de départ (±1 échantillon)."),
+    ("Gain from the output to the input (dB), 1/3-octave smoothing. Latency {} samples @ {} Hz.", "Gain de la sortie vers l'entrée (dB), lissage au 1/3 d'octave. Latence {} échantillons à {} Hz."),
+    ("Process / node", "Processus / nœud"),
+    ("Browse…", "Parcourir…"),
+    ("Add trigger", "Ajouter un déclencheur"),
+    ("When nothing matches:", "Quand rien ne correspond :"),
+    ("keep current state", "garder l'état actuel"),
+    ("Clear", "Effacer"),
+    ("Name", "Nom"),
+    ("Author", "Auteur"),
+    ("tracking, headphones, ...", "prise, casques, ..."),
+    ("When applying", "À l'application"),
+    ("Mute routes first, then set", "Couper les routes d'abord, puis régler"),
+    ("Ramp", "Rampe"),
+    ("Never touch", "Ne jamais toucher"),
+    ("One control name per line", "Un nom de contrôle par ligne"),
+    ("Save…", "Enregistrer…"),
+    ("Cancel", "Annuler"),
+    ("(no folder selected)", "(aucun dossier choisi)"),
+    ("Choose folder…", "Choisir un dossier…"),
+    ("Rescan", "Relire"),
+    ("Hot folder:", "Dossier surveillé :"),
+    ("Watch folder…", "Surveiller un dossier…"),
+    ("Stop", "Arrêter"),
+    ("Preset from another card:", "Preset d'une autre carte :"),
+    ("Filter:", "Filtre :"),
+    ("name, tag, author…", "nom, tag, auteur…"),
+    ("No presets found.", "Aucun preset trouvé."),
+    ("Load", "Charger"),
+    ("Preview", "Aperçu"),
+    ("MIDI Program Change that loads it, unless mapped in the config", "Program Change MIDI qui le charge, sauf correspondance dans la config"),
+    ("by {}", "par {}"),
+    ("{} ({} controls)", "{} ({} contrôles)"),
+    ("Preview: {}", "Aperçu : {}"),
+    ("Load this preset", "Charger ce preset"),
+    ("{} · {} controls · {}", "{} · {} contrôles · {}"),
+    ("Analog monitoring", "Monitoring analogique"),
+    ("Digital routing", "Routage digital"),
+    ("Mute Analog Monitoring", "Couper le monitoring analogique"),
+    ("Pass-through Analog Monitoring to Channel 1/2", "Monitoring analogique direct vers les canaux 1/2"),
+    ("Disable FX", "Désactiver les FX"),
+    ("Mute most digital routes", "Couper la plupart des routes digitales"),
+    ("Mute All Monitoring", "Couper tout le monitoring"),
+    ("Peak meters on the AIn headers, read from the capture PCM", "Crêtes sur les en-têtes AIn, lues depuis le PCM de capture"),
+    ("Peak meters on the Out headers: what PipeWire sends to each playback channel", "Crêtes sur les en-têtes Out : ce que PipeWire envoie à chaque canal de lecture"),
+    ("Meter bridge", "Pont de vumètres"),
+    ("Floating window with the meters and output mutes, kept on top of other windows", "Fenêtre flottante avec les vumètres et les mutes de sortie, gardée au-dessus des autres fenêtres"),
+    ("Signal LEDs in the headers instead of bars, refreshed 4 times a second", "LED de signal dans les en-têtes au lieu des barres, rafraîchies 4 fois par seconde"),
+    ("Peak (dBFS), RMS over 300 ms (dBFS) or short-term loudness per channel (LUFS, 3 s)", "Crête (dBFS), RMS sur 300 ms (dBFS) ou sonie court terme par canal (LUFS, 3 s)"),
+    ("Attack and release shape the peak bars; the reference is marked on every bar and sets where they turn yellow.", "L'attaque et le relâchement façonnent les barres de crête ; la référence est marquée sur chaque barre et fixe où elles passent au jaune."),
+    ("Meter ballistics and reference level", "Balistique des vumètres et niveau de référence"),
+    ("Compact matrix", "Matrice compacte"),
+    ("Show matrix cells as dB/percent numbers instead of knobs", "Afficher les cellules en dB/pourcentage au lieu de potentiomètres"),
+    ("Cells:", "Cellules :"),
+    ("Show all ({} hidden)", "Tout afficher ({} masqués)"),
+    ("Right-click a row or column header, or a cell, to hide it", "Clic droit sur un en-tête de ligne ou de colonne, ou sur une cellule, pour le masquer"),
+    ("Paint mode", "Mode peinture"),
+    ("Drag across a row to copy the first cell's level onto every cell touched; right-drag clears them", "Glisser le long d'une ligne copie le niveau de la première cellule sur chaque cellule touchée ; glisser au clic droit les efface"),
+    ("Reset aliases", "Réinitialiser les alias"),
+    ("Quick actions", "Actions rapides"),
+    ("FX → Outputs", "FX → Sorties"),
+    ("Effect Return level on each output, e.g. to keep reverb out of a cue mix", "Niveau de retour d'effet sur chaque sortie, p. ex. pour garder la réverb hors d'un mix casque"),
+    ("Stereo links:", "Liens stéréo :"),
+    ("Merge linked pairs", "Fusionner les paires liées"),
+    ("Dim the rest", "Atténuer le reste"),
+    ("Hold to talk (or hold T)", "Maintenir pour parler (ou maintenir T)"),
+    ("Scenes:", "Scènes :"),
+    ("Store {}", "Mémoriser {}"),
+    ("Save the current route levels as scene {}", "Enregistrer les niveaux de route actuels comme scène {}"),
+    ("Crossfade every route level from scene A to scene B", "Fondu de chaque niveau de route de la scène A vers la scène B"),
+    ("Headphone outputs", "Sorties casque"),
+    ("Select at least one output.", "Sélectionnez au moins une sortie."),
+    ("Input \\ Cue", "Entrée \\ Cue"),
+    ("No capture gain or input switch controls found on this card.", "Aucun gain de capture ni commutateur d'entrée trouvé sur cette carte."),
+    ("Appearance", "Apparence"),
+    ("Language:", "Langue :"),
+    ("UI scale", "Échelle de l'interface"),
+    ("Also Ctrl+= / Ctrl+- / Ctrl+0; applied when the slider is released", "Aussi Ctrl+= / Ctrl+- / Ctrl+0 ; appliquée au relâchement du curseur"),
+    ("Matrix", "Matrice"),
+    ("Compact matrix (numbers instead of knobs)", "Matrice compacte (nombres au lieu de potentiomètres)"),
+    ("Pan law", "Loi de pan"),
+    ("Knob", "Potentiomètre"),
+    ("Horizontal slider", "Curseur horizontal"),
+    ("Vertical fader", "Fader vertical"),
+    ("Knobs", "Potentiomètres"),
+    ("Drag range", "Course de glissement"),
+    ("Mouse travel from minimum to maximum; larger is slower", "Déplacement de la souris du minimum au maximum ; plus grand est plus lent"),
+    ("Drag:", "Glisser :"),
+    ("Vertical", "Vertical"),
+    ("Horizontal", "Horizontal"),
+    ("Invert direction", "Inverser le sens"),
+    ("Turn up by dragging down / left", "Monter en glissant vers le bas / la gauche"),
+    ("Fine adjustment:", "Réglage fin :"),
+    ("Hold while dragging to move ten times slower; Shift+drag also selects matrix cells", "Maintenir pendant le glissement pour aller dix fois moins vite ; Shift+glisser sélectionne aussi des cellules de la matrice"),
+    ("Safety", "Sécurité"),
+    ("Refresh rates", "Fréquences de rafraîchissement"),
+    ("Output limits", "Limites des sorties"),
+    ("Any write above the limit is clamped: knobs, presets, scenes, the command line and remote control", "Toute écriture au-dessus de la limite est écrêtée : potentiomètres, presets, scènes, ligne de commande et contrôle à distance"),
+    ("Limited to {} dB on this output", "Limité à {} dB sur cette sortie"),
+    ("Responsive", "Réactif"),
+    ("Mini", "Mini"),
+    ("Shrink to a small strip with output levels, panic mute and talkback", "Réduire à une petite bande avec les niveaux des sorties, la coupure d'urgence et le talkback"),
+    ("Back to the full mixer", "Revenir au mixeur complet"),
+    ("PANIC", "PANIQUE"),
+    ("Pick a talkback input in the full mixer first", "Choisissez d'abord une entrée de talkback dans le mixeur complet"),
+    ("Modified since the preset was loaded", "Modifié depuis le chargement du preset"),
+    ("No preset loaded", "Aucun preset chargé"),
+    ("Keep the mixer above other windows", "Garder le mixeur au-dessus des autres fenêtres"),
+    ("Startup", "Démarrage"),
+    ("Start minimized", "Démarrer réduit"),
+    ("The window opens minimized; restore it from the taskbar", "La fenêtre s'ouvre réduite ; la restaurer depuis la barre des tâches"),
+    ("Starts at login", "Démarre à l'ouverture de session"),
+    ("Remove autostart entry", "Supprimer le démarrage automatique"),
+    ("Create autostart entry", "Créer le démarrage automatique"),
+    ("Start the mixer minimized at login, via a desktop file in ~/.config/autostart", "Démarrer le mixeur réduit à l'ouverture de session, via un fichier desktop dans ~/.config/autostart"),
+    ("Battery saver", "Économie de batterie"),
+    ("Event fallback", "Secours des événements"),
+    ("Re-read the levels this often even without ALSA events, in case one was missed", "Relire les niveaux à cet intervalle même sans événement ALSA, au cas où l'un serait manqué"),
+    ("Auto refresh", "Rafraîchissement auto"),
+    ("Level polling when the card sends no ALSA events", "Lecture périodique des niveaux quand la carte n'envoie pas d'événements ALSA"),
+    ("Full refresh", "Rafraîchissement complet"),
+    ("Reload of the whole control list, to pick up controls that appear or vanish", "Rechargement de toute la liste des contrôles, pour détecter ceux qui apparaissent ou disparaissent"),
+    ("Feedback max", "Feedback max"),
+    ("Ceiling on Effect Feedback, also enforced on presets and scenes", "Plafond de l'Effect Feedback, aussi appliqué aux presets et aux scènes"),
+    ("Random FX feedback max", "Feedback max des FX aléatoires"),
+    ("Random FX level cap", "Plafond de niveau des FX aléatoires"),
+    ("Attenuation of the Dim buttons", "Atténuation des boutons Dim"),
+    ("Pink noise timeout", "Durée max du bruit rose"),
+    ("The tone generator stops pink noise on its own after this long", "Le générateur arrête le bruit rose de lui-même après cette durée"),
+    ("Card", "Carte"),
+    ("Current: {} (hw:{})", "Actuelle : {} (hw:{})"),
+    ("Default card:", "Carte par défaut :"),
+    ("Auto (FTU)", "Auto (FTU)"),
+    ("Used at the next start when --card is not given.", "Utilisée au prochain démarrage sans --card."),
+    ("Library:", "Bibliothèque :"),
+    ("Author:", "Auteur :"),
+    ("Scratch recordings:", "Enregistrements rapides :"),
+    ("Integrations", "Intégrations"),
+    ("Local command socket", "Socket de commande local"),
+    ("Global hotkeys", "Raccourcis globaux"),
+    ("MIDI program changes", "Program Changes MIDI"),
+    ("Gamepad", "Manette"),
+    ("These take effect at the next start.", "Pris en compte au prochain démarrage."),
+    ("Monitoring & Routing", "Monitoring & Routage"),
+    ("Mix per output", "Mix par sortie"),
+    ("Headphone mix", "Mix casque"),
+    ("Inputs", "Entrées"),
+    ("Settings", "Réglages"),
+    ("No routes found.", "Aucune route trouvée."),
+    ("Mono check: send each input equally to L and R, restore on release", "Contrôle mono : envoie chaque entrée également à G et D, rétabli au relâchement"),
+    ("Pan (double-click: center)", "Pan (double-clic : centre)"),
+    ("Effects (FX)", "Effets (FX)"),
+    ("Dedicated FX controls of the Fast Track Ultra.", "Contrôles FX dédiés de la Fast Track Ultra."),
+    ("No FX control found on this card.", "Aucun contrôle FX détecté sur cette carte."),
+    ("Cut the tail", "Couper la traîne"),
+    ("Mute the FX returns while the program changes, then bring them back", "Couper les retours FX pendant le changement de programme, puis les rétablir"),
+    ("Duration / Feedback", "Durée / Feedback"),
+    ("Duration", "Durée"),
+    ("Feedback only acts on Delay / Echo.", "Feedback n'agit que sur Delay / Echo."),
+    ("Other FX controls ({})", "Autres contrôles FX ({})"),
+    ("FX amount", "Quantité de FX"),
+    ("Scales every FX send and return together, keeping their balance", "Ajuste ensemble tous les envois et retours FX en gardant leur équilibre"),
+    ("Preset FX…", "Preset FX…"),
+    ("No FX preset", "Aucun preset FX"),
+    ("Save FX", "Enregistrer les FX"),
+    ("Save program, duration, feedback, sends and returns only", "Enregistrer seulement programme, durée, feedback, envois et retours"),
+    ("Surprise me", "Surprends-moi"),
+    ("Random program, duration and feedback within the ranges", "Programme, durée et feedback aléatoires dans les plages"),
+    ("Ranges…", "Plages…"),
+    ("Programs", "Programmes"),
+    ("Max sends / returns", "Envois / retours max"),
+    ("Compare:", "Comparer :"),
+    ("Switch to FX state {}", "Passer à l'état FX {}"),
+    ("Copy {} to {}", "Copier {} vers {}"),
+    ("Copy the current FX state into {}", "Copier l'état FX actuel dans {}"),
+    ("FX sends", "Envois FX"),
+    ("Mapping…", "Correspondance…"),
+    ("Choose the FX send control of each input by hand", "Choisir à la main le contrôle d'envoi FX de chaque entrée"),
+    ("No FX send found on this card.", "Aucun envoi FX détecté sur cette carte."),
+    ("FX returns", "Retours FX"),
+    ("No FX return found on this card.", "Aucun retour FX détecté sur cette carte."),
+    ("Effect processor", "Processeur d'effets"),
+    ("Copy as JSON", "Copier en JSON"),
+    ("Effect Program", "Programme d'effet"),
+    ("⇄ Outs as rows", "⇄ Out en lignes"),
+    ("Transpose: outputs as rows, inputs as columns", "Transposer : sorties en lignes, entrées en colonnes"),
+    ("Overall level of each output: scales every route feeding it", "Niveau global de chaque sortie : ajuste toutes les routes qui l'alimentent"),
+    ("Raw: {} (range {}..{})", "Brut : {} (plage {}..{})"),
+    ("Muted — right-click to unmute", "Coupé — clic droit pour rétablir"),
+    ("Enter exact value…", "Saisir une valeur exacte…"),
+    ("Locked controls are skipped by Disable FX, quick actions and presets", "Les contrôles verrouillés sont ignorés par Désactiver les FX, les actions rapides et les presets"),
+    ("Copy value", "Copier la valeur"),
+    ("Paste value", "Coller la valeur"),
+    ("Copy {} cells as JSON", "Copier {} cellules en JSON"),
+    ("Ctrl+V in another FTU mixer window applies it", "Ctrl+V dans une autre fenêtre du mixeur FTU l'applique"),
+    ("Control info", "Infos du contrôle"),
+    ("Integer {}..={}, step {}, {} ch", "Entier {}..={}, pas {}, {} canaux"),
+    ("dB range {}..={} dB", "Plage dB {}..={} dB"),
+    ("No dB range", "Pas de plage dB"),
+    ("Raw values: {}", "Valeurs brutes : {}"),
+    ("Exact value", "Valeur exacte"),
+    ("Raw value, {}..={}", "Valeur brute, {}..={}"),
+    ("Selection", "Sélection"),
+    ("{} cells selected (Ctrl+click to toggle, Shift+drag to add, Esc to clear)", "{} cellules sélectionnées (Ctrl+clic pour basculer, Maj+glisser pour ajouter, Échap pour effacer)"),
+    ("Relative trim", "Ajustement relatif"),
+    ("Apply offset", "Appliquer le décalage"),
+    ("Absolute level", "Niveau absolu"),
+    ("Set all", "Tout régler"),
+    ("Clear selection", "Effacer la sélection"),
+    ("Identify: beeps as many times as the output number on this output only", "Identifier : bipe autant de fois que le numéro de la sortie, sur cette sortie seulement"),
+    ("Copy {} routes", "Copier les routes de {}"),
+    ("Paste onto {}", "Coller sur {}"),
+    ("Unhide {}", "Réafficher {}"),
+    ("Hide {}", "Masquer {}"),
+    ("Identify {} (beeps)", "Identifier {} (bips)"),
+    ("Unmapped type: {}", "Type non mappé : {}"),
+    ("Solo: mute the other inputs on the outputs this input feeds", "Solo : coupe les autres entrées sur les sorties que cette entrée alimente"),
+    ("FX send mapping", "Correspondance des envois FX"),
+    ("Auto uses the name heuristic; pick a control when it guesses wrong on your kernel.", "Auto se fie aux noms ; choisissez un contrôle quand il se trompe sur votre noyau."),
+    ("Auto (none found)", "Auto (aucun trouvé)"),
+    ("No send", "Aucun envoi"),
+    ("Confirm", "Valider"),
+    ("{}% — click to type a dB value", "{} % — cliquer pour saisir une valeur en dB"),
+    ("Restore the FX sends and returns exactly as they were", "Rétablir les envois et retours FX exactement comme avant"),
+    ("Zero every FX send and return, remembering their levels", "Mettre à zéro tous les envois et retours FX en mémorisant leurs niveaux"),
+    ("No analog monitoring routes found.", "Aucune route de monitoring analogique trouvée."),
+    ("No routes found for this group.", "Aucune route trouvée pour ce groupe."),
+    ("Unmute (restore previous level)", "Rétablir (niveau précédent)"),
+    ("Mute (remember current level)", "Couper (mémorise le niveau actuel)"),
+    ("Unmute", "Rétablir"),
+    ("Mute", "Couper"),
+    ("🔓 Unlock", "🔓 Déverrouiller"),
+    ("★ Favorites", "★ Favoris"),
+    ("All controls", "Tous les contrôles"),
+    ("Search:", "Recherche :"),
+    ("e.g. ain1 out3", "ex. ain1 out3"),
+    ("Kind", "Type"),
+    ("Group", "Groupe"),
+    ("Interface", "Interface"),
+    ("All", "Tous"),
+    ("{} of {} controls", "{} contrôles sur {}"),
+    ("Value", "Valeur"),
+    ("Add a note", "Ajouter une note"),
+    ("Save", "Enregistrer"),
+    ("inactive", "inactif"),
+    ("Needs a pattern and an existing preset file", "Il faut un motif et un fichier de preset existant"),
+    ("Note", "Note"),
+    ("📝 Note…", "📝 Note…"),
+    ("e.g. kick drum mic — don't send to the singer's cue", "ex. micro grosse caisse — ne pas envoyer dans le retour du chanteur"),
+    ("An empty note removes it.", "Une note vide est supprimée."),
+    ("Hide this control", "Masquer ce contrôle"),
+    ("Unhide this control", "Réafficher ce contrôle"),
+    ("Hidden controls leave the matrices and the All controls tab until \"Show all\"", "Les contrôles masqués quittent les matrices et l'onglet Tous les contrôles jusqu'à « Tout afficher »"),
+    ("Show hidden ({})", "Afficher les masqués ({})"),
+    ("Right-click a control name to hide it", "Clic droit sur le nom d'un contrôle pour le masquer"),
+    ("Analog Routing", "Routage analogique"),
+    ("Digital Routing", "Routage digital"),
+    ("Effects", "Effets"),
+    ("Other", "Autres"),
+    ("Add to favorites", "Ajouter aux favoris"),
+    ("Remove from favorites", "Retirer des favoris"),
+    ("☆ Add to favorites", "☆ Ajouter aux favoris"),
+    ("★ Remove from favorites", "★ Retirer des favoris"),
+    ("No favorites yet: star a control with ☆, or use the menu of a matrix cell.", "Aucun favori pour l'instant : étoilez un contrôle avec ☆, ou passez par le menu d'une cellule de la matrice."),
+    ("🔒 Lock", "🔒 Verrouiller"),
+    ("Release: restore the routes to this output", "Relâcher : rétablir les routes vers cette sortie"),
+    ("Lower every route to this output by the Dim amount", "Baisser toutes les routes vers cette sortie de la valeur du Dim"),
+    ("Locked: skipped by Disable FX, quick actions and presets", "Verrouillé : ignoré par Désactiver les FX, les actions rapides et les presets"),
+    ("Lock against Disable FX, quick actions and presets", "Verrouiller contre Désactiver les FX, les actions rapides et les presets"),
+    ("Ignore", "Ignorer"),
+    ("Warn", "Avertir"),
+    ("Refuse", "Refuser"),
+    ("Small", "Petit"),
+    ("Medium", "Moyen"),
+    ("Large", "Grand"),
+    ("Reverb", "Réverb"),
+    ("Delay", "Delay"),
+    ("Decay", "Déclin"),
+    ("Delay time", "Temps de delay"),
+    ("Peak", "Crête"),
+    ("-3 dB (constant power)", "-3 dB (puissance constante)"),
+    ("-6 dB (linear)", "-6 dB (linéaire)"),
+    ("0 dB (balance)", "0 dB (balance)"),
+    ("Dark", "Sombre"),
+    ("Light", "Clair"),
+    ("High contrast", "Contraste élevé"),
+    ("Standard", "Standard"),
+    ("Remove", "Supprimer"),
+    ("Mute {}", "Couper {}"),
+    ("Unmute {}", "Rétablir {}"),
+    ("Solo {}", "Solo {}"),
+    ("Master {}", "Master {}"),
+    ("Dim {}", "Atténuer {}"),
+    ("Identify {}", "Identifier {}"),
+    ("Snapshot {}", "Instantané {}"),
+    ("Red-green safe", "Adapté rouge-vert"),
+    ("Blue-yellow safe", "Adapté bleu-jaune"),
+    ("State colors:", "Couleurs des états :"),
+    ("Patterns and symbols for states", "Motifs et symboles pour les états"),
+    ("Hatch muted cells and clipping meters, and mark linked channels with 🔗", "Hachurer les cellules coupées et les vumètres qui saturent, et marquer les canaux liés avec 🔗"),
+    ("Attack", "Attaque"),
+    ("Release", "Relâchement"),
+    ("Peak hold", "Maintien de crête"),
+    ("Reference", "Référence"),
+    ("Set to 0 dB", "Régler à 0 dB"),
+    ("Set to max", "Régler au max"),
+    ("Custom accent", "Accent personnalisé"),
+    ("Custom background", "Fond personnalisé"),
+    ("Analog inputs", "Entrées analogiques"),
+    ("Digital playback", "Lectures digitales"),
+    ("Feedback", "Feedback"),
+    ("Ready ({} backend)", "Prêt (backend {})"),
+    ("Config load warning: {}", "Avertissement au chargement de la configuration : {}"),
+    ("OSC disabled: {}", "OSC désactivé : {}"),
+    ("FX preset load warning: {}", "Avertissement au chargement des presets FX : {}"),
+    ("Startup preset load failed: {}", "Échec du chargement du preset de démarrage : {}"),
+    ("Script loaded: {}", "Script chargé : {}"),
+    ("Script disabled: {}", "Script désactivé : {}"),
+    ("Control catalog refreshed", "Catalogue des contrôles actualisé"),
+    ("Refresh failed: {}", "Échec de l'actualisation : {}"),
+    (" — the card is held by {} (pid {})", " — la carte est occupée par {} (pid {})"),
+    ("Write failed for {}: {}", "Échec de l'écriture de {} : {}"),
+    ("Updated {}", "{} mis à jour"),
+    ("Reload failed for {}: {}", "Échec de la relecture de {} : {}"),
+    ("Live refresh failed: {}", "Échec de l'actualisation en direct : {}"),
+    ("Preset loaded: {} ({} controls)", "Preset chargé : {} ({} contrôles)"),
+    (" — warning: {}", " — attention : {}"),
+    ("Preset fade stopped: {}", "Fondu du preset interrompu : {}"),
+    ("Snapshot {} stored", "Instantané {} mémorisé"),
+    ("Snapshot {} is empty", "L'instantané {} est vide"),
+    ("Snapshot {} recalled ({} controls)", "Instantané {} rappelé ({} contrôles)"),
+    ("Snapshot {} recall failed: {}", "Échec du rappel de l'instantané {} : {}"),
+    ("Load failed: {}", "Échec du chargement : {}"),
+    ("Diagram exported to {}", "Schéma exporté vers {}"),
+    ("Diagram export failed: {}", "Échec de l'export du schéma : {}"),
+    ("TALK (T)", "PARLER (T)"),
+    ("Factory preset \"{}\" applied ({} controls)", "Preset d'usine \"{}\" appliqué ({} contrôles)"),
+    ("Factory preset failed: {}", "Échec du preset d'usine : {}"),
+    ("Template load warning: {}", "Avertissement au chargement des modèles : {}"),
+    ("Template \"{}\" applied ({} controls)", "Modèle \"{}\" appliqué ({} contrôles)"),
+    ("Template failed: {}", "Échec du modèle : {}"),
+    ("Imported {} ({} controls) — use Save preset to keep it", "{} importé ({} contrôles) — utilisez Enregistrer le preset pour le conserver"),
+    ("Import failed: {}", "Échec de l'import : {}"),
+    ("Preset saved: {}", "Preset enregistré : {}"),
+    ("Save failed: {}", "Échec de l'enregistrement : {}"),
+    ("Preset library scan failed: {}", "Échec de l'analyse de la bibliothèque de presets : {}"),
+    ("Hot-folder preset rejected ({}): {}", "Preset du dossier surveillé refusé ({}) : {}"),
+    ("HTTP API disabled: {}", "API HTTP désactivée : {}"),
+    ("HTTP API on http://{} (token in ~/.ftu-mixer/config.json)", "API HTTP sur http://{} (jeton dans ~/.ftu-mixer/config.json)"),
+    ("MQTT: connecting to {}", "MQTT : connexion à {}"),
+    ("MQTT connected to {} (topics under {}/)", "MQTT connecté à {} (topics sous {}/)"),
+    ("MQTT: {}, retrying", "MQTT : {}, nouvelle tentative"),
+    ("Event hook: {}", "Hook d'événement : {}"),
+    ("Script preset failed ({}): {}", "Échec du preset du script ({}) : {}"),
+    ("Script: {}", "Script : {}"),
+    ("MIDI program {}: no preset mapped", "Programme MIDI {} : aucun preset associé"),
+    ("MIDI program {} — {}", "Programme MIDI {} — {}"),
+    ("MIDI program {} failed ({}): {}", "Échec du programme MIDI {} ({}) : {}"),
+    ("Gamepad connected: {}", "Manette connectée : {}"),
+    ("Gamepad disconnected: {}", "Manette déconnectée : {}"),
+    ("Gamepad: {} (unmapped)", "Manette : {} (non associé)"),
+    ("Gamepad: no control named \"{}\"", "Manette : aucun contrôle nommé \"{}\""),
+    ("Input meters need the card on this machine", "Les vumètres d'entrée nécessitent la carte sur cette machine"),
+    ("Input meters on {}", "Vumètres d'entrée sur {}"),
+    ("Input meters unavailable: {}", "Vumètres d'entrée indisponibles : {}"),
+    ("Input meters stopped ({}): {}", "Vumètres d'entrée arrêtés ({}) : {}"),
+    ("Output meters stopped ({}): {}", "Vumètres de sortie arrêtés ({}) : {}"),
+    (" — {} holds the card; set metering.capture_device to a loopback or dsnoop device", " — {} occupe la carte ; réglez metering.capture_device sur un périphérique loopback ou dsnoop"),
+    ("Output meters need the card on this machine", "Les vumètres de sortie nécessitent la carte sur cette machine"),
+    ("Output meters unavailable: no PipeWire sink for this card", "Vumètres de sortie indisponibles : aucune sortie PipeWire pour cette carte"),
+    ("Output meters on {}", "Vumètres de sortie sur {}"),
+    ("Output meters unavailable: {}", "Vumètres de sortie indisponibles : {}"),
+    ("Identify needs the card on this machine", "L'identification nécessite la carte sur cette machine"),
+    ("No DIn is routed to {}; open one in the digital matrix to identify it", "Aucune DIn n'est routée vers {} ; ouvrez-en une dans la matrice digitale pour l'identifier"),
+    ("{}: {} beep(s) via {}", "{} : {} bip(s) via {}"),
+    ("Identify failed: {}", "Échec de l'identification : {}"),
+    ("The tone generator needs the card on this machine", "Le générateur de signal nécessite la carte sur cette machine"),
+    ("{} on {} ({})", "{} sur {} ({})"),
+    ("Tone generator unavailable: {}", "Générateur de signal indisponible : {}"),
+    (" — {} holds the card; set generator.playback_device to e.g. \"pipewire\"", " — {} occupe la carte ; réglez generator.playback_device sur par ex. \"pipewire\""),
+    ("Tone generator stopped: {}", "Générateur de signal arrêté : {}"),
+    ("Pink noise stopped after its timeout", "Bruit rose arrêté après son délai"),
+    ("Tone stopped", "Signal arrêté"),
+    ("via {}", "via {}"),
+    ("The loopback test needs the card on this machine", "Le test en boucle nécessite la carte sur cette machine"),
+    ("No DIn is routed to {}; open one in the digital matrix first", "Aucune DIn n'est routée vers {} ; ouvrez-en d'abord une dans la matrice digitale"),
+    ("Measuring round-trip latency…", "Mesure de la latence aller-retour…"),
+    ("Sweeping…", "Balayage…"),
+    ("Recording needs the card on this machine", "L'enregistrement nécessite la carte sur cette machine"),
+    ("Recording failed: {}", "Échec de l'enregistrement : {}"),
+    ("Recording {} s…", "Enregistrement de {} s…"),
+    ("Playing {} on {} + {}", "Lecture de {} sur {} + {}"),
+    ("Playback failed: {}", "Échec de la lecture : {}"),
+    ("Recorded {}", "{} enregistré"),
+    ("Round-trip latency: {} samples ({} ms)", "Latence aller-retour : {} échantillons ({} ms)"),
+    ("Latency test failed: {}", "Échec du test de latence : {}"),
+    ("Sweep done ({} points)", "Balayage terminé ({} points)"),
+    ("Sweep failed: {}", "Échec du balayage : {}"),
+    ("{} was changed back right after our write — {}", "{} a été remis en arrière juste après notre écriture — {}"),
+    ("Global shortcuts active ({}): Ctrl+Alt+M, Ctrl+Alt+T, Ctrl+Alt+1…8", "Raccourcis globaux actifs ({}) : Ctrl+Alt+M, Ctrl+Alt+T, Ctrl+Alt+1…8"),
+    ("Global shortcuts unavailable: {}", "Raccourcis globaux indisponibles : {}"),
+    ("OSC: {}", "OSC : {}"),
+    ("\"{}\" is running", "\"{}\" est lancé"),
+    ("no trigger active", "aucun déclencheur actif"),
+    ("App trigger preset failed ({}): {}", "Échec du preset déclenché par une application ({}) : {}"),
+    ("Tags", "Étiquettes"),
+    ("Description", "Description"),
+    ("Tags: {}", "Étiquettes : {}"),
+    ("Preview failed: {}", "Échec de l'aperçu : {}"),
+    ("{} hidden", "{} masqué"),
+    ("{} shown", "{} affiché"),
+    ("Talkback:", "Talkback :"),
+    ("Scene {} stored ({} routes)", "Scène {} mémorisée ({} routes)"),
+    ("Crossfade A→B: {}%", "Fondu A→B : {} %"),
+    ("Talkback off", "Talkback désactivé"),
+    ("Talkback: choose an input first", "Talkback : choisissez d'abord une entrée"),
+    ("Talkback: {} → cues", "Talkback : {} → retours"),
+    ("Autostart entry removed", "Entrée de démarrage automatique supprimée"),
+    ("Autostart entry not removed: {}", "Entrée de démarrage automatique non supprimée : {}"),
+    ("Autostart entry written to {}", "Entrée de démarrage automatique écrite dans {}"),
+    ("Autostart entry not written: {}", "Entrée de démarrage automatique non écrite : {}"),
+    ("FX returns restored after program change", "Retours FX rétablis après le changement de programme"),
+    ("FX amount: {} dB", "Quantité d'FX : {} dB"),
+    ("FX bypass off: sends and returns restored", "Bypass FX désactivé : envois et retours rétablis"),
+    ("FX bypassed ({} sends/returns zeroed)", "FX en bypass ({} envois/retours mis à zéro)"),
+    ("Nothing to randomize (no unlocked FX controls)", "Rien à tirer au sort (aucun contrôle FX déverrouillé)"),
+    ("FX randomized: {} ({} sends/returns capped)", "FX tiré au sort : {} ({} envois/retours plafonnés)"),
+    ("FX randomized: {}", "FX tiré au sort : {}"),
+    ("FX preset \"{}\" saved ({} controls) to {}", "Preset FX \"{}\" enregistré ({} contrôles) dans {}"),
+    ("FX preset save failed: {}", "Échec de l'enregistrement du preset FX : {}"),
+    ("FX preset \"{}\" applied ({} changed, {} controls not on this card)", "Preset FX \"{}\" appliqué ({} modifiés, {} contrôles absents de cette carte)"),
+    ("FX preset \"{}\" applied ({} changed)", "Preset FX \"{}\" appliqué ({} modifiés)"),
+    ("FX state {} copied to {}", "État FX {} copié dans {}"),
+    ("FX state {} (copy of the current settings)", "État FX {} (copie des réglages actuels)"),
+    ("FX state {}", "État FX {}"),
+    ("numid={} {} (index {})", "numid={} {} (index {})"),
+    ("Invalid value '{}'", "Valeur invalide '{}'"),
+    ("Nothing to undo", "Rien à annuler"),
+    ("Undo: restored {} control(s)", "Annulation : {} contrôle(s) rétabli(s)"),
+    ("Copied {}", "{} copié"),
+    ("Adjusted {} routes", "{} routes ajustées"),
+    ("Copied {} as JSON", "{} copié en JSON"),
+    ("Copied {} controls as JSON", "{} contrôles copiés en JSON"),
+    ("Pasted {} controls from the clipboard", "{} contrôles collés depuis le presse-papiers"),
+    (" — skipped (locked or absent): {}", " — ignorés (verrouillés ou absents) : {}"),
+    ("No route at this position", "Aucune route à cette position"),
+    ("Pasted {} onto {} ({} routes)", "{} collé sur {} ({} routes)"),
+    ("Trim", "Ajustement"),
+    ("Master {}: {} dB", "Master {} : {} dB"),
+    ("Dim released: {}", "Atténuation relâchée : {}"),
+    ("Dim {}: {} dB", "Atténuation {} : {} dB"),
+    ("Unmuted: {}", "Rétabli : {}"),
+    ("Muted: {}", "Coupé : {}"),
+    ("Stereo restored: {}", "Stéréo rétablie : {}"),
+    ("Mono: {}", "Mono : {}"),
+    ("Trimmed {} by {} dB ({} routes)", "{} ajusté de {} dB ({} routes)"),
+    ("Solo released: {}", "Solo relâché : {}"),
+    ("Solo: {}", "Solo : {}"),
+    ("Unmuted {}", "{} rétabli"),
+    ("Muted {}", "{} coupé"),
+    ("Mute analog monitoring applied", "Coupure du monitoring analogique appliquée"),
+    ("Pass-through analog monitoring to channel 1/2 applied", "Monitoring analogique direct vers les canaux 1/2 appliqué"),
+    ("FX controls disabled", "Contrôles FX désactivés"),
+    ("Most digital routes muted", "La plupart des routes digitales coupées"),
+    ("Mute all monitoring applied", "Coupure de tout le monitoring appliquée"),
+    ("{} locked", "{} verrouillé"),
+    ("{} unlocked", "{} déverrouillé"),
+    ("{} added to favorites", "{} ajouté aux favoris"),
+    ("{} removed from favorites", "{} retiré des favoris"),
+    ("Configuration saved to ~/.ftu-mixer/config.json", "Configuration enregistrée dans ~/.ftu-mixer/config.json"),
+    ("Config save failed: {}", "Échec de l'enregistrement de la configuration : {}"),
+    ("FX send mapping saved", "Affectation des envois FX enregistrée"),
+    ("UI scale {}%", "Échelle de l'interface {} %"),
+    ("MIDI preset select disabled: {}", "Sélection de preset MIDI désactivée : {}"),
+    ("Gamepad input disabled: {}", "Entrée manette désactivée : {}"),
+    ("IPC socket disabled: {}", "Socket IPC désactivé : {}"),
+];
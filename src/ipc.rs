@@ -0,0 +1,270 @@
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Write},
+    os::unix::{
+        fs::PermissionsExt,
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::http_api::{self, ApiCall, ApiRequest, ApiResponse, Notify, QuickAction};
+
+type Subscribers = Arc<Mutex<Vec<Sender<String>>>>;
+
+/// `$XDG_RUNTIME_DIR/ftu-mixer.sock`, or `~/.ftu-mixer/ftu-mixer.sock`
+/// when there is no runtime dir.
+pub fn socket_path() -> Result<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_RUNTIME_DIR").filter(|d| !d.is_empty()) {
+        return Ok(Path::new(&dir).join("ftu-mixer.sock"));
+    }
+    let home = env::var("HOME").context("HOME environment variable is not set")?;
+    Ok(Path::new(&home).join(".ftu-mixer").join("ftu-mixer.sock"))
+}
+
+/// One JSON-RPC 2.0 request per line.
+#[derive(Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Handle on the running socket server, polled by the UI thread like the
+/// HTTP API.
+pub struct IpcServer {
+    pub calls: Receiver<ApiCall>,
+    subscribers: Subscribers,
+    path: PathBuf,
+}
+
+impl IpcServer {
+    pub fn has_subscribers(&self) -> bool {
+        self.subscribers.lock().map(|s| !s.is_empty()).unwrap_or(false)
+    }
+
+    /// Sends a `changes` notification to every subscribed connection.
+    pub fn publish(&self, controls: &Value) {
+        let line = json!({ "jsonrpc": "2.0", "method": "changes", "params": { "controls": controls } }).to_string();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|s| s.send(line.clone()).is_ok());
+        }
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Listens on the user-only socket at `socket_path()`. A socket left behind by
+/// a crashed instance is replaced; one that still answers is not.
+pub fn start<F>(notify: F) -> Result<IpcServer>
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    let path = socket_path()?;
+    if path.exists() {
+        anyhow::ensure!(
+            UnixStream::connect(&path).is_err(),
+            "Another instance is listening on {}",
+            path.display()
+        );
+        let _ = fs::remove_file(&path);
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let listener = UnixListener::bind(&path).with_context(|| format!("Failed to listen on {}", path.display()))?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+        .with_context(|| format!("Failed to restrict {}", path.display()))?;
+    let (tx, rx) = mpsc::channel();
+    let subscribers: Subscribers = Arc::new(Mutex::new(Vec::new()));
+    let notify: Notify = Arc::new(notify);
+    let server_subscribers = subscribers.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let tx = tx.clone();
+            let notify = notify.clone();
+            let subscribers = server_subscribers.clone();
+            thread::spawn(move || session(stream, tx, notify, subscribers));
+        }
+    });
+    Ok(IpcServer {
+        calls: rx,
+        subscribers,
+        path,
+    })
+}
+
+/// Answers requests on one connection; replies and change notifications
+/// share a writer thread so they never interleave mid-line.
+fn session(stream: UnixStream, tx: Sender<ApiCall>, notify: Notify, subscribers: Subscribers) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    let (out_tx, out_rx) = mpsc::channel::<String>();
+    thread::spawn(move || {
+        for line in out_rx {
+            if writeln!(writer, "{line}").is_err() {
+                break;
+            }
+        }
+    });
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request = match serde_json::from_str::<RpcRequest>(&line) {
+            Ok(request) => request,
+            Err(err) => {
+                let _ = out_tx.send(rpc_error(Value::Null, -32700, format!("Parse error: {err}")));
+                continue;
+            }
+        };
+        if request.method == "subscribe" {
+            if let Ok(mut subscribers) = subscribers.lock() {
+                subscribers.push(out_tx.clone());
+            }
+            let _ = out_tx.send(json!({ "jsonrpc": "2.0", "id": request.id, "result": true }).to_string());
+            continue;
+        }
+        let reply = match parse_request(&request.method, &request.params) {
+            Ok(api_request) => match http_api::call(api_request, &tx, &notify) {
+                Some(response) => rpc_reply(request.id, response),
+                None => break,
+            },
+            Err((code, message)) => rpc_error(request.id, code, message),
+        };
+        if out_tx.send(reply).is_err() {
+            break;
+        }
+    }
+}
+
+fn parse_request(method: &str, params: &Value) -> Result<ApiRequest, (i64, String)> {
+    let invalid = |what: &str| (-32602, format!("Invalid params: expected {what}"));
+    let numid = || {
+        params
+            .get("numid")
+            .and_then(Value::as_u64)
+            .map(|n| n as u32)
+            .ok_or_else(|| invalid("{\"numid\": N}"))
+    };
+    match method {
+        "list" => Ok(ApiRequest::ListControls),
+        "get" => Ok(ApiRequest::GetControl(numid()?)),
+        "set" => {
+            let values = params
+                .get("values")
+                .and_then(|v| serde_json::from_value::<Vec<String>>(v.clone()).ok())
+                .ok_or_else(|| invalid("{\"numid\": N, \"values\": [\"...\"]}"))?;
+            Ok(ApiRequest::SetControl(numid()?, values))
+        }
+        "apply" => params
+            .get("name")
+            .and_then(Value::as_str)
+            .map(|name| ApiRequest::ApplyPreset(name.to_string()))
+            .ok_or_else(|| invalid("{\"name\": \"...\"}")),
+        "action" => {
+            let name = params.get("name").and_then(Value::as_str).ok_or_else(|| invalid("{\"name\": \"...\"}"))?;
+            QuickAction::ALL
+                .into_iter()
+                .find(|a| a.name() == name)
+                .map(ApiRequest::QuickAction)
+                .ok_or_else(|| (-32602, format!("Unknown action \"{name}\"")))
+        }
+        _ => Err((-32601, format!("Method not found: {method}"))),
+    }
+}
+
+fn rpc_reply(id: Value, response: ApiResponse) -> String {
+    if response.status == 200 {
+        json!({ "jsonrpc": "2.0", "id": id, "result": response.body }).to_string()
+    } else {
+        let message = response.body["error"].as_str().unwrap_or("Request failed").to_string();
+        rpc_error(id, -32000 - response.status as i64, message)
+    }
+}
+
+fn rpc_error(id: Value, code: i64, message: String) -> String {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }).to_string()
+}
+
+/// Thin client used by `ftu-rust-mixer <command>`: talks to the running
+/// instance and prints what it answers.
+pub struct IpcClient {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+    next_id: u64,
+}
+
+impl IpcClient {
+    pub fn connect() -> Result<Self> {
+        let path = socket_path()?;
+        let writer = UnixStream::connect(&path)
+            .with_context(|| format!("No running mixer on {} (start the GUI first)", path.display()))?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(Self {
+            reader,
+            writer,
+            next_id: 1,
+        })
+    }
+
+    pub fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let line = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        writeln!(self.writer, "{line}").context("Failed to send the request")?;
+        loop {
+            let message = self.read_message()?;
+            if message["id"] != json!(id) {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                anyhow::bail!("{}", error["message"].as_str().unwrap_or("Request failed"));
+            }
+            return Ok(message["result"].clone());
+        }
+    }
+
+    /// Next line from the server, e.g. a `changes` notification after `subscribe`.
+    pub fn read_message(&mut self) -> Result<Value> {
+        let mut line = String::new();
+        let read = self.reader.read_line(&mut line).context("Failed to read the reply")?;
+        anyhow::ensure!(read > 0, "The mixer closed the connection");
+        serde_json::from_str(&line).context("Malformed reply")
+    }
+
+    /// Numid of a control given by numid or by exact name.
+    pub fn resolve(&mut self, control: &str) -> Result<u32> {
+        if let Ok(numid) = control.parse() {
+            return Ok(numid);
+        }
+        let controls = self.request("list", Value::Null)?;
+        controls
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|c| c["name"] == control)
+            .and_then(|c| c["numid"].as_u64())
+            .map(|n| n as u32)
+            .with_context(|| format!("No control named \"{control}\""))
+    }
+}
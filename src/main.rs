@@ -1,14 +1,50 @@
 mod alsa_backend;
 mod app;
+mod app_triggers;
+mod audio_server;
+mod autostart;
+mod ceilings;
+mod clipboard;
 mod config;
+mod converge;
+mod diagram;
+mod event_hooks;
+mod factory_presets;
+mod fuzzy;
+mod fx;
+mod gamepad;
+mod generator;
+mod global_hotkeys;
+mod hot_folder;
+mod http_api;
+mod i18n;
+mod ipc;
+mod measure;
+mod metering;
+mod metrics;
+mod midi;
 mod models;
+mod mqtt;
+mod osc;
+mod pan;
 mod presets;
+mod remote_backend;
+mod scratch;
+mod scripting;
+mod state_import;
+mod templates;
+mod theme;
+
+use std::path::Path;
 
 use anyhow::Result;
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::{Value, json};
 use eframe::{NativeOptions, Renderer};
 
-use crate::app::MixerApp;
+use crate::{
+    alsa_backend::AlsaBackend, app::MixerApp, config::AppUserConfig, ipc::IpcClient, remote_backend::RemoteBackend,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Fast Track Ultra mixer for Linux")]
@@ -21,10 +57,83 @@ struct Args {
     #[arg(long)]
     load_preset: Option<String>,
 
+    /// Folder to watch for preset files; new or rewritten presets are applied automatically
+    #[arg(long)]
+    watch_presets: Option<String>,
+
+    /// Send control changes as OSC bundles to this host:port (repeatable), on top of `osc_clients` in the config
+    #[arg(long = "osc-client")]
+    osc_clients: Vec<String>,
+
+    /// Serve the JSON HTTP API for this run even if it is off in the config
+    #[arg(long)]
+    http_api: bool,
+
+    /// Rhai script with event hooks, instead of `script_path` in the config
+    #[arg(long)]
+    script: Option<String>,
+
+    /// Register Ctrl+Alt+M (mute all), Ctrl+Alt+T (talkback) and Ctrl+Alt+1..8 (snapshots) system-wide
+    #[arg(long)]
+    global_hotkeys: bool,
+
+    /// Drive the card of a `--headless` instance at host:port instead of a local card;
+    /// the token comes from FTU_REMOTE_TOKEN or `remote_token` in the config.
+    /// The API is plain HTTP, so only loopback addresses are accepted: forward the
+    /// port with e.g. `ssh -L 7878:localhost:7878 host` and use `--remote localhost:7878`
+    #[arg(long, conflicts_with = "headless")]
+    remote: Option<String>,
+
+    /// Let `--remote` send the token and writes in clear text to a non-loopback address
+    #[arg(long, requires = "remote")]
+    allow_insecure_remote: bool,
+
+    /// Run without a window, serving the HTTP API (bind it with `http_api.bind`) for `--remote` clients
+    #[arg(long)]
+    headless: bool,
+
+    /// Open the window minimized, e.g. from a login autostart entry
+    #[arg(long, conflicts_with = "headless")]
+    minimized: bool,
+
     /// Graphics renderer: wgpu (default) or glow
     #[arg(long, value_enum, default_value_t = RenderMode::Wgpu)]
     render_mode: RenderMode,
 
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Commands run instead of opening a window; all but `converge` go to the
+/// running mixer over its Unix socket.
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print every control as JSON
+    List,
+    /// Print one control, by numid or exact name
+    Get { control: String },
+    /// Write a control's values, one per channel
+    Set {
+        control: String,
+        #[arg(required = true)]
+        values: Vec<String>,
+    },
+    /// Apply a preset of the library folder, by file name (`.json` optional)
+    Apply { name: String },
+    /// Run a quick action: mute-analog, pass-through, disable-fx, mute-digital or mute-all
+    Action { name: String },
+    /// Print control changes as they happen, one JSON line each
+    Subscribe,
+    /// Bring the card to the state described in a YAML file, writing only what differs
+    Converge {
+        file: String,
+        /// Report what would change without writing
+        #[arg(long)]
+        check: bool,
+        /// Print the report as one JSON object
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
@@ -34,8 +143,30 @@ enum RenderMode {
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
-    let app = MixerApp::bootstrap(args.card, args.load_preset.as_deref())?;
+    let mut args = Args::parse();
+    match args.command.take() {
+        Some(Command::Converge { file, check, json }) => {
+            return converge::run(Path::new(&file), &open_backend(&args)?, check, json);
+        }
+        Some(command) => return run_client(command),
+        None => {}
+    }
+    let backend = open_backend(&args)?;
+    let mut app = MixerApp::bootstrap(
+        backend,
+        args.load_preset.as_deref(),
+        args.watch_presets.as_deref(),
+        &args.osc_clients,
+        args.http_api,
+        args.script.as_deref(),
+        args.global_hotkeys,
+    )?;
+    if args.headless {
+        return app.run_headless();
+    }
+    if args.minimized {
+        app.start_minimized();
+    }
     let renderer = pick_renderer(args.render_mode);
 
     let native_options = NativeOptions {
@@ -52,6 +183,52 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+fn open_backend(args: &Args) -> Result<AlsaBackend> {
+    match &args.remote {
+        Some(address) => {
+            let token = std::env::var("FTU_REMOTE_TOKEN")
+                .ok()
+                .or_else(|| AppUserConfig::load_or_default().ok()?.remote_token)
+                .ok_or_else(|| anyhow::anyhow!("--remote needs FTU_REMOTE_TOKEN or remote_token in the config"))?;
+            if !args.allow_insecure_remote {
+                remote_backend::ensure_loopback(address)?;
+            }
+            AlsaBackend::connect_remote(RemoteBackend::new(address, &token))
+        }
+        None => {
+            let preferred = AppUserConfig::load_or_default().ok().and_then(|c| c.default_card);
+            AlsaBackend::pick_card(args.card, preferred.as_deref())
+        }
+    }
+}
+
+fn run_client(command: Command) -> Result<()> {
+    let mut client = IpcClient::connect()?;
+    let result = match command {
+        Command::List => client.request("list", Value::Null)?,
+        Command::Get { control } => {
+            let numid = client.resolve(&control)?;
+            client.request("get", json!({ "numid": numid }))?
+        }
+        Command::Set { control, values } => {
+            let numid = client.resolve(&control)?;
+            client.request("set", json!({ "numid": numid, "values": values }))?
+        }
+        Command::Apply { name } => client.request("apply", json!({ "name": name }))?,
+        Command::Action { name } => client.request("action", json!({ "name": name }))?,
+        Command::Subscribe => {
+            client.request("subscribe", Value::Null)?;
+            loop {
+                let message = client.read_message()?;
+                println!("{}", message["params"]);
+            }
+        }
+        Command::Converge { .. } => unreachable!("converge runs without the socket"),
+    };
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    Ok(())
+}
+
 fn pick_renderer(render_mode: RenderMode) -> Renderer {
     match render_mode {
         RenderMode::Wgpu => Renderer::Wgpu,
@@ -0,0 +1,332 @@
+use std::{
+    f32::consts::TAU,
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use alsa::{
+    Direction, ValueOr,
+    pcm::{Access, Format, HwParams, PCM},
+};
+use anyhow::{Context, Result};
+
+use crate::generator;
+
+/// Output and input joined by a cable for a measurement.
+#[derive(Debug, Clone)]
+pub struct Loopback {
+    pub playback_device: String,
+    pub playback_channels: u32,
+    /// Playback channel (DIn) sent to the output under test, 0-based.
+    pub output_channel: usize,
+    pub capture_device: String,
+    pub capture_channels: u32,
+    /// Capture channel (AIn) the cable comes back on, 0-based.
+    pub input_channel: usize,
+    /// Frames per period of both streams.
+    pub period: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyResult {
+    /// From a sample leaving the playback buffer to it arriving in the
+    /// capture buffer: converters, USB and the card's DSP.
+    pub frames: i64,
+    pub rate: u32,
+    pub period: i64,
+    /// Whether both streams started together; otherwise their trigger
+    /// timestamps were used to line them up.
+    pub linked: bool,
+}
+
+impl LatencyResult {
+    pub fn ms(&self, frames: i64) -> f64 {
+        frames as f64 * 1000.0 / self.rate as f64
+    }
+
+    /// What a duplex client (JACK, PipeWire) adds on top with this period
+    /// and two periods per buffer: the playback buffer plus one capture
+    /// period.
+    pub fn with_buffers(&self) -> i64 {
+        self.frames + self.period * 3
+    }
+}
+
+/// Gain from the output to the input across the audio band.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepResult {
+    /// Frequency (Hz) and gain (dB), from low to high.
+    pub points: Vec<(f32, f32)>,
+    pub latency_frames: i64,
+    pub rate: u32,
+}
+
+/// Captured input lined up with what was played: `samples[i]` was recorded
+/// while sample `i` of the signal left the playback buffer.
+struct Recording {
+    samples: Vec<f32>,
+    /// Peak of the input before anything was played.
+    noise: f32,
+    rate: u32,
+    period: i64,
+    linked: bool,
+}
+
+/// Plays a click and looks for it on the input, on a background thread.
+pub fn measure_latency(loopback: Loopback, notify: impl Fn() + Send + 'static) -> Receiver<Result<LatencyResult>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(latency(&loopback));
+        notify();
+    });
+    rx
+}
+
+/// Plays a click then a logarithmic sine sweep, on a background thread.
+pub fn measure_sweep(loopback: Loopback, notify: impl Fn() + Send + 'static) -> Receiver<Result<SweepResult>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(sweep(&loopback));
+        notify();
+    });
+    rx
+}
+
+/// A lone sample at -6 dBFS.
+const CLICK: f32 = 0.5;
+
+fn latency(loopback: &Loopback) -> Result<LatencyResult> {
+    // Half a second is more than any USB card needs.
+    let recording = play_and_record(loopback, |_| vec![CLICK], 0.5)?;
+    Ok(LatencyResult {
+        frames: click_front(loopback, &recording.samples, recording.noise)?,
+        rate: recording.rate,
+        period: recording.period,
+        linked: recording.linked,
+    })
+}
+
+/// Where the click shows up in `samples`.
+fn click_front(loopback: &Loopback, samples: &[f32], noise: f32) -> Result<i64> {
+    let peak = samples.iter().fold(0f32, |max, s| max.max(s.abs()));
+    if peak < (noise * 4.0).max(0.005) {
+        anyhow::bail!(
+            "No click came back on input {} — check the cable, the input gain and that the DIn is routed to the output",
+            loopback.input_channel + 1
+        );
+    }
+    // Converter filters smear the click; its front is where it crosses half
+    // of its peak.
+    Ok(samples.iter().position(|s| s.abs() >= peak * 0.5).unwrap_or_default() as i64)
+}
+
+fn sweep(loopback: &Loopback) -> Result<SweepResult> {
+    const START_HZ: f32 = 20.0;
+    const SECS: f32 = 4.0;
+    const LEVEL: f32 = 0.25;
+    // The click comes first and the sweep after this gap, so the latency
+    // found from the click lines the sweep up.
+    let gap = |rate: u32| (rate as f32 * 0.3) as usize;
+    let end_hz = |rate: u32| (rate as f32 * 0.45).min(20000.0);
+    // Time constant of the exponential sweep.
+    let time_constant = |rate: u32| SECS / (end_hz(rate) / START_HZ).ln();
+    let recording = play_and_record(
+        loopback,
+        |rate| {
+            let length = (SECS * rate as f32) as usize;
+            let fade = rate as usize / 20;
+            let l = time_constant(rate);
+            let mut signal = vec![0f32; gap(rate) + length];
+            signal[0] = CLICK;
+            for (i, sample) in signal[gap(rate)..].iter_mut().enumerate() {
+                let t = i as f32 / rate as f32;
+                let envelope = (i.min(length - i) as f32 / fade as f32).min(1.0);
+                *sample = LEVEL * envelope * (TAU * START_HZ * l * ((t / l).exp() - 1.0)).sin();
+            }
+            signal
+        },
+        0.5,
+    )?;
+    let rate = recording.rate;
+    let gap = gap(rate);
+    let latency = click_front(loopback, &recording.samples[..gap.min(recording.samples.len())], recording.noise)?;
+    let l = time_constant(rate);
+    let recorded = recording.samples.get(gap + latency as usize..).unwrap_or_default();
+    // One point per 1/6 octave, each averaged over a third of an octave of
+    // the sweep, away from the fades at both ends.
+    let half_window = (l * 2f32.powf(1.0 / 6.0).ln() * rate as f32) as usize;
+    let (low, high) = (START_HZ * 2f32.powf(1.0 / 3.0), end_hz(rate) / 2f32.powf(1.0 / 3.0));
+    let count = ((high / low).log2() * 6.0) as usize + 1;
+    let points = (0..count)
+        .filter_map(|k| {
+            let hz = low * 2f32.powf(k as f32 / 6.0);
+            let centre = (l * (hz / START_HZ).ln() * rate as f32) as usize;
+            let range = centre.saturating_sub(half_window)..centre + half_window;
+            let rms = |samples: &[f32]| {
+                let window = samples.get(range.clone())?;
+                Some((window.iter().map(|s| s * s).sum::<f32>() / window.len() as f32).sqrt())
+            };
+            let played = LEVEL / 2f32.sqrt();
+            let gain = rms(recorded)? / played;
+            Some((hz, 20.0 * gain.max(1e-6).log10()))
+        })
+        .collect();
+    Ok(SweepResult {
+        points,
+        latency_frames: latency,
+        rate,
+    })
+}
+
+/// Plays the signal `make_signal` builds for the stream's rate on the
+/// output channel and records the input channel for the signal's length
+/// plus `tail_secs`.
+fn play_and_record(
+    loopback: &Loopback,
+    make_signal: impl FnOnce(u32) -> Vec<f32>,
+    tail_secs: f32,
+) -> Result<Recording> {
+    let (capture, capture_format, capture_channels, rate) = open(
+        &loopback.capture_device,
+        Direction::Capture,
+        loopback.capture_channels,
+        loopback.period,
+    )?;
+    if loopback.input_channel >= capture_channels as usize {
+        anyhow::bail!(
+            "{} has {capture_channels} capture channels, channel {} requested",
+            loopback.capture_device,
+            loopback.input_channel + 1
+        );
+    }
+    let (playback, playback_format, playback_channels, playback_rate) = open(
+        &loopback.playback_device,
+        Direction::Playback,
+        loopback.playback_channels,
+        loopback.period,
+    )?;
+    if loopback.output_channel >= playback_channels as usize {
+        anyhow::bail!(
+            "{} has {playback_channels} playback channels, channel {} requested",
+            loopback.playback_device,
+            loopback.output_channel + 1
+        );
+    }
+    if playback_rate != rate {
+        anyhow::bail!("Playback runs at {playback_rate} Hz and capture at {rate} Hz");
+    }
+    let period = capture.hw_params_current()?.get_period_size()? as usize;
+    let (capture_channels, playback_channels) = (capture_channels as usize, playback_channels as usize);
+
+    let signal = make_signal(rate);
+    // A quarter second of silence first, to measure the noise floor.
+    let lead = rate as usize / 4;
+    let length = signal.len() + (tail_secs * rate as f32) as usize;
+    let mut timeline = vec![0f32; lead + length + period * 4];
+    timeline[lead..lead + signal.len()].copy_from_slice(&signal);
+    let mut frames_out = vec![0f32; period * playback_channels];
+    let mut next_out = timeline.chunks(period);
+    let mut write_next = || -> Result<()> {
+        let chunk = next_out.next().unwrap_or(&[]);
+        frames_out.iter_mut().for_each(|s| *s = 0.0);
+        for (frame, &sample) in frames_out.chunks_exact_mut(playback_channels).zip(chunk) {
+            frame[loopback.output_channel] = sample;
+        }
+        generator::write_frames(&playback, playback_format, playback_channels, &frames_out)
+    };
+    // Two periods queued before the start so playback never runs dry.
+    write_next()?;
+    write_next()?;
+    let linked = playback.link(&capture).is_ok();
+    playback.start().context("Failed to start playback")?;
+    if !linked {
+        capture.start().context("Failed to start capture")?;
+    }
+
+    let mut captured = Vec::with_capacity(timeline.len());
+    let mut frames_in = vec![0f32; period * capture_channels];
+    while captured.len() < lead + length + period * 2 {
+        let read = read_frames(&capture, capture_format, &mut frames_in).context("Capture stream failed")?;
+        captured.extend(
+            frames_in[..read * capture_channels]
+                .chunks_exact(capture_channels)
+                .map(|frame| frame[loopback.input_channel]),
+        );
+        write_next()?;
+    }
+    // Sample `i` of the timeline left playback while capture was at
+    // `i + shift`; zero when the streams were linked.
+    let shift = {
+        let played = playback.status()?.get_trigger_htstamp();
+        let recorded = capture.status()?.get_trigger_htstamp();
+        let seconds = (played.tv_sec - recorded.tv_sec) as f64 + (played.tv_nsec - recorded.tv_nsec) as f64 * 1e-9;
+        (seconds * rate as f64).round() as i64
+    };
+    if linked {
+        let _ = playback.unlink();
+    }
+    let start = (lead as i64 + shift).max(0) as usize;
+    let noise = captured[..start.min(captured.len())]
+        .iter()
+        .fold(0f32, |max, s| max.max(s.abs()));
+    let samples = captured.get(start..).unwrap_or_default();
+    Ok(Recording {
+        samples: samples[..length.min(samples.len())].to_vec(),
+        noise,
+        rate,
+        period: period as i64,
+        linked,
+    })
+}
+
+/// Opens a stream that only starts when asked to, so both directions can
+/// start together.
+pub fn open(device: &str, direction: Direction, channels: u32, period: i64) -> Result<(PCM, Format, u32, u32)> {
+    let pcm = PCM::new(device, direction, false).with_context(|| format!("Failed to open {device}"))?;
+    let format = {
+        let hwp = HwParams::any(&pcm).context("No hardware parameters")?;
+        hwp.set_access(Access::RWInterleaved)?;
+        let format = [Format::float(), Format::s32(), Format::s16()]
+            .into_iter()
+            .find(|f| hwp.set_format(*f).is_ok())
+            .with_context(|| format!("{device} offers no float, 32-bit or 16-bit format"))?;
+        hwp.set_channels_near(channels)?;
+        hwp.set_rate_near(48000, ValueOr::Nearest)?;
+        hwp.set_period_size_near(period, ValueOr::Nearest)?;
+        hwp.set_buffer_size_near(period * 4)?;
+        pcm.hw_params(&hwp).with_context(|| format!("{device} refused the stream settings"))?;
+        format
+    };
+    {
+        let swp = pcm.sw_params_current()?;
+        swp.set_start_threshold(swp.get_boundary()?)?;
+        pcm.sw_params(&swp)?;
+    }
+    let hwp = pcm.hw_params_current()?;
+    let (channels, rate) = (hwp.get_channels()?, hwp.get_rate()?);
+    drop(hwp);
+    Ok((pcm, format, channels, rate))
+}
+
+/// Reads one period as float; an overrun fails the measurement since the
+/// timeline would no longer line up.
+pub fn read_frames(pcm: &PCM, format: Format, float: &mut [f32]) -> Result<usize> {
+    let frames = if format == Format::float() {
+        pcm.io_f32()?.readi(float)?
+    } else if format == Format::s32() {
+        let mut int = vec![0i32; float.len()];
+        let frames = pcm.io_i32()?.readi(&mut int)?;
+        for (out, s) in float.iter_mut().zip(&int) {
+            *out = *s as f32 / i32::MAX as f32;
+        }
+        frames
+    } else {
+        let mut int = vec![0i16; float.len()];
+        let frames = pcm.io_i16()?.readi(&mut int)?;
+        for (out, s) in float.iter_mut().zip(&int) {
+            *out = *s as f32 / i16::MAX as f32;
+        }
+        frames
+    };
+    Ok(frames)
+}
@@ -0,0 +1,485 @@
+use std::{
+    collections::VecDeque,
+    io::Read,
+    process::{Child, Command, Stdio},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use alsa::{
+    Direction, ValueOr,
+    pcm::{Access, Format, HwParams, PCM},
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+
+/// Frames per read; with four periods in the buffer the tap holds ~20 ms.
+const PERIOD_FRAMES: i64 = 256;
+const BUFFER_FRAMES: i64 = PERIOD_FRAMES * 4;
+/// How often the UI is woken while signal comes in.
+const NOTIFY_INTERVAL: Duration = Duration::from_millis(33);
+
+/// What the meter bars show.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MeterMode {
+    /// Sample peak in dBFS, falling back at 24 dB/s.
+    #[default]
+    Peak,
+    /// RMS over the last 300 ms, in dBFS.
+    Rms,
+    /// Short-term loudness (K-weighted, 3 s window) per channel, in LUFS.
+    Lufs,
+}
+
+impl MeterMode {
+    pub const ALL: [MeterMode; 3] = [MeterMode::Peak, MeterMode::Rms, MeterMode::Lufs];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MeterMode::Peak => tr("Peak"),
+            MeterMode::Rms => "RMS",
+            MeterMode::Lufs => "LUFS-S",
+        }
+    }
+
+    /// Above these the bar turns yellow, then red, for an alignment level
+    /// of `reference` (dBFS, or LUFS for loudness).
+    fn thresholds(self, reference: f32) -> (f32, f32) {
+        match self {
+            MeterMode::Peak => (reference + 6.0, -1.0),
+            MeterMode::Rms => (reference, reference + 12.0),
+            MeterMode::Lufs => (reference, reference + 9.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeteringConfig {
+    pub mode: MeterMode,
+    /// Signal LEDs in the headers instead of bars, refreshed a few times a
+    /// second.
+    pub leds: bool,
+    /// Floating meter bridge window, kept on top of other windows.
+    pub bridge: bool,
+    /// Peak meters on the AIn headers, from a capture tap.
+    pub inputs: bool,
+    /// Peak meters on the Out headers, from the PipeWire sink monitor.
+    pub outputs: bool,
+    /// ALSA capture device for the input meters; `plughw:<card>,0` when
+    /// unset. A sound server holding the card makes that busy, so point it
+    /// at a loopback or dsnoop device in that case.
+    pub capture_device: Option<String>,
+    /// Time for the peak bar to reach a new peak, 0 for instant.
+    pub attack_ms: f32,
+    /// How fast the peak bar falls back.
+    pub release_db_per_sec: f32,
+    /// How long the hold mark stays on the highest reading, 0 for none.
+    pub peak_hold_ms: u64,
+    /// Alignment level, marked on the bars: -18 dBFS for EBU broadcast,
+    /// -20 for SMPTE, around -14 for music.
+    pub reference_dbfs: f32,
+}
+
+impl Default for MeteringConfig {
+    fn default() -> Self {
+        Self {
+            mode: MeterMode::Peak,
+            leds: false,
+            bridge: false,
+            inputs: false,
+            outputs: false,
+            capture_device: None,
+            attack_ms: 0.0,
+            release_db_per_sec: 24.0,
+            peak_hold_ms: 1500,
+            reference_dbfs: -18.0,
+        }
+    }
+}
+
+/// What one channel carried since the UI last looked.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChannelStats {
+    /// Linear sample peak, 0-1.
+    pub peak: f32,
+    pub sum_sq: f64,
+    /// Sum of squares after the BS.1770 K-weighting filter.
+    pub k_sum_sq: f64,
+    pub frames: u64,
+}
+
+/// Capture stream read on a background thread. Stats accumulate until the
+/// UI takes them, so no peak is lost between frames.
+pub struct MeterTap {
+    pub device: String,
+    stats: Arc<Mutex<Vec<ChannelStats>>>,
+    error: Arc<Mutex<Option<String>>>,
+    stop: Arc<AtomicBool>,
+    /// `pw-record` feeding a monitor tap.
+    child: Option<Child>,
+}
+
+impl MeterTap {
+    /// Stats of each channel since the last call.
+    pub fn take_stats(&self) -> Vec<ChannelStats> {
+        self.stats
+            .lock()
+            .map(|mut stats| stats.iter_mut().map(std::mem::take).collect())
+            .unwrap_or_default()
+    }
+
+    /// Why the stream stopped, if it did.
+    pub fn error(&self) -> Option<String> {
+        self.error.lock().ok()?.clone()
+    }
+}
+
+impl Drop for MeterTap {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Opens `device` for capture with `channels` channels and starts reading.
+pub fn start_capture_tap(device: &str, channels: u32, notify: impl Fn() + Send + 'static) -> Result<MeterTap> {
+    let stats = Arc::new(Mutex::new(vec![ChannelStats::default(); channels as usize]));
+    let error = Arc::new(Mutex::new(None));
+    let stop = Arc::new(AtomicBool::new(false));
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let tap = MeterTap {
+        device: device.to_string(),
+        stats: stats.clone(),
+        error: error.clone(),
+        stop: stop.clone(),
+        child: None,
+    };
+    let device = device.to_string();
+    thread::spawn(move || {
+        let (pcm, format, channels) = match open_capture(&device, channels) {
+            Ok(opened) => {
+                let _ = ready_tx.send(Ok(()));
+                opened
+            }
+            Err(err) => {
+                let _ = ready_tx.send(Err(err));
+                return;
+            }
+        };
+        let result = read_loop(&pcm, format, channels as usize, &stats, &stop, &notify);
+        if let Err(err) = result {
+            if let Ok(mut slot) = error.lock() {
+                *slot = Some(format!("{err:#}"));
+            }
+            notify();
+        }
+    });
+    ready_rx.recv().context("Meter thread exited")??;
+    Ok(tap)
+}
+
+fn open_capture(device: &str, channels: u32) -> Result<(PCM, Format, u32)> {
+    let pcm = PCM::new(device, Direction::Capture, false).with_context(|| format!("Failed to open {device}"))?;
+    let format = {
+        let hwp = HwParams::any(&pcm).context("No hardware parameters")?;
+        hwp.set_access(Access::RWInterleaved)?;
+        // plughw converts to float; raw hw devices and loopbacks may only
+        // offer integer formats.
+        let format = [Format::float(), Format::s32(), Format::s16()]
+            .into_iter()
+            .find(|f| hwp.set_format(*f).is_ok())
+            .with_context(|| format!("{device} offers no float, 32-bit or 16-bit format"))?;
+        hwp.set_channels_near(channels)?;
+        hwp.set_rate_near(48000, ValueOr::Nearest)?;
+        hwp.set_period_size_near(PERIOD_FRAMES, ValueOr::Nearest)?;
+        hwp.set_buffer_size_near(BUFFER_FRAMES)?;
+        pcm.hw_params(&hwp).with_context(|| format!("{device} refused the capture settings"))?;
+        format
+    };
+    let channels = pcm.hw_params_current()?.get_channels()?;
+    pcm.start()?;
+    Ok((pcm, format, channels))
+}
+
+fn read_loop(
+    pcm: &PCM,
+    format: Format,
+    channels: usize,
+    stats: &Mutex<Vec<ChannelStats>>,
+    stop: &AtomicBool,
+    notify: &impl Fn(),
+) -> Result<()> {
+    let samples = PERIOD_FRAMES as usize * channels.max(1);
+    let mut float = vec![0f32; samples];
+    let mut int32 = vec![0i32; samples];
+    let mut int16 = vec![0i16; samples];
+    let mut filters = vec![KWeighting::default(); channels.max(1)];
+    let mut last_notify = Instant::now();
+    while !stop.load(Ordering::Relaxed) {
+        let read = if format == Format::float() {
+            pcm.io_f32()?.readi(&mut float)
+        } else if format == Format::s32() {
+            pcm.io_i32()?.readi(&mut int32).inspect(|&frames| {
+                for (out, s) in float.iter_mut().zip(&int32[..frames * channels]) {
+                    *out = *s as f32 / i32::MAX as f32;
+                }
+            })
+        } else {
+            pcm.io_i16()?.readi(&mut int16).inspect(|&frames| {
+                for (out, s) in float.iter_mut().zip(&int16[..frames * channels]) {
+                    *out = *s as f32 / i16::MAX as f32;
+                }
+            })
+        };
+        let frames = match read {
+            Ok(frames) => frames,
+            // Overruns happen when the UI stalls; just pick up again.
+            Err(err) => {
+                pcm.try_recover(err, true).context("Capture stream failed")?;
+                continue;
+            }
+        };
+        accumulate(&float[..frames * channels], &mut filters, stats);
+        if last_notify.elapsed() >= NOTIFY_INTERVAL {
+            last_notify = Instant::now();
+            notify();
+        }
+    }
+    Ok(())
+}
+
+/// Folds interleaved samples into the shared per-channel stats.
+fn accumulate(samples: &[f32], filters: &mut [KWeighting], stats: &Mutex<Vec<ChannelStats>>) {
+    let mut period = vec![ChannelStats::default(); filters.len()];
+    for frame in samples.chunks_exact(filters.len().max(1)) {
+        for ((stat, filter), &sample) in period.iter_mut().zip(filters.iter_mut()).zip(frame) {
+            let weighted = filter.process(sample as f64);
+            stat.peak = stat.peak.max(sample.abs());
+            stat.sum_sq += (sample as f64).powi(2);
+            stat.k_sum_sq += weighted * weighted;
+            stat.frames += 1;
+        }
+    }
+    if let Ok(mut stats) = stats.lock() {
+        for (total, new) in stats.iter_mut().zip(&period) {
+            total.peak = total.peak.max(new.peak);
+            total.sum_sq += new.sum_sq;
+            total.k_sum_sq += new.k_sum_sq;
+            total.frames += new.frames;
+        }
+    }
+}
+
+/// ITU-R BS.1770 pre-filter (high shelf, then high pass), with the
+/// published coefficients for 48 kHz, the rate both taps ask for.
+#[derive(Debug, Clone, Copy, Default)]
+struct KWeighting {
+    shelf: [f64; 4],
+    high_pass: [f64; 4],
+}
+
+impl KWeighting {
+    const SHELF: ([f64; 3], [f64; 2]) = (
+        [1.535_124_859_586_97, -2.691_696_189_406_38, 1.198_392_810_852_85],
+        [-1.690_659_293_182_41, 0.732_480_774_215_85],
+    );
+    const HIGH_PASS: ([f64; 3], [f64; 2]) = ([1.0, -2.0, 1.0], [-1.990_047_454_833_98, 0.990_072_250_366_21]);
+
+    fn process(&mut self, x: f64) -> f64 {
+        let y = Self::biquad(&mut self.shelf, Self::SHELF, x);
+        Self::biquad(&mut self.high_pass, Self::HIGH_PASS, y)
+    }
+
+    /// Direct form I; `state` is x[n-1], x[n-2], y[n-1], y[n-2].
+    fn biquad(state: &mut [f64; 4], (b, a): ([f64; 3], [f64; 2]), x: f64) -> f64 {
+        let y = b[0] * x + b[1] * state[0] + b[2] * state[1] - a[0] * state[2] - a[1] * state[3];
+        *state = [x, state[0], y, state[2]];
+        y
+    }
+}
+
+/// PipeWire sink of the card (`node.name`) and its channel count, from `pw-dump`.
+pub fn find_pipewire_sink(card_index: u32) -> Option<(String, u32)> {
+    let output = Command::new("pw-dump").output().ok()?;
+    let objects: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).ok()?;
+    objects.iter().find_map(|obj| {
+        let props = obj.pointer("/info/props")?;
+        let card = props.get("api.alsa.card").or_else(|| props.get("alsa.card"))?;
+        let card = card.as_u64().or_else(|| card.as_str()?.parse().ok())?;
+        if props.get("media.class")?.as_str()? != "Audio/Sink" || card != card_index as u64 {
+            return None;
+        }
+        let name = props.get("node.name")?.as_str()?.to_string();
+        let channels = props.get("audio.channels").and_then(|c| c.as_u64()).unwrap_or(2) as u32;
+        Some((name, channels))
+    })
+}
+
+/// Records the monitor of a PipeWire sink through `pw-record`, i.e. what
+/// is sent to each of its playback channels.
+pub fn start_monitor_tap(sink: &str, channels: u32, notify: impl Fn() + Send + 'static) -> Result<MeterTap> {
+    let mut child = Command::new("pw-record")
+        .args(["--target", sink, "-P", "{ stream.capture.sink = true }"])
+        .args(["--format", "f32", "--rate", "48000", "--channels", &channels.to_string()])
+        .args(["--latency", &PERIOD_FRAMES.to_string(), "--raw", "-"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to run pw-record")?;
+    let mut stdout = child.stdout.take().context("pw-record has no output")?;
+    let stats = Arc::new(Mutex::new(vec![ChannelStats::default(); channels as usize]));
+    let error = Arc::new(Mutex::new(None));
+    let stop = Arc::new(AtomicBool::new(false));
+    let tap = MeterTap {
+        device: format!("{sink} (monitor)"),
+        stats: stats.clone(),
+        error: error.clone(),
+        stop: stop.clone(),
+        child: Some(child),
+    };
+    thread::spawn(move || {
+        let mut bytes = vec![0u8; PERIOD_FRAMES as usize * channels as usize * 4];
+        let mut filters = vec![KWeighting::default(); channels.max(1) as usize];
+        let mut last_notify = Instant::now();
+        while !stop.load(Ordering::Relaxed) {
+            if let Err(err) = stdout.read_exact(&mut bytes) {
+                if !stop.load(Ordering::Relaxed) {
+                    if let Ok(mut slot) = error.lock() {
+                        *slot = Some(format!("pw-record stopped: {err}"));
+                    }
+                    notify();
+                }
+                return;
+            }
+            let samples: Vec<f32> = bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect();
+            accumulate(&samples, &mut filters, &stats);
+            if last_notify.elapsed() >= NOTIFY_INTERVAL {
+                last_notify = Instant::now();
+                notify();
+            }
+        }
+    });
+    Ok(tap)
+}
+
+/// Where a meter bar stands, as fractions of its length.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeterReading {
+    pub fraction: f32,
+    pub warn: bool,
+    pub danger: bool,
+    pub hold: Option<f32>,
+    pub reference: f32,
+}
+
+/// Displayed level of one channel in every mode: the peak rises with the
+/// configured attack and falls back at the release rate, RMS and loudness
+/// average recent blocks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelMeter {
+    peak_db: f32,
+    /// Highest level of the current mode and when it was reached.
+    hold: (f32, Instant),
+    /// Blocks of the last 3 s: arrival, sum of squares, K-weighted sum, frames.
+    blocks: VecDeque<(Instant, f64, f64, u64)>,
+}
+
+impl LevelMeter {
+    pub const FLOOR_DB: f32 = -60.0;
+    /// Below this a channel counts as silent for the signal LEDs.
+    const SIGNAL_DB: f32 = -50.0;
+    const RMS_WINDOW: Duration = Duration::from_millis(300);
+    const LUFS_WINDOW: Duration = Duration::from_secs(3);
+
+    pub fn update(&mut self, stats: ChannelStats, dt: f32, config: &MeteringConfig) {
+        let db = (20.0 * stats.peak.max(1e-6).log10()).max(Self::FLOOR_DB);
+        let risen = if config.attack_ms > 0.0 && db > self.peak_db {
+            self.peak_db + (db - self.peak_db) * (1.0 - (-dt * 1000.0 / config.attack_ms).exp())
+        } else {
+            db
+        };
+        let fallen = self.peak_db - config.release_db_per_sec.max(0.0) * dt;
+        self.peak_db = risen.max(fallen).max(Self::FLOOR_DB);
+        let now = Instant::now();
+        if stats.frames > 0 {
+            self.blocks.push_back((now, stats.sum_sq, stats.k_sum_sq, stats.frames));
+        }
+        while self
+            .blocks
+            .front()
+            .is_some_and(|(at, ..)| now.duration_since(*at) > Self::LUFS_WINDOW)
+        {
+            self.blocks.pop_front();
+        }
+        let level = self.level(config.mode);
+        if level >= self.hold.0 || now.duration_since(self.hold.1) > Duration::from_millis(config.peak_hold_ms) {
+            self.hold = (level, now);
+        }
+    }
+
+    /// Whether the channel carried audio in the last second or so.
+    pub fn has_signal(&self) -> bool {
+        self.peak_db > Self::SIGNAL_DB
+    }
+
+    /// Level in the mode's unit (dBFS or LUFS), clamped to the floor.
+    pub fn level(&self, mode: MeterMode) -> f32 {
+        let mean = |window: Duration, weighted: bool| {
+            let now = Instant::now();
+            let (sum, frames) = self
+                .blocks
+                .iter()
+                .filter(|(at, ..)| now.duration_since(*at) <= window)
+                .fold((0.0, 0u64), |(sum, frames), &(_, sq, k_sq, n)| {
+                    (sum + if weighted { k_sq } else { sq }, frames + n)
+                });
+            (frames > 0).then(|| sum / frames as f64)
+        };
+        let level = match mode {
+            MeterMode::Peak => Some(self.peak_db),
+            MeterMode::Rms => mean(Self::RMS_WINDOW, false).map(|ms| 10.0 * ms.max(1e-12).log10() as f32),
+            MeterMode::Lufs => {
+                mean(Self::LUFS_WINDOW, true).map(|ms| (-0.691 + 10.0 * ms.max(1e-12).log10()) as f32)
+            }
+        };
+        level.unwrap_or(Self::FLOOR_DB).max(Self::FLOOR_DB)
+    }
+
+    pub fn reading(&self, config: &MeteringConfig) -> MeterReading {
+        let fraction = |db: f32| (1.0 - db.max(Self::FLOOR_DB) / Self::FLOOR_DB).min(1.0);
+        let level = self.level(config.mode);
+        let (warn, danger) = config.mode.thresholds(config.reference_dbfs);
+        MeterReading {
+            fraction: fraction(level),
+            warn: level > warn,
+            danger: level > danger,
+            hold: (config.peak_hold_ms > 0 && self.hold.0 > Self::FLOOR_DB).then(|| fraction(self.hold.0)),
+            reference: fraction(config.reference_dbfs),
+        }
+    }
+}
+
+impl Default for LevelMeter {
+    fn default() -> Self {
+        Self {
+            peak_db: Self::FLOOR_DB,
+            hold: (Self::FLOOR_DB, Instant::now()),
+            blocks: VecDeque::new(),
+        }
+    }
+}
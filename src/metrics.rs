@@ -0,0 +1,100 @@
+use std::{
+    fmt::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::alsa_backend::BackendCounters;
+
+/// Mixer activity the backend does not see itself, exported with the
+/// backend counters in the Prometheus text format.
+#[derive(Debug)]
+pub struct Metrics {
+    started: SystemTime,
+    /// Control catalog reloads that found a different set of numids, which
+    /// is what a device reset or reconnect looks like from here.
+    pub device_resets: u64,
+    last_preset: Option<(String, SystemTime)>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            started: SystemTime::now(),
+            device_resets: 0,
+            last_preset: None,
+        }
+    }
+}
+
+impl Metrics {
+    pub fn preset_applied(&mut self, label: &str) {
+        self.last_preset = Some((label.to_string(), SystemTime::now()));
+    }
+
+    pub fn render(&self, backend: &BackendCounters, controls: usize) -> String {
+        let mut out = String::new();
+        let samples = [
+            (
+                "ftu_start_time_seconds",
+                "gauge",
+                "When the mixer started, in Unix seconds.",
+                unix_seconds(self.started),
+            ),
+            (
+                "ftu_controls",
+                "gauge",
+                "Mixer controls currently exposed by the card.",
+                controls as u64,
+            ),
+            (
+                "ftu_control_writes_total",
+                "counter",
+                "Control writes sent to ALSA.",
+                backend.writes(),
+            ),
+            (
+                "ftu_alsa_errors_total",
+                "counter",
+                "Failed ALSA reads, writes and catalog loads.",
+                backend.errors(),
+            ),
+            (
+                "ftu_alsa_events_total",
+                "counter",
+                "Control change events received from ALSA.",
+                backend.events(),
+            ),
+            (
+                "ftu_device_resets_total",
+                "counter",
+                "Catalog reloads that found a different control set (device reset or reconnect).",
+                self.device_resets,
+            ),
+        ];
+        for (name, kind, help, value) in samples {
+            header(&mut out, name, kind, help);
+            let _ = writeln!(out, "{name} {value}");
+        }
+        if let Some((label, at)) = &self.last_preset {
+            let name = "ftu_last_preset_applied_timestamp_seconds";
+            header(&mut out, name, "gauge", "When the last preset was applied, in Unix seconds.");
+            let _ = writeln!(out, "{name} {}", unix_seconds(*at));
+            let name = "ftu_last_preset_info";
+            header(&mut out, name, "gauge", "Name of the last preset applied.");
+            let _ = writeln!(out, "{name}{{name=\"{}\"}} 1", escape_label(label));
+        }
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} {kind}");
+}
+
+fn unix_seconds(at: SystemTime) -> u64 {
+    at.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
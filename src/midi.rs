@@ -0,0 +1,106 @@
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use alsa::{
+    Direction,
+    seq::{EvCtrl, EventType, PortCap, PortType, Seq},
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Program Change → preset selection on the app's sequencer input port.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MidiPresetConfig {
+    pub enabled: bool,
+    /// MIDI channel to listen on, 1-16; any channel when unset.
+    pub channel: Option<u8>,
+    /// Combine Bank Select (CC 0 / CC 32) with the program: number = bank × 128 + program.
+    pub use_bank: bool,
+    /// Preset for a program number (0-127 as sent, plus the bank offset).
+    /// Numbers absent here pick the library preset at that position.
+    pub presets: BTreeMap<u32, PathBuf>,
+}
+
+/// A Program Change as received, with the bank last selected on its channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramChange {
+    /// 1-16.
+    pub channel: u8,
+    pub bank: u32,
+    pub program: u8,
+}
+
+impl ProgramChange {
+    pub fn number(&self, use_bank: bool) -> u32 {
+        if use_bank {
+            self.bank * 128 + self.program as u32
+        } else {
+            self.program as u32
+        }
+    }
+}
+
+/// Opens the "FTU Mixer" sequencer client with a "Preset select" input port
+/// that controllers connect to (aconnect, qjackctl, a patchbay…).
+pub fn start_program_listener(notify: impl Fn() + Send + 'static) -> Result<Receiver<ProgramChange>> {
+    let (tx, rx) = mpsc::channel();
+    let (ready_tx, ready_rx) = mpsc::channel();
+    // The sequencer handle is not Send, so it lives on its thread from the start.
+    thread::spawn(move || {
+        let seq = match open_port() {
+            Ok(seq) => {
+                let _ = ready_tx.send(Ok(()));
+                seq
+            }
+            Err(err) => {
+                let _ = ready_tx.send(Err(err));
+                return;
+            }
+        };
+        let mut input = seq.input();
+        let mut bank = [(0u32, 0u32); 16];
+        while let Ok(event) = input.event_input() {
+            let Some(ctrl) = event.get_data::<EvCtrl>() else {
+                continue;
+            };
+            let ch = (ctrl.channel & 0x0F) as usize;
+            match event.get_type() {
+                EventType::Controller if ctrl.param == 0 => bank[ch].0 = ctrl.value.clamp(0, 127) as u32,
+                EventType::Controller if ctrl.param == 32 => bank[ch].1 = ctrl.value.clamp(0, 127) as u32,
+                EventType::Pgmchange => {
+                    let change = ProgramChange {
+                        channel: ch as u8 + 1,
+                        bank: bank[ch].0 * 128 + bank[ch].1,
+                        program: ctrl.value.clamp(0, 127) as u8,
+                    };
+                    if tx.send(change).is_err() {
+                        break;
+                    }
+                    notify();
+                }
+                _ => {}
+            }
+        }
+    });
+    ready_rx
+        .recv()
+        .context("MIDI listener thread exited")??;
+    Ok(rx)
+}
+
+fn open_port() -> Result<Seq> {
+    let seq = Seq::open(None, Some(Direction::Capture), false).context("Failed to open the ALSA sequencer")?;
+    seq.set_client_name(c"FTU Mixer").context("Failed to name the sequencer client")?;
+    seq.create_simple_port(
+        c"Preset select",
+        PortCap::WRITE | PortCap::SUBS_WRITE,
+        PortType::MIDI_GENERIC | PortType::APPLICATION,
+    )
+    .context("Failed to create the sequencer port")?;
+    Ok(seq)
+}
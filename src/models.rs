@@ -1,3 +1,5 @@
+use std::{collections::HashMap, fmt};
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -23,6 +25,87 @@ pub enum ControlKind {
     },
 }
 
+impl ControlKind {
+    pub const NAMES: [&'static str; 4] = ["integer", "boolean", "enumerated", "unknown"];
+
+    /// One of [`Self::NAMES`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            ControlKind::Integer { .. } => "integer",
+            ControlKind::Boolean { .. } => "boolean",
+            ControlKind::Enumerated { .. } => "enumerated",
+            ControlKind::Unknown { .. } => "unknown",
+        }
+    }
+
+    /// Raw value closest to 0 dB for integer controls with a dB range, or the
+    /// maximum when the range does not reach 0 dB or is unknown.
+    pub fn unity_value(&self) -> Option<i64> {
+        let ControlKind::Integer {
+            min, max, db_range, ..
+        } = self
+        else {
+            return None;
+        };
+        if let Some((db_min, db_max)) = db_range {
+            if *db_min <= 0 && 0 <= *db_max && db_max > db_min {
+                let pos = -*db_min as f64 / (*db_max - *db_min) as f64;
+                let raw = *min as f64 + pos * (*max - *min) as f64;
+                return Some((raw.round() as i64).clamp(*min, *max));
+            }
+        }
+        Some(*max)
+    }
+
+    /// Integer kind carrying only a range, for the dB conversions of widgets
+    /// that work on bare `min`/`max`/`db_range` values.
+    pub fn integer(min: i64, max: i64, db_range: Option<(i64, i64)>) -> Self {
+        ControlKind::Integer {
+            min,
+            max,
+            step: 1,
+            channels: 1,
+            db_range,
+        }
+    }
+
+    /// dB level of `raw` for integer controls with a dB range, clamped to the range.
+    pub fn db_at_raw(&self, raw: i64) -> Option<f64> {
+        let ControlKind::Integer {
+            min,
+            max,
+            db_range: Some((db_min, db_max)),
+            ..
+        } = self
+        else {
+            return None;
+        };
+        if db_max <= db_min || max <= min {
+            return None;
+        }
+        let pos = (raw - min).clamp(0, max - min) as f64 / (max - min) as f64;
+        Some((*db_min as f64 + pos * (db_max - db_min) as f64) / 100.0)
+    }
+
+    /// Raw value of `db` for integer controls with a dB range, clamped to the range.
+    pub fn raw_at_db(&self, db: f64) -> Option<i64> {
+        let ControlKind::Integer {
+            min,
+            max,
+            db_range: Some((db_min, db_max)),
+            ..
+        } = self
+        else {
+            return None;
+        };
+        if db_max <= db_min || max <= min {
+            return None;
+        }
+        let pos = ((db * 100.0 - *db_min as f64) / (*db_max - *db_min) as f64).clamp(0.0, 1.0);
+        Some((*min as f64 + pos * (*max - *min) as f64).round() as i64)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ControlDescriptor {
     pub numid: u32,
@@ -37,6 +120,85 @@ pub struct ControlDescriptor {
     pub favorite: bool,
 }
 
+impl ControlDescriptor {
+    pub fn key(&self) -> ControlKey {
+        ControlKey {
+            name: self.name.clone(),
+            index: self.index,
+        }
+    }
+}
+
+/// Identity of a control in the config: ALSA tells same-named controls
+/// apart by index, and numids move between kernels. Written as the bare name
+/// for index 0, so keys saved before the index was recorded still match,
+/// and as `name,index` otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(into = "String", from = "String")]
+pub struct ControlKey {
+    pub name: String,
+    pub index: u32,
+}
+
+impl fmt::Display for ControlKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // A name that itself ends in `,<digits>` keeps an explicit `,0`.
+        let ambiguous = self
+            .name
+            .rsplit_once(',')
+            .is_some_and(|(_, tail)| tail.parse::<u32>().is_ok());
+        if self.index == 0 && !ambiguous {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{},{}", self.name, self.index)
+        }
+    }
+}
+
+impl From<ControlKey> for String {
+    fn from(key: ControlKey) -> Self {
+        key.to_string()
+    }
+}
+
+impl From<String> for ControlKey {
+    fn from(text: String) -> Self {
+        match text.rsplit_once(',').and_then(|(name, index)| Some((name, index.parse().ok()?))) {
+            Some((name, index)) => ControlKey {
+                name: name.to_string(),
+                index,
+            },
+            None => ControlKey { name: text, index: 0 },
+        }
+    }
+}
+
+/// Remembers control values between calls to report which ones changed.
+#[derive(Debug, Default)]
+pub struct ChangeTracker {
+    /// Values last seen, by numid; `None` until the first snapshot.
+    seen: Option<HashMap<u32, Vec<String>>>,
+}
+
+impl ChangeTracker {
+    /// Controls whose values differ from the previous call. The first call
+    /// only records the current state.
+    pub fn changed<'a>(&mut self, controls: &'a [ControlDescriptor]) -> Vec<&'a ControlDescriptor> {
+        let Some(seen) = self.seen.as_mut() else {
+            self.seen = Some(controls.iter().map(|c| (c.numid, c.values.clone())).collect());
+            return Vec::new();
+        };
+        let mut changed = Vec::new();
+        for control in controls {
+            if seen.get(&control.numid) != Some(&control.values) {
+                seen.insert(control.numid, control.values.clone());
+                changed.push(control);
+            }
+        }
+        changed
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RouteRef {
     pub output: usize,
@@ -56,9 +218,53 @@ pub struct PresetControlValue {
     pub values: Vec<String>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresetMetadata {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub created: Option<String>,
+}
+
+/// How a preset wants to be applied. Defaults keep the plain numid-by-numid write.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresetApplyPolicy {
+    /// Mute every route the preset touches before writing the new values.
+    #[serde(default)]
+    pub mute_first: bool,
+    /// Fade integer controls to their target over this many milliseconds.
+    #[serde(default)]
+    pub ramp_ms: u32,
+    /// Control names (case-insensitive) the preset must never write.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardIdentity {
+    #[serde(default)]
+    pub usb_id: Option<String>,
+    #[serde(default)]
+    pub serial: Option<String>,
+    #[serde(default)]
+    pub control_count: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PresetFile {
     pub schema_version: u32,
     pub card_name: String,
+    #[serde(default)]
+    pub card_identity: Option<CardIdentity>,
+    #[serde(default)]
+    pub metadata: PresetMetadata,
+    #[serde(default)]
+    pub apply_policy: PresetApplyPolicy,
     pub controls: Vec<PresetControlValue>,
 }
@@ -0,0 +1,264 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+    time::Duration,
+};
+
+use rumqttc::{Client, Event, Incoming, LastWill, MqttOptions, Outgoing, Publish, QoS, SubscribeFilter};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{
+    http_api::{self, ApiCall, ApiRequest, Notify, QuickAction},
+    models::ControlDescriptor,
+};
+
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// How long closing waits for the DISCONNECT to go out.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(1);
+/// Packets queued for the connection thread; enough for every control.
+const QUEUE_CAPACITY: usize = 1024;
+const DEFAULT_PORT: u16 = 1883;
+
+/// Broker connection; every topic lives under `topic_prefix`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub enabled: bool,
+    /// `host:port` of the broker (plain TCP, MQTT 3.1.1).
+    pub broker: String,
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub topic_prefix: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            broker: "localhost:1883".to_string(),
+            client_id: "ftu-mixer".to_string(),
+            username: None,
+            password: None,
+            topic_prefix: "ftu".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum MqttStatus {
+    /// Retained control states should be published again.
+    Connected,
+    Disconnected(String),
+}
+
+/// Handle on the broker connection, polled by the UI thread like the HTTP API.
+/// Publishing only queues the packet; the connection thread writes it.
+pub struct MqttClient {
+    pub calls: Receiver<ApiCall>,
+    pub status: Receiver<MqttStatus>,
+    client: Client,
+    connected: Arc<AtomicBool>,
+    /// Signalled once the DISCONNECT went out.
+    closed: Receiver<()>,
+    prefix: String,
+}
+
+impl MqttClient {
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// Publishes each control, retained, on `<prefix>/control/<numid>`.
+    pub fn publish_controls<'a>(&self, controls: impl IntoIterator<Item = &'a ControlDescriptor>) {
+        for control in controls {
+            let payload = json!({ "numid": control.numid, "name": control.name, "values": control.values });
+            let topic = format!("{}/control/{}", self.prefix, control.numid);
+            // A full queue means the broker is unreachable; the states are
+            // published again on reconnect.
+            let _ = self
+                .client
+                .try_publish(topic, QoS::AtMostOnce, true, payload.to_string());
+        }
+    }
+}
+
+impl Drop for MqttClient {
+    /// A clean DISCONNECT discards the will, so say "offline" ourselves.
+    fn drop(&mut self) {
+        if !self.is_connected() {
+            return;
+        }
+        let status = format!("{}/status", self.prefix);
+        let _ = self.client.try_publish(status, QoS::AtMostOnce, true, "offline");
+        if self.client.try_disconnect().is_ok() {
+            let _ = self.closed.recv_timeout(CLOSE_TIMEOUT);
+        }
+    }
+}
+
+/// Connects in the background and keeps reconnecting; commands received on
+/// `<prefix>/set/<numid or name>`, `<prefix>/apply` and `<prefix>/action`
+/// come out of `calls`.
+pub fn start<F>(config: MqttConfig, notify: F) -> MqttClient
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    let (tx, calls) = mpsc::channel();
+    let (status_tx, status) = mpsc::channel();
+    let (closed_tx, closed) = mpsc::channel();
+    let prefix = config.topic_prefix.trim_end_matches('/').to_string();
+    let notify: Notify = Arc::new(notify);
+    let connected = Arc::new(AtomicBool::new(false));
+    let (client, mut connection) = Client::new(options(&config, &prefix), QUEUE_CAPACITY);
+
+    // Commands wait on the UI thread, so they run apart from the connection
+    // thread, which must keep polling to send pings.
+    let (command_tx, commands) = mpsc::channel::<Publish>();
+    let command_client = client.clone();
+    let command_prefix = prefix.clone();
+    let command_notify = notify.clone();
+    thread::spawn(move || {
+        for publish in commands {
+            let topic = publish.topic.clone();
+            let Some(request) = command_request(&command_prefix, &topic, &publish.payload, &tx, &command_notify)
+            else {
+                continue;
+            };
+            let message = match request {
+                Ok(request) => match http_api::call(request, &tx, &command_notify) {
+                    Some(response) if response.status != 200 => {
+                        response.body["error"].as_str().unwrap_or("Request failed").to_string()
+                    }
+                    Some(_) => continue,
+                    None => return,
+                },
+                Err(message) => message,
+            };
+            // Failed commands are reported on `<prefix>/error`, not retained.
+            let payload = json!({ "topic": topic, "error": message }).to_string();
+            let _ = command_client.try_publish(format!("{command_prefix}/error"), QoS::AtMostOnce, false, payload);
+        }
+    });
+
+    let session_client = client.clone();
+    let session_connected = connected.clone();
+    let session_prefix = prefix.clone();
+    thread::spawn(move || {
+        for event in connection.iter() {
+            match event {
+                Ok(Event::Incoming(Incoming::ConnAck(_))) => {
+                    let topics = ["set/+", "apply", "action"].map(|topic| {
+                        SubscribeFilter::new(format!("{session_prefix}/{topic}"), QoS::AtMostOnce)
+                    });
+                    let _ = session_client.try_subscribe_many(topics);
+                    let _ = session_client.try_publish(format!("{session_prefix}/status"), QoS::AtMostOnce, true, "online");
+                    session_connected.store(true, Ordering::Relaxed);
+                    if status_tx.send(MqttStatus::Connected).is_err() {
+                        return;
+                    }
+                    notify();
+                }
+                Ok(Event::Incoming(Incoming::Publish(publish))) => {
+                    if command_tx.send(publish).is_err() {
+                        return;
+                    }
+                }
+                Ok(Event::Outgoing(Outgoing::Disconnect)) => {
+                    let _ = closed_tx.send(());
+                    return;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    session_connected.store(false, Ordering::Relaxed);
+                    if status_tx.send(MqttStatus::Disconnected(err.to_string())).is_err() {
+                        return;
+                    }
+                    notify();
+                    // The next poll reconnects.
+                    thread::sleep(RECONNECT_DELAY);
+                }
+            }
+        }
+    });
+
+    MqttClient {
+        calls,
+        status,
+        client,
+        connected,
+        closed,
+        prefix,
+    }
+}
+
+/// Clean session, with a retained "offline" will on `<prefix>/status`.
+fn options(config: &MqttConfig, prefix: &str) -> MqttOptions {
+    let (host, port) = match config.broker.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse().unwrap_or(DEFAULT_PORT)),
+        None => (config.broker.as_str(), DEFAULT_PORT),
+    };
+    let mut options = MqttOptions::new(&config.client_id, host, port);
+    options.set_keep_alive(KEEP_ALIVE);
+    options.set_clean_session(true);
+    options.set_last_will(LastWill::new(
+        format!("{prefix}/status"),
+        "offline",
+        QoS::AtMostOnce,
+        true,
+    ));
+    // MQTT 3.1.1 forbids a password without a user name.
+    if let Some(username) = &config.username {
+        options.set_credentials(username, config.password.clone().unwrap_or_default());
+    }
+    options
+}
+
+/// Turns a message on one of the command topics into an API request:
+/// - `set/<numid or name>`: a JSON array with one value per channel, or a
+///   single value for every channel (`-20`, `"on"`, `["0","10"]`)
+/// - `apply`: the file name of a preset in the library folder
+/// - `action`: a quick action name, e.g. `mute-all`
+fn command_request(
+    prefix: &str,
+    topic: &str,
+    payload: &[u8],
+    tx: &Sender<ApiCall>,
+    notify: &Notify,
+) -> Option<Result<ApiRequest, String>> {
+    let command = topic.strip_prefix(prefix)?.strip_prefix('/')?;
+    let text = String::from_utf8_lossy(payload).trim().to_string();
+    Some(match command {
+        "apply" => Ok(ApiRequest::ApplyPreset(text)),
+        "action" => QuickAction::ALL
+            .into_iter()
+            .find(|a| a.name() == text)
+            .map(ApiRequest::QuickAction)
+            .ok_or_else(|| format!("Unknown action \"{text}\"")),
+        _ => {
+            let target = command.strip_prefix("set/")?;
+            let controls = http_api::call(ApiRequest::ListControls, tx, notify)?;
+            let controls: Vec<ControlDescriptor> = serde_json::from_value(controls.body).unwrap_or_default();
+            let Some(control) = controls
+                .iter()
+                .find(|c| c.name == target || target.parse() == Ok(c.numid))
+            else {
+                return Some(Err(format!("No control \"{target}\"")));
+            };
+            let as_text = |v: &Value| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string());
+            let values = match serde_json::from_str::<Value>(&text) {
+                Ok(Value::Array(values)) => values.iter().map(as_text).collect(),
+                Ok(value) => vec![as_text(&value); control.values.len().max(1)],
+                // Bare words such as `on` are not JSON.
+                Err(_) => vec![text; control.values.len().max(1)],
+            };
+            Ok(ApiRequest::SetControl(control.numid, values))
+        }
+    })
+}
@@ -0,0 +1,167 @@
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+use anyhow::{Context, Result};
+
+use crate::models::{ChangeTracker, ControlDescriptor};
+
+/// Address of the message sent for each changed control:
+/// `/ftu/control ,is[i|s]*` = numid, name, then one argument per channel.
+pub const CONTROL_ADDRESS: &str = "/ftu/control";
+
+/// Larger change sets are split over several bundles of at most this size.
+const MAX_BUNDLE_BYTES: usize = 8192;
+
+#[derive(Debug, Clone, PartialEq)]
+enum OscArg {
+    Int(i32),
+    Str(String),
+}
+
+/// Sends control changes to every registered client as OSC bundles, so remote
+/// surfaces follow edits made here, by other clients or on the card itself.
+pub struct OscBroadcaster {
+    /// One socket per address family in use, since an IPv4 socket cannot
+    /// send to an IPv6 client.
+    v4: Option<UdpSocket>,
+    v6: Option<UdpSocket>,
+    clients: Vec<SocketAddr>,
+    changes: ChangeTracker,
+}
+
+impl OscBroadcaster {
+    /// `clients` are `host:port` strings; each is resolved once here, to its
+    /// IPv4 address when it has one (`localhost` often lists `::1` first).
+    pub fn new(clients: &[String]) -> Result<Self> {
+        let mut resolved = Vec::new();
+        for client in clients {
+            let addrs: Vec<SocketAddr> = client
+                .to_socket_addrs()
+                .with_context(|| format!("Invalid OSC client \"{client}\""))?
+                .collect();
+            let addr = addrs
+                .iter()
+                .find(|a| a.is_ipv4())
+                .or_else(|| addrs.first())
+                .copied()
+                .with_context(|| format!("OSC client \"{client}\" did not resolve"))?;
+            resolved.push(addr);
+        }
+        let v4 = resolved
+            .iter()
+            .any(SocketAddr::is_ipv4)
+            .then(|| open_socket("0.0.0.0:0"))
+            .transpose()?;
+        let v6 = resolved
+            .iter()
+            .any(SocketAddr::is_ipv6)
+            .then(|| open_socket("[::]:0"))
+            .transpose()?;
+        Ok(Self {
+            v4,
+            v6,
+            clients: resolved,
+            changes: ChangeTracker::default(),
+        })
+    }
+
+    /// Sends the controls whose values differ from the last call and returns
+    /// how many there were. The first call only records the current state.
+    pub fn broadcast_changes(&mut self, controls: &[ControlDescriptor]) -> Result<usize> {
+        let messages: Vec<Vec<u8>> = self
+            .changes
+            .changed(controls)
+            .into_iter()
+            .map(control_message)
+            .collect();
+        if messages.is_empty() {
+            return Ok(0);
+        }
+        // One unreachable client must not starve the others.
+        let mut failure = None;
+        for bundle in encode_bundles(&messages) {
+            for client in &self.clients {
+                let socket = if client.is_ipv4() { &self.v4 } else { &self.v6 };
+                let Some(socket) = socket else {
+                    continue;
+                };
+                if let Err(err) = socket.send_to(&bundle, client) {
+                    failure.get_or_insert_with(|| anyhow::anyhow!("OSC send to {client} failed: {err}"));
+                }
+            }
+        }
+        match failure {
+            Some(err) => Err(err),
+            None => Ok(messages.len()),
+        }
+    }
+}
+
+fn open_socket(bind: &str) -> Result<UdpSocket> {
+    let socket = UdpSocket::bind(bind).context("Failed to open the OSC socket")?;
+    socket
+        .set_nonblocking(true)
+        .context("Failed to configure the OSC socket")?;
+    Ok(socket)
+}
+
+fn control_message(control: &ControlDescriptor) -> Vec<u8> {
+    let mut args = vec![
+        OscArg::Int(control.numid as i32),
+        OscArg::Str(control.name.clone()),
+    ];
+    args.extend(control.values.iter().map(|v| match v.parse::<i64>() {
+        Ok(n) => OscArg::Int(n.clamp(i32::MIN as i64, i32::MAX as i64) as i32),
+        Err(_) => OscArg::Str(v.clone()),
+    }));
+    encode_message(CONTROL_ADDRESS, &args)
+}
+
+/// OSC strings are NUL-terminated and padded to a multiple of 4 bytes.
+fn push_padded_str(out: &mut Vec<u8>, text: &str) {
+    out.extend_from_slice(text.as_bytes());
+    out.push(0);
+    while !out.len().is_multiple_of(4) {
+        out.push(0);
+    }
+}
+
+fn encode_message(address: &str, args: &[OscArg]) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_padded_str(&mut out, address);
+    let tags: String = std::iter::once(',')
+        .chain(args.iter().map(|a| match a {
+            OscArg::Int(_) => 'i',
+            OscArg::Str(_) => 's',
+        }))
+        .collect();
+    push_padded_str(&mut out, &tags);
+    for arg in args {
+        match arg {
+            OscArg::Int(n) => out.extend_from_slice(&n.to_be_bytes()),
+            OscArg::Str(s) => push_padded_str(&mut out, s),
+        }
+    }
+    out
+}
+
+/// Groups messages into `#bundle`s with the "immediately" time tag.
+fn encode_bundles(messages: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let header = |out: &mut Vec<u8>| {
+        push_padded_str(out, "#bundle");
+        out.extend_from_slice(&1u64.to_be_bytes());
+    };
+    let mut bundles = Vec::new();
+    let mut current = Vec::new();
+    header(&mut current);
+    let empty_len = current.len();
+    for message in messages {
+        if current.len() > empty_len && current.len() + 4 + message.len() > MAX_BUNDLE_BYTES {
+            bundles.push(std::mem::take(&mut current));
+            header(&mut current);
+        }
+        current.extend_from_slice(&(message.len() as i32).to_be_bytes());
+        current.extend_from_slice(message);
+    }
+    bundles.push(current);
+    bundles
+}
@@ -0,0 +1,94 @@
+use std::f64::consts::FRAC_PI_2;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::tr, models::ControlKind};
+
+/// How a mono source is split across a stereo output pair.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PanLaw {
+    /// -3 dB at center, constant perceived loudness.
+    #[default]
+    ConstantPower,
+    /// -6 dB at center, sums to the same level in mono.
+    Linear,
+    /// 0 dB at center, the far side is attenuated when panning (balance).
+    Balance,
+}
+
+impl PanLaw {
+    pub const ALL: [PanLaw; 3] = [PanLaw::ConstantPower, PanLaw::Linear, PanLaw::Balance];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PanLaw::ConstantPower => tr("-3 dB (constant power)"),
+            PanLaw::Linear => tr("-6 dB (linear)"),
+            PanLaw::Balance => tr("0 dB (balance)"),
+        }
+    }
+
+    /// Left/right amplitude gains for `pan` in -1.0 (left) ..= 1.0 (right).
+    pub fn gains(self, pan: f64) -> (f64, f64) {
+        let pan = pan.clamp(-1.0, 1.0);
+        match self {
+            PanLaw::ConstantPower => {
+                let theta = (pan + 1.0) * FRAC_PI_2 / 2.0;
+                (theta.cos(), theta.sin())
+            }
+            PanLaw::Linear => ((1.0 - pan) / 2.0, (1.0 + pan) / 2.0),
+            PanLaw::Balance => ((1.0 - pan).min(1.0), (1.0 + pan).min(1.0)),
+        }
+    }
+
+    /// Inverse of [`PanLaw::gains`]: recovers `(level, pan)` from the two
+    /// output amplitudes. `pan` is `None` when both sides are silent.
+    pub fn level_and_pan(self, left: f64, right: f64) -> (f64, Option<f64>) {
+        let (left, right) = (left.max(0.0), right.max(0.0));
+        let level = match self {
+            PanLaw::ConstantPower => (left * left + right * right).sqrt(),
+            PanLaw::Linear => left + right,
+            PanLaw::Balance => left.max(right),
+        };
+        if level <= f64::EPSILON {
+            return (0.0, None);
+        }
+        let pan = match self {
+            PanLaw::ConstantPower => right.atan2(left) * 4.0 / std::f64::consts::PI - 1.0,
+            PanLaw::Linear => (right - left) / level,
+            PanLaw::Balance if right >= left => 1.0 - left / level,
+            PanLaw::Balance => right / level - 1.0,
+        };
+        (level, Some(pan.clamp(-1.0, 1.0)))
+    }
+}
+
+/// Linear amplitude (0..=1, 1 at the control maximum) for a raw route value.
+pub fn amplitude_from_raw(raw: i64, min: i64, max: i64, db_range: Option<(i64, i64)>) -> f64 {
+    if max <= min || raw <= min {
+        return 0.0;
+    }
+    match (ControlKind::integer(min, max, db_range).db_at_raw(raw), db_range) {
+        (Some(db), Some((_, db_max))) => 10f64.powf((db - db_max as f64 / 100.0) / 20.0),
+        _ => (raw - min).clamp(0, max - min) as f64 / (max - min) as f64,
+    }
+}
+
+/// Inverse of [`amplitude_from_raw`], clamped to the control range.
+pub fn raw_from_amplitude(amp: f64, min: i64, max: i64, db_range: Option<(i64, i64)>) -> i64 {
+    if max <= min || amp <= 0.0 {
+        return min;
+    }
+    let kind = ControlKind::integer(min, max, db_range);
+    let db = db_range.map(|(_, db_max)| db_max as f64 / 100.0 + 20.0 * amp.min(1.0).log10());
+    db.and_then(|db| kind.raw_at_db(db))
+        .unwrap_or_else(|| (min as f64 + amp.clamp(0.0, 1.0) * (max - min) as f64).round() as i64)
+}
+
+pub fn pan_text(pan: f64) -> String {
+    let pct = (pan * 100.0).round() as i64;
+    match pct {
+        0 => "C".to_string(),
+        p if p < 0 => format!("L{}", -p),
+        p => format!("R{p}"),
+    }
+}
@@ -1,13 +1,64 @@
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use anyhow::{Context, Result};
 
-use crate::models::{ControlDescriptor, PresetControlValue, PresetFile};
+use crate::models::{
+    CardIdentity, ControlDescriptor, PresetApplyPolicy, PresetControlValue, PresetFile,
+    PresetMetadata,
+};
 
-pub fn to_preset(card_name: &str, controls: &[ControlDescriptor]) -> PresetFile {
+#[derive(Debug, Clone)]
+pub struct PresetLibraryEntry {
+    pub path: PathBuf,
+    pub metadata: PresetMetadata,
+    pub card_name: String,
+    pub control_count: usize,
+}
+
+impl PresetLibraryEntry {
+    pub fn display_name(&self) -> String {
+        self.metadata.name.clone().unwrap_or_else(|| {
+            self.path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| self.path.display().to_string())
+        })
+    }
+
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.trim().to_lowercase();
+        if filter.is_empty() {
+            return true;
+        }
+        let m = &self.metadata;
+        self.display_name().to_lowercase().contains(&filter)
+            || m.description.as_deref().unwrap_or("").to_lowercase().contains(&filter)
+            || m.author.as_deref().unwrap_or("").to_lowercase().contains(&filter)
+            || m.tags.iter().any(|t| t.to_lowercase().contains(&filter))
+    }
+}
+
+pub fn to_preset(
+    card_name: &str,
+    card_identity: &CardIdentity,
+    controls: &[ControlDescriptor],
+) -> PresetFile {
     PresetFile {
         schema_version: 1,
         card_name: card_name.to_string(),
+        card_identity: Some(CardIdentity {
+            control_count: controls.len(),
+            ..card_identity.clone()
+        }),
+        metadata: PresetMetadata {
+            created: Some(now_timestamp()),
+            ..Default::default()
+        },
+        apply_policy: PresetApplyPolicy::default(),
         controls: controls
             .iter()
             .map(|c| PresetControlValue {
@@ -29,3 +80,129 @@ pub fn load_preset(path: &Path) -> Result<PresetFile> {
     let preset = serde_json::from_str::<PresetFile>(&text)?;
     Ok(preset)
 }
+
+/// Describes why a preset does not belong to the given card, if it doesn't.
+/// Presets saved before card identities were recorded are always accepted.
+pub fn card_mismatch(preset: &PresetFile, card: &CardIdentity, control_count: usize) -> Option<String> {
+    let saved = preset.card_identity.as_ref()?;
+    if let (Some(saved_id), Some(card_id)) = (&saved.usb_id, &card.usb_id) {
+        if saved_id != card_id {
+            return Some(format!(
+                "preset is for USB device {saved_id}, this card is {card_id}"
+            ));
+        }
+    }
+    if saved.control_count != 0 && saved.control_count != control_count {
+        return Some(format!(
+            "preset has {} controls, this card has {control_count}",
+            saved.control_count
+        ));
+    }
+    if let (Some(saved_serial), Some(card_serial)) = (&saved.serial, &card.serial) {
+        if saved_serial != card_serial {
+            return Some(format!(
+                "preset is bound to serial {saved_serial}, this card is {card_serial}"
+            ));
+        }
+    }
+    None
+}
+
+pub fn scan_library(dir: &Path) -> Result<Vec<PresetLibraryEntry>> {
+    let mut entries = Vec::new();
+    let read_dir =
+        fs::read_dir(dir).with_context(|| format!("Failed to read preset folder {:?}", dir))?;
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        let is_json = path
+            .extension()
+            .map(|e| e.eq_ignore_ascii_case("json"))
+            .unwrap_or(false);
+        if !is_json || !path.is_file() {
+            continue;
+        }
+        let Ok(preset) = load_preset(&path) else {
+            continue;
+        };
+        entries.push(PresetLibraryEntry {
+            path,
+            metadata: preset.metadata,
+            card_name: preset.card_name,
+            control_count: preset.controls.len(),
+        });
+    }
+    entries.sort_by_key(|e| e.display_name().to_lowercase());
+    Ok(entries)
+}
+
+/// Resolves a preset name sent over HTTP, IPC or MQTT to a file of the preset
+/// library. Only a bare file name is accepted, with or without `.json`, so
+/// remote clients cannot make the mixer read arbitrary paths.
+pub fn resolve_library_preset(dir: &Path, name: &str) -> Result<PathBuf> {
+    let name = name.trim();
+    let mut components = Path::new(name).components();
+    let is_bare = matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    );
+    anyhow::ensure!(
+        is_bare && !name.contains(['/', '\\']),
+        "\"{name}\" is not a preset name; give the file name of a preset in the library"
+    );
+    let file = if Path::new(name).extension().is_some_and(|e| e.eq_ignore_ascii_case("json")) {
+        name.to_string()
+    } else {
+        format!("{name}.json")
+    };
+    let dir = dir
+        .canonicalize()
+        .with_context(|| format!("Failed to open preset folder {}", dir.display()))?;
+    let path = dir
+        .join(&file)
+        .canonicalize()
+        .with_context(|| format!("No preset \"{file}\" in {}", dir.display()))?;
+    anyhow::ensure!(
+        path.starts_with(&dir) && path.is_file(),
+        "No preset \"{file}\" in {}",
+        dir.display()
+    );
+    Ok(path)
+}
+
+/// Splits a comma-separated tag list as typed in the UI.
+pub fn parse_tags(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn now_timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format_utc_timestamp(secs)
+}
+
+fn format_utc_timestamp(secs: u64) -> String {
+    // Civil-from-days conversion (proleptic Gregorian calendar), avoids pulling in a date crate.
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
@@ -0,0 +1,172 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tungstenite::{HandshakeError, Message, WebSocket, client::IntoClientRequest};
+
+use crate::models::{CardIdentity, ControlDescriptor};
+
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Refuses addresses that resolve outside the machine: the token and every
+/// write travel in clear text, so they should go through an SSH or VPN forward.
+pub fn ensure_loopback(address: &str) -> Result<()> {
+    let addrs: Vec<_> = address
+        .to_socket_addrs()
+        .with_context(|| format!("Failed to resolve {address}"))?
+        .collect();
+    anyhow::ensure!(
+        !addrs.is_empty() && addrs.iter().all(|a| a.ip().is_loopback()),
+        "{address} is not a loopback address. The remote API is plain HTTP: forward it with \
+         `ssh -L 7878:localhost:7878 <host>` and use --remote localhost:7878, \
+         or pass --allow-insecure-remote on a trusted network"
+    );
+    Ok(())
+}
+
+/// Card of the machine running `--headless`, as reported by `GET /api/card`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoteCard {
+    pub index: u32,
+    pub label: String,
+    pub identity: CardIdentity,
+}
+
+/// Client of another instance's HTTP API, used in place of the local card.
+#[derive(Debug, Clone)]
+pub struct RemoteBackend {
+    address: String,
+    token: String,
+}
+
+impl RemoteBackend {
+    pub fn new(address: &str, token: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn card(&self) -> Result<RemoteCard> {
+        let body = self.request("GET", "/api/card", None)?;
+        serde_json::from_value(body).context("Unexpected /api/card answer")
+    }
+
+    pub fn list_controls(&self) -> Result<Vec<ControlDescriptor>> {
+        let body = self.request("GET", "/api/controls", None)?;
+        serde_json::from_value(body).context("Unexpected /api/controls answer")
+    }
+
+    pub fn get_control(&self, numid: u32) -> Result<ControlDescriptor> {
+        let body = self.request("GET", &format!("/api/controls/{numid}"), None)?;
+        serde_json::from_value(body).context("Unexpected control answer")
+    }
+
+    pub fn set_values(&self, numid: u32, values: &[String]) -> Result<()> {
+        self.request("PUT", &format!("/api/controls/{numid}"), Some(&json!({ "values": values })))?;
+        Ok(())
+    }
+
+    fn connect(&self) -> Result<TcpStream> {
+        let stream = TcpStream::connect(&self.address)
+            .with_context(|| format!("Failed to reach the mixer at {}", self.address))?;
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+        Ok(stream)
+    }
+
+    fn request(&self, method: &str, path: &str, body: Option<&Value>) -> Result<Value> {
+        let mut stream = self.connect()?;
+        let body = body.map(|b| b.to_string()).unwrap_or_default();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: {}\r\nAuthorization: Bearer {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            self.address,
+            self.token,
+            body.len()
+        )
+        .context("Failed to send the request")?;
+        let mut reader = BufReader::new(stream);
+        let status = read_head(&mut reader)?;
+        let mut text = String::new();
+        reader.read_to_string(&mut text).context("Failed to read the answer")?;
+        let value: Value = serde_json::from_str(&text).unwrap_or(Value::String(text));
+        if status != 200 {
+            let message = value["error"].as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+            anyhow::bail!("{} answered {status}: {message}", self.address);
+        }
+        Ok(value)
+    }
+
+    /// Opens the WebSocket that pushes control changes.
+    pub fn open_change_stream(&self) -> Result<ChangeStream> {
+        let stream = self.connect()?;
+        let mut request = format!("ws://{}/api/ws", self.address)
+            .into_client_request()
+            .with_context(|| format!("Invalid remote address {}", self.address))?;
+        let authorization = format!("Bearer {}", self.token)
+            .parse()
+            .context("The remote token is not a valid header value")?;
+        request.headers_mut().insert("Authorization", authorization);
+        let (socket, _) = tungstenite::client(request, stream).map_err(|err| match err {
+            HandshakeError::Failure(tungstenite::Error::Http(response)) => anyhow::anyhow!(
+                "{} refused the change stream ({})",
+                self.address,
+                response.status().as_u16()
+            ),
+            err => anyhow::anyhow!("{} refused the change stream: {err}", self.address),
+        })?;
+        // Changes may be minutes apart.
+        socket.get_ref().set_read_timeout(None)?;
+        Ok(ChangeStream { socket })
+    }
+}
+
+/// Reads the status line and headers, returning the status code.
+fn read_head(reader: &mut impl BufRead) -> Result<u16> {
+    let mut line = String::new();
+    reader.read_line(&mut line).context("No answer from the mixer")?;
+    let status = line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .with_context(|| format!("Malformed status line \"{}\"", line.trim()))?;
+    loop {
+        line.clear();
+        reader.read_line(&mut line).context("Truncated headers")?;
+        if line.trim_end().is_empty() {
+            return Ok(status);
+        }
+    }
+}
+
+pub struct ChangeStream {
+    socket: WebSocket<TcpStream>,
+}
+
+impl ChangeStream {
+    /// Blocks until the next change event and returns how many controls it carries.
+    pub fn next_changes(&mut self) -> Result<usize> {
+        loop {
+            match self.socket.read().context("The change stream broke")? {
+                Message::Text(text) => {
+                    let event: Value = serde_json::from_str(text.as_str()).unwrap_or_default();
+                    if event["type"] == "changes" {
+                        return Ok(event["controls"].as_array().map_or(0, Vec::len));
+                    }
+                }
+                Message::Close(_) => anyhow::bail!("The mixer closed the change stream"),
+                _ => {}
+            }
+        }
+    }
+}
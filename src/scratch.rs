@@ -0,0 +1,195 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+    },
+    thread,
+};
+
+use alsa::Direction;
+use anyhow::{Context, Result};
+
+use crate::{generator, measure, presets};
+
+pub const SECONDS: u32 = 10;
+
+/// `configured`, or `~/.ftu-mixer/scratch`.
+pub fn scratch_dir(configured: Option<&Path>) -> Result<PathBuf> {
+    if let Some(dir) = configured {
+        return Ok(dir.to_path_buf());
+    }
+    let home = env::var("HOME").context("HOME environment variable is not set")?;
+    Ok(Path::new(&home).join(".ftu-mixer").join("scratch"))
+}
+
+/// Records `inputs` (capture channels, 0-based) for [`SECONDS`] into a new
+/// WAV file in `dir`, on a background thread.
+pub fn record(
+    device: &str,
+    channels: u32,
+    inputs: Vec<usize>,
+    dir: PathBuf,
+    notify: impl Fn() + Send + 'static,
+) -> Receiver<Result<PathBuf>> {
+    let (tx, rx) = mpsc::channel();
+    let device = device.to_string();
+    thread::spawn(move || {
+        let _ = tx.send(record_to_file(&device, channels, &inputs, &dir));
+        notify();
+    });
+    rx
+}
+
+fn record_to_file(device: &str, channels: u32, inputs: &[usize], dir: &Path) -> Result<PathBuf> {
+    let (pcm, format, channels, rate) = measure::open(device, Direction::Capture, channels, 1024)?;
+    if let Some(missing) = inputs.iter().find(|&&i| i >= channels as usize) {
+        anyhow::bail!("{device} has {channels} capture channels, input {} requested", missing + 1);
+    }
+    let channels = channels as usize;
+    let frames = (SECONDS * rate) as usize;
+    let mut samples = Vec::with_capacity(frames * inputs.len());
+    let mut period = vec![0f32; 1024 * channels];
+    pcm.start().context("Failed to start capture")?;
+    while samples.len() < frames * inputs.len() {
+        let read = measure::read_frames(&pcm, format, &mut period).context("Capture stream failed")?;
+        for frame in period[..read * channels].chunks_exact(channels) {
+            samples.extend(inputs.iter().map(|&i| frame[i]));
+        }
+    }
+    samples.truncate(frames * inputs.len());
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let name = format!("scratch-{}.wav", presets::now_timestamp().replace(':', "-"));
+    let path = dir.join(name);
+    fs::write(&path, wav_bytes(&samples, inputs.len() as u16, rate))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// 32-bit float WAV.
+fn wav_bytes(samples: &[f32], channels: u16, rate: u32) -> Vec<u8> {
+    let data_len = samples.len() as u32 * 4;
+    let mut out = Vec::with_capacity(58 + data_len as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(50 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&18u32.to_le_bytes());
+    out.extend_from_slice(&3u16.to_le_bytes()); // IEEE float
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&rate.to_le_bytes());
+    out.extend_from_slice(&(rate * channels as u32 * 4).to_le_bytes());
+    out.extend_from_slice(&(channels * 4).to_le_bytes());
+    out.extend_from_slice(&32u16.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    // Float data needs a fact chunk with the frame count.
+    out.extend_from_slice(b"fact");
+    out.extend_from_slice(&4u32.to_le_bytes());
+    out.extend_from_slice(&(samples.len() as u32 / channels.max(1) as u32).to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        out.extend_from_slice(&sample.to_le_bytes());
+    }
+    out
+}
+
+/// Reads back a file written by [`record`]: channels, rate and samples.
+fn read_wav(path: &Path) -> Result<(usize, u32, Vec<f32>)> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if bytes.get(..4) != Some(b"RIFF") || bytes.get(8..12) != Some(b"WAVE") {
+        anyhow::bail!("{} is not a WAV file", path.display());
+    }
+    let mut format = None;
+    let mut pos = 12;
+    while let Some(header) = bytes.get(pos..pos + 8) {
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let body = bytes.get(pos + 8..pos + 8 + len).context("Truncated WAV file")?;
+        match &header[..4] {
+            b"fmt " if body.len() >= 16 => {
+                let tag = u16::from_le_bytes([body[0], body[1]]);
+                let channels = u16::from_le_bytes([body[2], body[3]]) as usize;
+                let rate = u32::from_le_bytes([body[4], body[5], body[6], body[7]]);
+                let bits = u16::from_le_bytes([body[14], body[15]]);
+                if tag != 3 || bits != 32 {
+                    anyhow::bail!("{} is not a 32-bit float WAV", path.display());
+                }
+                format = Some((channels, rate));
+            }
+            b"data" => {
+                let (channels, rate) = format.context("WAV data before its format")?;
+                let samples = body
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect();
+                return Ok((channels, rate, samples));
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even length.
+        pos += 8 + len + len % 2;
+    }
+    anyhow::bail!("{} has no audio data", path.display())
+}
+
+/// A recording playing on the first two playback channels until it ends or
+/// is dropped.
+pub struct Player {
+    stop: Arc<AtomicBool>,
+    done: Arc<AtomicBool>,
+}
+
+impl Player {
+    pub fn is_done(&self) -> bool {
+        self.done.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for Player {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Mono recordings play on both channels, others alternate left/right.
+pub fn play(device: &str, channels: u32, path: &Path, notify: impl Fn() + Send + 'static) -> Result<Player> {
+    let (recorded, rate, samples) = read_wav(path)?;
+    let (pcm, format, channels) = generator::open_playback(device, channels.max(2), &[0, 1], rate)?;
+    let (recorded, channels) = (recorded.max(1), channels as usize);
+    let stop = Arc::new(AtomicBool::new(false));
+    let done = Arc::new(AtomicBool::new(false));
+    let player = Player {
+        stop: stop.clone(),
+        done: done.clone(),
+    };
+    thread::spawn(move || {
+        let mut out = vec![0f32; 1024 * channels];
+        for block in samples.chunks(1024 * recorded) {
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+            out.iter_mut().for_each(|s| *s = 0.0);
+            for (frame, input) in out.chunks_exact_mut(channels).zip(block.chunks_exact(recorded)) {
+                for (i, sample) in input.iter().enumerate() {
+                    if recorded == 1 {
+                        frame[0] = *sample;
+                        frame[1] = *sample;
+                    } else {
+                        frame[i % 2] += *sample;
+                    }
+                }
+            }
+            let frames = block.len() / recorded;
+            if generator::write_frames(&pcm, format, channels, &out[..frames * channels]).is_err() {
+                break;
+            }
+        }
+        if !stop.load(Ordering::Relaxed) {
+            let _ = pcm.drain();
+        }
+        done.store(true, Ordering::Relaxed);
+        notify();
+    });
+    Ok(player)
+}
@@ -0,0 +1,315 @@
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use rhai::{AST, Array, Dynamic, Engine, EvalAltResult, FuncArgs, INT, Scope};
+
+use crate::models::{ChangeTracker, ControlDescriptor};
+
+/// Hooks run on the UI thread, so a runaway loop or recursion is stopped
+/// instead of freezing the mixer.
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_FUNCTION_EXPR_DEPTH: usize = 32;
+
+/// Writes and other side effects requested by a script, carried out by the
+/// app once the hook returns.
+#[derive(Debug, Clone)]
+pub enum ScriptCommand {
+    Set { numid: u32, values: Vec<String> },
+    ApplyPreset(PathBuf),
+    Log(String),
+}
+
+/// What hooks left behind: the commands they queued, kept when one fails
+/// part way like the statements before an error, and the first error.
+#[derive(Default)]
+pub struct HookRun {
+    pub commands: Vec<ScriptCommand>,
+    pub error: Option<anyhow::Error>,
+}
+
+impl HookRun {
+    fn extend(&mut self, other: HookRun) {
+        self.commands.extend(other.commands);
+        if self.error.is_none() {
+            self.error = other.error;
+        }
+    }
+}
+
+/// What the script functions see and produce. Values written by `set` are
+/// reflected here at once so a later `get` in the same hook reads them back.
+#[derive(Default)]
+struct ScriptState {
+    controls: Vec<ControlDescriptor>,
+    commands: Vec<ScriptCommand>,
+    /// Pending `after` calls: when, and the script function to call.
+    timers: Vec<(Instant, String)>,
+}
+
+impl ScriptState {
+    fn find(&self, name: &str) -> Option<usize> {
+        self.controls.iter().position(|c| c.name == name)
+    }
+
+    fn set(&mut self, idx: usize, value: Dynamic) -> Result<(), Box<rhai::EvalAltResult>> {
+        let control = &mut self.controls[idx];
+        let values = if value.is_array() {
+            value
+                .cast::<Array>()
+                .into_iter()
+                .map(value_to_string)
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            vec![value_to_string(value)?; control.values.len().max(1)]
+        };
+        control.values = values.clone();
+        self.commands.push(ScriptCommand::Set {
+            numid: control.numid,
+            values,
+        });
+        Ok(())
+    }
+}
+
+/// A user script with hooks called on mixer events:
+/// `on_startup()`, `on_device_connected()`, `on_control_changed(numid, name, value)`
+/// and `on_talkback(active)`. Hooks the script does not define are skipped.
+pub struct ScriptEngine {
+    path: PathBuf,
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    hooks: HashSet<String>,
+    state: Rc<RefCell<ScriptState>>,
+    changes: ChangeTracker,
+}
+
+impl ScriptEngine {
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read script {}", path.display()))?;
+        let state = Rc::new(RefCell::new(ScriptState::default()));
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(MAX_CALL_LEVELS)
+            .set_max_expr_depths(MAX_EXPR_DEPTH, MAX_FUNCTION_EXPR_DEPTH);
+        register_api(&mut engine, &state);
+        let ast = engine
+            .compile(&source)
+            .map_err(|err| anyhow::anyhow!("{}: {err}", path.display()))?;
+        let hooks = ast.iter_functions().map(|f| f.name.to_string()).collect();
+        let mut script = Self {
+            path: path.to_path_buf(),
+            engine,
+            ast,
+            scope: Scope::new(),
+            hooks,
+            state,
+            changes: ChangeTracker::default(),
+        };
+        // Top-level statements run once, e.g. to set up globals.
+        script
+            .engine
+            .run_ast_with_scope(&mut script.scope, &script.ast)
+            .map_err(|err| anyhow::anyhow!("{}: {:#}", script.path.display(), hook_error("top level", err)))?;
+        Ok(script)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn on_startup(&mut self, controls: &[ControlDescriptor]) -> HookRun {
+        self.changes.changed(controls);
+        self.call(controls, "on_startup", ())
+    }
+
+    pub fn on_device_connected(&mut self, controls: &[ControlDescriptor]) -> HookRun {
+        self.call(controls, "on_device_connected", ())
+    }
+
+    pub fn on_talkback(&mut self, controls: &[ControlDescriptor], active: bool) -> HookRun {
+        self.call(controls, "on_talkback", (active,))
+    }
+
+    /// Calls `on_control_changed` for every control that moved since the last
+    /// call; a failing call does not stop the others.
+    pub fn on_controls_changed(&mut self, controls: &[ControlDescriptor]) -> HookRun {
+        let changed: Vec<(INT, String, Dynamic)> = self
+            .changes
+            .changed(controls)
+            .into_iter()
+            .map(|c| (c.numid as INT, c.name.clone(), control_value(c)))
+            .collect();
+        let mut run = HookRun::default();
+        if changed.is_empty() || !self.hooks.contains("on_control_changed") {
+            return run;
+        }
+        for args in changed {
+            run.extend(self.call(controls, "on_control_changed", args));
+        }
+        run
+    }
+
+    /// Forgets changes made by the commands a hook returned, once they are
+    /// applied, so a script does not react to its own writes.
+    pub fn absorb_own_writes(&mut self, controls: &[ControlDescriptor]) {
+        self.changes.changed(controls);
+    }
+
+    /// Runs the `after` callbacks that are due.
+    pub fn run_timers(&mut self, controls: &[ControlDescriptor]) -> HookRun {
+        let now = Instant::now();
+        let due: Vec<String> = {
+            let mut state = self.state.borrow_mut();
+            let (due, pending) = std::mem::take(&mut state.timers)
+                .into_iter()
+                .partition(|(at, _)| *at <= now);
+            state.timers = pending;
+            due.into_iter().map(|(_, name)| name).collect()
+        };
+        let mut run = HookRun::default();
+        for name in due {
+            run.extend(self.call(controls, &name, ()));
+        }
+        run
+    }
+
+    pub fn next_timer_in(&self) -> Option<Duration> {
+        let state = self.state.borrow();
+        let now = Instant::now();
+        state
+            .timers
+            .iter()
+            .map(|(at, _)| at.saturating_duration_since(now))
+            .min()
+    }
+
+    fn call(&mut self, controls: &[ControlDescriptor], hook: &str, args: impl FuncArgs) -> HookRun {
+        if !self.hooks.contains(hook) {
+            return HookRun::default();
+        }
+        self.state.borrow_mut().controls = controls.to_vec();
+        let result = self
+            .engine
+            .call_fn::<Dynamic>(&mut self.scope, &self.ast, hook, args);
+        HookRun {
+            commands: std::mem::take(&mut self.state.borrow_mut().commands),
+            error: result.err().map(|err| hook_error(hook, err)),
+        }
+    }
+}
+
+/// Names the limit when one stopped the script, since rhai's own message
+/// does not say which.
+fn hook_error(hook: &str, err: Box<EvalAltResult>) -> anyhow::Error {
+    match err.unwrap_inner() {
+        EvalAltResult::ErrorTooManyOperations(_) => anyhow::anyhow!(
+            "{hook} stopped after {MAX_OPERATIONS} operations (endless loop?)"
+        ),
+        EvalAltResult::ErrorStackOverflow(_) => anyhow::anyhow!(
+            "{hook} stopped at {MAX_CALL_LEVELS} nested calls (runaway recursion?)"
+        ),
+        _ => anyhow::anyhow!("{hook}: {err}"),
+    }
+}
+
+fn register_api(engine: &mut Engine, state: &Rc<RefCell<ScriptState>>) {
+    let s = state.clone();
+    engine.register_fn("controls", move || -> Array {
+        s.borrow().controls.iter().map(|c| Dynamic::from(c.name.clone())).collect()
+    });
+    let s = state.clone();
+    engine.register_fn("get", move |name: &str| -> Result<Dynamic, Box<rhai::EvalAltResult>> {
+        let state = s.borrow();
+        let idx = state.find(name).ok_or_else(|| format!("No control named \"{name}\""))?;
+        Ok(control_value(&state.controls[idx]))
+    });
+    let s = state.clone();
+    engine.register_fn("get", move |numid: INT| -> Result<Dynamic, Box<rhai::EvalAltResult>> {
+        let state = s.borrow();
+        let control = state
+            .controls
+            .iter()
+            .find(|c| c.numid as INT == numid)
+            .ok_or_else(|| format!("No control with numid {numid}"))?;
+        Ok(control_value(control))
+    });
+    let s = state.clone();
+    engine.register_fn("set", move |name: &str, value: Dynamic| -> Result<(), Box<rhai::EvalAltResult>> {
+        let mut state = s.borrow_mut();
+        let idx = state.find(name).ok_or_else(|| format!("No control named \"{name}\""))?;
+        state.set(idx, value)
+    });
+    let s = state.clone();
+    engine.register_fn("set", move |numid: INT, value: Dynamic| -> Result<(), Box<rhai::EvalAltResult>> {
+        let mut state = s.borrow_mut();
+        let idx = state
+            .controls
+            .iter()
+            .position(|c| c.numid as INT == numid)
+            .ok_or_else(|| format!("No control with numid {numid}"))?;
+        state.set(idx, value)
+    });
+    let s = state.clone();
+    engine.register_fn("apply_preset", move |path: &str| {
+        s.borrow_mut().commands.push(ScriptCommand::ApplyPreset(PathBuf::from(path)));
+    });
+    let s = state.clone();
+    engine.register_fn("after", move |ms: INT, callback: &str| {
+        let at = Instant::now() + Duration::from_millis(ms.max(0) as u64);
+        s.borrow_mut().timers.push((at, callback.to_string()));
+    });
+    let s = state.clone();
+    engine.register_fn("log", move |message: &str| {
+        s.borrow_mut().commands.push(ScriptCommand::Log(message.to_string()));
+    });
+}
+
+/// A single-channel control reads as a plain value, others as an array.
+/// Integers become numbers, "on"/"off" booleans, anything else a string.
+fn control_value(control: &ControlDescriptor) -> Dynamic {
+    let convert = |v: &String| match v.parse::<INT>() {
+        Ok(n) => Dynamic::from(n),
+        Err(_) if v == "on" => Dynamic::from(true),
+        Err(_) if v == "off" => Dynamic::from(false),
+        Err(_) => Dynamic::from(v.clone()),
+    };
+    match control.values.as_slice() {
+        [single] => convert(single),
+        values => Dynamic::from_array(values.iter().map(convert).collect()),
+    }
+}
+
+fn value_to_string(value: Dynamic) -> Result<String, Box<rhai::EvalAltResult>> {
+    if let Some(b) = value.clone().try_cast::<bool>() {
+        return Ok(if b { "on" } else { "off" }.to_string());
+    }
+    if let Some(n) = value.clone().try_cast::<INT>() {
+        return Ok(n.to_string());
+    }
+    if let Some(f) = value.clone().try_cast::<rhai::FLOAT>() {
+        return Ok((f.round() as INT).to_string());
+    }
+    if value.is_string() {
+        return Ok(value.into_string().unwrap_or_default());
+    }
+    Err(format!("Cannot write a {} to a control", value.type_name()).into())
+}
+
+/// `~/.ftu-mixer/script.rhai`, used when no script is configured and it exists.
+pub fn default_script_path() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    let path = Path::new(&home).join(".ftu-mixer").join("script.rhai");
+    path.exists().then_some(path)
+}
@@ -0,0 +1,201 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+
+use crate::{
+    models::{CardIdentity, ControlDescriptor, PresetControlValue, PresetFile, PresetMetadata},
+    presets,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ImportedControl {
+    numid: u32,
+    name: String,
+    index: u32,
+    values: Vec<String>,
+}
+
+/// Reads an `alsactl store` state file or saved `amixer contents` output and
+/// turns it into a preset for the current card.
+///
+/// Controls are matched by name and index first, since numids can shift between
+/// kernel versions; the dumped numid is only used as a fallback.
+pub fn import_state_file(
+    path: &Path,
+    card_name: &str,
+    card_identity: &CardIdentity,
+    controls: &[ControlDescriptor],
+) -> Result<PresetFile> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read state dump {:?}", path))?;
+    let imported = parse_state_dump(&text)?;
+
+    let by_name: HashMap<(&str, u32), &ControlDescriptor> =
+        controls.iter().map(|c| ((c.name.as_str(), c.index), c)).collect();
+    let by_numid: HashMap<u32, &ControlDescriptor> = controls.iter().map(|c| (c.numid, c)).collect();
+
+    let mut preset_controls = Vec::new();
+    for entry in imported {
+        let target = by_name
+            .get(&(entry.name.as_str(), entry.index))
+            .or_else(|| by_numid.get(&entry.numid));
+        if let Some(control) = target {
+            preset_controls.push(PresetControlValue {
+                numid: control.numid,
+                values: entry.values,
+            });
+        }
+    }
+    if preset_controls.is_empty() {
+        bail!("No control in {:?} matches this card", path);
+    }
+
+    let mut preset = presets::to_preset(card_name, card_identity, controls);
+    preset.controls = preset_controls;
+    preset.metadata = PresetMetadata {
+        name: path.file_stem().map(|s| s.to_string_lossy().into_owned()),
+        description: Some(format!("Imported from {}", path.display())),
+        created: preset.metadata.created,
+        ..Default::default()
+    };
+    Ok(preset)
+}
+
+fn parse_state_dump(text: &str) -> Result<Vec<ImportedControl>> {
+    let looks_like_amixer = text
+        .lines()
+        .any(|l| l.trim_start().starts_with("numid="));
+    let controls = if looks_like_amixer {
+        parse_amixer_contents(text)
+    } else {
+        parse_alsactl_state(text)
+    };
+    if controls.is_empty() {
+        bail!("Unrecognized state dump: expected `alsactl store` or `amixer contents` output");
+    }
+    Ok(controls)
+}
+
+fn parse_alsactl_state(text: &str) -> Vec<ImportedControl> {
+    let control_re = Regex::new(r"^control\.(\d+)\s*\{$").expect("valid regex");
+    let value_re = Regex::new(r"^value(?:\.(\d+))?\s+(.+)$").expect("valid regex");
+
+    let mut out = Vec::new();
+    let mut current: Option<ImportedControl> = None;
+    let mut values_by_channel: Vec<(usize, String)> = Vec::new();
+    // Depth relative to the current `control.N { }` block; nested blocks are comments.
+    let mut depth = 0usize;
+
+    for raw in text.lines() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if current.is_none() {
+            if let Some(cap) = control_re.captures(line) {
+                current = Some(ImportedControl {
+                    numid: cap[1].parse().unwrap_or(0),
+                    name: String::new(),
+                    index: 0,
+                    values: Vec::new(),
+                });
+                values_by_channel.clear();
+                depth = 1;
+            }
+            continue;
+        }
+        if line.ends_with('{') {
+            depth += 1;
+            continue;
+        }
+        if line == "}" {
+            depth -= 1;
+            if depth == 0 {
+                if let Some(mut control) = current.take() {
+                    values_by_channel.sort_by_key(|(ch, _)| *ch);
+                    control.values = values_by_channel.drain(..).map(|(_, v)| v).collect();
+                    if !control.values.is_empty() {
+                        out.push(control);
+                    }
+                }
+            }
+            continue;
+        }
+        if depth != 1 {
+            continue;
+        }
+        let Some(control) = current.as_mut() else {
+            continue;
+        };
+        if let Some(rest) = line.strip_prefix("name ") {
+            control.name = unquote(rest);
+        } else if let Some(rest) = line.strip_prefix("index ") {
+            control.index = rest.trim().parse().unwrap_or(0);
+        } else if let Some(cap) = value_re.captures(line) {
+            let ch = cap.get(1).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+            values_by_channel.push((ch, normalize_value(&unquote(&cap[2]))));
+        }
+    }
+    out
+}
+
+fn parse_amixer_contents(text: &str) -> Vec<ImportedControl> {
+    let header_re = Regex::new(r"^numid=(\d+),(.*)$").expect("valid regex");
+    let name_re = Regex::new(r"name='((?:[^'\\]|\\.)*)'").expect("valid regex");
+    let index_re = Regex::new(r"(?:^|,)index=(\d+)").expect("valid regex");
+
+    let mut out = Vec::new();
+    let mut current: Option<ImportedControl> = None;
+    for raw in text.lines() {
+        let line = raw.trim();
+        if let Some(cap) = header_re.captures(line) {
+            if let Some(control) = current.take() {
+                if !control.values.is_empty() {
+                    out.push(control);
+                }
+            }
+            let rest = &cap[2];
+            current = Some(ImportedControl {
+                numid: cap[1].parse().unwrap_or(0),
+                name: name_re
+                    .captures(rest)
+                    .map(|c| c[1].replace("\\'", "'"))
+                    .unwrap_or_default(),
+                index: index_re
+                    .captures(rest)
+                    .and_then(|c| c[1].parse().ok())
+                    .unwrap_or(0),
+                values: Vec::new(),
+            });
+        } else if let Some(rest) = line.strip_prefix(": values=") {
+            if let Some(control) = current.as_mut() {
+                control.values = rest.split(',').map(|v| normalize_value(v.trim())).collect();
+            }
+        }
+    }
+    if let Some(control) = current.take() {
+        if !control.values.is_empty() {
+            out.push(control);
+        }
+    }
+    out
+}
+
+fn unquote(raw: &str) -> String {
+    let raw = raw.trim();
+    let stripped = raw
+        .strip_prefix('\'')
+        .and_then(|r| r.strip_suffix('\''))
+        .or_else(|| raw.strip_prefix('"').and_then(|r| r.strip_suffix('"')))
+        .unwrap_or(raw);
+    stripped.replace("\\'", "'")
+}
+
+fn normalize_value(raw: &str) -> String {
+    match raw {
+        "true" => "on".to_string(),
+        "false" => "off".to_string(),
+        other => other.to_string(),
+    }
+}
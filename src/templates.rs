@@ -0,0 +1,308 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::AppUserConfig,
+    factory_presets::FactoryPreset,
+    models::{
+        CardIdentity, ControlDescriptor, ControlKind, PresetControlValue, PresetFile,
+        PresetMetadata, RoutingIndex,
+    },
+    presets,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Bus {
+    Analog,
+    Digital,
+}
+
+/// 1-based channel selection, as printed on the device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Channels {
+    All,
+    List(Vec<usize>),
+}
+
+/// Output selection relative to the input of each route.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outputs {
+    /// InN -> OutN.
+    Same,
+    All,
+    List(Vec<usize>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    Unity,
+    Off,
+    Db(f64),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteRule {
+    pub bus: Bus,
+    pub inputs: Channels,
+    pub outputs: Outputs,
+    pub level: Level,
+}
+
+/// A preset described in terms of inputs and outputs instead of numids, so it
+/// can be instantiated on any matrix interface the routing index understands.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresetTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Smallest device the template makes sense on.
+    #[serde(default)]
+    pub min_inputs: usize,
+    #[serde(default)]
+    pub min_outputs: usize,
+    /// Mute every route no rule matched.
+    #[serde(default)]
+    pub mute_unmatched: bool,
+    /// When several rules match a route, the last one wins.
+    pub rules: Vec<RouteRule>,
+}
+
+/// Effect Program values of the Fast Track Ultra DSP, by enum index.
+pub const FTU_FX_PROGRAMS: [&str; 8] = [
+    "Room 1", "Room 2", "Room 3", "Hall 1", "Hall 2", "Plate", "Delay", "Echo",
+];
+
+/// Raw range of the FTU Effect Duration control, whose unit is 1 ms.
+pub const FTU_FX_DURATION_RANGE: (i64, i64) = (0, 0x2000);
+
+/// Matrix dimensions of the connected card, derived from the routing index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DeviceProfile {
+    pub analog_inputs: usize,
+    pub digital_inputs: usize,
+    pub outputs: usize,
+    /// Names of the Effect Program values; the kernel only exposes indices.
+    pub fx_programs: &'static [&'static str],
+    /// Effect Duration range known to be in milliseconds.
+    pub fx_duration_ms_range: Option<(i64, i64)>,
+}
+
+impl DeviceProfile {
+    pub fn from_routing(routing: &RoutingIndex) -> Self {
+        let count = |it: &mut dyn Iterator<Item = usize>| it.max().map(|m| m + 1).unwrap_or(0);
+        Self {
+            analog_inputs: count(&mut routing.analog_routes.iter().map(|r| r.input)),
+            digital_inputs: count(&mut routing.digital_routes.iter().map(|r| r.input)),
+            outputs: count(
+                &mut routing
+                    .analog_routes
+                    .iter()
+                    .chain(routing.digital_routes.iter())
+                    .map(|r| r.output),
+            ),
+            fx_programs: &FTU_FX_PROGRAMS,
+            fx_duration_ms_range: Some(FTU_FX_DURATION_RANGE),
+        }
+    }
+
+    /// Whether a Duration control with this range can be shown in ms; other
+    /// firmwares or kernels fall back to a percentage.
+    pub fn duration_in_ms(&self, min: i64, max: i64) -> bool {
+        self.fx_duration_ms_range == Some((min, max))
+    }
+
+    pub fn fx_program_name(&self, index: i64) -> String {
+        usize::try_from(index)
+            .ok()
+            .and_then(|i| self.fx_programs.get(i))
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("Program {index}"))
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} AIn × {} DIn × {} Out",
+            self.analog_inputs, self.digital_inputs, self.outputs
+        )
+    }
+
+    pub fn supports(&self, template: &PresetTemplate) -> bool {
+        self.analog_inputs.max(self.digital_inputs) >= template.min_inputs
+            && self.outputs >= template.min_outputs
+    }
+}
+
+/// Instantiation parameters: the template only covers the first `inputs`
+/// inputs and `outputs` outputs ("N inputs × M outputs").
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemplateParams {
+    pub inputs: usize,
+    pub outputs: usize,
+}
+
+impl PresetTemplate {
+    pub fn instantiate(
+        &self,
+        params: TemplateParams,
+        card_name: &str,
+        card_identity: &CardIdentity,
+        controls: &[ControlDescriptor],
+        routing: &RoutingIndex,
+    ) -> PresetFile {
+        let mut targets: HashMap<usize, i64> = HashMap::new();
+        for (bus, routes) in [
+            (Bus::Analog, &routing.analog_routes),
+            (Bus::Digital, &routing.digital_routes),
+        ] {
+            for route in routes {
+                let Some(control) = controls.get(route.control_index) else {
+                    continue;
+                };
+                let in_scope = route.input < params.inputs && route.output < params.outputs;
+                let level = self
+                    .rules
+                    .iter()
+                    .rev()
+                    .find(|rule| in_scope && rule.matches(bus, route.input, route.output))
+                    .map(|rule| rule.level)
+                    .or(self.mute_unmatched.then_some(Level::Off));
+                if let Some(raw) = level.and_then(|l| l.raw(&control.kind)) {
+                    targets.insert(route.control_index, raw);
+                }
+            }
+        }
+
+        let mut preset = presets::to_preset(card_name, card_identity, controls);
+        preset.controls = controls
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, control)| {
+                let raw = targets.get(&idx)?;
+                let ControlKind::Integer { channels, .. } = control.kind else {
+                    return None;
+                };
+                Some(PresetControlValue {
+                    numid: control.numid,
+                    values: vec![raw.to_string(); channels],
+                })
+            })
+            .collect();
+        preset.metadata = PresetMetadata {
+            name: Some(format!(
+                "{} ({}×{})",
+                self.name, params.inputs, params.outputs
+            )),
+            description: (!self.description.is_empty()).then(|| self.description.clone()),
+            tags: vec!["template".to_string()],
+            created: preset.metadata.created,
+            ..Default::default()
+        };
+        preset
+    }
+}
+
+impl Level {
+    /// Raw value of this level on an integer control; `None` for other
+    /// kinds, and for dB levels on controls without a dB range.
+    pub fn raw(self, kind: &ControlKind) -> Option<i64> {
+        let ControlKind::Integer { min, .. } = kind else {
+            return None;
+        };
+        match self {
+            Level::Unity => kind.unity_value(),
+            Level::Off => Some(*min),
+            Level::Db(db) => kind.raw_at_db(db),
+        }
+    }
+}
+
+impl RouteRule {
+    fn matches(&self, bus: Bus, input: usize, output: usize) -> bool {
+        if self.bus != bus {
+            return false;
+        }
+        let input_ok = match &self.inputs {
+            Channels::All => true,
+            Channels::List(list) => list.contains(&(input + 1)),
+        };
+        let output_ok = match &self.outputs {
+            Outputs::Same => input == output,
+            Outputs::All => true,
+            Outputs::List(list) => list.contains(&(output + 1)),
+        };
+        input_ok && output_ok
+    }
+}
+
+/// The routing-only factory presets, plus templates that only make sense
+/// scaled to a sub-matrix.
+pub fn builtin_templates() -> Vec<PresetTemplate> {
+    let mut templates: Vec<PresetTemplate> = [
+        FactoryPreset::DirectMonitoring,
+        FactoryPreset::PlaybackOnly,
+        FactoryPreset::AllMuted,
+    ]
+    .into_iter()
+    .map(FactoryPreset::template)
+    .collect();
+    templates.push(PresetTemplate {
+        name: "Stereo cue on Out1/2".to_string(),
+        description: "Every analog input to Out1/2 at -6 dB, DIn1/2 playback on Out1/2."
+            .to_string(),
+        min_inputs: 2,
+        min_outputs: 2,
+        mute_unmatched: true,
+        rules: vec![
+            RouteRule {
+                bus: Bus::Analog,
+                inputs: Channels::All,
+                outputs: Outputs::List(vec![1, 2]),
+                level: Level::Db(-6.0),
+            },
+            RouteRule {
+                bus: Bus::Digital,
+                inputs: Channels::List(vec![1, 2]),
+                outputs: Outputs::Same,
+                level: Level::Unity,
+            },
+        ],
+    });
+    templates
+}
+
+pub fn user_templates_dir() -> Result<PathBuf> {
+    let config_path = AppUserConfig::config_file_path()?;
+    let dir = config_path
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Invalid config path {}", config_path.display()))?;
+    Ok(dir.join("templates"))
+}
+
+/// Loads `*.json` templates from `~/.ftu-mixer/templates`; a missing folder is not an error.
+pub fn load_user_templates() -> Result<Vec<PresetTemplate>> {
+    let dir = user_templates_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut out = Vec::new();
+    let read_dir = fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if path.extension().map(|e| e != "json").unwrap_or(true) {
+            continue;
+        }
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read template {}", path.display()))?;
+        let template = serde_json::from_str::<PresetTemplate>(&text)
+            .with_context(|| format!("Failed to parse template {}", path.display()))?;
+        out.push(template);
+    }
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(out)
+}
@@ -0,0 +1,249 @@
+use eframe::egui::{self, Color32, Stroke};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl ThemePreset {
+    pub const ALL: [ThemePreset; 3] = [ThemePreset::Dark, ThemePreset::Light, ThemePreset::HighContrast];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ThemePreset::Dark => tr("Dark"),
+            ThemePreset::Light => tr("Light"),
+            ThemePreset::HighContrast => tr("High contrast"),
+        }
+    }
+}
+
+/// Colors of the meter and mute states, picked to stay apart for a color
+/// vision deficiency.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorVision {
+    #[default]
+    Standard,
+    /// Deuteranopia and protanopia: blue, yellow and vermillion.
+    RedGreen,
+    /// Tritanopia: teal, pink and red.
+    BlueYellow,
+}
+
+impl ColorVision {
+    pub const ALL: [ColorVision; 3] = [ColorVision::Standard, ColorVision::RedGreen, ColorVision::BlueYellow];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorVision::Standard => tr("Standard"),
+            ColorVision::RedGreen => tr("Red-green safe"),
+            ColorVision::BlueYellow => tr("Blue-yellow safe"),
+        }
+    }
+}
+
+/// Preset plus optional overrides, as sRGB triplets.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub preset: ThemePreset,
+    pub accent: Option<[u8; 3]>,
+    pub background: Option<[u8; 3]>,
+    pub vision: ColorVision,
+    /// Back states up with patterns and symbols, not only colors.
+    pub state_cues: bool,
+}
+
+/// Colors the app paints by hand, outside of egui's own widgets.
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    /// Central panel.
+    pub background: Color32,
+    /// Side panels and windows.
+    pub panel: Color32,
+    /// Framed sections inside a tab.
+    pub section: Color32,
+    /// The quick actions frame, a shade off `section`.
+    pub raised: Color32,
+    pub stroke: Color32,
+    pub text: Color32,
+    pub accent: Color32,
+    /// Meter below the warning level.
+    pub good: Color32,
+    /// Meter over the warning level.
+    pub warn: Color32,
+    /// Meter clipping, muted outputs.
+    pub danger: Color32,
+    /// Muted matrix cells.
+    pub muted: Color32,
+    /// Hatch muted cells and clipping meters and mark linked channels with 🔗.
+    pub state_cues: bool,
+}
+
+fn rgb([r, g, b]: [u8; 3]) -> Color32 {
+    Color32::from_rgb(r, g, b)
+}
+
+/// Moves `color` towards white (`amount` > 0) or black (< 0) by `amount` of 255.
+fn shade(color: Color32, amount: i16) -> Color32 {
+    let step = |c: u8| (c as i16 + amount).clamp(0, 255) as u8;
+    Color32::from_rgb(step(color.r()), step(color.g()), step(color.b()))
+}
+
+fn is_light(color: Color32) -> bool {
+    let [r, g, b, _] = color.to_array();
+    0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32 > 128.0
+}
+
+impl ThemeConfig {
+    pub fn palette(&self) -> Palette {
+        let (good, warn, danger, muted) = match self.vision {
+            ColorVision::Standard => (
+                Color32::from_rgb(80, 200, 110),
+                Color32::from_rgb(235, 200, 70),
+                Color32::from_rgb(230, 70, 60),
+                Color32::from_rgb(240, 190, 90),
+            ),
+            ColorVision::RedGreen => (
+                Color32::from_rgb(86, 180, 233),
+                Color32::from_rgb(240, 228, 66),
+                Color32::from_rgb(213, 94, 0),
+                Color32::from_rgb(204, 121, 167),
+            ),
+            ColorVision::BlueYellow => (
+                Color32::from_rgb(0, 170, 170),
+                Color32::from_rgb(240, 150, 180),
+                Color32::from_rgb(215, 40, 40),
+                Color32::from_rgb(200, 200, 200),
+            ),
+        };
+        let mut palette = match self.preset {
+            ThemePreset::Dark => Palette {
+                background: Color32::from_rgb(12, 14, 18),
+                panel: Color32::from_rgb(14, 16, 20),
+                section: Color32::from_rgb(18, 22, 27),
+                raised: Color32::from_rgb(20, 24, 30),
+                stroke: Color32::from_rgb(44, 52, 64),
+                text: Color32::from_rgb(232, 236, 240),
+                accent: Color32::from_rgb(54, 168, 178),
+                good,
+                warn,
+                danger,
+                muted,
+                state_cues: self.state_cues,
+            },
+            ThemePreset::Light => Palette {
+                background: Color32::from_rgb(236, 238, 242),
+                panel: Color32::from_rgb(246, 247, 249),
+                section: Color32::from_rgb(252, 252, 253),
+                raised: Color32::from_rgb(244, 246, 249),
+                stroke: Color32::from_rgb(190, 196, 206),
+                text: Color32::from_rgb(24, 28, 34),
+                accent: Color32::from_rgb(20, 120, 135),
+                good,
+                warn,
+                danger,
+                muted,
+                state_cues: self.state_cues,
+            },
+            ThemePreset::HighContrast => Palette {
+                background: Color32::BLACK,
+                panel: Color32::BLACK,
+                section: Color32::BLACK,
+                raised: Color32::from_rgb(10, 10, 10),
+                stroke: Color32::WHITE,
+                text: Color32::WHITE,
+                accent: Color32::from_rgb(255, 210, 0),
+                good,
+                warn,
+                danger,
+                muted,
+                state_cues: self.state_cues,
+            },
+        };
+        if let Some(accent) = self.accent {
+            palette.accent = rgb(accent);
+        }
+        if let Some(background) = self.background {
+            let background = rgb(background);
+            // Sections step towards the text color so they stay visible.
+            let step = if is_light(background) { -1 } else { 1 };
+            palette.background = background;
+            palette.panel = shade(background, 2 * step);
+            palette.section = shade(background, 6 * step);
+            palette.raised = shade(background, 9 * step);
+            palette.stroke = shade(background, 32 * step);
+            palette.text = if is_light(background) {
+                Color32::from_rgb(24, 28, 34)
+            } else {
+                Color32::from_rgb(232, 236, 240)
+            };
+        }
+        palette
+    }
+
+    pub fn visuals(&self) -> egui::Visuals {
+        let palette = self.palette();
+        let light = is_light(palette.background);
+        let mut visuals = if light { egui::Visuals::light() } else { egui::Visuals::dark() };
+        // Widget fills sit between the panel and the text color.
+        let step: i16 = if light { -1 } else { 1 };
+        let widget = |amount: i16| shade(palette.panel, amount * step);
+        visuals.override_text_color = Some(palette.text);
+        visuals.panel_fill = palette.panel;
+        visuals.window_fill = palette.panel;
+        visuals.hyperlink_color = palette.accent;
+        visuals.selection.bg_fill = palette.accent;
+        visuals.selection.stroke = Stroke::new(1.0, shade(palette.accent, if light { -80 } else { 80 }));
+        if self.preset == ThemePreset::Dark && self.background.is_none() {
+            // The original studio look, with its slightly blue greys.
+            visuals.extreme_bg_color = Color32::from_rgb(20, 23, 28);
+            visuals.faint_bg_color = Color32::from_rgb(30, 33, 40);
+            visuals.widgets.inactive.bg_fill = Color32::from_rgb(28, 32, 38);
+            visuals.widgets.inactive.weak_bg_fill = Color32::from_rgb(24, 27, 33);
+            visuals.widgets.hovered.bg_fill = Color32::from_rgb(44, 50, 58);
+            visuals.widgets.active.bg_fill = Color32::from_rgb(57, 66, 76);
+            visuals.widgets.open.bg_fill = Color32::from_rgb(40, 46, 54);
+            visuals.widgets.noninteractive.bg_stroke = Stroke::new(1.0, Color32::from_rgb(52, 57, 66));
+            visuals.widgets.inactive.fg_stroke = Stroke::new(1.0, Color32::from_rgb(210, 214, 220));
+            visuals.widgets.hovered.fg_stroke = Stroke::new(1.0, Color32::from_rgb(235, 240, 244));
+            visuals.widgets.active.fg_stroke = Stroke::new(1.0, Color32::from_rgb(245, 250, 252));
+            if self.accent.is_none() {
+                visuals.selection.stroke = Stroke::new(1.0, Color32::from_rgb(180, 245, 250));
+            }
+            return visuals;
+        }
+        visuals.extreme_bg_color = shade(palette.panel, 6 * step);
+        visuals.faint_bg_color = shade(palette.panel, 16 * step);
+        visuals.widgets.inactive.bg_fill = widget(14);
+        visuals.widgets.inactive.weak_bg_fill = widget(10);
+        visuals.widgets.hovered.bg_fill = widget(30);
+        visuals.widgets.active.bg_fill = widget(43);
+        visuals.widgets.open.bg_fill = widget(26);
+        visuals.widgets.noninteractive.bg_stroke = Stroke::new(1.0, widget(40));
+        visuals.widgets.inactive.fg_stroke = Stroke::new(1.0, shade(palette.text, -22 * step));
+        visuals.widgets.hovered.fg_stroke = Stroke::new(1.0, shade(palette.text, 3 * step));
+        visuals.widgets.active.fg_stroke = Stroke::new(1.0, shade(palette.text, 13 * step));
+        if self.preset == ThemePreset::HighContrast {
+            for widgets in [
+                &mut visuals.widgets.inactive,
+                &mut visuals.widgets.hovered,
+                &mut visuals.widgets.active,
+                &mut visuals.widgets.open,
+            ] {
+                widgets.bg_stroke = Stroke::new(1.5, Color32::WHITE);
+                widgets.fg_stroke = Stroke::new(1.5, Color32::WHITE);
+            }
+            visuals.widgets.noninteractive.bg_stroke = Stroke::new(1.0, Color32::WHITE);
+            visuals.selection.stroke = Stroke::new(2.0, Color32::BLACK);
+        }
+        visuals
+    }
+}